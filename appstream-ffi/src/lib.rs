@@ -0,0 +1,289 @@
+//! Bindings of the appstream crate for Python, Kotlin and Swift, generated with
+//! [UniFFI](https://mozilla.github.io/uniffi-rs/).
+#![deny(missing_docs)]
+
+use appstream::enums::{Category, Icon, ProjectUrl};
+use appstream::xmltree::Element;
+use appstream::{MarkupTranslatableString, ParseError, TranslatableString};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+uniffi::setup_scaffolding!();
+
+/// The locale of the untranslated texts.
+const DEFAULT_LOCALE: &str = "C";
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+#[uniffi(flat_error)]
+/// The errors reported to the foreign code.
+pub enum BindingsError {
+    #[error("{0}")]
+    /// The catalog couldn't be read or parsed.
+    Parse(String),
+}
+
+impl From<ParseError> for BindingsError {
+    fn from(error: ParseError) -> Self {
+        BindingsError::Parse(error.to_string())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+/// A release of a component.
+pub struct Release {
+    /// The version of the release.
+    pub version: String,
+    /// The release date, formatted as `YYYY-MM-DD` or as an RFC 3339 timestamp.
+    pub date: Option<String>,
+    /// Either `stable` or `development`.
+    pub kind: String,
+    /// The release notes, with their markup.
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+/// A component, with its texts in a single locale.
+pub struct Component {
+    /// The unique identifier of the component.
+    pub id: String,
+    /// The component type, like `desktop-application`.
+    pub kind: String,
+    /// The name of the component.
+    pub name: String,
+    /// A short summary of what the component does.
+    pub summary: Option<String>,
+    /// The long description, with its markup.
+    pub description: Option<String>,
+    /// The name of the developers.
+    pub developer_name: Option<String>,
+    /// The SPDX license expression of the component.
+    pub project_license: Option<String>,
+    /// The URL of the upstream homepage.
+    pub homepage: Option<String>,
+    /// The name of the stock icon, if any.
+    pub stock_icon: Option<String>,
+    /// The URLs of the remote icons.
+    pub icon_urls: Vec<String>,
+    /// The URLs of the source image of each screenshot.
+    pub screenshot_urls: Vec<String>,
+    /// The categories.
+    pub categories: Vec<String>,
+    /// The search keywords.
+    pub keywords: Vec<String>,
+    /// The MIME types the component handles.
+    pub mimetypes: Vec<String>,
+    /// The releases, most recent first as in the metadata.
+    pub releases: Vec<Release>,
+}
+
+/// The text for `locale`, falling back to the default locale.
+fn localized<'a>(texts: &'a BTreeMap<String, String>, locale: Option<&str>) -> Option<&'a String> {
+    locale
+        .and_then(|l| texts.get(l))
+        .or_else(|| texts.get(DEFAULT_LOCALE))
+}
+
+fn text(text: &TranslatableString, locale: Option<&str>) -> Option<String> {
+    localized(&text.0, locale).cloned()
+}
+
+fn category_name(category: &Category) -> String {
+    match category {
+        Category::Unknown(name) => name.clone(),
+        category => category.to_string(),
+    }
+}
+
+fn markup(text: &MarkupTranslatableString, locale: Option<&str>) -> Option<String> {
+    localized(&text.0, locale).cloned()
+}
+
+impl Component {
+    fn new(component: &appstream::Component, locale: Option<&str>) -> Self {
+        let keywords = component.keywords.as_ref().and_then(|k| {
+            locale
+                .and_then(|l| k.0.get(l))
+                .or_else(|| k.0.get(DEFAULT_LOCALE))
+        });
+        Self {
+            id: component.id.to_string(),
+            kind: component.kind.to_string(),
+            name: text(&component.name, locale).unwrap_or_default(),
+            summary: component.summary.as_ref().and_then(|s| text(s, locale)),
+            description: component
+                .description
+                .as_ref()
+                .and_then(|d| markup(d, locale)),
+            developer_name: component
+                .developer_name
+                .as_ref()
+                .and_then(|d| text(d, locale)),
+            project_license: component.project_license.as_ref().map(|l| l.to_string()),
+            homepage: component.urls.iter().find_map(|u| match u {
+                ProjectUrl::Homepage(url) => Some(url.to_string()),
+                _ => None,
+            }),
+            stock_icon: component.icons.iter().find_map(|i| match i {
+                Icon::Stock(name) => Some(name.clone()),
+                _ => None,
+            }),
+            icon_urls: component
+                .icons
+                .iter()
+                .filter_map(|i| match i {
+                    Icon::Remote { url, .. } => Some(url.to_string()),
+                    _ => None,
+                })
+                .collect(),
+            screenshot_urls: component
+                .screenshots
+                .iter()
+                .filter_map(|s| s.images.first())
+                .map(|i| i.url.to_string())
+                .collect(),
+            categories: component.categories.iter().map(category_name).collect(),
+            keywords: keywords.cloned().unwrap_or_default(),
+            mimetypes: component.mimetypes.clone(),
+            releases: component
+                .releases
+                .iter()
+                .map(|r| Release {
+                    version: r.version.clone(),
+                    date: r.date.as_ref().map(|d| d.to_string()),
+                    kind: r.kind.to_string(),
+                    description: r.description.as_ref().and_then(|d| markup(d, locale)),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, uniffi::Object)]
+/// A parsed catalog.
+///
+/// The crate is built as a `cdylib` as well, the foreign code is generated out of
+/// the library with the `uniffi-bindgen` tool of the same UniFFI version:
+///
+/// ```text
+/// cargo build --release -p appstream-ffi
+/// uniffi-bindgen generate --library target/release/libappstream_ffi.so --language python --out-dir out
+/// ```
+pub struct Collection(appstream::Collection);
+
+#[uniffi::export]
+impl Collection {
+    #[uniffi::constructor]
+    /// Parses an XML catalog file.
+    pub fn from_path(path: String) -> Result<Arc<Self>, BindingsError> {
+        Ok(Arc::new(Self(appstream::Collection::from_path(
+            path.into(),
+        )?)))
+    }
+
+    #[uniffi::constructor]
+    /// Parses a YAML (DEP-11) catalog file.
+    pub fn from_yaml_path(path: String) -> Result<Arc<Self>, BindingsError> {
+        Ok(Arc::new(Self(appstream::Collection::from_yaml_path(
+            path.into(),
+        )?)))
+    }
+
+    #[uniffi::constructor]
+    /// Parses an XML catalog.
+    pub fn from_xml(xml: String) -> Result<Arc<Self>, BindingsError> {
+        let element = Element::parse(xml.as_bytes()).map_err(ParseError::from)?;
+        Ok(Arc::new(Self(appstream::Collection::try_from(&element)?)))
+    }
+
+    /// The origin of the catalog, like `flathub`.
+    pub fn origin(&self) -> Option<String> {
+        self.0.origin.clone()
+    }
+
+    /// The number of components.
+    pub fn len(&self) -> u64 {
+        self.0.len() as u64
+    }
+
+    /// Whether the catalog has no components.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The ids of all the components.
+    pub fn ids(&self) -> Vec<String> {
+        self.0.iter().map(|c| c.id.to_string()).collect()
+    }
+
+    /// All the components, with their texts in `locale` when translated.
+    pub fn components(&self, locale: Option<String>) -> Vec<Component> {
+        self.0
+            .iter()
+            .map(|c| Component::new(c, locale.as_deref()))
+            .collect()
+    }
+
+    /// The components with a specific id, see `Collection::find_by_id`.
+    pub fn find_by_id(&self, id: String, locale: Option<String>) -> Vec<Component> {
+        self.0
+            .find_by_id(id.as_str().into())
+            .into_iter()
+            .map(|c| Component::new(c, locale.as_deref()))
+            .collect()
+    }
+
+    /// The components that can handle a specific MIME type.
+    pub fn find_by_mimetype(&self, mimetype: String, locale: Option<String>) -> Vec<Component> {
+        self.0
+            .find_by_mimetype(&mimetype)
+            .into_iter()
+            .map(|c| Component::new(c, locale.as_deref()))
+            .collect()
+    }
+
+    /// The components of a category, like `Graphics`.
+    pub fn find_by_category(&self, category: String, locale: Option<String>) -> Vec<Component> {
+        self.0
+            .iter()
+            .filter(|c| {
+                c.categories
+                    .iter()
+                    .any(|k| category_name(k).eq_ignore_ascii_case(&category))
+            })
+            .map(|c| Component::new(c, locale.as_deref()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn exported_collection() -> Result<(), Box<dyn Error>> {
+        let collection = Collection::from_path("../tests/collections/spec_example.xml".into())?;
+        assert_eq!(collection.len(), 3);
+        assert_eq!(collection.ids()[0], "org.mozilla.Firefox");
+
+        let firefox = collection.find_by_id("org.mozilla.Firefox".into(), Some("fr_FR".into()));
+        assert_eq!(firefox.len(), 1);
+        assert_eq!(firefox[0].summary.as_deref(), Some("Navigateur web"));
+        assert_eq!(firefox[0].keywords, vec!["navigateur"]);
+        assert_eq!(
+            firefox[0].homepage.as_deref(),
+            Some("https://www.mozilla.com/")
+        );
+
+        let firefox = collection.find_by_mimetype("text/html".into(), Some("de".into()));
+        assert_eq!(firefox[0].summary.as_deref(), Some("Web browser"));
+        assert_eq!(collection.find_by_category("Network".into(), None).len(), 1);
+
+        match Collection::from_xml("<components".into()) {
+            Err(BindingsError::Parse(_)) => (),
+            r => panic!("Unexpected result {:?}", r),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+//! Compares the streaming reader of `Collection::from_path` with building the
+//! whole tree with `Element::parse` first.
+//!
+//! Run with `cargo bench --bench reader`.
+
+use appstream::xmltree::Element;
+use appstream::Collection;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fs::{self, File};
+use std::hint::black_box;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const ROUNDS: u32 = 10;
+
+/// The average duration of a round of `f`, after a warm up round.
+fn measure(
+    mut f: impl FnMut() -> Result<Collection, Box<dyn Error>>,
+) -> Result<Duration, Box<dyn Error>> {
+    black_box(f()?);
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f()?);
+    }
+    Ok(start.elapsed() / ROUNDS)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    for name in ["gnome-apps.xml", "kde-apps.xml", "flathub-old.xml"] {
+        let path = PathBuf::from("./tests/collections").join(name);
+        let size = fs::metadata(&path)?.len() as f64 / 1_000_000.0;
+
+        let streamed = measure(|| Ok(Collection::from_path(path.clone())?))?;
+        let tree = measure(|| {
+            let element = Element::parse(BufReader::new(File::open(&path)?))?;
+            Ok(Collection::try_from(&element)?)
+        })?;
+        println!(
+            "{:<16} {:>6.2} MB  quick-xml {:>8.2?} ({:>6.1} MB/s)  xmltree {:>8.2?} ({:>6.1} MB/s)",
+            name,
+            size,
+            streamed,
+            size / streamed.as_secs_f64(),
+            tree,
+            size / tree.as_secs_f64(),
+        );
+    }
+    Ok(())
+}
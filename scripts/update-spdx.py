@@ -0,0 +1,52 @@
+#!/usr/bin/env python3
+"""Generates src/spdx_list.rs from a checkout of the SPDX license list data.
+
+Usage: scripts/update-spdx.py <path to license-list-data> > src/spdx_list.rs
+
+See https://github.com/spdx/license-list-data.
+"""
+
+import json
+import os
+import sys
+
+
+def main(data):
+    with open(os.path.join(data, "json", "licenses.json")) as f:
+        licenses = json.load(f)
+    with open(os.path.join(data, "json", "exceptions.json")) as f:
+        exceptions = json.load(f)
+
+    print(
+        "// Generated by scripts/update-spdx.py from the SPDX license list {}, do not edit.".format(
+            licenses["licenseListVersion"]
+        )
+    )
+    print()
+    print("/// The license is approved by the OSI.")
+    print("pub(crate) const OSI_APPROVED: u8 = 1;")
+    print("/// The license is considered free by the FSF.")
+    print("pub(crate) const FSF_LIBRE: u8 = 1 << 1;")
+    print()
+    print("/// The SPDX license identifiers, deprecated ones included, with their flags.")
+    print("pub(crate) const LICENSES: &[(&str, u8)] = &[")
+    for license in sorted(licenses["licenses"], key=lambda l: l["licenseId"]):
+        flags = []
+        if license.get("isOsiApproved"):
+            flags.append("OSI_APPROVED")
+        if license.get("isFsfLibre"):
+            flags.append("FSF_LIBRE")
+        print('    ("{}", {}),'.format(license["licenseId"], " | ".join(flags) or "0"))
+    print("];")
+    print()
+    print("/// The SPDX license exception identifiers, deprecated ones included.")
+    print("pub(crate) const EXCEPTIONS: &[&str] = &[")
+    for exception in sorted(e["licenseExceptionId"] for e in exceptions["exceptions"]):
+        print('    "{}",'.format(exception))
+    print("];")
+
+
+if __name__ == "__main__":
+    if len(sys.argv) != 2:
+        sys.exit(__doc__.strip())
+    main(sys.argv[1])
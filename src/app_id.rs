@@ -1,23 +1,187 @@
+use super::error::ParseError;
+use super::interner;
 use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::string::ToString;
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+use std::sync::Arc;
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(from = "String")]
 /// Unique identifier of a component. It should be reverse-DNS name.
-pub struct AppId(pub String);
+///
+/// The ids are interned while parsing a catalog, so comparing the ids of the same
+/// component across catalogs only compares pointers. An `AppId` derefs and borrows
+/// as a `str`, maps keyed by ids can be queried with a `&str`.
+pub struct AppId(pub Arc<str>);
 
 impl From<&str> for AppId {
     fn from(id: &str) -> Self {
-        Self(id.to_string())
+        Self(interner::intern(id))
     }
 }
 
 impl From<String> for AppId {
     fn from(id: String) -> Self {
-        Self(id)
+        Self::from(id.as_str())
+    }
+}
+
+impl Deref for AppId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for AppId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for AppId {
+    fn as_ref(&self) -> &str {
+        &self.0
     }
 }
 
 impl ToString for AppId {
     fn to_string(&self) -> String {
-        self.0.clone()
+        self.0.to_string()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// A table of renamed ids, from their former id to their new one, for the
+/// components whose id changed, like when moving to Flathub, and which catalogs
+/// still disagree on.
+///
+/// The tables are loaded from text files with one rename per line, the former
+/// id then the new one separated by whitespace. Empty lines and the lines
+/// starting with `#` are skipped.
+///
+/// # Example
+///
+/// ```
+/// use appstream::AppIdAliases;
+///
+/// let aliases: AppIdAliases = "# former id, new id\n\
+///     gedit.desktop org.gnome.gedit\n\
+///     org.gnome.gedit org.gnome.TextEditor\n"
+///     .parse()
+///     .unwrap();
+/// assert_eq!(aliases.resolve("gedit.desktop"), "org.gnome.TextEditor");
+/// ```
+pub struct AppIdAliases(HashMap<AppId, AppId>);
+
+impl AppIdAliases {
+    /// Loads a table of renamed ids from a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the table.
+    pub fn from_path(path: PathBuf) -> Result<Self, ParseError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Adds a renamed id.
+    ///
+    /// # Arguments
+    ///
+    /// * `former` - The former id of the component.
+    /// * `new` - Its new id.
+    pub fn insert(&mut self, former: AppId, new: AppId) {
+        self.0.insert(former, new);
+    }
+
+    /// The current id of a component, following the renames, or `id` itself
+    /// when it wasn't renamed.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id, former or not, of the component.
+    pub fn resolve<'a>(&'a self, id: &'a str) -> &'a str {
+        let mut current = id;
+        // A cycle in the table can't be longer than the table.
+        for _ in 0..self.0.len() {
+            match self.0.get(current) {
+                Some(new) if &**new != id => current = new,
+                _ => break,
+            }
+        }
+        current
+    }
+
+    /// The number of renamed ids.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the table has no renamed ids.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromStr for AppIdAliases {
+    type Err = ParseError;
+
+    fn from_str(table: &str) -> Result<Self, Self::Err> {
+        let mut aliases = Self::default();
+        for line in table.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [former, new] => aliases.insert(former.into(), new.into()),
+                _ => return Err(ParseError::invalid_value(line, "alias", "aliases")),
+            }
+        }
+        Ok(aliases)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interned() {
+        let (a, b) = interner::scope(|| {
+            (
+                AppId::from("org.gnome.Characters"),
+                AppId::from(String::from("org.gnome.Characters")),
+            )
+        });
+        assert!(Arc::ptr_eq(&a.0, &b.0));
+        assert_eq!(a, AppId::from("org.gnome.Characters"));
+        assert!(a.starts_with("org.gnome."));
+
+        let mut ids = HashMap::new();
+        ids.insert(a, 1);
+        assert_eq!(ids.get("org.gnome.Characters"), Some(&1));
+        assert_eq!(ids.get("org.gnome.Weather"), None);
+    }
+
+    #[test]
+    fn aliases() -> Result<(), Box<dyn std::error::Error>> {
+        let aliases = "org.example.Old org.example.Mid\n\n  # renamed again\n\
+                       org.example.Mid\torg.example.New\n\
+                       org.example.A org.example.B\norg.example.B org.example.A\n"
+            .parse::<AppIdAliases>()?;
+        assert_eq!(aliases.len(), 4);
+        let resolve = |id| aliases.resolve(id);
+        assert_eq!(resolve("org.example.Old"), "org.example.New");
+        assert_eq!(resolve("org.example.New"), "org.example.New");
+        // Cycles stop before coming back to the id.
+        assert_eq!(resolve("org.example.A"), "org.example.B");
+        assert!("org.example.Old".parse::<AppIdAliases>().is_err());
+        assert!("a b c".parse::<AppIdAliases>().is_err());
+        Ok(())
     }
 }
@@ -0,0 +1,272 @@
+use super::enums::{Category, Size};
+use super::{Collection, Component, Release};
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{ArrayRef, Date32Array, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::errors::ParquetError;
+use std::io::Write;
+use std::sync::Arc;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// A column of the exported tables, each one a field of the components.
+pub enum Column {
+    /// The component id, as `Utf8`.
+    Id,
+    /// The component type, like `desktop-application`, as `Utf8`.
+    Kind,
+    /// The origin of the component, or of its collection, as a nullable `Utf8`.
+    Origin,
+    /// The category names, as a `List<Utf8>`.
+    Categories,
+    /// The SPDX expression of the project license, as a nullable `Utf8`.
+    ProjectLicense,
+    /// The date of the latest release, as a nullable `Date32`.
+    LatestReleaseDate,
+    /// The download size of the latest release in bytes, as a nullable `UInt64`.
+    DownloadSize,
+}
+
+impl Column {
+    /// All the columns, in the order of their declaration.
+    pub const ALL: [Column; 7] = [
+        Column::Id,
+        Column::Kind,
+        Column::Origin,
+        Column::Categories,
+        Column::ProjectLicense,
+        Column::LatestReleaseDate,
+        Column::DownloadSize,
+    ];
+
+    /// The name of the column.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Kind => "kind",
+            Column::Origin => "origin",
+            Column::Categories => "categories",
+            Column::ProjectLicense => "project_license",
+            Column::LatestReleaseDate => "latest_release_date",
+            Column::DownloadSize => "download_size",
+        }
+    }
+
+    fn field(&self) -> Field {
+        match self {
+            Column::Id | Column::Kind => Field::new(self.name(), DataType::Utf8, false),
+            Column::Origin | Column::ProjectLicense => {
+                Field::new(self.name(), DataType::Utf8, true)
+            }
+            Column::Categories => Field::new(
+                self.name(),
+                DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+                false,
+            ),
+            Column::LatestReleaseDate => Field::new(self.name(), DataType::Date32, true),
+            Column::DownloadSize => Field::new(self.name(), DataType::UInt64, true),
+        }
+    }
+
+    fn array(&self, components: &[Component], origin: Option<&str>) -> ArrayRef {
+        match self {
+            Column::Id => Arc::new(StringArray::from_iter_values(
+                components.iter().map(|c| c.id.0.as_ref()),
+            )),
+            Column::Kind => Arc::new(StringArray::from_iter_values(
+                components.iter().map(|c| c.kind.to_string()),
+            )),
+            Column::Origin => Arc::new(
+                components
+                    .iter()
+                    .map(|c| c.origin.as_deref().or(origin))
+                    .collect::<StringArray>(),
+            ),
+            Column::Categories => {
+                let mut builder = ListBuilder::new(StringBuilder::new());
+                for component in components.iter() {
+                    for category in component.categories.iter() {
+                        builder.values().append_value(match category {
+                            Category::Unknown(name) => name.clone(),
+                            category => category.to_string(),
+                        });
+                    }
+                    builder.append(true);
+                }
+                Arc::new(builder.finish())
+            }
+            Column::ProjectLicense => Arc::new(
+                components
+                    .iter()
+                    .map(|c| c.project_license.as_ref().map(|l| l.to_string()))
+                    .collect::<StringArray>(),
+            ),
+            Column::LatestReleaseDate => Arc::new(
+                components
+                    .iter()
+                    .map(|c| {
+                        let date = c.latest_release()?.date.as_ref()?;
+                        Some(date.as_unix().div_euclid(SECONDS_PER_DAY) as i32)
+                    })
+                    .collect::<Date32Array>(),
+            ),
+            Column::DownloadSize => Arc::new(
+                components
+                    .iter()
+                    .map(|c| c.latest_release().and_then(download_size))
+                    .collect::<UInt64Array>(),
+            ),
+        }
+    }
+}
+
+/// The download size of a release, or of its first artifact declaring one.
+fn download_size(release: &Release) -> Option<u64> {
+    let artifacts = release.artifacts.iter().flat_map(|a| a.sizes.iter());
+    release.sizes.iter().chain(artifacts).find_map(|s| match s {
+        Size::Download(size) => Some(*size),
+        _ => None,
+    })
+}
+
+/// The schema of the tables made of `columns`.
+///
+/// # Arguments
+///
+/// * `columns` - The exported columns.
+pub fn schema(columns: &[Column]) -> Schema {
+    Schema::new(columns.iter().map(Column::field).collect::<Vec<Field>>())
+}
+
+/// Exports some fields of the components of a collection to a record batch, one
+/// row per component.
+///
+/// # Arguments
+///
+/// * `collection` - The exported collection.
+/// * `columns` - The exported columns.
+///
+/// # Example
+///
+/// ```
+/// use appstream::arrow::{self, Column};
+/// use appstream::Collection;
+///
+/// let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into()).unwrap();
+/// let batch = arrow::record_batch(&collection, &[Column::Id, Column::Categories]).unwrap();
+/// assert_eq!(batch.num_rows(), collection.len());
+/// ```
+pub fn record_batch(
+    collection: &Collection,
+    columns: &[Column],
+) -> Result<RecordBatch, ArrowError> {
+    let origin = collection.origin.as_deref();
+    RecordBatch::try_new(
+        Arc::new(schema(columns)),
+        columns
+            .iter()
+            .map(|c| c.array(&collection.components, origin))
+            .collect(),
+    )
+}
+
+/// Writes some fields of the components of several collections to a Parquet
+/// file, one row group per collection.
+///
+/// # Arguments
+///
+/// * `writer` - Where to write the file.
+/// * `collections` - The exported collections, like the catalogs of several
+///   distributions.
+/// * `columns` - The exported columns.
+pub fn write_parquet<W: Write + Send>(
+    writer: W,
+    collections: &[Collection],
+    columns: &[Column],
+) -> Result<(), ParquetError> {
+    let mut writer = ArrowWriter::try_new(writer, Arc::new(schema(columns)), None)?;
+    for collection in collections.iter() {
+        writer.write(&record_batch(collection, columns)?)?;
+        writer.flush()?;
+    }
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::{CollectionBuilder, ComponentBuilder, ReleaseBuilder};
+    use crate::{Timestamp, TranslatableString};
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Date32Type, UInt64Type};
+    use arrow_array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::error::Error;
+    use std::fs::File;
+
+    #[test]
+    fn export_columns() -> Result<(), Box<dyn Error>> {
+        let collection = CollectionBuilder::new("0.14")
+            .origin("flathub")
+            .component(
+                ComponentBuilder::default()
+                    .id("org.example.Foo".into())
+                    .name(TranslatableString::with_default("Foo"))
+                    .category(Category::Graphics)
+                    .category(Category::Unknown("Painting".into()))
+                    .project_license("GPL-3.0-or-later".into())
+                    .release(
+                        ReleaseBuilder::new("1.10")
+                            .date(Timestamp::from_ymd(2021, 3, 1).ok_or("invalid date")?)
+                            .size(Size::Download(4096))
+                            .build(),
+                    )
+                    .release(
+                        ReleaseBuilder::new("1.9")
+                            .date(Timestamp::from_ymd(2020, 1, 1).ok_or("invalid date")?)
+                            .build(),
+                    )
+                    .build(),
+            )
+            .component(
+                ComponentBuilder::default()
+                    .id("org.example.Bar".into())
+                    .name(TranslatableString::with_default("Bar"))
+                    .build(),
+            )
+            .build();
+
+        let batch = record_batch(&collection, &Column::ALL)?;
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), Column::ALL.len());
+        let origins = batch.column(2).as_string::<i32>();
+        assert_eq!(origins.value(1), "flathub");
+        let categories = batch.column(3).as_list::<i32>();
+        let foo = categories.value(0);
+        assert_eq!(foo.as_string::<i32>().value(1), "Painting");
+        assert!(batch.column(4).is_null(1));
+        let dates = batch.column(5).as_primitive::<Date32Type>();
+        assert_eq!(dates.value(0), 18687);
+        assert!(dates.is_null(1));
+        let sizes = batch.column(6).as_primitive::<UInt64Type>();
+        assert_eq!(sizes.value(0), 4096);
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("export.parquet");
+        let columns = [Column::Id, Column::DownloadSize];
+        write_parquet(
+            File::create(&path)?,
+            &[collection.clone(), collection],
+            &columns,
+        )?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path)?)?.build()?;
+        let rows = reader
+            .map(|batch| batch.map(|b| b.num_rows()))
+            .sum::<Result<usize, ArrowError>>()?;
+        assert_eq!(rows, 4);
+        Ok(())
+    }
+}
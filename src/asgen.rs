@@ -0,0 +1,304 @@
+use super::error::ParseError;
+use super::validation::{IssueSeverity, ValidationIssue};
+use super::Component;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use xmltree::Element;
+use yaml_rust::YamlLoader;
+
+#[derive(Clone, Debug, PartialEq)]
+/// A hint emitted by appstream-generator while processing a package.
+pub struct GeneratorHint {
+    /// The identifier of the package, like `contrast/0.0.5-1/amd64`.
+    pub package: String,
+    /// The component the hint is about, or `general` for the package itself.
+    pub component: String,
+    /// The tag of the hint, like `icon-not-found`.
+    pub tag: String,
+    /// The variables substituted in the text of the hint.
+    pub vars: BTreeMap<String, String>,
+}
+
+impl GeneratorHint {
+    /// Turns the hint into a validation issue, with the severity and the text
+    /// of its definition. The hints without a definition are errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `definitions` - The hint definitions, see `read_hint_definitions`.
+    pub fn to_issue(&self, definitions: &BTreeMap<String, HintDefinition>) -> ValidationIssue {
+        let issue = match definitions.get(&self.tag) {
+            Some(definition) => {
+                let message = self
+                    .vars
+                    .iter()
+                    .fold(definition.text.clone(), |text, (k, v)| {
+                        text.replace(&format!("{{{{{}}}}}", k), v)
+                    });
+                ValidationIssue::new(definition.severity, &self.tag, &message)
+            }
+            None => {
+                ValidationIssue::error(&self.tag, &format!("Unknown hint for {}", self.package))
+            }
+        };
+        if self.component == "general" {
+            issue
+        } else {
+            issue.for_component(&self.component.as_str().into())
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// What a hint tag means, as defined in the `asgen-hints.json` file.
+pub struct HintDefinition {
+    /// The text of the hint, with `{{var}}` placeholders.
+    pub text: String,
+    /// How serious the hint is.
+    pub severity: IssueSeverity,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Text {
+    Line(String),
+    Lines(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct RawDefinition {
+    text: Text,
+    severity: IssueSeverity,
+}
+
+#[derive(Deserialize)]
+struct RawHint {
+    tag: String,
+    #[serde(default)]
+    vars: BTreeMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct RawHints {
+    package: String,
+    #[serde(default)]
+    hints: BTreeMap<String, Vec<RawHint>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawResult {
+    package: String,
+    #[serde(default)]
+    origin: String,
+    #[serde(default)]
+    media_base_url: String,
+    #[serde(default)]
+    components: Vec<String>,
+    #[serde(default)]
+    hints: BTreeMap<String, Vec<RawHint>>,
+}
+
+fn hints(package: &str, hints: BTreeMap<String, Vec<RawHint>>) -> Vec<GeneratorHint> {
+    let mut list = Vec::new();
+    for (component, raw_hints) in hints {
+        for hint in raw_hints {
+            let vars = hint
+                .vars
+                .into_iter()
+                .map(|(k, v)| match v {
+                    Value::String(s) => (k, s),
+                    v => (k, v.to_string()),
+                })
+                .collect();
+            list.push(GeneratorHint {
+                package: package.to_string(),
+                component: component.clone(),
+                tag: hint.tag,
+                vars,
+            });
+        }
+    }
+    list
+}
+
+/// Reads a `Hints-<arch>.json` file exported by appstream-generator, a list of
+/// the hints of each package. The exported files are gzipped, they can be read
+/// through a `flate2::read::GzDecoder`.
+///
+/// # Arguments
+///
+/// * `reader` - The uncompressed content of the file.
+///
+/// # Example
+///
+/// ```
+/// let json = r#"[{"package": "contrast/0.0.5-1/amd64", "hints": {
+///     "org.gnome.design.Contrast": [{"tag": "icon-not-found", "vars": {"icon_fname": "contrast"}}]
+/// }}]"#;
+/// let hints = appstream::asgen::read_hints(json.as_bytes()).unwrap();
+/// assert_eq!(hints[0].tag, "icon-not-found");
+/// assert_eq!(hints[0].vars["icon_fname"], "contrast");
+/// ```
+pub fn read_hints<R: Read>(reader: R) -> Result<Vec<GeneratorHint>, ParseError> {
+    let entries: Vec<RawHints> = serde_json::from_reader(reader).map_err(io::Error::from)?;
+    Ok(entries
+        .into_iter()
+        .flat_map(|entry| hints(&entry.package, entry.hints))
+        .collect())
+}
+
+/// Reads the `asgen-hints.json` file of appstream-generator, the definitions of
+/// the hint tags.
+///
+/// # Arguments
+///
+/// * `reader` - The content of the file.
+pub fn read_hint_definitions<R: Read>(
+    reader: R,
+) -> Result<BTreeMap<String, HintDefinition>, ParseError> {
+    let definitions: BTreeMap<String, RawDefinition> =
+        serde_json::from_reader(reader).map_err(io::Error::from)?;
+    Ok(definitions
+        .into_iter()
+        .map(|(tag, d)| {
+            let text = match d.text {
+                Text::Line(line) => line,
+                Text::Lines(lines) => lines.join("\n"),
+            };
+            let definition = HintDefinition {
+                text,
+                severity: d.severity,
+            };
+            (tag, definition)
+        })
+        .collect())
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// The result of appstream-generator for a single package: the components it
+/// extracted and the hints it emitted.
+///
+/// The JSON object has the identifier of the package as `package`, the DEP-11
+/// YAML documents or metainfo XML files of the components as `components`, and
+/// the hints by component id as `hints`. The `origin` and `mediaBaseUrl` of the
+/// DEP-11 documents are optional.
+///
+/// # Example
+///
+/// ```
+/// use appstream::asgen::PackageResult;
+///
+/// let json = r#"{
+///     "package": "contrast/0.0.5-1/amd64",
+///     "components": ["ID: org.gnome.design.Contrast\nType: desktop-application\nName:\n  C: Contrast\n"],
+///     "hints": {"org.gnome.design.Contrast": [{"tag": "no-metainfo"}]}
+/// }"#;
+/// let result = PackageResult::from_reader(json.as_bytes()).unwrap();
+/// assert_eq!(result.components[0].id, "org.gnome.design.Contrast".into());
+/// assert_eq!(result.hints[0].tag, "no-metainfo");
+/// ```
+pub struct PackageResult {
+    /// The identifier of the package, like `contrast/0.0.5-1/amd64`.
+    pub package: String,
+    /// The components extracted from the package.
+    pub components: Vec<Component>,
+    /// The hints emitted while processing the package.
+    pub hints: Vec<GeneratorHint>,
+}
+
+impl PackageResult {
+    /// Reads the result of a package from a JSON file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the JSON file.
+    pub fn from_path(path: &Path) -> Result<Self, ParseError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads the result of a package from JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The JSON content.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, ParseError> {
+        let result: RawResult = serde_json::from_reader(reader).map_err(io::Error::from)?;
+        let mut components = Vec::new();
+        for data in result.components.iter() {
+            if data.trim_start().starts_with('<') {
+                components.push(Component::try_from(&Element::parse(data.as_bytes())?)?);
+                continue;
+            }
+            let docs = YamlLoader::load_from_str(data)
+                .map_err(|_| ParseError::invalid_value(&result.package, "components", "package"))?;
+            for doc in docs.iter() {
+                components.push(Component::try_from((
+                    result.origin.as_str(),
+                    result.media_base_url.as_str(),
+                    doc,
+                ))?);
+            }
+        }
+        Ok(Self {
+            hints: hints(&result.package, result.hints),
+            package: result.package,
+            components,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn package_result() -> Result<(), Box<dyn Error>> {
+        let metainfo = std::fs::read_to_string("./tests/desktop.xml")?;
+        let json = serde_json::json!({
+            "package": "contrast/0.0.5-1/amd64",
+            "origin": "debian",
+            "mediaBaseUrl": "https://example.org/media/",
+            "components": [
+                metainfo,
+                "ID: org.example.App\nType: desktop-application\nName:\n  C: App\n",
+            ],
+            "hints": {
+                "general": [{"tag": "pkg-extract-error", "vars": {"msg": "broken", "code": 2}}],
+                "org.example.App": [{"tag": "icon-not-found", "vars": {"icon_fname": "app"}}]
+            }
+        });
+        let result = PackageResult::from_reader(json.to_string().as_bytes())?;
+        assert_eq!(result.components.len(), 2);
+        assert_eq!(result.components[1].id, "org.example.App".into());
+        assert_eq!(result.components[1].origin.as_deref(), Some("debian"));
+        assert_eq!(result.hints.len(), 2);
+        assert_eq!(result.hints[0].component, "general");
+        assert_eq!(result.hints[0].vars["code"], "2");
+
+        let definitions = read_hint_definitions(
+            r#"{
+                "icon-not-found": {"text": ["The icon {{icon_fname}}", "was not found."], "severity": "error"},
+                "pkg-extract-error": {"text": "Could not extract: {{msg}}", "severity": "warning"}
+            }"#
+            .as_bytes(),
+        )?;
+        let general = result.hints[0].to_issue(&definitions);
+        assert_eq!(general.severity, IssueSeverity::Warning);
+        assert_eq!(general.message, "Could not extract: broken");
+        assert_eq!(general.component, None);
+        let icon = result.hints[1].to_issue(&definitions);
+        assert_eq!(icon.severity, IssueSeverity::Error);
+        assert_eq!(icon.message, "The icon app\nwas not found.");
+        assert_eq!(icon.component, Some("org.example.App".into()));
+
+        assert!(read_hints("{}".as_bytes()).is_err());
+        Ok(())
+    }
+}
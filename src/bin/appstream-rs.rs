@@ -0,0 +1,803 @@
+//! A command line interface around the `appstream` crate.
+//!
+//! ```text
+//! appstream-rs validate [--format text|json|sarif] <file>...
+//! appstream-rs dump <id> [--catalog <file>]...
+//! appstream-rs convert <input> <output>
+//! appstream-rs search <term> [--catalog <file>]...
+//! appstream-rs what-provides <kind> <item> [--catalog <file>]...
+//! appstream-rs vercmp <version> [<op>] <version>
+//! appstream-rs stats [<file>...]
+//! ```
+use appstream::enums::{Category, FirmwareKind, Provide};
+use appstream::validation::{IssueSeverity, ValidationReport, Validator};
+use appstream::{vercmp, Collection, Component, ParseError};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use xmltree::Element;
+use yaml_rust::YamlLoader;
+
+const USAGE: &str = "Usage: appstream-rs <command> [options]
+
+Commands:
+  validate [--format <format>] <file>...
+                                      Validate metainfo files or catalogs, the
+                                      issues are printed as text, json or sarif
+  dump <id> [--catalog <file>]...     Print a component of the catalogs as JSON
+  convert <input> <output>            Convert a metainfo file or a catalog to
+                                      XML or JSON, the format is guessed from
+                                      the output extension
+  search <term> [--catalog <file>]... Search the catalogs for components
+  what-provides <kind> <item> [--catalog <file>]...
+                                      List the components providing an item, the
+                                      kind is one of mediatype, library, binary,
+                                      font, modalias, firmware-runtime,
+                                      firmware-flashed, python2, python3, dbus,
+                                      id or codec
+  vercmp <version> [<op>] <version>   Compare two versions, with an operator
+                                      (lt, le, eq, ne, ge, gt) exits with 1 if
+                                      the comparison doesn't hold
+  stats [<file>...]                   Print statistics about the catalogs
+
+The system catalogs are used when no --catalog is passed.";
+
+/// The directories the system catalogs are installed in.
+const SYSTEM_CATALOG_DIRS: &[&str] = &[
+    "/usr/share/swcatalog/xml",
+    "/usr/share/swcatalog/yaml",
+    "/usr/share/app-info/xmls",
+    "/usr/share/app-info/yaml",
+    "/var/lib/app-info/xmls",
+    "/var/lib/app-info/yaml",
+    "/var/cache/app-info/xmls",
+    "/var/cache/app-info/yaml",
+];
+
+#[derive(Debug)]
+enum Error {
+    Usage(String),
+    Parse(PathBuf, ParseError),
+    Json(PathBuf, serde_json::Error),
+    Io(PathBuf, std::io::Error),
+    Unsupported(String),
+    NotFound(String),
+    Invalid,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(msg) => write!(f, "{}\n\n{}", msg, USAGE),
+            Error::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+            Error::Json(path, e) => write!(f, "{}: {}", path.display(), e),
+            Error::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            Error::Unsupported(msg) => f.write_str(msg),
+            Error::NotFound(id) => write!(f, "No component matching {} was found", id),
+            Error::Invalid => f.write_str("Check failed"),
+        }
+    }
+}
+
+impl Error {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Error::Usage(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// A parsed metainfo file or catalog.
+enum Document {
+    Component(Box<Component>),
+    Collection(Collection),
+}
+
+impl Document {
+    fn components(&self) -> Vec<&Component> {
+        match self {
+            Document::Component(c) => vec![c.as_ref()],
+            Document::Collection(c) => c.components.iter().collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Xml,
+    Yaml,
+    Json,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        let name = name.strip_suffix(".gz").unwrap_or(name);
+        if name.ends_with(".xml") {
+            Some(Format::Xml)
+        } else if name.ends_with(".yml") || name.ends_with(".yaml") {
+            Some(Format::Yaml)
+        } else if name.ends_with(".json") {
+            Some(Format::Json)
+        } else {
+            None
+        }
+    }
+}
+
+fn read_to_string(path: &Path) -> Result<String, Error> {
+    #[cfg(feature = "gzip")]
+    if path.extension().is_some_and(|e| e == "gz") {
+        use std::io::Read;
+        let file = fs::File::open(path).map_err(|e| Error::Io(path.into(), e))?;
+        let mut content = String::new();
+        flate2::read::GzDecoder::new(file)
+            .read_to_string(&mut content)
+            .map_err(|e| Error::Io(path.into(), e))?;
+        return Ok(content);
+    }
+    fs::read_to_string(path).map_err(|e| Error::Io(path.into(), e))
+}
+
+fn load(path: &Path) -> Result<Document, Error> {
+    let format = Format::from_path(path)
+        .ok_or_else(|| Error::Unsupported(format!("Unknown file format: {}", path.display())))?;
+    let content = read_to_string(path)?;
+    let parse_error = |e: ParseError| Error::Parse(path.into(), e);
+
+    match format {
+        Format::Xml => {
+            let element = Element::parse(content.as_bytes()).map_err(|e| parse_error(e.into()))?;
+            if element.name == "components" {
+                Ok(Document::Collection(
+                    Collection::try_from(&element).map_err(parse_error)?,
+                ))
+            } else {
+                Ok(Document::Component(Box::new(
+                    Component::try_from(&element).map_err(parse_error)?,
+                )))
+            }
+        }
+        Format::Yaml => {
+            let documents = YamlLoader::load_from_str(&content).map_err(|e| {
+                parse_error(ParseError::invalid_value(&e.to_string(), "$value", "yaml"))
+            })?;
+            Ok(Document::Collection(
+                Collection::try_from(&documents).map_err(parse_error)?,
+            ))
+        }
+        Format::Json => {
+            if let Ok(collection) = serde_json::from_str::<Collection>(&content) {
+                return Ok(Document::Collection(collection));
+            }
+            let component = serde_json::from_str::<Component>(&content)
+                .map_err(|e| Error::Json(path.into(), e))?;
+            Ok(Document::Component(Box::new(component)))
+        }
+    }
+}
+
+/// The catalogs passed with `--catalog`, the system ones otherwise.
+fn catalogs(paths: &[PathBuf]) -> Result<Vec<Document>, Error> {
+    if !paths.is_empty() {
+        return paths.iter().map(|p| load(p)).collect();
+    }
+    let mut documents = Vec::new();
+    for dir in SYSTEM_CATALOG_DIRS.iter() {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        let mut files = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| Format::from_path(p).is_some())
+            .collect::<Vec<PathBuf>>();
+        files.sort();
+        for file in files {
+            match load(&file) {
+                Ok(document) => documents.push(document),
+                Err(e) => eprintln!("Skipping {}", e),
+            }
+        }
+    }
+    Ok(documents)
+}
+
+/// Splits the `--catalog <file>` options from the positional arguments.
+fn parse_args(args: &[String]) -> Result<(Vec<String>, Vec<PathBuf>), Error> {
+    let mut positional = Vec::new();
+    let mut catalogs = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--catalog" | "-c" => {
+                let path = iter
+                    .next()
+                    .ok_or_else(|| Error::Usage("--catalog expects a file".into()))?;
+                catalogs.push(PathBuf::from(path));
+            }
+            a if a.starts_with("--catalog=") => {
+                catalogs.push(PathBuf::from(&a["--catalog=".len()..]));
+            }
+            a if a.starts_with('-') && a.len() > 1 => {
+                return Err(Error::Usage(format!("Unknown option {}", a)));
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+    Ok((positional, catalogs))
+}
+
+fn validate(args: &[String]) -> Result<(), Error> {
+    let mut format = "text";
+    let mut files = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = iter
+                    .next()
+                    .ok_or_else(|| Error::Usage("--format expects a format".into()))?;
+            }
+            a if a.starts_with("--format=") => format = &a["--format=".len()..],
+            a if a.starts_with('-') && a.len() > 1 => {
+                return Err(Error::Usage(format!("Unknown option {}", a)));
+            }
+            _ => files.push(arg),
+        }
+    }
+    if !["text", "json", "sarif"].contains(&format) {
+        return Err(Error::Usage(format!("Unknown format {}", format)));
+    }
+    if files.is_empty() {
+        return Err(Error::Usage("validate expects at least one file".into()));
+    }
+    let validator = Validator::default();
+    let mut report = ValidationReport::default();
+    for file in files {
+        let path = Path::new(file);
+        let issues = match load(path)? {
+            Document::Component(c) => validator.validate_component(&c),
+            Document::Collection(c) => validator.validate_collection(&c),
+        };
+        if format == "text" {
+            for issue in issues.iter() {
+                println!("{}: {}", file, issue);
+            }
+            if issues.iter().any(|i| i.severity == IssueSeverity::Error) {
+                println!("{}: validation failed", file);
+            } else {
+                println!("{}: validation was successful", file);
+            }
+        }
+        report.add(file, issues);
+    }
+    let json = match format {
+        "json" => Some(serde_json::to_string_pretty(&report)),
+        "sarif" => Some(serde_json::to_string_pretty(&report.to_sarif())),
+        _ => None,
+    };
+    if let Some(json) = json {
+        println!("{}", json.expect("reports serialize to JSON"));
+    }
+    if report.passed() {
+        Ok(())
+    } else {
+        Err(Error::Invalid)
+    }
+}
+
+fn dump(args: &[String]) -> Result<(), Error> {
+    let (positional, paths) = parse_args(args)?;
+    let id = match positional.as_slice() {
+        [id] => id,
+        _ => return Err(Error::Usage("dump expects a component id".into())),
+    };
+    let documents = catalogs(&paths)?;
+    let components = documents
+        .iter()
+        .flat_map(|d| d.components())
+        .filter(|c| *c.id == **id || *c.id == *format!("{}.desktop", id))
+        .collect::<Vec<&Component>>();
+    if components.is_empty() {
+        return Err(Error::NotFound(id.clone()));
+    }
+    for component in components {
+        let json = serde_json::to_string_pretty(component)
+            .map_err(|e| Error::Json(PathBuf::from("-"), e))?;
+        println!("{}", json);
+    }
+    Ok(())
+}
+
+fn convert(args: &[String]) -> Result<(), Error> {
+    let (input, output) = match args {
+        [input, output] => (Path::new(input), Path::new(output)),
+        _ => {
+            return Err(Error::Usage(
+                "convert expects an input and an output file".into(),
+            ))
+        }
+    };
+    let document = load(input)?;
+    let content = match Format::from_path(output) {
+        Some(Format::Xml) => match &document {
+            Document::Component(c) => c.to_metainfo_xml(),
+            Document::Collection(c) => c.to_xml(),
+        },
+        Some(Format::Json) => match &document {
+            Document::Component(c) => serde_json::to_string_pretty(c),
+            Document::Collection(c) => serde_json::to_string_pretty(c),
+        }
+        .map_err(|e| Error::Json(output.into(), e))?,
+        Some(Format::Yaml) => {
+            return Err(Error::Unsupported(
+                "Writing YAML files is not supported, use XML or JSON".into(),
+            ))
+        }
+        None => {
+            return Err(Error::Unsupported(format!(
+                "Unknown file format: {}",
+                output.display()
+            )))
+        }
+    };
+    write(output, content.as_bytes())
+}
+
+fn write(path: &Path, content: &[u8]) -> Result<(), Error> {
+    if path.extension().is_some_and(|e| e == "gz") {
+        #[cfg(feature = "gzip")]
+        {
+            use std::io::Write;
+            let file = fs::File::create(path).map_err(|e| Error::Io(path.into(), e))?;
+            let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            return encoder
+                .write_all(content)
+                .and_then(|_| encoder.finish().map(|_| ()))
+                .map_err(|e| Error::Io(path.into(), e));
+        }
+        #[cfg(not(feature = "gzip"))]
+        return Err(Error::Unsupported(
+            "Writing gzipped files requires the gzip feature".into(),
+        ));
+    }
+    fs::write(path, content).map_err(|e| Error::Io(path.into(), e))
+}
+
+/// Prints the id, name and summary of a component on a single line.
+fn print_component(component: &Component) {
+    let name = component
+        .name
+        .get_default()
+        .map(|n| n.as_str())
+        .unwrap_or("");
+    let summary = component
+        .summary
+        .as_ref()
+        .and_then(|s| s.get_default())
+        .map(|s| s.as_str())
+        .unwrap_or("");
+    println!("{}\t{} - {}", component.id.0, name, summary);
+}
+
+/// Whether the component matches all the words of `term`.
+fn matches(component: &Component, term: &str) -> bool {
+    let mut haystack = vec![component.id.0.to_lowercase()];
+    haystack.extend(component.name.0.values().map(|n| n.to_lowercase()));
+    if let Some(summary) = &component.summary {
+        haystack.extend(summary.0.values().map(|s| s.to_lowercase()));
+    }
+    if let Some(keywords) = &component.keywords {
+        haystack.extend(keywords.0.values().flatten().map(|k| k.to_lowercase()));
+    }
+    term.to_lowercase()
+        .split_whitespace()
+        .all(|word| haystack.iter().any(|h| h.contains(word)))
+}
+
+fn search(args: &[String]) -> Result<(), Error> {
+    let (positional, paths) = parse_args(args)?;
+    if positional.is_empty() {
+        return Err(Error::Usage("search expects a term".into()));
+    }
+    let term = positional.join(" ");
+    let documents = catalogs(&paths)?;
+    let mut found = false;
+    for component in documents.iter().flat_map(|d| d.components()) {
+        if !matches(component, &term) {
+            continue;
+        }
+        found = true;
+        print_component(component);
+    }
+    if found {
+        Ok(())
+    } else {
+        Err(Error::NotFound(term))
+    }
+}
+
+/// The item described by a `what-provides` kind, `None` for media types which
+/// are not a `Provide`.
+fn provide(kind: &str, item: &str) -> Result<Option<Provide>, Error> {
+    let item = item.to_string();
+    let provide = match kind {
+        "mediatype" | "mimetype" => return Ok(None),
+        "library" | "lib" => Provide::Library(item.into()),
+        "binary" | "bin" => Provide::Binary(item),
+        "font" => Provide::Font(item),
+        "modalias" => Provide::Modalias(item),
+        "firmware-runtime" => Provide::Firmware {
+            kind: FirmwareKind::Runtime,
+            item,
+        },
+        "firmware-flashed" => Provide::Firmware {
+            kind: FirmwareKind::Flashed,
+            item,
+        },
+        "python2" => Provide::Python2(item),
+        "python3" => Provide::Python3(item),
+        "dbus" => Provide::DBus(item),
+        "id" => Provide::Id(item.into()),
+        "codec" => Provide::Codec(item),
+        other => {
+            return Err(Error::Usage(format!(
+                "Unknown provided item kind {}",
+                other
+            )))
+        }
+    };
+    Ok(Some(provide))
+}
+
+fn what_provides(args: &[String]) -> Result<(), Error> {
+    let (positional, paths) = parse_args(args)?;
+    let (kind, item) = match positional.as_slice() {
+        [kind, item] => (kind.as_str(), item.as_str()),
+        _ => {
+            return Err(Error::Usage(
+                "what-provides expects a kind and an item".into(),
+            ))
+        }
+    };
+    let provide = provide(kind, item)?;
+    let documents = catalogs(&paths)?;
+    let mut found = false;
+    for document in documents.iter() {
+        let components = match (document, &provide) {
+            (Document::Collection(c), Some(provide)) => c.find_by_provide(provide),
+            (Document::Collection(c), None) => c.find_by_mimetype(item),
+            (Document::Component(c), Some(provide)) if c.provides.contains(provide) => {
+                vec![c.as_ref()]
+            }
+            (Document::Component(c), None)
+                if c.mimetypes.iter().any(|m| m.eq_ignore_ascii_case(item)) =>
+            {
+                vec![c.as_ref()]
+            }
+            _ => vec![],
+        };
+        for component in components {
+            found = true;
+            print_component(component);
+        }
+    }
+    if found {
+        Ok(())
+    } else {
+        Err(Error::NotFound(format!("{} {}", kind, item)))
+    }
+}
+
+/// Compares two versions, `vercmp <a> <b>` prints the result while
+/// `vercmp <a> <op> <b>` exits with a failure if the comparison doesn't hold.
+fn compare_versions(args: &[String]) -> Result<(), Error> {
+    match args {
+        [a, b] => {
+            let sign = match vercmp(a, b) {
+                Ordering::Less => "<",
+                Ordering::Equal => "==",
+                Ordering::Greater => ">",
+            };
+            println!("{} {} {}", a, sign, b);
+            Ok(())
+        }
+        [a, op, b] => {
+            let ordering = vercmp(a, b);
+            let holds = match op.as_str() {
+                "lt" | "<<" => ordering == Ordering::Less,
+                "le" | "<=" => ordering != Ordering::Greater,
+                "eq" | "==" => ordering == Ordering::Equal,
+                "ne" | "!=" => ordering != Ordering::Equal,
+                "ge" | ">=" => ordering != Ordering::Less,
+                "gt" | ">>" => ordering == Ordering::Greater,
+                other => return Err(Error::Usage(format!("Unknown comparison {}", other))),
+            };
+            if holds {
+                Ok(())
+            } else {
+                Err(Error::Invalid)
+            }
+        }
+        _ => Err(Error::Usage("vercmp expects two versions".into())),
+    }
+}
+
+/// A metadata a component is expected to have and how to check for it.
+type QualityCheck = (&'static str, fn(&Component) -> bool);
+
+/// The checks used to score the metadata quality of a component.
+const QUALITY_CHECKS: &[QualityCheck] = &[
+    ("summary", |c| c.summary.is_some()),
+    ("description", |c| c.description.is_some()),
+    ("icon", |c| !c.icons.is_empty()),
+    ("screenshots", |c| !c.screenshots.is_empty()),
+    ("categories", |c| !c.categories.is_empty()),
+    ("keywords", |c| c.keywords.is_some()),
+    ("releases", |c| !c.releases.is_empty()),
+    ("urls", |c| !c.urls.is_empty()),
+    ("project-license", |c| c.project_license.is_some()),
+    ("developer-name", |c| c.developer_name.is_some()),
+    ("content-rating", |c| c.content_rating.is_some()),
+];
+
+/// The percentage of the quality checks a component passes.
+fn quality_score(component: &Component) -> u32 {
+    let passed = QUALITY_CHECKS
+        .iter()
+        .filter(|(_, check)| check(component))
+        .count();
+    (passed * 100 / QUALITY_CHECKS.len()) as u32
+}
+
+/// The nearest-rank percentile of sorted values.
+fn percentile(sorted: &[u32], percent: usize) -> u32 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (percent * sorted.len()).div_ceil(100);
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn percentage(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
+
+/// Prints the counts sorted from the most to the least frequent.
+fn print_counts(title: &str, counts: &BTreeMap<String, usize>, total: usize) {
+    println!("{}:", title);
+    let mut counts = counts.iter().collect::<Vec<(&String, &usize)>>();
+    counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    for (key, count) in counts {
+        println!(
+            "  {:<24} {:>7} {:>6.1}%",
+            key,
+            count,
+            percentage(*count, total)
+        );
+    }
+    println!();
+}
+
+fn stats(args: &[String]) -> Result<(), Error> {
+    let (positional, mut paths) = parse_args(args)?;
+    paths.extend(positional.iter().map(PathBuf::from));
+    let documents = catalogs(&paths)?;
+
+    let mut kinds = BTreeMap::new();
+    let mut categories = BTreeMap::new();
+    let mut origins = BTreeMap::new();
+    let mut locales = BTreeMap::new();
+    let mut missing = BTreeMap::new();
+    let mut scores = Vec::new();
+    for document in documents.iter() {
+        let collection_origin = match document {
+            Document::Collection(c) => c.origin.as_deref(),
+            Document::Component(_) => None,
+        };
+        for component in document.components() {
+            *kinds.entry(component.kind.to_string()).or_insert(0) += 1;
+            for category in component.categories.iter() {
+                let category = match category {
+                    Category::Unknown(name) => name.clone(),
+                    category => category.to_string(),
+                };
+                *categories.entry(category).or_insert(0) += 1;
+            }
+            let origin = component
+                .origin
+                .as_deref()
+                .or(collection_origin)
+                .unwrap_or("unknown");
+            *origins.entry(origin.to_string()).or_insert(0) += 1;
+            for locale in component.name.0.keys().filter(|l| *l != "C") {
+                *locales.entry(locale.clone()).or_insert(0) += 1;
+            }
+            for (check, _) in QUALITY_CHECKS.iter().filter(|(_, check)| !check(component)) {
+                *missing.entry(check.to_string()).or_insert(0) += 1;
+            }
+            scores.push(quality_score(component));
+        }
+    }
+    scores.sort_unstable();
+    let total = scores.len();
+
+    println!("Components: {}\n", total);
+    print_counts("Kinds", &kinds, total);
+    print_counts("Categories", &categories, total);
+    print_counts("Origins", &origins, total);
+    print_counts("Translated names", &locales, total);
+    print_counts("Missing metadata", &missing, total);
+    println!("Metadata quality:");
+    for percent in [10, 25, 50, 75, 90].iter() {
+        println!("  p{:<23} {:>6}%", percent, percentile(&scores, *percent));
+    }
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<(), Error> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return Err(Error::Usage("No command given".into())),
+    };
+    match command {
+        "validate" => validate(rest),
+        "dump" => dump(rest),
+        "convert" => convert(rest),
+        "search" => search(rest),
+        "what-provides" => what_provides(rest),
+        "vercmp" => compare_versions(rest),
+        "stats" => stats(rest),
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(Error::Usage(format!("Unknown command {}", other))),
+    }
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if let Err(e) = run(&args) {
+        match e {
+            Error::Invalid => (),
+            ref e => eprintln!("{}", e),
+        }
+        process::exit(e.exit_code());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn catalog_options() {
+        let (positional, catalogs) = parse_args(&args(&[
+            "gnome",
+            "--catalog",
+            "a.xml",
+            "-c",
+            "b.yml",
+            "--catalog=c.json",
+        ]))
+        .unwrap();
+        assert_eq!(positional, vec!["gnome"]);
+        assert_eq!(
+            catalogs,
+            vec![
+                PathBuf::from("a.xml"),
+                PathBuf::from("b.yml"),
+                PathBuf::from("c.json")
+            ]
+        );
+        assert!(parse_args(&args(&["--catalog"])).is_err());
+        assert!(parse_args(&args(&["--unknown"])).is_err());
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(Format::from_path(Path::new("a.xml.gz")), Some(Format::Xml));
+        assert_eq!(Format::from_path(Path::new("a.yml")), Some(Format::Yaml));
+        assert_eq!(Format::from_path(Path::new("a.json")), Some(Format::Json));
+        assert_eq!(Format::from_path(Path::new("a.txt")), None);
+    }
+
+    #[test]
+    fn convert_documents() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let output = |name: &str| dir.join(name).to_string_lossy().into_owned();
+
+        let input = "./tests/collections/spec_example.xml".to_string();
+        convert(&[input.clone(), output("catalog.xml")]).unwrap();
+        let (original, converted) = match (load(Path::new(&input)), load(&dir.join("catalog.xml")))
+        {
+            (Ok(Document::Collection(a)), Ok(Document::Collection(b))) => (a, b),
+            _ => panic!("Failed to load the collections"),
+        };
+        assert_eq!(original.components, converted.components);
+
+        let input = "./tests/app-org.gnome.design.Contrast.xml".to_string();
+        convert(&[input, output("metainfo.xml")]).unwrap();
+        assert!(matches!(
+            load(&dir.join("metainfo.xml")),
+            Ok(Document::Component(_))
+        ));
+
+        assert!(matches!(
+            convert(&[output("catalog.xml"), output("catalog.yml")]),
+            Err(Error::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn search_components() {
+        let collection = match load(Path::new("./tests/collections/gnome-apps.xml")) {
+            Ok(Document::Collection(c)) => c,
+            _ => panic!("Failed to load the collection"),
+        };
+        let found = collection
+            .components
+            .iter()
+            .filter(|c| matches(c, "gnome weather"))
+            .count();
+        assert!(found > 0);
+        assert!(!collection
+            .components
+            .iter()
+            .any(|c| matches(c, "no such thing")));
+    }
+
+    #[test]
+    fn provided_items() {
+        assert_eq!(
+            provide("binary", "firefox").unwrap(),
+            Some(Provide::Binary("firefox".into()))
+        );
+        assert_eq!(provide("mediatype", "text/html").unwrap(), None);
+        assert!(provide("spaceship", "firefox").is_err());
+    }
+
+    #[test]
+    fn version_comparisons() {
+        let args = |a: &[&str]| a.iter().map(|a| a.to_string()).collect::<Vec<String>>();
+        assert!(compare_versions(&args(&["1.2~rc1", "lt", "1.2"])).is_ok());
+        assert!(compare_versions(&args(&["1.2~rc1", "ge", "1.2"])).is_err());
+        assert!(compare_versions(&args(&["1.2", "eq", "1.02"])).is_ok());
+        assert!(compare_versions(&args(&["1.2", "~", "1.2"])).is_err());
+    }
+
+    #[test]
+    fn quality_percentiles() {
+        let scores = [10, 20, 30, 40, 50, 60, 70, 80, 90, 100];
+        assert_eq!(percentile(&scores, 10), 10);
+        assert_eq!(percentile(&scores, 25), 30);
+        assert_eq!(percentile(&scores, 50), 50);
+        assert_eq!(percentile(&scores, 90), 90);
+        assert_eq!(percentile(&[], 50), 0);
+
+        let collection = match load(Path::new("./tests/collections/spec_example.xml")) {
+            Ok(Document::Collection(c)) => c,
+            _ => panic!("Failed to load the collection"),
+        };
+        let firefox = &collection.components[0];
+        assert!(quality_score(firefox) > 0);
+        assert!(quality_score(firefox) < 100);
+    }
+}
@@ -0,0 +1,176 @@
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// The most threads the loaders run on, whatever the number of CPUs.
+const MAX_THREADS: usize = 4;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The threads the loaders run on, started on the first call to `run`.
+///
+/// The closures are queued and picked up by the first idle thread, so loading
+/// many catalogs at once doesn't start as many threads.
+struct Pool {
+    jobs: Mutex<Sender<Job>>,
+}
+
+impl Pool {
+    fn get() -> &'static Pool {
+        static POOL: OnceLock<Pool> = OnceLock::new();
+        POOL.get_or_init(|| {
+            let (jobs, queue) = mpsc::channel::<Job>();
+            let queue = Arc::new(Mutex::new(queue));
+            let threads = thread::available_parallelism()
+                .map_or(1, |n| n.get())
+                .min(MAX_THREADS);
+            for n in 0..threads {
+                let queue = queue.clone();
+                thread::Builder::new()
+                    .name(format!("appstream-{}", n))
+                    .spawn(move || work(&queue))
+                    .expect("failed to start a loader thread");
+            }
+            Pool {
+                jobs: Mutex::new(jobs),
+            }
+        })
+    }
+}
+
+/// Runs the queued jobs, one at a time.
+fn work(queue: &Mutex<Receiver<Job>>) {
+    loop {
+        // The lock is released before running the job.
+        let job = queue.lock().unwrap().recv();
+        match job {
+            Ok(job) => job(),
+            Err(_) => return,
+        }
+    }
+}
+
+struct Shared<T> {
+    result: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// A future resolving to the result of a closure run on the threads of the
+/// loaders.
+///
+/// It doesn't depend on any runtime, the thread wakes up whichever executor
+/// polled the future last. Panics of the closure are resumed in the task that
+/// awaits the future.
+pub(crate) struct Blocking<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+/// Queues `f` to run on the threads of the loaders, returns a future resolving
+/// to its result.
+pub(crate) fn run<T, F>(f: F) -> Blocking<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let shared = Arc::new(Mutex::new(Shared {
+        result: None,
+        waker: None,
+    }));
+    let thread_shared = shared.clone();
+    let job = Box::new(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        let waker = {
+            let mut shared = thread_shared.lock().unwrap();
+            shared.result = Some(result);
+            shared.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    });
+    Pool::get()
+        .jobs
+        .lock()
+        .unwrap()
+        .send(job)
+        .expect("the loader threads never stop");
+    Blocking { shared }
+}
+
+impl<T> Future for Blocking<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.result.take() {
+            Some(Ok(value)) => Poll::Ready(value),
+            Some(Err(payload)) => {
+                drop(shared);
+                panic::resume_unwind(payload)
+            }
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+/// Polls `future` to completion on the current thread.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    use std::task::Wake;
+
+    struct Unpark(thread::Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn bounded_threads() {
+        static RUNNING: AtomicUsize = AtomicUsize::new(0);
+        static MOST: AtomicUsize = AtomicUsize::new(0);
+        let futures = (0..4 * MAX_THREADS)
+            .map(|n| {
+                run(move || {
+                    let running = RUNNING.fetch_add(1, Ordering::SeqCst) + 1;
+                    MOST.fetch_max(running, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(10));
+                    RUNNING.fetch_sub(1, Ordering::SeqCst);
+                    n
+                })
+            })
+            .collect::<Vec<_>>();
+        let results = futures.into_iter().map(block_on).collect::<Vec<_>>();
+        assert_eq!(results, (0..4 * MAX_THREADS).collect::<Vec<_>>());
+        assert!(MOST.load(Ordering::SeqCst) <= MAX_THREADS);
+
+        let panicked = panic::catch_unwind(|| block_on(run(|| panic!("boom"))));
+        assert!(panicked.is_err());
+        assert_eq!(block_on(run(|| 1)), 1);
+    }
+}
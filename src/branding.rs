@@ -0,0 +1,34 @@
+use super::enums::ColorScheme;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+/// A primary color of the brand of the component, the software centers use it
+/// as the background of its banner.
+/// See [\<branding\/\>](https://www.freedesktop.org/software/appstream/docs/chap-Metadata.html#tag-branding).
+pub struct BrandingColor {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The color scheme the color is meant for, any of them if unset.
+    pub scheme_preference: Option<ColorScheme>,
+    /// The color, as a hex code like `#ff00ff`.
+    pub value: String,
+}
+
+impl BrandingColor {
+    /// Creates a color for any color scheme.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The color, like `#ff00ff`.
+    pub fn new(value: &str) -> Self {
+        Self {
+            scheme_preference: None,
+            value: value.to_string(),
+        }
+    }
+
+    /// Sets the color scheme the color is meant for.
+    pub fn scheme_preference(mut self, scheme: ColorScheme) -> Self {
+        self.scheme_preference = Some(scheme);
+        self
+    }
+}
@@ -1,12 +1,17 @@
 use super::collection::Collection;
 use super::component::Component;
 use super::enums::*;
+use super::firmware::FirmwareRequirement;
+use super::interner;
+#[cfg(feature = "raw")]
+use super::raw::RawNode;
 use super::{
-    AppId, Artifact, ContentRating, Image, Language, License, MarkupTranslatableString, Release,
-    Screenshot, TranslatableList, TranslatableString, Video,
+    AppId, Artifact, BrandingColor, ContentRating, Image, Issue, Language, License,
+    MarkupTranslatableString, Release, Screenshot, Timestamp, TranslatableList, TranslatableString,
+    Video,
 };
-use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use url::Url;
 
 #[derive(Default, Debug)]
@@ -90,6 +95,8 @@ pub struct CollectionBuilder {
     pub components: Vec<Component>,
     /// The targeted CPU architecture of the collection.
     pub architecture: Option<String>,
+    /// When the collection was generated.
+    pub timestamp: Option<Timestamp>,
 }
 
 #[allow(dead_code)]
@@ -106,6 +113,7 @@ impl CollectionBuilder {
             media_base_url: None,
             components: vec![],
             architecture: None,
+            timestamp: None,
         }
     }
 
@@ -127,6 +135,12 @@ impl CollectionBuilder {
         self
     }
 
+    /// Sets when the collection was generated.
+    pub fn timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
     /// Adds a new component to the collection.
     pub fn component(mut self, component: Component) -> Self {
         self.components.push(component);
@@ -141,6 +155,8 @@ impl CollectionBuilder {
             media_base_url: self.media_base_url,
             components: self.components,
             architecture: self.architecture,
+            timestamp: self.timestamp,
+            indices: Default::default(),
         }
     }
 }
@@ -149,10 +165,14 @@ impl CollectionBuilder {
 pub struct ComponentBuilder {
     /// The component type.
     pub kind: ComponentKind,
+    /// How the component is merged into the one of the same id.
+    pub merge: Option<MergeKind>,
+    /// The priority of the component over the ones of the same id.
+    pub priority: Option<i32>,
     /// A unique identifier of the component.
     pub id: Option<AppId>,
     /// The origin of the collection. Could be something like `flathub`.
-    pub origin: Option<String>,
+    pub origin: Option<Arc<str>>,
     /// The component name.
     pub name: Option<TranslatableString>,
     /// A short summary.
@@ -164,7 +184,7 @@ pub struct ComponentBuilder {
     /// The license of the metainfo.
     pub metadata_license: Option<License>,
     /// The project group of the component.
-    pub project_group: Option<String>,
+    pub project_group: Option<Arc<str>>,
     /// Indicate for which desktop environment the component is essential for its functionality.
     pub compulsory_for_desktop: Option<String>,
     /// The various AppId that the current component extends.
@@ -195,6 +215,8 @@ pub struct ComponentBuilder {
     pub mimetypes: Vec<String>,
     /// Defines the "awesomeness" of a component.
     pub kudos: Vec<Kudo>,
+    /// Branding colors
+    pub branding: Vec<BrandingColor>,
     /// A list of keywords, to help the user find the component easily.
     pub keywords: Option<TranslatableList>,
     /// Specifies the age rating of the component.
@@ -207,10 +229,19 @@ pub struct ComponentBuilder {
     pub source_pkgname: Option<String>,
     /// Suggested components.
     pub suggestions: Vec<AppId>,
+    /// Recommended components.
+    pub recommendations: Vec<AppId>,
+    /// Replaced components.
+    pub replaces: Vec<AppId>,
     /// Required components.
     pub requirements: Vec<AppId>,
+    /// Required firmware versions.
+    pub firmware_requirements: Vec<FirmwareRequirement>,
     /// Custom metadata
-    pub metadata: HashMap<String, Option<String>>,
+    pub metadata: BTreeMap<String, Option<String>>,
+    #[cfg(feature = "raw")]
+    /// The unparsed child tags of the component.
+    pub unknown_nodes: Vec<RawNode>,
 }
 
 #[allow(dead_code)]
@@ -223,7 +254,7 @@ impl ComponentBuilder {
 
     /// Sets the origin of the collection.
     pub fn origin(mut self, origin: &str) -> Self {
-        self.origin = Some(origin.to_string());
+        self.origin = Some(interner::intern(origin));
         self
     }
 
@@ -245,6 +276,18 @@ impl ComponentBuilder {
         self
     }
 
+    /// Makes the component a merge component.
+    pub fn merge(mut self, merge: MergeKind) -> Self {
+        self.merge = Some(merge);
+        self
+    }
+
+    /// Sets the priority of the component over the ones of the same id.
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
     /// Sets the developer name.
     pub fn developer_name(mut self, developer_name: TranslatableString) -> Self {
         if !developer_name.is_empty() {
@@ -297,7 +340,7 @@ impl ComponentBuilder {
     /// Sets the upstream umberall.
     /// Known values includes: GNOME, KDE, XFCE, MATE, LXDE.
     pub fn project_group(mut self, group: &str) -> Self {
-        self.project_group = Some(group.to_string());
+        self.project_group = Some(interner::intern(group));
         self
     }
 
@@ -307,6 +350,18 @@ impl ComponentBuilder {
         self
     }
 
+    /// Recommends a component to be installed.
+    pub fn recommend(mut self, id: AppId) -> Self {
+        self.recommendations.push(id);
+        self
+    }
+
+    /// Adds a component replaced by this one.
+    pub fn replace(mut self, id: AppId) -> Self {
+        self.replaces.push(id);
+        self
+    }
+
     /// Adds a Web URL to the component.
     pub fn url(mut self, url: ProjectUrl) -> Self {
         self.urls.push(url);
@@ -331,6 +386,12 @@ impl ComponentBuilder {
         self
     }
 
+    /// Adds a branding color.
+    pub fn branding_color(mut self, color: BrandingColor) -> Self {
+        self.branding.push(color);
+        self
+    }
+
     /// Adds a translation context to the component.
     pub fn translation(mut self, translation: Translation) -> Self {
         self.translations.push(translation);
@@ -409,47 +470,68 @@ impl ComponentBuilder {
         self
     }
 
+    /// Adds a new firmware requirement to the component.
+    pub fn require_firmware(mut self, requirement: FirmwareRequirement) -> Self {
+        self.firmware_requirements.push(requirement);
+        self
+    }
+
     /// Adds a new metadata (key, value) to the component.
     pub fn metadata(mut self, key: String, val: Option<String>) -> Self {
         self.metadata.insert(key, val);
         self
     }
 
+    #[cfg(feature = "raw")]
+    /// Keeps a child tag of the component the crate doesn't model.
+    pub fn unknown_node(mut self, node: RawNode) -> Self {
+        self.unknown_nodes.push(node);
+        self
+    }
+
     /// Constructs a `Component`.
     pub fn build(self) -> Component {
         Component {
             kind: self.kind,
+            merge: self.merge,
+            priority: self.priority,
             id: self.id.expect("An 'id' is required"),
             origin: self.origin,
             name: self.name.expect("A 'name' is required"),
             summary: self.summary,
-            description: self.description,
+            description: self.description.map(Arc::new),
             project_license: self.project_license,
             metadata_license: self.metadata_license,
             project_group: self.project_group,
             compulsory_for_desktop: self.compulsory_for_desktop,
             extends: self.extends,
             icons: self.icons,
-            screenshots: self.screenshots,
+            screenshots: Arc::new(self.screenshots),
             urls: self.urls,
-            developer_name: self.developer_name,
+            developer_name: self.developer_name.map(Arc::new),
             update_contact: self.update_contact,
             categories: self.categories,
             launchables: self.launchables,
             pkgname: self.pkgname,
             bundles: self.bundles,
-            releases: self.releases,
-            languages: self.languages,
+            releases: Arc::new(self.releases),
+            languages: Arc::new(self.languages),
             mimetypes: self.mimetypes,
             kudos: self.kudos,
-            keywords: self.keywords,
-            content_rating: self.content_rating,
+            branding: self.branding,
+            keywords: self.keywords.map(Arc::new),
+            content_rating: self.content_rating.map(Arc::new),
             provides: self.provides,
             translations: self.translations,
             source_pkgname: self.source_pkgname,
             suggestions: self.suggestions,
+            recommendations: self.recommendations,
+            replaces: self.replaces,
             requirements: self.requirements,
+            firmware_requirements: self.firmware_requirements,
             metadata: self.metadata,
+            #[cfg(feature = "raw")]
+            unknown_nodes: self.unknown_nodes,
         }
     }
 }
@@ -537,7 +619,7 @@ pub struct LanguageBuilder {
     /// A percentage represnting how complete the language support is.
     pub percentage: Option<u32>,
     /// The locale identifier, e.g fr_BE.
-    pub locale: String,
+    pub locale: Arc<str>,
 }
 
 #[allow(dead_code)]
@@ -550,7 +632,7 @@ impl LanguageBuilder {
     pub fn new(locale: &str) -> Self {
         Self {
             percentage: None,
-            locale: locale.to_string(),
+            locale: interner::intern(locale),
         }
     }
 
@@ -573,9 +655,9 @@ impl LanguageBuilder {
 /// A helper to build a `Release`.
 pub struct ReleaseBuilder {
     /// The release date.
-    pub date: Option<DateTime<Utc>>,
+    pub date: Option<Timestamp>,
     /// The end-of-life date of the release.
-    pub date_eol: Option<DateTime<Utc>>,
+    pub date_eol: Option<Timestamp>,
     /// The release description.
     pub description: Option<MarkupTranslatableString>,
     /// The version of the release.
@@ -590,6 +672,8 @@ pub struct ReleaseBuilder {
     pub artifacts: Vec<Artifact>,
     /// A web page containing the release changelog.
     pub url: Option<Url>,
+    /// The issues fixed by the release.
+    pub issues: Vec<Issue>,
 }
 
 #[allow(dead_code)]
@@ -610,6 +694,7 @@ impl ReleaseBuilder {
             urgency: ReleaseUrgency::Medium,
             artifacts: vec![],
             url: None,
+            issues: vec![],
         }
     }
 
@@ -634,14 +719,14 @@ impl ReleaseBuilder {
     }
 
     /// Sets the release date.
-    pub fn date(mut self, date: DateTime<Utc>) -> Self {
-        self.date = Some(date);
+    pub fn date(mut self, date: impl Into<Timestamp>) -> Self {
+        self.date = Some(date.into());
         self
     }
 
     /// Sets the End-of-life release date.
-    pub fn date_eol(mut self, date_eol: DateTime<Utc>) -> Self {
-        self.date_eol = Some(date_eol);
+    pub fn date_eol(mut self, date_eol: impl Into<Timestamp>) -> Self {
+        self.date_eol = Some(date_eol.into());
         self
     }
 
@@ -669,6 +754,12 @@ impl ReleaseBuilder {
         self
     }
 
+    /// Adds an issue fixed by the release.
+    pub fn issue(mut self, issue: Issue) -> Self {
+        self.issues.push(issue);
+        self
+    }
+
     /// Constructs a `Release`.
     pub fn build(self) -> Release {
         let kind = self.kind.unwrap_or_default();
@@ -681,7 +772,8 @@ impl ReleaseBuilder {
             sizes: self.sizes,
             urgency: self.urgency,
             artifacts: self.artifacts,
-            url: self.url,
+            url: self.url.map(Box::new),
+            issues: self.issues,
         }
     }
 }
@@ -714,6 +806,8 @@ impl ReleaseBuilder {
 pub struct ScreenshotBuilder {
     /// Whether the screenhot is the default one or not.
     pub is_default: Option<bool>,
+    /// The desktop environment the screenshot was taken in.
+    pub environment: Option<String>,
     /// A translatable short description of the screenshot.
     pub caption: Option<TranslatableString>,
     /// The various images on that screenshot.
@@ -738,6 +832,12 @@ impl ScreenshotBuilder {
         self
     }
 
+    /// Sets the desktop environment the screenshot was taken in, like `gnome:dark`.
+    pub fn environment(mut self, environment: &str) -> Self {
+        self.environment = Some(environment.to_string());
+        self
+    }
+
     /// Adds a new `Image` to the `Screenshot`.
     pub fn image(mut self, image: Image) -> Self {
         self.images.push(image);
@@ -769,6 +869,7 @@ impl ScreenshotBuilder {
             images: self.images,
             videos: self.videos,
             is_default: self.is_default.unwrap_or(true),
+            environment: self.environment,
         }
     }
 }
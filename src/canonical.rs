@@ -0,0 +1,411 @@
+use serde::ser::{self, Serialize};
+use std::fmt;
+
+/// Encodes `value` canonically, for content hashes.
+///
+/// The encoding only depends on the data: the entries of maps and the fields of
+/// structs are sorted, values are tagged and length-prefixed so different values
+/// can't share an encoding, and integers are encoded the same whatever their type.
+pub(crate) fn to_bytes<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    value.serialize(Serializer)
+}
+
+#[derive(Debug)]
+pub(crate) struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+fn tagged(tag: u8, data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + 9);
+    out.push(tag);
+    out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    out.extend_from_slice(data);
+    out
+}
+
+fn list(items: Vec<Vec<u8>>) -> Vec<u8> {
+    tagged(b'l', &items.concat())
+}
+
+fn map(mut entries: Vec<(Vec<u8>, Vec<u8>)>) -> Vec<u8> {
+    entries.sort();
+    let data = entries
+        .into_iter()
+        .flat_map(|(k, v)| k.into_iter().chain(v))
+        .collect::<Vec<u8>>();
+    tagged(b'm', &data)
+}
+
+fn string(s: &str) -> Vec<u8> {
+    tagged(b's', s.as_bytes())
+}
+
+fn integer(i: i128) -> Vec<u8> {
+    tagged(b'i', i.to_string().as_bytes())
+}
+
+struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Vec<u8>;
+    type Error = Error;
+    type SerializeSeq = Seq;
+    type SerializeTuple = Seq;
+    type SerializeTupleStruct = Seq;
+    type SerializeTupleVariant = Seq;
+    type SerializeMap = Map;
+    type SerializeStruct = Map;
+    type SerializeStructVariant = Map;
+
+    fn serialize_bool(self, v: bool) -> Result<Vec<u8>, Error> {
+        Ok(tagged(b'b', &[v as u8]))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Vec<u8>, Error> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Vec<u8>, Error> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Vec<u8>, Error> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Vec<u8>, Error> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Vec<u8>, Error> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Vec<u8>, Error> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Vec<u8>, Error> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Vec<u8>, Error> {
+        Ok(integer(v.into()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Vec<u8>, Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Vec<u8>, Error> {
+        Ok(tagged(b'f', v.to_string().as_bytes()))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Vec<u8>, Error> {
+        Ok(string(v.encode_utf8(&mut [0; 4])))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Vec<u8>, Error> {
+        Ok(string(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(tagged(b'y', v))
+    }
+
+    fn serialize_none(self) -> Result<Vec<u8>, Error> {
+        Ok(tagged(b'n', &[]))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<u8>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Vec<u8>, Error> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Vec<u8>, Error> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Vec<u8>, Error> {
+        Ok(string(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Vec<u8>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Vec<u8>, Error> {
+        Ok(map(vec![(string(variant), to_bytes(value)?)]))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Seq, Error> {
+        Ok(Seq {
+            variant: None,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Seq, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Seq, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Seq, Error> {
+        Ok(Seq {
+            variant: Some(variant),
+            items: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Map, Error> {
+        Ok(Map {
+            variant: None,
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Map, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Map, Error> {
+        Ok(Map {
+            variant: Some(variant),
+            entries: Vec::with_capacity(len),
+            key: None,
+        })
+    }
+}
+
+struct Seq {
+    variant: Option<&'static str>,
+    items: Vec<Vec<u8>>,
+}
+
+impl Seq {
+    fn finish(self) -> Vec<u8> {
+        let items = list(self.items);
+        match self.variant {
+            Some(variant) => map(vec![(string(variant), items)]),
+            None => items,
+        }
+    }
+}
+
+impl ser::SerializeSeq for Seq {
+    type Ok = Vec<u8>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(to_bytes(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Vec<u8>, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for Seq {
+    type Ok = Vec<u8>;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Vec<u8>, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for Seq {
+    type Ok = Vec<u8>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Vec<u8>, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for Seq {
+    type Ok = Vec<u8>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Vec<u8>, Error> {
+        Ok(self.finish())
+    }
+}
+
+struct Map {
+    variant: Option<&'static str>,
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+    key: Option<Vec<u8>>,
+}
+
+impl Map {
+    fn finish(self) -> Vec<u8> {
+        let entries = map(self.entries);
+        match self.variant {
+            Some(variant) => map(vec![(string(variant), entries)]),
+            None => entries,
+        }
+    }
+}
+
+impl ser::SerializeMap for Map {
+    type Ok = Vec<u8>;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(to_bytes(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error("value without a key".into()))?;
+        self.entries.push((key, to_bytes(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Vec<u8>, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStruct for Map {
+    type Ok = Vec<u8>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.entries.push((string(key), to_bytes(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Vec<u8>, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for Map {
+    type Ok = Vec<u8>;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<Vec<u8>, Error> {
+        Ok(self.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use std::collections::{BTreeMap, HashMap};
+    use std::error::Error;
+
+    #[derive(Serialize)]
+    struct Ab {
+        a: u32,
+        b: Option<String>,
+    }
+
+    #[derive(Serialize)]
+    struct Ba {
+        b: Option<&'static str>,
+        a: u64,
+    }
+
+    #[test]
+    fn canonical_encoding() -> Result<(), Box<dyn Error>> {
+        assert_eq!(
+            to_bytes(&Ab {
+                a: 1,
+                b: Some("x".into())
+            })?,
+            to_bytes(&Ba { b: Some("x"), a: 1 })?
+        );
+
+        let hashed = (0..20)
+            .map(|i| (i.to_string(), i))
+            .collect::<HashMap<String, u8>>();
+        let sorted = hashed
+            .iter()
+            .map(|(k, v)| (k.clone(), *v))
+            .collect::<BTreeMap<String, u8>>();
+        assert_eq!(to_bytes(&hashed)?, to_bytes(&sorted)?);
+
+        assert_ne!(to_bytes(&vec!["ab", "c"])?, to_bytes(&vec!["a", "bc"])?);
+        assert_ne!(to_bytes(&vec![1, 2])?, to_bytes(&vec![2, 1])?);
+        assert_ne!(to_bytes(&"1")?, to_bytes(&1)?);
+        Ok(())
+    }
+}
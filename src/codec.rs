@@ -0,0 +1,285 @@
+use super::enums::Provide;
+use super::error::ParseError;
+use super::Component;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// What a GStreamer plugin provides for a media type or a protocol.
+pub enum CodecKind {
+    /// Decodes a media type, like `decoder-video/x-h264`.
+    Decoder,
+    /// Encodes a media type, like `encoder-audio/mpeg`.
+    Encoder,
+    /// A named element, like `element-faac`.
+    Element,
+    /// Reads from a URI scheme, like `urisource-http`.
+    UriSource,
+    /// Writes to a URI scheme, like `urisink-file`.
+    UriSink,
+}
+
+impl CodecKind {
+    const PREFIXES: [(&'static str, CodecKind); 5] = [
+        ("decoder-", CodecKind::Decoder),
+        ("encoder-", CodecKind::Encoder),
+        ("element-", CodecKind::Element),
+        ("urisource-", CodecKind::UriSource),
+        ("urisink-", CodecKind::UriSink),
+    ];
+
+    /// Splits the kind prefix off a codec string.
+    fn strip(s: &str) -> Option<(Self, &str)> {
+        Self::PREFIXES
+            .iter()
+            .find_map(|(prefix, kind)| s.strip_prefix(prefix).map(|rest| (*kind, rest)))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A GStreamer codec, as provided by a codec component or requested by a
+/// "missing codec" installer.
+///
+/// The codec strings are the ones of the `<codec>` provides, like
+/// `decoder-audio/mpeg, mpegversion=(int){ 4, 2 }`, the details of the
+/// GStreamer missing plugin messages, like `gstreamer|1.0|totem|H.264 decoder|decoder-video/x-h264`,
+/// the RPM style of PackageKit, like `gstreamer1(decoder-video/x-h264)(stream-format=avc)()(64bit)`,
+/// bare caps like `audio/mpeg, mpegversion=4`, and bare element names.
+///
+/// # Example
+///
+/// ```
+/// use appstream::codec::{Codec, CodecKind};
+///
+/// let codec: Codec = "decoder-audio/mpeg, mpegversion=(int){ 4, 2 }".parse().unwrap();
+/// assert_eq!(codec.kind, Some(CodecKind::Decoder));
+/// assert_eq!(codec.name, "audio/mpeg");
+/// assert_eq!(codec.fields["mpegversion"], vec!["4", "2"]);
+///
+/// let request: Codec = "gstreamer1(decoder-audio/mpeg)(mpegversion=4)()(64bit)".parse().unwrap();
+/// assert!(codec.satisfies(&request));
+/// ```
+pub struct Codec {
+    /// What is provided, `None` for bare caps which match the decoders and the
+    /// encoders.
+    pub kind: Option<CodecKind>,
+    /// The media type, like `audio/mpeg`, the element name or the URI scheme.
+    pub name: String,
+    /// The fields of the caps, with their possible values.
+    pub fields: BTreeMap<String, Vec<String>>,
+}
+
+impl Codec {
+    /// The codecs a component provides.
+    ///
+    /// The fields of the caps of a codec may be split in following `<codec>`
+    /// provides, they are grouped with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - The codec component.
+    pub fn from_component(component: &Component) -> Vec<Self> {
+        let mut codecs: Vec<Self> = Vec::new();
+        for provide in component.provides.iter() {
+            let value = match provide {
+                Provide::Codec(value) => value.trim(),
+                _ => continue,
+            };
+            let head = value.split(',').next().unwrap_or_default();
+            if CodecKind::strip(value).is_none() && head.contains('=') {
+                if let Some(codec) = codecs.last_mut() {
+                    codec.add_fields(value);
+                    continue;
+                }
+            }
+            if let Ok(codec) = value.parse() {
+                codecs.push(codec);
+            }
+        }
+        codecs
+    }
+
+    /// Whether the provided codec satisfies a requested one.
+    ///
+    /// The kinds and the names have to be the same, a request without a kind
+    /// matching the decoders and encoders. The fields both codecs have need a
+    /// common value, the other ones are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The requested codec.
+    pub fn satisfies(&self, request: &Codec) -> bool {
+        let kind = match (self.kind, request.kind) {
+            (Some(provided), Some(requested)) => provided == requested,
+            (Some(CodecKind::Decoder), None) | (Some(CodecKind::Encoder), None) => true,
+            (provided, requested) => provided == requested,
+        };
+        kind && self.name.eq_ignore_ascii_case(&request.name)
+            && request.fields.iter().all(|(field, values)| {
+                self.fields
+                    .get(field)
+                    .is_none_or(|provided| provided.iter().any(|v| values.contains(v)))
+            })
+    }
+
+    /// Parses the `field=(type)value` parts of caps, separated by commas.
+    fn add_fields(&mut self, caps: &str) {
+        for field in split_fields(caps) {
+            if let Some((key, value)) = field.split_once('=') {
+                self.fields
+                    .entry(key.trim().to_string())
+                    .or_default()
+                    .extend(parse_values(value));
+            }
+        }
+    }
+}
+
+impl FromStr for Codec {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // The detail of a GStreamer missing plugin message.
+        let s = match s.strip_prefix("gstreamer|") {
+            Some(detail) => detail.rsplit('|').next().unwrap_or(detail).trim(),
+            None => s,
+        };
+
+        let (caps, rpm_fields) = match s
+            .strip_prefix("gstreamer1(")
+            .or_else(|| s.strip_prefix("gstreamer0.10("))
+        {
+            Some(rest) => {
+                let mut parts = rest.split(")(");
+                let caps = parts.next().unwrap_or_default();
+                let fields = parts
+                    .map(|p| p.trim_end_matches(')'))
+                    .filter(|p| p.contains('='))
+                    .collect::<Vec<_>>();
+                (caps.trim_end_matches(')'), fields)
+            }
+            None => (s, vec![]),
+        };
+
+        let (name, fields) = match caps.split_once(',') {
+            Some((name, fields)) => (name.trim(), fields),
+            None => (caps.trim(), ""),
+        };
+        let (kind, name) = match CodecKind::strip(name) {
+            Some((kind, name)) => (Some(kind), name),
+            // A bare name without a media type is an element.
+            None if !name.contains('/') => (Some(CodecKind::Element), name),
+            None => (None, name),
+        };
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(ParseError::invalid_value(s, "$value", "codec"));
+        }
+
+        let mut codec = Self {
+            kind,
+            name: name.to_string(),
+            fields: BTreeMap::new(),
+        };
+        codec.add_fields(fields);
+        for field in rpm_fields {
+            codec.add_fields(field);
+        }
+        Ok(codec)
+    }
+}
+
+/// Splits caps on the commas which aren't part of a list of values.
+fn split_fields(caps: &str) -> Vec<&str> {
+    let mut fields = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in caps.char_indices() {
+        match c {
+            '{' | '[' | '<' => depth += 1,
+            '}' | ']' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                fields.push(&caps[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    fields.push(&caps[start..]);
+    fields
+        .into_iter()
+        .filter(|f| !f.trim().is_empty())
+        .collect()
+}
+
+/// Parses a value of caps, like `(int){ 4, 2 }`, without its type.
+fn parse_values(value: &str) -> Vec<String> {
+    let value = value.trim();
+    let value = match value.strip_prefix('(') {
+        Some(rest) => rest.split_once(')').map_or(rest, |(_, v)| v).trim(),
+        None => value,
+    };
+    value
+        .trim_start_matches(&['{', '[', '<'][..])
+        .trim_end_matches(&['}', ']', '>'][..])
+        .split(',')
+        .map(|v| v.trim().trim_matches('"').to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::{CollectionBuilder, ComponentBuilder};
+    use crate::enums::ComponentKind;
+    use crate::TranslatableString;
+    use std::error::Error;
+
+    #[test]
+    fn codecs() -> Result<(), Box<dyn Error>> {
+        let component = Component::from_path("./tests/codec.xml".into())?;
+        let codecs = Codec::from_component(&component);
+        assert_eq!(codecs.len(), 8);
+        assert_eq!(codecs[0].kind, Some(CodecKind::Encoder));
+        assert_eq!(codecs[0].name, "audio/mpeg");
+        assert_eq!(codecs[0].fields["mpegversion"], vec!["4", "2"]);
+        assert_eq!(codecs[0].fields["stream-format"], vec!["adts", "raw"]);
+        assert_eq!(codecs[1].fields["systemstream"], vec!["false", "true"]);
+        assert_eq!(codecs[3].kind, Some(CodecKind::Element));
+        assert_eq!(codecs[3].name, "faac");
+
+        let request: Codec =
+            "gstreamer|1.0|totem|MPEG-4 AAC encoder|encoder-audio/mpeg, mpegversion=(int)4"
+                .parse()?;
+        assert!(codecs[0].satisfies(&request));
+        let request: Codec = "encoder-audio/mpeg, mpegversion=(int)1".parse()?;
+        assert!(!codecs[0].satisfies(&request));
+        let request: Codec = "audio/mpeg".parse()?;
+        assert_eq!(request.kind, None);
+        assert!(codecs[0].satisfies(&request));
+        assert!(!codecs[3].satisfies(&request));
+        assert!("".parse::<Codec>().is_err());
+
+        let collection = CollectionBuilder::new("0.14")
+            .component(component.clone())
+            .component(
+                ComponentBuilder::default()
+                    .id("org.example.Player".into())
+                    .name(TranslatableString::with_default("Player"))
+                    .kind(ComponentKind::DesktopApplication)
+                    .provide(Provide::Codec("element-faac".into()))
+                    .build(),
+            )
+            .build();
+        let found = collection.find_codec_for("faac");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, component.id);
+        assert_eq!(
+            collection.find_codec_for("gstreamer1(element-xviddec)()(64bit)")[0].id,
+            component.id
+        );
+        assert!(collection.find_codec_for("decoder-video/x-h264").is_empty());
+        Ok(())
+    }
+}
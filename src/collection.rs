@@ -1,20 +1,37 @@
-use super::error::ParseError;
+#[cfg(feature = "async")]
+use super::blocking;
+use super::canonical;
+use super::codec::Codec;
+use super::delta::CollectionDelta;
+use super::digest::{self, Digest, HashingReader};
+use super::enums::{Checksum, ComponentKind, Icon, Launchable, Provide};
+use super::error::{ParseError, ParseWarning};
+use super::indices::Indices;
+use super::media::{MediaKind, MediaUrl};
+#[cfg(feature = "json")]
+use super::ndjson;
+#[cfg(feature = "net")]
+use super::net::{self, FetchOptions, HttpClient};
+use super::options::ParseOptions;
+use super::reader;
+use super::search::SearchIndex;
+use super::warnings;
+use super::xml_writer::{self, XmlNode};
 use super::AppId;
 use super::Component;
+use super::Timestamp;
 #[cfg(feature = "gzip")]
 use flate2::read::GzDecoder;
-#[cfg(feature = "gzip")]
-use std::io::prelude::*;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::fs;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{self, BufReader, Read, Write};
+use std::ops::Index;
 use std::path::PathBuf;
-use xmltree::Element;
-use yaml_rust::YamlLoader;
-
-
+use std::time::Duration;
+use url::Url;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 /// A collection is a wrapper around multiple components at once.
@@ -35,11 +52,21 @@ pub struct Collection {
 
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     /// The components that are part of this collection.
+    ///
+    /// The lookups using an index, like `search`, only notice the components
+    /// replaced in place through this field after `reindex` is called.
     pub components: Vec<Component>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// The targeted CPU architecture of the collection.
     pub architecture: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// When the collection was generated.
+    pub timestamp: Option<Timestamp>,
+
+    #[serde(skip)]
+    pub(crate) indices: Indices,
 }
 
 impl Collection {
@@ -49,9 +76,230 @@ impl Collection {
     ///
     /// * `path` - The path to the collection.
     pub fn from_path(path: PathBuf) -> Result<Self, ParseError> {
+        Self::from_path_with_options(path, &ParseOptions::default())
+    }
+
+    /// Create a new `Collection` from an XML file, skipping the fields and checking
+    /// the limits set in `options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the collection.
+    /// * `options` - The parsing options.
+    pub fn from_path_with_options(
+        path: PathBuf,
+        options: &ParseOptions,
+    ) -> Result<Self, ParseError> {
         let file = BufReader::new(File::open(path)?);
-        let collection = Collection::try_from(&Element::parse(file)?)?;
-        Ok(collection)
+        reader::read_collection(file, options)
+    }
+
+    /// Create a new `Collection` from an XML file, along with the warnings about
+    /// what the parser skipped or recovered from: unknown tags, ignored attributes
+    /// and invalid values replaced by a fallback.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the collection.
+    pub fn from_path_with_warnings(path: PathBuf) -> Result<(Self, Vec<ParseWarning>), ParseError> {
+        let (collection, warnings) = warnings::collect(|| Self::from_path(path));
+        Ok((collection?, warnings))
+    }
+
+    #[cfg(feature = "mmap")]
+    /// Create a new `Collection` from a memory-mapped XML file.
+    ///
+    /// The components are streamed out of the mapping, so the file isn't copied
+    /// into a buffer and its pages can be dropped by the kernel once parsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the uncompressed collection.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified nor truncated, by this process or another
+    /// one, until the function returns. The parser would read the mapping while
+    /// it changes, which is undefined behavior, and reading the pages past the
+    /// end of a truncated file kills the process with `SIGBUS`. The catalogs
+    /// updated by replacing their file, like the ones of the system, are safe
+    /// to map.
+    pub unsafe fn from_mapped_path(path: PathBuf) -> Result<Self, ParseError> {
+        let file = File::open(path)?;
+        let map = memmap2::Mmap::map(&file)?;
+        reader::read_collection(&map[..], &ParseOptions::default())
+    }
+
+    /// Create a new `Collection` out of the components of an XML file that match
+    /// some ids, the other components are skipped without being parsed.
+    ///
+    /// Like with `find_by_id`, the components whose id is suffixed with `.desktop`
+    /// match as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the collection.
+    /// * `ids` - The ids of the components to extract.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::Collection;
+    ///
+    /// let collection = Collection::extract_ids(
+    ///     "./tests/collections/gnome-apps.xml".into(),
+    ///     &["org.gnome.Maps".into(), "org.gnome.eog".into()],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(collection.components.len(), 2);
+    /// ```
+    pub fn extract_ids(path: PathBuf, ids: &[AppId]) -> Result<Self, ParseError> {
+        let file = BufReader::new(File::open(path)?);
+        reader::read_collection_filtered(file, &ParseOptions::default(), |element| {
+            let id = match element.get_child("id").and_then(|id| id.get_text()) {
+                Some(id) => id,
+                None => return false,
+            };
+            ids.iter()
+                .any(|i| *id == *i.0 || id.strip_suffix(".desktop") == Some(&*i.0))
+        })
+    }
+
+    /// Create a new `Collection` from an XML or YAML file, verifying its checksum.
+    ///
+    /// The file is hashed while it is parsed, the checksum being the one of the file
+    /// as stored, gzip-compressed or not, like the ones listed in a repository's
+    /// `Release` file. YAML files are detected by their `.yml` or `.yaml` extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the collection.
+    /// * `checksum` - The expected checksum of the file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use appstream::enums::Checksum;
+    /// use appstream::Collection;
+    ///
+    /// let collection = Collection::from_path_verified(
+    ///     "/var/lib/app-info/yaml/Components-amd64.yml.gz".into(),
+    ///     Checksum::Sha256(
+    ///         "b2c5e3d7b0a2b1ee3f2ab0fcd1ae6b0e3e3c38cf3ce1bda3a3b5bb1bb7d16e41".into(),
+    ///     ),
+    /// );
+    /// ```
+    pub fn from_path_verified(path: PathBuf, checksum: Checksum) -> Result<Self, ParseError> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (digest, expected) = Digest::for_checksum(&checksum);
+        let expected = expected.to_lowercase();
+        let mut reader = HashingReader::new(BufReader::new(File::open(&path)?), digest);
+
+        let collection = {
+            #[cfg(feature = "gzip")]
+            let source: Box<dyn Read + '_> = if name.ends_with(".gz") {
+                Box::new(GzDecoder::new(&mut reader))
+            } else {
+                Box::new(&mut reader)
+            };
+            #[cfg(not(feature = "gzip"))]
+            let source = &mut reader;
+
+            Self::from_named_reader(&name, source)
+        };
+
+        // A corrupted download fails to parse as well, the checksum tells why.
+        let actual = digest::to_hex(&reader.finish()?);
+        if actual != expected {
+            return Err(ParseError::ChecksumMismatch(expected, actual));
+        }
+        collection
+    }
+
+    /// Parses an uncompressed collection named `name`, YAML files being detected
+    /// by their `.yml` or `.yaml` extension, with or without a `.gz` suffix.
+    fn from_named_reader<R: Read>(name: &str, source: R) -> Result<Self, ParseError> {
+        let name = name.trim_end_matches(".gz");
+        if name.ends_with(".yml") || name.ends_with(".yaml") {
+            Collection::try_from(&reader::read_yaml(source, &ParseOptions::default())?)
+        } else {
+            reader::read_collection(source, &ParseOptions::default())
+        }
+    }
+
+    #[cfg(feature = "net")]
+    /// Create a new `Collection` by downloading an XML or YAML catalog.
+    ///
+    /// Gzip-compressed catalogs are detected by their content and decompressed
+    /// while parsing, which requires the `gzip` feature. YAML catalogs are detected
+    /// by the `.yml` or `.yaml` extension of the URL path. Use a `net::HttpCache`
+    /// to avoid downloading unchanged catalogs again.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to send the requests through.
+    /// * `url` - The URL of the catalog.
+    /// * `options` - The redirect and timeout options.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use appstream::net::{FetchOptions, HttpClient, Request, Response};
+    /// use appstream::Collection;
+    /// use std::time::Duration;
+    /// use url::Url;
+    /// # struct MyClient;
+    /// # impl HttpClient for MyClient {
+    /// #     fn send(&self, _: &Request) -> std::io::Result<Response> { unimplemented!() }
+    /// # }
+    ///
+    /// let collection = Collection::from_url(
+    ///     &MyClient,
+    ///     &Url::parse("https://dl.flathub.org/repo/appstream/x86_64/appstream.xml.gz").unwrap(),
+    ///     &FetchOptions::default().timeout(Duration::from_secs(60)),
+    /// );
+    /// ```
+    pub fn from_url<C: HttpClient + ?Sized>(
+        client: &C,
+        url: &Url,
+        options: &FetchOptions,
+    ) -> Result<Self, ParseError> {
+        Self::from_download(url, &net::fetch(client, url, options)?)
+    }
+
+    #[cfg(feature = "net")]
+    /// Parses the catalog downloaded from `url`, decompressing it if needed.
+    pub(crate) fn from_download(url: &Url, data: &[u8]) -> Result<Self, ParseError> {
+        #[cfg(feature = "gzip")]
+        {
+            if data.starts_with(&[0x1f, 0x8b]) {
+                return Self::from_named_reader(url.path(), GzDecoder::new(data));
+            }
+        }
+        Self::from_named_reader(url.path(), data)
+    }
+
+    #[cfg(all(feature = "net", feature = "async"))]
+    /// Like `from_url`, without blocking the calling task.
+    ///
+    /// The catalog is downloaded and parsed on one of the few threads the loaders
+    /// share, which takes the client along, clients sharing a connection pool are
+    /// usually cheap to clone.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to send the requests through.
+    /// * `url` - The URL of the catalog.
+    /// * `options` - The redirect and timeout options.
+    pub async fn from_url_async<C: HttpClient + Send + 'static>(
+        client: C,
+        url: Url,
+        options: FetchOptions,
+    ) -> Result<Self, ParseError> {
+        blocking::run(move || Self::from_url(&client, &url, &options)).await
     }
 
     /// Create a new `Collection` from an YAML file.
@@ -60,9 +308,32 @@ impl Collection {
     ///
     /// * `path` - The path to the collection.
     pub fn from_yaml_path(path: PathBuf) -> Result<Self, ParseError> {
-        let s = fs::read_to_string(path)?;
-        let rrr = YamlLoader::load_from_str(s.as_str()).unwrap();
-        let collection = Collection::try_from(&rrr)?;
+        Self::from_yaml_path_with_options(path, &ParseOptions::default())
+    }
+
+    /// Create a new `Collection` from an YAML file, leaving out the fields set in
+    /// `options`. They are still parsed, but not stored. The limits of `options`
+    /// are checked.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the collection.
+    /// * `options` - The parsing options.
+    pub fn from_yaml_path_with_options(
+        path: PathBuf,
+        options: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        Self::from_yaml_reader(BufReader::new(File::open(path)?), options)
+    }
+
+    /// Parses an uncompressed YAML collection, see `from_yaml_path_with_options`.
+    fn from_yaml_reader<R: Read>(reader: R, options: &ParseOptions) -> Result<Self, ParseError> {
+        let mut collection = Collection::try_from(&reader::read_yaml(reader, options)?)?;
+        options.check_components(collection.components.len())?;
+        for component in collection.components.iter_mut() {
+            options.check_component(component)?;
+            options.strip(component);
+        }
         Ok(collection)
     }
 
@@ -73,13 +344,25 @@ impl Collection {
     ///
     /// * `path` - The path to the gzipped collection.
     pub fn from_gzipped(path: PathBuf) -> Result<Self, ParseError> {
-        let f = File::open(path)?;
+        Self::from_gzipped_with_options(path, &ParseOptions::default())
+    }
 
-        let d = GzDecoder::new(f);
-        let element = Element::parse(d)?;
-        let collection: Collection = Collection::try_from(&element)?;
+    #[cfg(feature = "gzip")]
+    /// Create a new `Collection` from a gzipped XML file, skipping the fields and
+    /// checking the limits set in `options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the gzipped collection.
+    /// * `options` - The parsing options.
+    pub fn from_gzipped_with_options(
+        path: PathBuf,
+        options: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let f = File::open(path)?;
 
-        Ok(collection)
+        let d = GzDecoder::new(BufReader::new(f));
+        reader::read_collection(d, options)
     }
 
     #[cfg(feature = "gzip")]
@@ -89,15 +372,25 @@ impl Collection {
     ///
     /// * `path` - The path to the gzipped collection.
     pub fn from_yaml_gzipped(path: PathBuf) -> Result<Self, ParseError> {
-        let f = File::open(path)?;
+        Self::from_yaml_gzipped_with_options(path, &ParseOptions::default())
+    }
 
-        let mut d = GzDecoder::new(f);
-        let mut s = String::new();
-        d.read_to_string(&mut s)?;
-        let rrr = YamlLoader::load_from_str(s.as_str()).unwrap();
-        let collection: Collection = Collection::try_from(&rrr)?;
+    #[cfg(feature = "gzip")]
+    /// Create a new `Collection` from a gzipped YAML file, skipping the fields and
+    /// checking the limits set in `options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the gzipped collection.
+    /// * `options` - The parsing options.
+    pub fn from_yaml_gzipped_with_options(
+        path: PathBuf,
+        options: &ParseOptions,
+    ) -> Result<Self, ParseError> {
+        let f = File::open(path)?;
 
-        Ok(collection)
+        let d = GzDecoder::new(BufReader::new(f));
+        Self::from_yaml_reader(d, options)
     }
 
     #[cfg(feature = "gzip")]
@@ -108,10 +401,115 @@ impl Collection {
     /// * `bytes` - The byte slice (gzip compressed).
     pub fn from_gzipped_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
         let d = GzDecoder::new(bytes);
-        let element = Element::parse(d)?;
+        reader::read_collection(d, &ParseOptions::default())
+    }
 
-        let collection: Collection = Collection::try_from(&element)?;
-        Ok(collection)
+    #[cfg(feature = "async")]
+    /// Create a new `Collection` from an XML file, without blocking the calling task.
+    ///
+    /// The file is read and parsed on one of the few threads the loaders share, so
+    /// the future can be awaited from any executor.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the collection.
+    pub async fn from_path_async(path: PathBuf) -> Result<Self, ParseError> {
+        blocking::run(move || Self::from_path(path)).await
+    }
+
+    #[cfg(feature = "async")]
+    /// Create a new `Collection` from an XML document read out of `reader`, without
+    /// blocking the calling task.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the uncompressed collection.
+    pub async fn from_reader_async<R: Read + Send + 'static>(
+        reader: R,
+    ) -> Result<Self, ParseError> {
+        blocking::run(move || reader::read_collection(reader, &ParseOptions::default())).await
+    }
+
+    #[cfg(all(feature = "async", feature = "gzip"))]
+    /// Create a new `Collection` from a gzipped XML file, without blocking the
+    /// calling task. The file is decompressed while it is parsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the gzipped collection.
+    pub async fn from_gzipped_async(path: PathBuf) -> Result<Self, ParseError> {
+        blocking::run(move || Self::from_gzipped(path)).await
+    }
+
+    #[cfg(all(feature = "async", feature = "gzip"))]
+    /// Create a new `Collection` from a gzipped XML document read out of `reader`,
+    /// without blocking the calling task.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the gzipped collection.
+    pub async fn from_gzipped_reader_async<R: Read + Send + 'static>(
+        reader: R,
+    ) -> Result<Self, ParseError> {
+        blocking::run(move || {
+            reader::read_collection(GzDecoder::new(reader), &ParseOptions::default())
+        })
+        .await
+    }
+
+    /// A SHA-256 fingerprint of the collection, as an hex string.
+    ///
+    /// It combines the origin, the specification version, the number of components
+    /// and their `Component::content_hash`, so caches built from the collection only
+    /// need to be invalidated when it changes. The order of the components doesn't
+    /// matter.
+    pub fn fingerprint(&self) -> String {
+        let mut hashes = self
+            .components
+            .iter()
+            .map(|c| c.content_hash())
+            .collect::<Vec<String>>();
+        hashes.sort();
+        let bytes = canonical::to_bytes(&(&self.origin, &self.version, hashes.len(), &hashes))
+            .expect("strings are always serializable");
+        let mut hasher = Sha256::default();
+        hasher.update(&bytes);
+        digest::to_hex(&hasher.finalize())
+    }
+
+    /// Computes the changes from this collection to `new`, which can be shipped to
+    /// clients instead of the whole catalog and applied with `apply_delta`.
+    ///
+    /// # Arguments
+    ///
+    /// * `new` - The new version of the collection.
+    pub fn diff(&self, new: &Collection) -> CollectionDelta {
+        CollectionDelta::new(self, new)
+    }
+
+    /// Applies a delta computed by `diff` from this collection, the removed
+    /// components are dropped and the updated ones replace the components with
+    /// the same id, or are appended.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The changes to apply.
+    pub fn apply_delta(&mut self, delta: &CollectionDelta) {
+        delta.apply(self);
+        self.reindex();
+    }
+
+    /// Sorts the components by id then origin, so the collection serializes the
+    /// same way whatever the order its components were loaded in.
+    pub fn sort(&mut self) {
+        self.components.sort();
+        self.reindex();
+    }
+
+    /// Drops the indices of the lookups, they are rebuilt by the next ones. Only
+    /// needed after replacing components in place through the `components` field.
+    pub fn reindex(&mut self) {
+        self.indices.clear();
     }
 
     /// Find the components that corresponds to a specific `AppId`
@@ -126,6 +524,345 @@ impl Collection {
             .filter(|c| c.id == id || c.id == alternative_id)
             .collect::<Vec<&Component>>()
     }
+
+    /// Find the components that provide a specific public interface, like a binary
+    /// or a shared library.
+    ///
+    /// # Arguments
+    ///
+    /// * `provide` - The provided item to look for.
+    pub fn find_by_provide(&self, provide: &Provide) -> Vec<&Component> {
+        self.components
+            .iter()
+            .filter(|c| c.provides.contains(provide))
+            .collect::<Vec<&Component>>()
+    }
+
+    /// Find the codec components providing a GStreamer codec, so a "missing
+    /// codec" installer can map a request to the components to install.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The requested codec, as caps like `decoder-audio/mpeg, mpegversion=(int)4`,
+    ///   an element name or any other format `Codec` parses.
+    pub fn find_codec_for(&self, request: &str) -> Vec<&Component> {
+        let request = match request.parse::<Codec>() {
+            Ok(request) => request,
+            Err(_) => return vec![],
+        };
+        self.components
+            .iter()
+            .filter(|c| c.kind == ComponentKind::Codec)
+            .filter(|c| {
+                Codec::from_component(c)
+                    .iter()
+                    .any(|codec| codec.satisfies(&request))
+            })
+            .collect::<Vec<&Component>>()
+    }
+
+    /// Find the components that can handle a specific MIME type.
+    ///
+    /// # Arguments
+    ///
+    /// * `mimetype` - The MIME type, like `text/html`.
+    pub fn find_by_mimetype(&self, mimetype: &str) -> Vec<&Component> {
+        self.components
+            .iter()
+            .filter(|c| c.mimetypes.iter().any(|m| m.eq_ignore_ascii_case(mimetype)))
+            .collect::<Vec<&Component>>()
+    }
+
+    /// Find the components launched by a specific launchable, like the desktop
+    /// file of a running application.
+    ///
+    /// The desktop ids are compared with or without their `.desktop` suffix. The
+    /// components without a desktop id launchable are matched by their id, like
+    /// the legacy ones named after their desktop file.
+    ///
+    /// # Arguments
+    ///
+    /// * `launchable` - The launchable to look for.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::enums::Launchable;
+    /// use appstream::Collection;
+    ///
+    /// let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into()).unwrap();
+    /// let found = collection.find_by_launchable(&Launchable::DesktopId("org.gnome.Weather.Application.desktop".into()));
+    /// assert_eq!(found[0].id, "org.gnome.Weather.Application.desktop".into());
+    /// ```
+    pub fn find_by_launchable(&self, launchable: &Launchable) -> Vec<&Component> {
+        fn strip(id: &str) -> &str {
+            id.strip_suffix(".desktop").unwrap_or(id)
+        }
+        let desktop_id = match launchable {
+            Launchable::DesktopId(id) => strip(id),
+            _ => {
+                return self
+                    .components
+                    .iter()
+                    .filter(|c| c.launchables.contains(launchable))
+                    .collect::<Vec<&Component>>()
+            }
+        };
+        self.components
+            .iter()
+            .filter(|c| {
+                let mut ids = c
+                    .launchables
+                    .iter()
+                    .filter_map(|l| match l {
+                        Launchable::DesktopId(id) => Some(strip(id)),
+                        _ => None,
+                    })
+                    .peekable();
+                match ids.peek() {
+                    Some(_) => ids.any(|id| id == desktop_id),
+                    None => strip(c.id.as_ref()) == desktop_id,
+                }
+            })
+            .collect::<Vec<&Component>>()
+    }
+
+    /// Find the components shipped by a distribution package.
+    ///
+    /// The components are looked up in an index of their package names, built by
+    /// the first lookup and kept by the collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `pkgname` - The name of the package, like `gnome-weather`.
+    pub fn find_by_pkgname(&self, pkgname: &str) -> Vec<&Component> {
+        self.indices
+            .pkgnames(&self.components)
+            .get(pkgname)
+            .map(|positions| positions.iter().map(|&i| &self.components[i]).collect())
+            .unwrap_or_default()
+    }
+
+    /// The token index of the components, to search them with
+    /// `SearchIndex::search`.
+    ///
+    /// The index is built by the first search and kept by the collection, the
+    /// next ones only look up their terms.
+    pub fn search_index(&self) -> SearchIndex<'_> {
+        SearchIndex::with_tokens(&self.components, self.indices.search(&self.components))
+    }
+
+    /// Find the components matching every term of a query in their id, name,
+    /// keywords, summary or description, in any locale, the most relevant first,
+    /// see `search_index`.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The terms to look for, like `text editor`.
+    pub fn search(&self, query: &str) -> Vec<&Component> {
+        self.search_index().search(query)
+    }
+
+    /// Every media URL referenced by the components: remote icons, screenshot
+    /// images and videos, and release artifacts.
+    ///
+    /// Each URL is listed once, in the order it first appears, along with the ids
+    /// of the components referencing it, so the media of an offline store can be
+    /// pre-fetched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::media::MediaKind;
+    /// use appstream::Collection;
+    ///
+    /// let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into()).unwrap();
+    /// let images = collection
+    ///     .media_urls()
+    ///     .into_iter()
+    ///     .filter(|m| m.kind == MediaKind::Image)
+    ///     .count();
+    /// assert!(images > 0);
+    /// ```
+    pub fn media_urls(&self) -> Vec<MediaUrl> {
+        let mut urls: Vec<MediaUrl> = Vec::new();
+        let mut indices: HashMap<&Url, usize> = HashMap::new();
+        for component in self.components.iter() {
+            let icons = component.icons.iter().filter_map(|i| match i {
+                Icon::Remote { url, .. } => Some((url, MediaKind::Icon)),
+                _ => None,
+            });
+            let screenshots = component.screenshots.iter().flat_map(|s| {
+                let images = s.images.iter().map(|i| (&i.url, MediaKind::Image));
+                images.chain(s.videos.iter().map(|v| (&v.url, MediaKind::Video)))
+            });
+            let artifacts = component
+                .releases
+                .iter()
+                .flat_map(|r| r.artifacts.iter())
+                .map(|a| (&a.url, MediaKind::Artifact));
+
+            for (url, kind) in icons.chain(screenshots).chain(artifacts) {
+                let index = *indices.entry(url).or_insert_with(|| {
+                    urls.push(MediaUrl {
+                        url: url.clone(),
+                        kind,
+                        components: vec![],
+                    });
+                    urls.len() - 1
+                });
+                let media = &mut urls[index];
+                if !media.components.contains(&component.id) {
+                    media.components.push(component.id.clone());
+                }
+            }
+        }
+        urls
+    }
+
+    /// Serializes the collection as a `<components>` catalog XML document.
+    ///
+    /// The translations are written with their `xml:lang` attribute. Parsing the
+    /// document back gives the same collection.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::Collection;
+    /// use std::convert::TryFrom;
+    ///
+    /// let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into()).unwrap();
+    /// let xml = collection.to_xml();
+    /// let parsed = Collection::try_from(&appstream::xmltree::Element::parse(xml.as_bytes()).unwrap()).unwrap();
+    /// assert_eq!(parsed, collection);
+    /// ```
+    pub fn to_xml(&self) -> String {
+        let mut output = Vec::new();
+        self.to_xml_writer(&mut output)
+            .expect("writing to a vector doesn't fail");
+        String::from_utf8(output).expect("the written XML is valid UTF-8")
+    }
+
+    /// Writes the collection as a `<components>` catalog XML document, see
+    /// `to_xml`.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where to write the document.
+    pub fn to_xml_writer<W: Write>(&self, writer: W) -> io::Result<()> {
+        xml_writer::write_document(&XmlNode::from(self), writer)
+    }
+
+    #[cfg(feature = "json")]
+    /// Writes the components as newline-delimited JSON, one object per line, for
+    /// tools like `jq` or analytics databases.
+    ///
+    /// The objects are the JSON serialization of the components, the ones without
+    /// an origin get the one of the collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where to write the components.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::Collection;
+    ///
+    /// let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into()).unwrap();
+    /// let mut output = Vec::new();
+    /// collection.to_ndjson(&mut output).unwrap();
+    /// assert_eq!(output.iter().filter(|b| **b == b'\n').count(), collection.len());
+    /// ```
+    pub fn to_ndjson<W: Write>(&self, writer: W) -> io::Result<()> {
+        ndjson::write(self, writer, None)
+    }
+
+    #[cfg(feature = "json")]
+    /// Like `to_ndjson`, with the translatable fields flattened to their text in
+    /// `locale`, or the untranslated one, and the categories flattened to their
+    /// name. The fields are then plain strings, which suits tabular analytics.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Where to write the components.
+    /// * `locale` - The locale of the texts, like `fr_FR`.
+    pub fn to_ndjson_localized<W: Write>(&self, writer: W, locale: &str) -> io::Result<()> {
+        ndjson::write(self, writer, Some(locale))
+    }
+
+    /// How long ago the collection was generated, `None` when it doesn't tell.
+    /// Clients can use it to warn when their cached catalog is stale.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::CollectionBuilder;
+    /// use appstream::Timestamp;
+    /// use std::time::Duration;
+    ///
+    /// let collection = CollectionBuilder::new("0.14")
+    ///     .timestamp("20220209T161703".parse().unwrap())
+    ///     .build();
+    /// let now: Timestamp = "2022-02-12T16:17:03Z".parse().unwrap();
+    /// assert_eq!(collection.age(now), Some(Duration::from_secs(3 * 24 * 3600)));
+    /// ```
+    pub fn age(&self, now: Timestamp) -> Option<Duration> {
+        let seconds = now.as_unix() - self.timestamp?.as_unix();
+        Some(Duration::from_secs(seconds.max(0) as u64))
+    }
+
+    /// The number of components.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Whether the collection has no components.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Iterates over the components.
+    pub fn iter(&self) -> std::slice::Iter<'_, Component> {
+        self.components.iter()
+    }
+}
+
+impl IntoIterator for Collection {
+    type Item = Component;
+    type IntoIter = std::vec::IntoIter<Component>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.components.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Collection {
+    type Item = &'a Component;
+    type IntoIter = std::slice::Iter<'a, Component>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.components.iter()
+    }
+}
+
+impl Extend<Component> for Collection {
+    fn extend<T: IntoIterator<Item = Component>>(&mut self, components: T) {
+        self.components.extend(components);
+        self.reindex();
+    }
+}
+
+impl Index<usize> for Collection {
+    type Output = Component;
+
+    fn index(&self, index: usize) -> &Component {
+        &self.components[index]
+    }
 }
 
 #[cfg(test)]
@@ -135,11 +872,18 @@ mod tests {
     use crate::builders::{
         CollectionBuilder, ComponentBuilder, ImageBuilder, ReleaseBuilder, ScreenshotBuilder,
     };
-    use crate::enums::{Category, ComponentKind, Icon, ImageKind, ProjectUrl, Provide, ReleaseKind};
-    use crate::{MarkupTranslatableString, TranslatableList, TranslatableString};
-    use chrono::{TimeZone, Utc};
+    use crate::enums::{
+        Category, Compare, ComponentKind, ContentAttribute, ContentRatingVersion, ContentState,
+        FirmwareKind, Icon, ImageKind, ProjectUrl, Provide, ReleaseKind, ReleaseUrgency,
+    };
+    use crate::firmware::FirmwareRequirement;
+    use crate::Timestamp;
+    use crate::{
+        ContentRating, Fields, MarkupTranslatableString, TranslatableList, TranslatableString,
+    };
     use std::error::Error;
     use url::Url;
+    use yaml_rust::YamlLoader;
 
     #[cfg(feature = "gzip")]
     #[test]
@@ -160,6 +904,14 @@ mod tests {
     fn ubuntu_latest_yaml_collection() -> Result<(), Box<dyn Error>> {
         let c1 = Collection::from_yaml_gzipped("./tests/collections/main_dep11_Components-amd64.yml.gz".into())?;
         assert_eq!(c1.components.len(), 94);
+        let generated = Timestamp::from_ymd_hms(2022, 2, 9, 16, 17, 3).ok_or("invalid date")?;
+        assert_eq!(c1.timestamp, Some(generated));
+        let later = Timestamp::from_unix(generated.as_unix() + 3600);
+        assert_eq!(c1.age(later), Some(std::time::Duration::from_secs(3600)));
+        assert_eq!(c1.age(Timestamp::from_unix(0)), Some(Default::default()));
+        let fonts = c1.find_by_provide(&Provide::Font("Keraleeyam Regular".into()));
+        assert_eq!(fonts.len(), 1);
+        assert_eq!(fonts[0].id, "in.org.smc.keraleeyam".into());
         Ok(())
     }
 
@@ -177,6 +929,98 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_loading() -> Result<(), Box<dyn Error>> {
+        use crate::blocking::block_on;
+
+        let path = "./tests/collections/gnome-apps.xml";
+        let c1 = Collection::from_path(path.into())?;
+        let c2 = block_on(Collection::from_path_async(path.into()))?;
+        let c3 = block_on(Collection::from_reader_async(File::open(path)?))?;
+        assert_eq!(c1, c2);
+        assert_eq!(c1, c3);
+        assert!(block_on(Collection::from_path_async("./tests/missing.xml".into())).is_err());
+
+        #[cfg(feature = "gzip")]
+        {
+            let path = "./tests/collections/flathub-beta.xml.gz";
+            let c1 = block_on(Collection::from_gzipped_async(path.into()))?;
+            let c2 = block_on(Collection::from_gzipped_reader_async(File::open(path)?))?;
+            assert_eq!(c1.components.len(), 149);
+            assert_eq!(c1, c2);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn fetch_from_url() -> Result<(), Box<dyn Error>> {
+        use crate::net::{FetchOptions, HttpClient, Request, Response};
+        use std::io;
+        use std::time::Duration;
+
+        struct FakeServer;
+
+        impl HttpClient for FakeServer {
+            fn send(&self, request: &Request) -> io::Result<Response> {
+                assert_eq!(request.timeout, Some(Duration::from_secs(5)));
+                let path = match request.url.path() {
+                    "/latest/appstream.xml" => "./tests/collections/gnome-apps.xml",
+                    "/flathub/appstream.xml.gz" => "./tests/collections/flathub-beta.xml.gz",
+                    "/components.yml" => "./tests/collections/spec_example.yaml",
+                    "/loop" => {
+                        let mut response = Response::new(302);
+                        response.headers.push(("Location".into(), "loop".into()));
+                        return Ok(response);
+                    }
+                    _ => {
+                        let mut response = Response::new(301);
+                        response
+                            .headers
+                            .push(("location".into(), "/latest/appstream.xml".into()));
+                        return Ok(response);
+                    }
+                };
+                let mut response = Response::new(200);
+                response.body = Box::new(File::open(path)?);
+                Ok(response)
+            }
+        }
+
+        let base = Url::parse("https://example.org")?;
+        let options = FetchOptions::default().timeout(Duration::from_secs(5));
+        let c1 = Collection::from_url(&FakeServer, &base.join("stable")?, &options)?;
+        assert_eq!(
+            c1,
+            Collection::from_path("./tests/collections/gnome-apps.xml".into())?
+        );
+        let c2 = Collection::from_url(&FakeServer, &base.join("components.yml")?, &options)?;
+        assert_eq!(c2.origin.as_deref(), Some("chromodoris-main"));
+
+        assert!(Collection::from_url(&FakeServer, &base.join("loop")?, &options).is_err());
+        let no_redirects = options.max_redirects(0);
+        assert!(Collection::from_url(&FakeServer, &base.join("stable")?, &no_redirects).is_err());
+
+        #[cfg(feature = "gzip")]
+        {
+            let url = base.join("flathub/appstream.xml.gz")?;
+            let c3 = Collection::from_url(&FakeServer, &url, &options)?;
+            assert_eq!(c3.components.len(), 149);
+        }
+
+        #[cfg(feature = "async")]
+        {
+            let c4 = crate::blocking::block_on(Collection::from_url_async(
+                FakeServer,
+                base.join("latest/appstream.xml")?,
+                options,
+            ))?;
+            assert_eq!(c1, c4);
+        }
+        Ok(())
+    }
+
     #[test]
     fn spec_example_collection() -> Result<(), Box<dyn Error>> {
         let c1 = Collection::from_path("./tests/collections/spec_example.xml".into())?;
@@ -315,6 +1159,48 @@ mod tests {
             .and_locale("hr", vec!["Slika", "Preglednik"])
             .and_locale("sv", vec!["Bild", "Visare"]))
             .compulsory_for_desktop("GNOME".into())
+            .mimetype("application/x-gconf-schemas")
+            .provide(Provide::Binary("gconf-editor".into()))
+            .provide(Provide::Library("libgconf-2.so.4".into()))
+            .provide(Provide::DBus("org.gnome.GConf".into()))
+            .provide(Provide::Firmware {
+                kind: FirmwareKind::Runtime,
+                item: "gconf-editor.bin".into()
+            })
+            .provide(Provide::Firmware {
+                kind: FirmwareKind::Flashed,
+                item: "84f40464-9272-4ef7-9399-cd95f12da696".into()
+            })
+            .provide(Provide::Font("Cantarell Regular".into()))
+            .provide(Provide::Modalias("usb:v1130p0202d*".into()))
+            .provide(Provide::Python3("gconf".into()))
+            .provide(Provide::Id("org.gnome.gconf-editor".into()))
+            .url(ProjectUrl::Homepage(Url::parse(
+                "https://wiki.gnome.org/Apps/ConfigurationEditor",
+            )?))
+            .url(ProjectUrl::BugTracker(Url::parse(
+                "https://bugzilla.gnome.org/enter_bug.cgi?product=gconf-editor",
+            )?))
+            .url(ProjectUrl::Unknown(Url::parse(
+                "https://gitlab.gnome.org/Archive/gconf-editor",
+            )?))
+            .metadata("flathub::verification::verified".into(), Some("true".into()))
+            .metadata("flathub::verification::method".into(), Some("website".into()))
+            .metadata("X-Empty".into(), None)
+            .suggest("org.gnome.dconf-editor".into())
+            .require("org.gnome.gconf".into())
+            .require_firmware(FirmwareRequirement {
+                target: Some("bootloader".into()),
+                compare: Compare::Ge,
+                version: Some("0.1.2".into()),
+            })
+            .content_rating(ContentRating {
+                version: ContentRatingVersion::Oars1_1,
+                attributes: vec![
+                    ContentAttribute::ViolenceCartoon(ContentState::Mild),
+                    ContentAttribute::SocialChat(ContentState::Intense),
+                ],
+            })
             .icon(Icon::Cached {
                 path: "qutebrowser_qutebrowser.png".into(),
                 width: Some(48),
@@ -338,22 +1224,29 @@ mod tests {
             })
             .release(ReleaseBuilder::new("2.20.0")
                 .kind(ReleaseKind::Stable)
-                .date(Utc.ymd(2019, 6, 20).and_hms_milli(0, 0, 0, 0))
+                .date(Timestamp::from_unix(1560988800))
+                .urgency(ReleaseUrgency::High)
+                .description(
+                    MarkupTranslatableString::with_default("<p>Fixes a crash when saving a key.</p>")
+                    .and_locale("de", "<p>Behebt einen Absturz beim Speichern eines Schlüssels.</p>")
+                )
+                .url(Url::parse("https://example.org/gconf-editor/2.20.0")?)
                 .build()
             )
             .release(ReleaseBuilder::new("2.19.0")
                 .kind(ReleaseKind::Stable)
-                .date(Utc.ymd(2019, 4, 20).and_hms_milli(0, 0, 0, 0))
+                .date(Timestamp::from_unix(1555718400))
                 .build()
             )
             .release(ReleaseBuilder::new("2.18.0")
                 .kind(ReleaseKind::Stable)
-                .date(Utc.ymd(2019, 2, 11).and_hms_milli(0, 0, 0, 0))
+                .date(Timestamp::from_unix(1549843200))
                 .build()
             )
             .release(ReleaseBuilder::new("2.17.0")
                 .kind(ReleaseKind::Stable)
-                .date(Utc.ymd(2018, 12, 16).and_hms_milli(0, 0, 0, 0))
+                .date("2018-12-16".parse::<Timestamp>()?)
+                .date_eol("2019-12-16".parse::<Timestamp>()?)
                 .build()
             )
             .screenshot(
@@ -556,6 +1449,14 @@ mod tests {
         assert_eq!(Some("flatpak".into()), collection.origin);
         assert_eq!("0.8", collection.version);
 
+        let groups = collection
+            .components
+            .iter()
+            .filter_map(|c| c.project_group.as_ref())
+            .collect::<Vec<_>>();
+        assert_eq!(groups.len(), 24);
+        assert!(groups.iter().all(|g| std::sync::Arc::ptr_eq(g, groups[0])));
+
         #[cfg(feature = "test_json")]
         {
             let c2: Collection = serde_json::from_str(&serde_json::to_string(&collection)?)?;
@@ -564,6 +1465,150 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn extract_ids() -> Result<(), Box<dyn Error>> {
+        let path = "./tests/collections/gnome-apps.xml";
+        let collection = Collection::from_path(path.into())?;
+        let ids: Vec<AppId> = vec!["org.gnome.Maps".into(), "org.gnome.Builder.desktop".into()];
+        let extracted = Collection::extract_ids(path.into(), &ids)?;
+
+        let expected = ids
+            .iter()
+            .flat_map(|id| collection.find_by_id(id.clone()))
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(extracted.components.len(), 2);
+        assert!(expected.iter().all(|c| extracted.components.contains(c)));
+        assert_eq!(extracted.origin, collection.origin);
+        assert!(Collection::extract_ids(path.into(), &[])?
+            .components
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn fingerprint() -> Result<(), Box<dyn Error>> {
+        let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into())?;
+        let fingerprint = collection.fingerprint();
+
+        let mut reordered = collection.clone();
+        reordered.components.reverse();
+        assert_eq!(reordered.fingerprint(), fingerprint);
+
+        let mut changed = collection.clone();
+        changed.components[0].pkgname = Some("gnome-weather".into());
+        assert_ne!(changed.fingerprint(), fingerprint);
+
+        let mut removed = collection.clone();
+        removed.components.pop();
+        assert_ne!(removed.fingerprint(), fingerprint);
+
+        let mut moved = collection;
+        moved.origin = Some("gnome".into());
+        assert_ne!(moved.fingerprint(), fingerprint);
+        Ok(())
+    }
+
+    #[test]
+    fn container() -> Result<(), Box<dyn Error>> {
+        let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into())?;
+        assert_eq!(collection.len(), collection.components.len());
+        assert!(!collection.is_empty());
+        assert_eq!(collection[1], collection.components[1]);
+        assert_eq!(
+            (&collection).into_iter().collect::<Vec<_>>(),
+            collection.iter().collect::<Vec<_>>()
+        );
+
+        let mut copy = CollectionBuilder::new("0.8").build();
+        assert!(copy.is_empty());
+        copy.extend(collection.clone());
+        assert_eq!(copy.components, collection.components);
+        for (copied, component) in copy.into_iter().zip(&collection) {
+            assert_eq!(&copied, component);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sort() -> Result<(), Box<dyn Error>> {
+        let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into())?;
+        let mut sorted = collection.clone();
+        sorted.sort();
+        assert!(sorted.components.windows(2).all(|w| w[0].id <= w[1].id));
+
+        let mut reordered = collection;
+        reordered.components.reverse();
+        reordered.sort();
+        assert_eq!(reordered, sorted);
+
+        let ids = sorted
+            .components
+            .iter()
+            .map(|c| c.id.clone())
+            .collect::<std::collections::BTreeSet<AppId>>();
+        assert_eq!(ids.len(), sorted.components.len());
+        Ok(())
+    }
+
+    #[test]
+    fn skip_fields() -> Result<(), Box<dyn Error>> {
+        let options =
+            ParseOptions::skip(Fields::DESCRIPTIONS | Fields::SCREENSHOTS | Fields::RELEASES);
+        for (path, yaml) in [
+            ("./tests/collections/gnome-apps.xml", false),
+            ("./tests/collections/spec_example.yaml", true),
+        ]
+        .iter()
+        {
+            let (mut full, skipped) = if *yaml {
+                (
+                    Collection::from_yaml_path(path.into())?,
+                    Collection::from_yaml_path_with_options(path.into(), &options)?,
+                )
+            } else {
+                (
+                    Collection::from_path(path.into())?,
+                    Collection::from_path_with_options(path.into(), &options)?,
+                )
+            };
+            assert!(full.components.iter().any(|c| !c.releases.is_empty()));
+            full.components.iter_mut().for_each(|c| options.strip(c));
+            assert_eq!(full, skipped);
+            assert!(skipped
+                .components
+                .iter()
+                .all(|c| c.description.is_none() && c.screenshots.is_empty()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn yaml_limits() -> Result<(), Box<dyn Error>> {
+        let path = PathBuf::from("./tests/collections/spec_example.yaml");
+        let read =
+            |options: ParseOptions| Collection::from_yaml_path_with_options(path.clone(), &options);
+        let exceeded = |options: ParseOptions| match read(options) {
+            Err(ParseError::LimitExceeded(what, _)) => Some(what),
+            _ => None,
+        };
+        let len = std::fs::metadata(&path)?.len();
+        assert!(read(ParseOptions::default().max_decompressed_bytes(len)).is_ok());
+        assert_eq!(
+            exceeded(ParseOptions::default().max_decompressed_bytes(len - 1)),
+            Some("decompressed bytes".into())
+        );
+        assert_eq!(
+            exceeded(ParseOptions::default().max_string_length(20)),
+            Some("bytes in a string".into())
+        );
+        assert_eq!(
+            exceeded(ParseOptions::default().max_components(0)),
+            Some("components".into())
+        );
+        Ok(())
+    }
+
     #[test]
     fn kde_collection() -> Result<(), Box<dyn Error>> {
         let collection = Collection::from_path("./tests/collections/kde-apps.xml".into())?;
@@ -608,4 +1653,260 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn find_by_provide() -> Result<(), Box<dyn Error>> {
+        let c = Collection::from_path("./tests/collections/spec_example.xml".into())?;
+        let found = c.find_by_provide(&Provide::Binary("firefox".into()));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "org.mozilla.Firefox".into());
+        assert!(c
+            .find_by_provide(&Provide::Binary("chromium".into()))
+            .is_empty());
+
+        let found = c.find_by_mimetype("text/html");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "org.mozilla.Firefox".into());
+
+        let found = c.find_by_pkgname("firefox-bin");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "org.mozilla.Firefox".into());
+        assert!(c.find_by_pkgname("firefox").is_empty());
+
+        // The index kept by the collection follows its components.
+        let mut c = c.clone();
+        let mut firefox = found[0].clone();
+        firefox.pkgname = Some("firefox".into());
+        c.components.push(firefox);
+        assert_eq!(c.find_by_pkgname("firefox").len(), 1);
+        c.components.swap(0, 1);
+        c.reindex();
+        assert_eq!(
+            c.find_by_pkgname("firefox-bin")[0].pkgname.as_deref(),
+            Some("firefox-bin")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn find_by_launchable() -> Result<(), Box<dyn Error>> {
+        let c = Collection::from_path("./tests/collections/appstream.xml".into())?;
+        for desktop_id in &[
+            "cat.xtec.clic.JClic.jclicauthor.desktop",
+            "cat.xtec.clic.JClic.jclic",
+        ] {
+            let found = c.find_by_launchable(&Launchable::DesktopId(desktop_id.to_string()));
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].id, "cat.xtec.clic.JClic".into());
+        }
+        // The id of a component with a desktop id launchable isn't a desktop id.
+        let desktop_id = Launchable::DesktopId("cat.xtec.clic.JClic.desktop".into());
+        assert!(c.find_by_launchable(&desktop_id).is_empty());
+        let service = Launchable::Service("jclic.service".into());
+        assert!(c.find_by_launchable(&service).is_empty());
+
+        let c = Collection::from_path("./tests/collections/gnome-apps.xml".into())?;
+        let desktop_id = Launchable::DesktopId("org.gnome.Weather.Application".into());
+        let found = c.find_by_launchable(&desktop_id);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "org.gnome.Weather.Application.desktop".into());
+        Ok(())
+    }
+
+    #[test]
+    fn search() -> Result<(), Box<dyn Error>> {
+        let c = Collection::from_path("./tests/collections/gnome-apps.xml".into())?;
+        let index = c.search_index();
+        let found = index.search("weather");
+        assert_eq!(found[0].id, "org.gnome.Weather.Application.desktop".into());
+        assert_eq!(index.search("Weather forecast"), c.search("weather forec"));
+        assert!(index.search("no such thing").is_empty());
+
+        // The index kept by the collection follows its components.
+        let mut c = c.clone();
+        let weather = c.search("weather")[0].clone();
+        c.components.retain(|component| component.id != weather.id);
+        assert!(c
+            .search("weather")
+            .iter()
+            .all(|found| found.id != weather.id));
+        c.components.push(weather.clone());
+        c.sort();
+        assert_eq!(c.search("weather")[0].id, weather.id);
+        Ok(())
+    }
+
+    #[test]
+    fn verified_collection() -> Result<(), Box<dyn Error>> {
+        let path = PathBuf::from("./tests/collections/spec_example.xml");
+        let c1 = Collection::from_path_verified(
+            path.clone(),
+            Checksum::Sha256(
+                "605DDE56B598F369181812308BD9A9590440D2813AED79678CBB32D3CD96D306".into(),
+            ),
+        )?;
+        assert_eq!(c1, Collection::from_path(path.clone())?);
+
+        match Collection::from_path_verified(path, Checksum::Sha1("0".repeat(40))) {
+            Err(ParseError::ChecksumMismatch(expected, _)) => assert_eq!(expected, "0".repeat(40)),
+            r => panic!("Unexpected result {:?}", r.map(|c| c.components.len())),
+        }
+
+        let path = PathBuf::from("./tests/collections/spec_example.yaml");
+        let c2 = Collection::from_path_verified(
+            path.clone(),
+            Checksum::Sha256(
+                "0e9b72cb9b271f66c327cd23cee5e8b4baeb009a6e51b199c1227e9711ebdaf6".into(),
+            ),
+        )?;
+        assert_eq!(c2, Collection::from_yaml_path(path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn media_inventory() -> Result<(), Box<dyn Error>> {
+        use crate::builders::ArtifactBuilder;
+        use crate::enums::ArtifactKind;
+        use crate::media::MediaKind;
+
+        let shared = Url::parse("https://example.org/shared.png")?;
+        let tarball = Url::parse("https://example.org/foo-1.0.tar.xz")?;
+        let icon = Icon::Remote {
+            url: Url::parse("https://example.org/foo.png")?,
+            width: None,
+            height: None,
+        };
+        let screenshot = ScreenshotBuilder::default()
+            .image(ImageBuilder::new(shared.clone()).build())
+            .build();
+        let release = ReleaseBuilder::new("1.0")
+            .artifact(
+                ArtifactBuilder::default()
+                    .kind(ArtifactKind::Source)
+                    .url(tarball.clone())
+                    .build(),
+            )
+            .build();
+        let c = CollectionBuilder::new("0.14")
+            .component(
+                ComponentBuilder::default()
+                    .id("org.example.Foo".into())
+                    .name(TranslatableString::with_default("Foo"))
+                    .icon(icon)
+                    .icon(Icon::Stock("foo".into()))
+                    .screenshot(screenshot.clone())
+                    .screenshot(screenshot.clone())
+                    .release(release)
+                    .build(),
+            )
+            .component(
+                ComponentBuilder::default()
+                    .id("org.example.Bar".into())
+                    .name(TranslatableString::with_default("Bar"))
+                    .screenshot(screenshot)
+                    .build(),
+            )
+            .build();
+
+        let urls = c.media_urls();
+        assert_eq!(urls.len(), 3);
+        assert_eq!(urls[0].kind, MediaKind::Icon);
+        assert_eq!(urls[1].url, shared);
+        assert_eq!(urls[1].kind, MediaKind::Image);
+        assert_eq!(
+            urls[1].components,
+            vec!["org.example.Foo".into(), "org.example.Bar".into()]
+        );
+        assert_eq!(urls[2].url, tarball);
+        assert_eq!(urls[2].kind, MediaKind::Artifact);
+        assert_eq!(urls[2].components, vec!["org.example.Foo".into()]);
+        Ok(())
+    }
+
+    #[test]
+    fn collection_timestamp() -> Result<(), Box<dyn Error>> {
+        let parse = |xml: &str| Collection::try_from(&xmltree::Element::parse(xml.as_bytes())?);
+        let c = parse(r"<components version='0.14' time='2022-02-09T16:17:03Z'/>")?;
+        assert_eq!(c.timestamp, Timestamp::from_ymd_hms(2022, 2, 9, 16, 17, 3));
+        let (c, warnings) =
+            warnings::collect(|| parse(r"<components version='0.14' time='yesterday'/>"));
+        assert_eq!(c?.timestamp, None);
+        assert_eq!(
+            warnings,
+            vec![ParseWarning::recovered_value(
+                "yesterday",
+                "time",
+                "components",
+                "none"
+            )]
+        );
+        let yaml = YamlLoader::load_from_str(
+            "---\nFile: DEP-11\nVersion: '0.14'\nOrigin: x\nMediaBaseUrl: ''\nTime: yesterday\n",
+        )?;
+        let (c, warnings) = warnings::collect(|| Collection::try_from(&yaml));
+        assert_eq!(c?.timestamp, None);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            parse(r"<components version='0.14'/>")?.age(Timestamp::from_unix(0)),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn component_origins() -> Result<(), Box<dyn Error>> {
+        let yaml = YamlLoader::load_from_str(
+            "---\nFile: DEP-11\nVersion: '0.14'\nOrigin: aggregated\nMediaBaseUrl: ''\n\
+             ---\nID: org.example.Foo\nName:\n  C: Foo\n\
+             ---\nID: org.example.Bar\nOrigin: flathub\nName:\n  C: Bar\n",
+        )?;
+        let c1 = Collection::try_from(&yaml)?;
+        let origins = |c: &Collection| {
+            c.iter()
+                .map(|c| c.origin.as_deref().map(String::from))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(c1.origin.as_deref(), Some("aggregated"));
+        assert_eq!(
+            origins(&c1),
+            vec![Some("aggregated".into()), Some("flathub".into())]
+        );
+
+        let xml = r"<components version='0.14' origin='aggregated'>
+            <component><id>org.example.Foo</id><name>Foo</name></component>
+            <component origin='flathub'><id>org.example.Bar</id><name>Bar</name></component>
+        </components>";
+        let c2 = Collection::try_from(&xmltree::Element::parse(xml.as_bytes())?)?;
+        assert_eq!(c2.origin.as_deref(), Some("aggregated"));
+        assert_eq!(origins(&c2), vec![None, Some("flathub".into())]);
+        Ok(())
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn ndjson_export() -> Result<(), Box<dyn Error>> {
+        let c = Collection::from_path("./tests/collections/spec_example.xml".into())?;
+        let mut output = Vec::new();
+        c.to_ndjson(&mut output)?;
+        let lines = std::str::from_utf8(&output)?.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), c.len());
+        let firefox: Component = serde_json::from_str(lines[0])?;
+        assert_eq!(firefox, c.components[0]);
+
+        let mut output = Vec::new();
+        c.to_ndjson_localized(&mut output, "fr_FR")?;
+        let line = std::str::from_utf8(&output)?
+            .lines()
+            .next()
+            .ok_or("no line")?;
+        let firefox: serde_json::Value = serde_json::from_str(line)?;
+        assert_eq!(firefox["name"], "Firefox");
+        assert_eq!(firefox["summary"], "Navigateur web");
+        assert_eq!(firefox["keywords"], serde_json::json!(["navigateur"]));
+        assert_eq!(
+            firefox["categories"],
+            serde_json::json!(["network", "webbrowser"])
+        );
+        Ok(())
+    }
 }
@@ -1,36 +1,66 @@
+use super::canonical;
+use super::digest;
 use super::enums::{
-    Bundle, Category, ComponentKind, Icon, Kudo, Launchable, ProjectUrl, Provide, Translation,
+    Bundle, Category, ComponentKind, Icon, Kudo, Launchable, MergeKind, ProjectUrl, Provide,
+    ReleaseKind, Translation,
 };
-use super::error::ParseError;
+use super::error::{ParseError, ParseWarning};
+use super::firmware::FirmwareRequirement;
+use super::launch::LaunchDescriptor;
+use super::media::{IconSource, MediaResolver};
+use super::options::ParseOptions;
+#[cfg(feature = "raw")]
+use super::raw::RawNode;
+use super::reader;
+use super::warnings;
+use super::xml_writer::{self, XmlNode};
 use super::{
-    AppId, ContentRating, Language, License, MarkupTranslatableString, Release, Screenshot,
-    TranslatableList, TranslatableString,
+    vercmp, AppId, BrandingColor, Changelog, ContentRating, DesktopEntry, Language, License,
+    MarkupTranslatableString, Release, Screenshot, TranslatableList, TranslatableString,
 };
 #[cfg(feature = "gzip")]
 use flate2::read::GzDecoder;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use sha2::{Digest as _, Sha256};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use std::convert::TryFrom;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, Read};
 use xmltree::Element;
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 /// A component is wrapper around a `metainfo.xml` file or previously an `appdata.xml` file.
 /// It describes an application to the various stores out there on Linux.
+///
+/// Cloning a component is cheap: its heavy fields, the description, the
+/// screenshots, the releases, the languages, the keywords, the developer name and
+/// the content rating, are shared behind `Arc`s. The components returned by
+/// queries can thus be cloned to outlive their collection, like when sending them
+/// to another thread. Modifying a shared field with `Arc::make_mut` copies it.
 pub struct Component {
     #[serde(default, rename = "type")]
     /// The component type.
     pub kind: ComponentKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Set on the merge components, which only modify the component of the same id.
+    pub merge: Option<MergeKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The priority of the component over the ones of the same id from other
+    /// catalogs, the highest wins, `0` when unset.
+    pub priority: Option<i32>,
     /// Unique identifier for this component.
     pub id: AppId,
     /// A human-readable name.
     pub name: TranslatableString,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    /// The origin of the collection, could be something like `flathub`.
-    pub origin: Option<String>,
+    /// The origin of the component, could be something like `flathub`. It's the
+    /// one of its collection, unless the component sets its own, like in the
+    /// catalogs aggregating several upstream ones.
+    pub origin: Option<Arc<str>>,
     
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// A short summary of the component.
@@ -38,7 +68,7 @@ pub struct Component {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// A long description of this component.
-    pub description: Option<MarkupTranslatableString>,
+    pub description: Option<Arc<MarkupTranslatableString>>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// The license of the compoonent.
@@ -51,7 +81,7 @@ pub struct Component {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Identify the project with a specific upstream umbrella.
     /// Known values includes: GNOME, KDE, XFCE, MATE, LXDE.
-    pub project_group: Option<String>,
+    pub project_group: Option<Arc<str>>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Indicate for which desktop environment the component is essential for its functionality.
@@ -65,9 +95,9 @@ pub struct Component {
     /// The icons of the component.
     pub icons: Vec<Icon>,
 
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "is_empty")]
     /// The component screenshots, composed of either images, videos or both.
-    pub screenshots: Vec<Screenshot>,
+    pub screenshots: Arc<Vec<Screenshot>>,
 
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     /// Web URLs.
@@ -75,7 +105,7 @@ pub struct Component {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// The developers or the projects responsible for the development of the project.
-    pub developer_name: Option<TranslatableString>,
+    pub developer_name: Option<Arc<TranslatableString>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Used by distributors to contact the project.
@@ -102,13 +132,13 @@ pub struct Component {
     /// 3rd-party sources to grab the component from.
     pub bundles: Vec<Bundle>,
 
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "is_empty")]
     /// Metainformation that describes the various releases.
-    pub releases: Vec<Release>,
+    pub releases: Arc<Vec<Release>>,
 
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "is_empty")]
     /// The languages supported by the component.
-    pub languages: Vec<Language>,
+    pub languages: Arc<Vec<Language>>,
 
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     /// The MIME types the component supports.
@@ -118,13 +148,17 @@ pub struct Component {
     /// Defines the "awesomeness" of a component.
     pub kudos: Vec<Kudo>,
 
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// The primary colors of the brand of the component.
+    pub branding: Vec<BrandingColor>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// A list of keywords, to help the user find the component easily.
-    pub keywords: Option<TranslatableList>,
+    pub keywords: Option<Arc<TranslatableList>>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// Specifies the age rating of the component.
-    pub content_rating: Option<ContentRating>,
+    pub content_rating: Option<Arc<ContentRating>>,
 
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     /// Public interfaces the component provides.
@@ -138,25 +172,107 @@ pub struct Component {
     /// Suggested components to install.
     pub suggestions: Vec<AppId>,
 
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Recommended components.
+    pub recommendations: Vec<AppId>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// The ids of the components this one replaces, like its ids before a rename.
+    pub replaces: Vec<AppId>,
+
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     /// Required components.
     pub requirements: Vec<AppId>,
 
-    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Required firmware versions, of the device or of other ones.
+    pub firmware_requirements: Vec<FirmwareRequirement>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     /// Custom metadata.
-    pub metadata: HashMap<String, Option<String>>,
+    pub metadata: BTreeMap<String, Option<String>>,
+
+    #[cfg(feature = "raw")]
+    #[serde(skip)]
+    /// The child tags, or DEP-11 keys, of the component the crate doesn't model.
+    pub unknown_nodes: Vec<RawNode>,
+}
+
+fn is_empty<T>(list: &Arc<Vec<T>>) -> bool {
+    list.is_empty()
+}
+
+/// Orders the releases by version, then by date.
+fn compare_releases(a: &Release, b: &Release) -> Ordering {
+    vercmp(&a.version, &b.version).then_with(|| a.date.cmp(&b.date))
 }
 
 impl Component {
-    /// Create a new `Component` from an XML file.
+    /// Create a new `Component` from an XML file, like an upstream
+    /// `*.metainfo.xml` file or a legacy `*.appdata.xml` one.
     ///
     /// # Arguments
     ///
     /// * `path` - The path to the component.
     pub fn from_path(path: PathBuf) -> Result<Self, ParseError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Create a new `Component` from an XML document read out of `reader`.
+    ///
+    /// The root of the document has to be a `<component>` tag, catalogs are
+    /// parsed with `Collection` instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the uncompressed component.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::Component;
+    ///
+    /// let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <component type="desktop-application">
+    ///   <id>org.example.App</id>
+    ///   <name>App</name>
+    ///   <summary>An example application</summary>
+    /// </component>"#;
+    /// let component = Component::from_reader(xml.as_bytes()).unwrap();
+    /// assert_eq!(component.id, "org.example.App".into());
+    /// ```
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, ParseError> {
+        let element = Element::parse(reader)?;
+        if element.name != "component" {
+            return Err(ParseError::invalid_tag(&element.name));
+        }
+        Component::try_from(&element)
+    }
+
+    /// Create a new `Component` from an XML file, skipping the fields and checking
+    /// the limits set in `options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the component.
+    /// * `options` - The parsing options.
+    pub fn from_path_with_options(
+        path: PathBuf,
+        options: &ParseOptions,
+    ) -> Result<Self, ParseError> {
         let file = BufReader::new(File::open(path)?);
-        let component = Component::try_from(&Element::parse(file)?)?;
-        Ok(component)
+        reader::read_component(file, options)
+    }
+
+    /// Create a new `Component` from an XML file, along with the warnings about
+    /// what the parser skipped or recovered from.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the component.
+    pub fn from_path_with_warnings(path: PathBuf) -> Result<(Self, Vec<ParseWarning>), ParseError> {
+        let (component, warnings) = warnings::collect(|| Self::from_path(path));
+        Ok((component?, warnings))
     }
 
     #[cfg(feature = "gzip")]
@@ -188,6 +304,838 @@ impl Component {
         let component: Component = Component::try_from(&element)?;
         Ok(component)
     }
+
+    /// Fills the fields missing from the component with the ones of its desktop entry.
+    ///
+    /// The metainfo data always takes precedence: the name, summary, icon,
+    /// categories, keywords and mimetypes of the desktop entry are only used when the
+    /// component doesn't define them. The translations of the name and the summary
+    /// are completed as well, but only when both files agree on the untranslated text.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The desktop entry launching the component.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::ComponentBuilder;
+    /// use appstream::{DesktopEntry, TranslatableString};
+    ///
+    /// let entry: DesktopEntry = "[Desktop Entry]\nName=Contrast\nName[fr]=Contraste\nComment=Check contrast"
+    ///     .parse()
+    ///     .unwrap();
+    /// let mut component = ComponentBuilder::default()
+    ///     .id("org.gnome.design.Contrast".into())
+    ///     .name(TranslatableString::with_default("Contrast"))
+    ///     .build();
+    /// component.merge_desktop_entry(&entry);
+    /// assert_eq!(component.name.get_for_locale("fr"), Some(&"Contraste".to_string()));
+    /// assert_eq!(component.summary, Some(TranslatableString::with_default("Check contrast")));
+    /// ```
+    pub fn merge_desktop_entry(&mut self, entry: &DesktopEntry) {
+        merge_translations(&mut self.name, &entry.name);
+        match (&mut self.summary, &entry.comment) {
+            (Some(summary), Some(comment)) => merge_translations(summary, comment),
+            (None, Some(comment)) => self.summary = Some(comment.clone()),
+            _ => (),
+        }
+
+        if self.icons.is_empty() {
+            if let Some(icon) = &entry.icon {
+                let icon = if Path::new(icon).is_absolute() {
+                    Icon::Local {
+                        path: icon.into(),
+                        width: None,
+                        height: None,
+                    }
+                } else {
+                    Icon::Stock(icon.clone())
+                };
+                self.icons.push(icon);
+            }
+        }
+
+        if self.categories.is_empty() {
+            // Only the registered categories are kept, not the desktop specific ones.
+            let categories = entry.categories.iter();
+            for category in categories.filter_map(|c| Category::from_desktop_name(c)) {
+                self.push_category(category);
+            }
+        }
+
+        if self.keywords.as_ref().is_none_or(|k| k.is_empty()) {
+            self.keywords = entry.keywords.clone().map(Arc::new);
+        }
+
+        if self.mimetypes.is_empty() {
+            self.mimetypes = entry.mimetypes.clone();
+        }
+    }
+
+    /// Builds a desktop entry out of the component, the reverse of
+    /// `merge_desktop_entry`.
+    ///
+    /// The entry gets the name, summary as comment, icon, categories, keywords and
+    /// mimetypes of the component, with their translations. The icon is the stock
+    /// one, else the first local or cached icon. The `Exec` line isn't known from
+    /// the metadata, it's up to the tool writing the launcher.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::ComponentBuilder;
+    /// use appstream::enums::{Category, Icon};
+    /// use appstream::TranslatableString;
+    ///
+    /// let component = ComponentBuilder::default()
+    ///     .id("org.gnome.design.Contrast".into())
+    ///     .name(TranslatableString::with_default("Contrast").and_locale("fr", "Contraste"))
+    ///     .icon(Icon::Stock("org.gnome.design.Contrast".into()))
+    ///     .category(Category::Graphics)
+    ///     .build();
+    /// let entry = component.to_desktop_entry();
+    /// assert_eq!(
+    ///     entry.to_string(),
+    ///     "[Desktop Entry]\nType=Application\nName=Contrast\nName[fr]=Contraste\n\
+    ///      Icon=org.gnome.design.Contrast\nCategories=Graphics;\n"
+    /// );
+    /// ```
+    pub fn to_desktop_entry(&self) -> DesktopEntry {
+        let stock = self.icons.iter().find_map(|i| match i {
+            Icon::Stock(name) => Some(name.clone()),
+            _ => None,
+        });
+        let icon = stock.or_else(|| {
+            self.icons.iter().find_map(|i| match i {
+                Icon::Local { path, .. } => Some(path.to_string_lossy().into_owned()),
+                // Cached icons are looked up by name in the icon theme.
+                Icon::Cached { path, .. } => path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned()),
+                _ => None,
+            })
+        });
+
+        DesktopEntry {
+            kind: "Application".into(),
+            name: self.name.clone(),
+            comment: self.summary.clone(),
+            icon,
+            categories: self
+                .categories
+                .iter()
+                .map(|c| match c {
+                    Category::Unknown(name) => name.clone(),
+                    category => category.to_string(),
+                })
+                .collect(),
+            keywords: self.keywords.as_deref().cloned(),
+            mimetypes: self.mimetypes.clone(),
+            no_display: false,
+        }
+    }
+
+    /// Serializes the component as a `<component>` element of a catalog, without
+    /// an XML declaration, see `Collection::to_xml`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::ComponentBuilder;
+    /// use appstream::TranslatableString;
+    ///
+    /// let component = ComponentBuilder::default()
+    ///     .id("org.gnome.design.Contrast".into())
+    ///     .name(TranslatableString::with_default("Contrast").and_locale("fr", "Contraste"))
+    ///     .build();
+    /// assert_eq!(
+    ///     component.to_xml(),
+    ///     "<component type=\"generic\">\n  <id>org.gnome.design.Contrast</id>\n  \
+    ///      <name>Contrast</name>\n  <name xml:lang=\"fr\">Contraste</name>\n</component>\n"
+    /// );
+    /// ```
+    pub fn to_xml(&self) -> String {
+        let mut output = Vec::new();
+        XmlNode::from(self)
+            .write(&mut output, 0)
+            .expect("writing to a vector doesn't fail");
+        String::from_utf8(output).expect("the written XML is valid UTF-8")
+    }
+
+    /// Serializes the component as an upstream metainfo file, like
+    /// `org.gnome.design.Contrast.metainfo.xml`.
+    ///
+    /// Unlike `to_xml`, the document starts with an XML declaration and leaves
+    /// out what only the catalogs have: the package names, the bundles, the
+    /// cached icons, the origin and the merge attributes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::ComponentBuilder;
+    /// use appstream::enums::{ComponentKind, Launchable};
+    /// use appstream::{License, TranslatableString};
+    ///
+    /// let component = ComponentBuilder::default()
+    ///     .id("org.gnome.design.Contrast".into())
+    ///     .kind(ComponentKind::DesktopApplication)
+    ///     .name(TranslatableString::with_default("Contrast"))
+    ///     .metadata_license(License::from("CC0-1.0"))
+    ///     .launchable(Launchable::DesktopId("org.gnome.design.Contrast.desktop".into()))
+    ///     .pkgname("contrast")
+    ///     .build();
+    /// let metainfo = component.to_metainfo_xml();
+    /// assert!(metainfo.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<component type=\"desktop-application\">\n"));
+    /// assert!(metainfo.contains("  <metadata_license>CC0-1.0</metadata_license>\n"));
+    /// assert!(!metainfo.contains("<pkgname>"));
+    /// ```
+    pub fn to_metainfo_xml(&self) -> String {
+        let mut output = Vec::new();
+        xml_writer::write_document(&xml_writer::metainfo(self), &mut output)
+            .expect("writing to a vector doesn't fail");
+        String::from_utf8(output).expect("the written XML is valid UTF-8")
+    }
+
+    /// Whether the component is an application with a graphical interface, a
+    /// desktop or a web application.
+    pub fn is_gui_application(&self) -> bool {
+        matches!(
+            self.kind,
+            ComponentKind::DesktopApplication | ComponentKind::WebApplication
+        )
+    }
+
+    /// Whether the component is an addon of another one.
+    pub fn is_addon(&self) -> bool {
+        self.kind == ComponentKind::Addon
+    }
+
+    /// Whether the component is a font.
+    pub fn is_font(&self) -> bool {
+        self.kind == ComponentKind::Font
+    }
+
+    /// The names of the font faces the component provides, like `Lato Bold`.
+    pub fn font_faces(&self) -> Vec<&str> {
+        self.provides
+            .iter()
+            .filter_map(|p| match p {
+                Provide::Font(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether the component shows up in the application grid of a store.
+    ///
+    /// Only the graphical applications with a name are displayed, the merge
+    /// components, which only modify another component, and the generic
+    /// components or console applications aren't.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::ComponentBuilder;
+    /// use appstream::enums::{ComponentKind, MergeKind};
+    /// use appstream::TranslatableString;
+    ///
+    /// let builder = || {
+    ///     ComponentBuilder::default()
+    ///         .id("org.gnome.Maps".into())
+    ///         .name(TranslatableString::with_default("Maps"))
+    /// };
+    /// assert!(builder().kind(ComponentKind::DesktopApplication).build().is_displayable_in_store());
+    /// assert!(!builder().kind(ComponentKind::ConsoleApplication).build().is_displayable_in_store());
+    /// assert!(!builder()
+    ///     .kind(ComponentKind::DesktopApplication)
+    ///     .merge(MergeKind::Append)
+    ///     .build()
+    ///     .is_displayable_in_store());
+    /// ```
+    pub fn is_displayable_in_store(&self) -> bool {
+        self.merge.is_none() && self.is_gui_application() && !self.name.is_empty()
+    }
+
+    /// How to launch the component, from its launchables: its desktop file first,
+    /// then the URL of a web application, its Cockpit page, and its service last.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::launch::LaunchDescriptor;
+    /// use appstream::Component;
+    ///
+    /// let component = Component::from_path("./tests/app-org.gnome.design.Contrast.xml".into()).unwrap();
+    /// assert_eq!(
+    ///     component.launch_descriptor(),
+    ///     Some(LaunchDescriptor::DesktopFile("org.gnome.design.Contrast.desktop".into()))
+    /// );
+    /// ```
+    pub fn launch_descriptor(&self) -> Option<LaunchDescriptor> {
+        self.launchables
+            .iter()
+            .filter_map(LaunchDescriptor::from_launchable)
+            .min_by_key(LaunchDescriptor::preference)
+    }
+
+    #[cfg(feature = "raw")]
+    /// The first child tag, or DEP-11 key, of a name the crate doesn't model.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the tag or the key, like `sponsor`.
+    pub fn unknown_node(&self, name: &str) -> Option<&RawNode> {
+        self.unknown_nodes.iter().find(|n| n.name() == name)
+    }
+
+    /// The icon best fitting a size among all the icons of the component, with
+    /// the location it's loaded from.
+    ///
+    /// An icon of the exact size is preferred, then a stock icon, which the icon
+    /// theme renders at any size, then the smallest icon larger than the size so
+    /// it can be scaled down, and the largest of the smaller ones last. Between
+    /// icons of the same size, the cached and local ones are preferred to the
+    /// remote ones, which have to be downloaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The size the icon is displayed at, in logical pixels.
+    /// * `scale` - The scale factor of the display, `1` for the non-HiDPI ones.
+    /// * `resolver` - Rewrites the URLs of the remote icons.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::ComponentBuilder;
+    /// use appstream::enums::Icon;
+    /// use appstream::media::{IconSource, MediaResolver};
+    /// use appstream::TranslatableString;
+    /// use url::Url;
+    ///
+    /// let component = ComponentBuilder::default()
+    ///     .id("org.gnome.Maps".into())
+    ///     .name(TranslatableString::with_default("Maps"))
+    ///     .icon(Icon::Stock("org.gnome.Maps".into()))
+    ///     .icon(Icon::Remote {
+    ///         url: Url::parse("https://dl.flathub.org/media/maps-128.png").unwrap(),
+    ///         width: Some(128),
+    ///         height: Some(128),
+    ///     })
+    ///     .build();
+    /// let resolver = MediaResolver::new();
+    ///
+    /// let (_, source) = component.icon_for(64, 2, &resolver).unwrap();
+    /// assert_eq!(source, IconSource::Url(Url::parse("https://dl.flathub.org/media/maps-128.png").unwrap()));
+    /// let (_, source) = component.icon_for(64, 1, &resolver).unwrap();
+    /// assert_eq!(source, IconSource::Stock("org.gnome.Maps".into()));
+    /// ```
+    pub fn icon_for(
+        &self,
+        size: u32,
+        scale: u32,
+        resolver: &MediaResolver,
+    ) -> Option<(&Icon, IconSource)> {
+        let pixels = size * scale.max(1);
+        let icon = self.icons.iter().min_by_key(|icon| {
+            let (width, kind) = match icon {
+                Icon::Stock(_) => return (1, false, 0, 0),
+                Icon::Cached { width, .. } => (width, 0),
+                Icon::Local { width, .. } => (width, 1),
+                Icon::Remote { width, .. } => (width, 2),
+            };
+            match width {
+                Some(width) if *width == pixels => (0, false, 0, kind),
+                Some(width) => (
+                    2,
+                    *width < pixels,
+                    width.max(&pixels) - width.min(&pixels),
+                    kind,
+                ),
+                None => (3, false, 0, kind),
+            }
+        })?;
+
+        let source = match icon {
+            Icon::Stock(name) => IconSource::Stock(name.clone()),
+            Icon::Cached {
+                path,
+                width,
+                height,
+            } => {
+                let mut relative = PathBuf::new();
+                if let Some(origin) = &self.origin {
+                    relative.push(&**origin);
+                }
+                if let (Some(width), Some(height)) = (width, height) {
+                    relative.push(format!("{}x{}", width, height));
+                }
+                relative.push(path);
+                IconSource::Cached(relative)
+            }
+            Icon::Local { path, .. } => IconSource::Path(path.clone()),
+            Icon::Remote { url, .. } => IconSource::Url(resolver.resolve(url)),
+        };
+        Some((icon, source))
+    }
+
+    /// A SHA-256 hash of the content of the component, as an hex string.
+    ///
+    /// The hash is computed on a canonical encoding of the component, so it doesn't
+    /// depend on the format the component was parsed from nor on the order of its
+    /// fields, only on their values. Comparing the hashes of two catalog snapshots
+    /// tells which components changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::ComponentBuilder;
+    /// use appstream::TranslatableString;
+    ///
+    /// let component = ComponentBuilder::default()
+    ///     .id("org.gnome.Weather".into())
+    ///     .name(TranslatableString::with_default("Weather"))
+    ///     .build();
+    /// let mut changed = component.clone();
+    /// assert_eq!(component.content_hash(), changed.content_hash());
+    /// changed.pkgname = Some("gnome-weather".into());
+    /// assert_ne!(component.content_hash(), changed.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> String {
+        let bytes = canonical::to_bytes(self).expect("components are always serializable");
+        let mut hasher = Sha256::default();
+        hasher.update(&bytes);
+        digest::to_hex(&hasher.finalize())
+    }
+
+    /// Sets the summary.
+    pub fn set_summary(&mut self, summary: TranslatableString) {
+        self.summary = Some(summary);
+    }
+
+    /// Sets the description.
+    pub fn set_description(&mut self, description: MarkupTranslatableString) {
+        self.description = Some(Arc::new(description));
+    }
+
+    /// Sets the developer name.
+    pub fn set_developer_name(&mut self, developer_name: TranslatableString) {
+        self.developer_name = Some(Arc::new(developer_name));
+    }
+
+    /// Sets the keywords.
+    pub fn set_keywords(&mut self, keywords: TranslatableList) {
+        self.keywords = Some(Arc::new(keywords));
+    }
+
+    /// Sets the age rating.
+    pub fn set_content_rating(&mut self, content_rating: ContentRating) {
+        self.content_rating = Some(Arc::new(content_rating));
+    }
+
+    /// Adds an icon.
+    pub fn push_icon(&mut self, icon: Icon) {
+        self.icons.push(icon);
+    }
+
+    /// Removes all the icons.
+    pub fn clear_icons(&mut self) {
+        self.icons.clear();
+    }
+
+    /// Adds a category, unless the component already has it.
+    pub fn push_category(&mut self, category: Category) {
+        if !self.categories.contains(&category) {
+            self.categories.push(category);
+        }
+    }
+
+    /// Removes a category, returns whether the component had it.
+    pub fn remove_category(&mut self, category: &Category) -> bool {
+        let len = self.categories.len();
+        self.categories.retain(|c| c != category);
+        self.categories.len() != len
+    }
+
+    /// The screenshots in the order they should be displayed: the default one
+    /// first, then the others in catalog order.
+    ///
+    /// With a preferred environment, the screenshots taken in it come first, then
+    /// the ones taken in the same desktop with another style, then the ones
+    /// without an environment and the ones of other environments last.
+    ///
+    /// # Arguments
+    ///
+    /// * `environment` - The preferred environment, like `gnome` or `gnome:dark`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::{ComponentBuilder, ScreenshotBuilder};
+    /// use appstream::TranslatableString;
+    ///
+    /// let component = ComponentBuilder::default()
+    ///     .id("org.kde.kate".into())
+    ///     .name(TranslatableString::with_default("Kate"))
+    ///     .screenshot(ScreenshotBuilder::default().environment("plasma").build())
+    ///     .screenshot(ScreenshotBuilder::default().set_default(false).environment("gnome").build())
+    ///     .build();
+    /// let ordered = component.screenshots_ordered(Some("gnome:dark"));
+    /// assert_eq!(ordered[0].environment.as_deref(), Some("gnome"));
+    /// ```
+    pub fn screenshots_ordered(&self, environment: Option<&str>) -> Vec<&Screenshot> {
+        let mut screenshots = self.screenshots.iter().collect::<Vec<&Screenshot>>();
+        screenshots.sort_by_key(|s| {
+            let rank = match (environment, s.environment.as_deref()) {
+                (None, _) => 0,
+                (Some(preferred), Some(taken)) if preferred == taken => 0,
+                (Some(preferred), Some(taken))
+                    if preferred.split(':').next() == taken.split(':').next() =>
+                {
+                    1
+                }
+                (Some(_), None) => 2,
+                (Some(_), Some(_)) => 3,
+            };
+            (rank, !s.is_default)
+        });
+        screenshots
+    }
+
+    /// The screenshots, to be modified in place. They are copied first if they are
+    /// shared with a clone of the component.
+    pub fn screenshots_mut(&mut self) -> &mut Vec<Screenshot> {
+        Arc::make_mut(&mut self.screenshots)
+    }
+
+    /// Adds a screenshot.
+    pub fn push_screenshot(&mut self, screenshot: Screenshot) {
+        self.screenshots_mut().push(screenshot);
+    }
+
+    /// Removes the screenshot at `index`, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The position of the screenshot.
+    pub fn remove_screenshot(&mut self, index: usize) -> Option<Screenshot> {
+        if index >= self.screenshots.len() {
+            return None;
+        }
+        Some(self.screenshots_mut().remove(index))
+    }
+
+    /// Removes all the screenshots.
+    pub fn clear_screenshots(&mut self) {
+        self.screenshots = Arc::default();
+    }
+
+    /// The release with the highest version, compared with `vercmp`, the newest
+    /// one between releases of equal versions.
+    pub fn latest_release(&self) -> Option<&Release> {
+        self.releases.iter().max_by(|a, b| compare_releases(a, b))
+    }
+
+    /// The latest release, ignoring the development releases and the snapshots.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::{ComponentBuilder, ReleaseBuilder};
+    /// use appstream::enums::ReleaseKind;
+    /// use appstream::TranslatableString;
+    ///
+    /// let component = ComponentBuilder::default()
+    ///     .id("org.gnome.Maps".into())
+    ///     .name(TranslatableString::with_default("Maps"))
+    ///     .release(ReleaseBuilder::new("41.beta").kind(ReleaseKind::Development).build())
+    ///     .release(ReleaseBuilder::new("40.1").build())
+    ///     .build();
+    /// assert_eq!(component.latest_release().unwrap().version, "41.beta");
+    /// assert_eq!(component.latest_stable_release().unwrap().version, "40.1");
+    /// ```
+    pub fn latest_stable_release(&self) -> Option<&Release> {
+        self.releases
+            .iter()
+            .filter(|r| r.kind == ReleaseKind::Stable)
+            .max_by(|a, b| compare_releases(a, b))
+    }
+
+    /// The latest stable release if it's newer than the installed version,
+    /// compared with `vercmp`.
+    ///
+    /// # Arguments
+    ///
+    /// * `installed_version` - The version currently installed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::{ComponentBuilder, ReleaseBuilder};
+    /// use appstream::TranslatableString;
+    ///
+    /// let component = ComponentBuilder::default()
+    ///     .id("org.gnome.Maps".into())
+    ///     .name(TranslatableString::with_default("Maps"))
+    ///     .release(ReleaseBuilder::new("40.1").build())
+    ///     .build();
+    /// assert_eq!(component.update_available("3.38").unwrap().version, "40.1");
+    /// assert!(component.update_available("40.1").is_none());
+    /// ```
+    pub fn update_available(&self, installed_version: &str) -> Option<&Release> {
+        self.latest_stable_release()
+            .filter(|r| vercmp(&r.version, installed_version) == Ordering::Greater)
+    }
+
+    /// The changes of the releases newer than `from_version`, up to `to_version`
+    /// included, to present what's new after an update skipping several versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_version` - The version installed before the update.
+    /// * `to_version` - The version installed by the update.
+    /// * `locale` - The locale of the descriptions, falling back to its language
+    ///   and to the untranslated ones.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::Component;
+    ///
+    /// let component = Component::from_path("./tests/firmware.xml".into()).unwrap();
+    /// let changelog = component.changelog_between("3.0.0", "3.0.2", Some("de_DE"));
+    /// for (release, description) in changelog.releases {
+    ///     println!("{}: {}", release.version, description.unwrap_or_default());
+    /// }
+    /// ```
+    pub fn changelog_between(
+        &self,
+        from_version: &str,
+        to_version: &str,
+        locale: Option<&str>,
+    ) -> Changelog<'_> {
+        let mut releases = self
+            .releases
+            .iter()
+            .filter(|r| {
+                vercmp(&r.version, from_version) == Ordering::Greater
+                    && vercmp(&r.version, to_version) != Ordering::Greater
+            })
+            .collect::<Vec<&Release>>();
+        releases.sort_by(|a, b| compare_releases(b, a));
+        Changelog::new(releases, locale)
+    }
+
+    /// Sorts the releases from the newest to the oldest version, compared with
+    /// `vercmp`. The releases of equal versions are ordered by date.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::{ComponentBuilder, ReleaseBuilder};
+    /// use appstream::TranslatableString;
+    ///
+    /// let mut component = ComponentBuilder::default()
+    ///     .id("org.gnome.Maps".into())
+    ///     .name(TranslatableString::with_default("Maps"))
+    ///     .release(ReleaseBuilder::new("3.38").build())
+    ///     .release(ReleaseBuilder::new("40~rc").build())
+    ///     .release(ReleaseBuilder::new("40.1").build())
+    ///     .build();
+    /// component.sort_releases();
+    /// let versions = component.releases.iter().map(|r| r.version.as_str()).collect::<Vec<_>>();
+    /// assert_eq!(versions, vec!["40.1", "40~rc", "3.38"]);
+    /// ```
+    pub fn sort_releases(&mut self) {
+        self.releases_mut().sort_by(|a, b| compare_releases(b, a));
+    }
+
+    /// The releases, to be modified in place. They are copied first if they are
+    /// shared with a clone of the component.
+    pub fn releases_mut(&mut self) -> &mut Vec<Release> {
+        Arc::make_mut(&mut self.releases)
+    }
+
+    /// Adds a release.
+    pub fn push_release(&mut self, release: Release) {
+        self.releases_mut().push(release);
+    }
+
+    /// Removes the release with a specific version, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version of the release.
+    pub fn remove_release(&mut self, version: &str) -> Option<Release> {
+        let index = self.releases.iter().position(|r| r.version == version)?;
+        Some(self.releases_mut().remove(index))
+    }
+
+    /// Removes all the releases.
+    pub fn clear_releases(&mut self) {
+        self.releases = Arc::default();
+    }
+
+    /// The languages, to be modified in place. They are copied first if they are
+    /// shared with a clone of the component.
+    pub fn languages_mut(&mut self) -> &mut Vec<Language> {
+        Arc::make_mut(&mut self.languages)
+    }
+
+    /// Adds a language.
+    pub fn push_language(&mut self, language: Language) {
+        self.languages_mut().push(language);
+    }
+
+    /// Removes all the languages.
+    pub fn clear_languages(&mut self) {
+        self.languages = Arc::default();
+    }
+
+    /// Sets a custom metadata, replacing the previous value of `key`.
+    pub fn set_metadata(&mut self, key: &str, value: Option<String>) {
+        self.metadata.insert(key.to_string(), value);
+    }
+
+    /// Removes a custom metadata, returns whether the component had it.
+    pub fn remove_metadata(&mut self, key: &str) -> bool {
+        self.metadata.remove(key).is_some()
+    }
+}
+
+/// The unknown nodes kept with the `raw` feature aren't compared, like they
+/// aren't serialized.
+impl PartialEq for Component {
+    fn eq(&self, other: &Self) -> bool {
+        // Destructured so a new field can't be forgotten.
+        let Self {
+            kind,
+            merge,
+            priority,
+            id,
+            name,
+            origin,
+            summary,
+            description,
+            project_license,
+            metadata_license,
+            project_group,
+            compulsory_for_desktop,
+            extends,
+            icons,
+            screenshots,
+            urls,
+            developer_name,
+            update_contact,
+            categories,
+            launchables,
+            pkgname,
+            source_pkgname,
+            bundles,
+            releases,
+            languages,
+            mimetypes,
+            kudos,
+            branding,
+            keywords,
+            content_rating,
+            provides,
+            translations,
+            suggestions,
+            recommendations,
+            replaces,
+            requirements,
+            firmware_requirements,
+            metadata,
+            #[cfg(feature = "raw")]
+            unknown_nodes,
+        } = self;
+        #[cfg(feature = "raw")]
+        let _ = unknown_nodes;
+        kind == &other.kind
+            && merge == &other.merge
+            && priority == &other.priority
+            && id == &other.id
+            && name == &other.name
+            && origin == &other.origin
+            && summary == &other.summary
+            && description == &other.description
+            && project_license == &other.project_license
+            && metadata_license == &other.metadata_license
+            && project_group == &other.project_group
+            && compulsory_for_desktop == &other.compulsory_for_desktop
+            && extends == &other.extends
+            && icons == &other.icons
+            && screenshots == &other.screenshots
+            && urls == &other.urls
+            && developer_name == &other.developer_name
+            && update_contact == &other.update_contact
+            && categories == &other.categories
+            && launchables == &other.launchables
+            && pkgname == &other.pkgname
+            && source_pkgname == &other.source_pkgname
+            && bundles == &other.bundles
+            && releases == &other.releases
+            && languages == &other.languages
+            && mimetypes == &other.mimetypes
+            && kudos == &other.kudos
+            && branding == &other.branding
+            && keywords == &other.keywords
+            && content_rating == &other.content_rating
+            && provides == &other.provides
+            && translations == &other.translations
+            && suggestions == &other.suggestions
+            && recommendations == &other.recommendations
+            && replaces == &other.replaces
+            && requirements == &other.requirements
+            && firmware_requirements == &other.firmware_requirements
+            && metadata == &other.metadata
+    }
+}
+
+impl Eq for Component {}
+
+impl PartialOrd for Component {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Components are ordered by id, then by origin. The rare components sharing both
+/// are ordered by their canonical serialization, so the order stays consistent with
+/// equality and doesn't depend on the order they were loaded in.
+impl Ord for Component {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id
+            .cmp(&other.id)
+            .then_with(|| self.origin.cmp(&other.origin))
+            .then_with(|| {
+                let bytes = |c: &Component| {
+                    canonical::to_bytes(c).expect("components are always serializable")
+                };
+                bytes(self).cmp(&bytes(other))
+            })
+    }
+}
+
+/// Completes `target` with the translations of `source`.
+///
+/// Everything is taken from `source` if `target` is empty, the missing locales
+/// only if both have the same untranslated text otherwise.
+fn merge_translations(target: &mut TranslatableString, source: &TranslatableString) {
+    if target.is_empty() {
+        *target = source.clone();
+        return;
+    }
+    if target.get_default() != source.get_default() {
+        return;
+    }
+    for (locale, text) in source.0.iter() {
+        target
+            .0
+            .entry(locale.clone())
+            .or_insert_with(|| text.clone());
+    }
 }
 
 #[cfg(test)]
@@ -202,10 +1150,14 @@ mod tests {
         ArtifactKind, Bundle, Category, ComponentKind, ContentRatingVersion, FirmwareKind, Icon,
         ImageKind, Kudo, Launchable, ProjectUrl, Provide, ReleaseKind, Translation,
     };
+    use crate::error::ParseError;
+    use crate::Timestamp;
     use crate::{ContentRating, MarkupTranslatableString, TranslatableList, TranslatableString};
-    use chrono::{TimeZone, Utc};
+    use std::convert::TryFrom;
     use std::error::Error;
+    use std::sync::Arc;
     use url::Url;
+    use xmltree::Element;
 
     #[test]
     fn addon_component() -> Result<(), Box<dyn Error>> {
@@ -230,6 +1182,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn content_hash() -> Result<(), Box<dyn Error>> {
+        let c1 = Component::from_path("./tests/desktop.xml".into())?;
+
+        // Same document, with the tags of the component in another order.
+        let mut element = Element::parse(std::fs::read("./tests/desktop.xml")?.as_slice())?;
+        element
+            .children
+            .sort_by_key(|node| std::cmp::Reverse(node.as_element().map(|e| e.name.clone())));
+        let c2 = Component::try_from(&element)?;
+        assert_eq!(c1.content_hash(), c2.content_hash());
+
+        let mut c3 = c1.clone();
+        let release = c3.releases[0].clone();
+        Arc::make_mut(&mut c3.releases).push(release);
+        assert_ne!(c1.content_hash(), c3.content_hash());
+
+        let c4 = ComponentBuilder::default()
+            .id("org.gnome.Weather".into())
+            .name(TranslatableString::with_default("Weather"))
+            .build();
+        // Changing the encoding would invalidate the hashes stored by indexers.
+        assert_eq!(
+            c4.content_hash(),
+            "eb3b663b6033db33a0e306c9da10b8f4422895715b4fe4740ef0cb28c3aec4c0"
+        );
+        Ok(())
+    }
+
     #[test]
     fn codec_component() -> Result<(), Box<dyn Error>> {
         let c1 = Component::from_path("./tests/codec.xml".into())?;
@@ -311,7 +1292,7 @@ mod tests {
             .release(
                 ReleaseBuilder::new("3.12.2")
                     .description(MarkupTranslatableString::with_default("<p>Fixes issues X, Y and Z</p>"))
-                    .date(Utc.ymd(2013, 4, 12).and_hms_milli(0, 0, 0, 0))
+                    .date(Timestamp::from_ymd(2013, 4, 12).unwrap())
                     .build(),
             )
             .build();
@@ -375,7 +1356,7 @@ mod tests {
             })
             .release(
                 ReleaseBuilder::new("3.0.2")
-                    .date(Utc.ymd(2015, 2, 16).and_hms_milli(0, 0, 0, 0))
+                    .date(Timestamp::from_ymd(2015, 2, 16).unwrap())
                     .artifact(
                         ArtifactBuilder::default()
                         .url(Url::parse("http://www.hughski.com/downloads/colorhug-als/firmware/colorhug-als-3.0.2.cab")?) 
@@ -436,7 +1417,7 @@ mod tests {
             .provide(Provide::Binary("foobar".into()))
             .release(
                 ReleaseBuilder::new("1.2")
-                    .date(Utc.ymd(2015, 2, 16).and_hms_milli(0, 0, 0, 0))
+                    .date(Timestamp::from_ymd(2015, 2, 16).unwrap())
                     .build(),
             )
             .build();
@@ -552,8 +1533,8 @@ mod tests {
             .release(
                 ReleaseBuilder::new("9.0")
                     .description(MarkupTranslatableString::with_default("<p>Now contains the Linux kernel 4.9, GNOME 3.22, KDE Plasma 5, LibreOffice 5.2 and Qt 5.7. LXQt has been added.</p>"))
-                    .date(Utc.ymd(2017, 7, 17).and_hms_milli(0, 0, 0, 0))
-                    .date_eol(Utc.ymd(2020, 7, 17).and_hms_milli(0, 0, 0, 0))
+                    .date(Timestamp::from_ymd(2017, 7, 17).unwrap())
+                    .date_eol(Timestamp::from_ymd(2020, 7, 17).unwrap())
                     .build(),
             )
             .build();
@@ -583,7 +1564,7 @@ mod tests {
             .release(ReleaseBuilder::new("10.0").build())
             .release(
                 ReleaseBuilder::new("9.0")
-                    .date(Utc.ymd(2020, 01, 12).and_hms_milli(0, 0, 0, 0))
+                    .date(Timestamp::from_ymd(2020, 1, 12).unwrap())
                     .build(),
             )
             .build();
@@ -706,19 +1687,19 @@ mod tests {
             })
             .release(
                 ReleaseBuilder::new("0.0.3")
-                    .date(Utc.datetime_from_str("1582329600", "%s")?)
+                    .date(Timestamp::from_unix(1582329600))
                     .description(MarkupTranslatableString::with_default("<p>Stylesheet fixes</p><p>Translations updates</p>"))
                     .build()
             )
             .release(
                 ReleaseBuilder::new("0.0.2")
-                    .date(Utc.datetime_from_str("1566691200", "%s")?)
+                    .date(Timestamp::from_unix(1566691200))
                     .description(MarkupTranslatableString::with_default("<p>Translations updates</p>"))
                     .build()
             )
             .release(
                 ReleaseBuilder::new("0.0.1")
-                    .date(Utc.datetime_from_str("1565136000", "%s")?)
+                    .date(Timestamp::from_unix(1565136000))
                     .description(MarkupTranslatableString::with_default("<p>First release of Contrast</p>"))
                     .build()
             )
@@ -784,4 +1765,348 @@ mod tests {
         assert_eq!(c1, c2);
         Ok(())
     }
+
+    #[test]
+    fn merge_desktop_entry() -> Result<(), Box<dyn Error>> {
+        let entry = crate::DesktopEntry::from_path(
+            "./tests/compose/usr/share/applications/org.gnome.design.Contrast.desktop".as_ref(),
+        )?;
+
+        let mut c1 = ComponentBuilder::default()
+            .id("org.gnome.design.Contrast".into())
+            .name(TranslatableString::with_default("Contrast").and_locale("de", "Kontrast"))
+            .summary(TranslatableString::with_default("Check contrast"))
+            .category(Category::Development)
+            .build();
+        c1.merge_desktop_entry(&entry);
+
+        let c2 = ComponentBuilder::default()
+            .id("org.gnome.design.Contrast".into())
+            .name(
+                TranslatableString::with_default("Contrast")
+                    .and_locale("de", "Kontrast")
+                    .and_locale("fr", "Contraste"),
+            )
+            .summary(TranslatableString::with_default("Check contrast"))
+            .category(Category::Development)
+            .icon(Icon::Stock("org.gnome.design.Contrast".into()))
+            .keywords(
+                TranslatableList::with_default(vec!["Color", "Contrast"])
+                    .and_locale("fr", vec!["Couleur"]),
+            )
+            .build();
+        assert_eq!(c1, c2);
+
+        // The translations of a different text are not merged
+        let mut c3 = ComponentBuilder::default()
+            .id("org.gnome.design.Contrast".into())
+            .name(TranslatableString::with_default("Contrast Checker"))
+            .build();
+        c3.merge_desktop_entry(&entry);
+        assert_eq!(
+            c3.name,
+            TranslatableString::with_default("Contrast Checker")
+        );
+        assert_eq!(c3.categories, vec![Category::Utility, Category::Graphics]);
+
+        // The categories are normalized
+        let mut entry = entry.clone();
+        entry.categories = vec![
+            "GTK".into(),
+            "2DGraphics".into(),
+            "Internet".into(),
+            "Network".into(),
+            "X-GNOME-Utilities".into(),
+            "TrayIcon".into(),
+        ];
+        let mut c4 = ComponentBuilder::default()
+            .id("org.gnome.design.Contrast".into())
+            .name(TranslatableString::with_default("Contrast"))
+            .build();
+        c4.merge_desktop_entry(&entry);
+        assert_eq!(
+            c4.categories,
+            vec![Category::TwoDGraphics, Category::Network]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn edit_in_place() -> Result<(), Box<dyn Error>> {
+        let original = Component::from_path("./tests/app-org.gnome.design.Contrast.xml".into())?;
+        let mut c = original.clone();
+        c.set_summary(TranslatableString::with_default("Check colors"));
+        c.push_category(Category::Utility);
+        c.push_category(Category::Utility);
+        assert!(c.remove_category(&Category::Utility));
+        assert!(!c.remove_category(&Category::Utility));
+        c.clear_icons();
+
+        let version = original.releases[0].version.clone();
+        let release = c.remove_release(&version).ok_or("no release")?;
+        assert!(c.remove_release(&version).is_none());
+        assert!(c.remove_screenshot(c.screenshots.len()).is_none());
+        let screenshot = c.remove_screenshot(0).ok_or("no screenshot")?;
+        c.set_metadata("X-Editor", Some("true".into()));
+
+        assert_eq!(
+            c.summary,
+            Some(TranslatableString::with_default("Check colors"))
+        );
+        assert!(c.icons.is_empty());
+        assert_eq!(c.releases.len(), original.releases.len() - 1);
+        assert_eq!(c.screenshots.len(), original.screenshots.len() - 1);
+        // The original is untouched by the edits of its clone
+        assert_eq!(original.releases[0], release);
+        assert_eq!(original.screenshots[0], screenshot);
+
+        c.push_release(release);
+        c.push_screenshot(screenshot);
+        assert!(c.remove_metadata("X-Editor"));
+        c.clear_releases();
+        c.clear_screenshots();
+        assert!(c.releases.is_empty() && c.screenshots.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn cheap_clone() -> Result<(), Box<dyn Error>> {
+        let c1 = Component::from_path("./tests/app-org.gnome.design.Contrast.xml".into())?;
+        let mut c2 = c1.clone();
+        assert!(Arc::ptr_eq(&c1.releases, &c2.releases));
+        assert!(Arc::ptr_eq(&c1.screenshots, &c2.screenshots));
+
+        // Modifying a clone leaves the original untouched
+        Arc::make_mut(&mut c2.releases).clear();
+        assert!(!Arc::ptr_eq(&c1.releases, &c2.releases));
+        assert!(!c1.releases.is_empty());
+        assert!(c2.releases.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn icon_for_size() -> Result<(), Box<dyn Error>> {
+        use crate::media::{IconSource, MediaResolver};
+
+        let remote = |width: u32| -> Result<Icon, url::ParseError> {
+            Ok(Icon::Remote {
+                url: Url::parse(&format!("https://dl.flathub.org/media/{}.png", width))?,
+                width: Some(width),
+                height: Some(width),
+            })
+        };
+        let mut component = ComponentBuilder::default()
+            .id("org.gnome.Maps".into())
+            .name(TranslatableString::with_default("Maps"))
+            .icon(remote(128)?)
+            .icon(Icon::Cached {
+                path: "org.gnome.Maps.png".into(),
+                width: Some(64),
+                height: Some(64),
+            })
+            .icon(remote(64)?)
+            .icon(remote(32)?)
+            .build();
+        component.origin = Some("flathub".into());
+        let resolver = MediaResolver::new().rewrite(
+            &Url::parse("https://dl.flathub.org/media/")?,
+            Url::parse("https://mirror.example.org/")?,
+        );
+
+        let (icon, source) = component.icon_for(64, 1, &resolver).ok_or("no icon")?;
+        assert!(matches!(icon, Icon::Cached { .. }));
+        assert_eq!(
+            source,
+            IconSource::Cached("flathub/64x64/org.gnome.Maps.png".into())
+        );
+        let (_, source) = component.icon_for(48, 2, &resolver).ok_or("no icon")?;
+        assert_eq!(
+            source,
+            IconSource::Url(Url::parse("https://mirror.example.org/128.png")?)
+        );
+        let (_, source) = component.icon_for(40, 1, &resolver).ok_or("no icon")?;
+        assert_eq!(
+            source,
+            IconSource::Cached("flathub/64x64/org.gnome.Maps.png".into())
+        );
+        let (_, source) = component.icon_for(256, 1, &resolver).ok_or("no icon")?;
+        assert_eq!(
+            source,
+            IconSource::Url(Url::parse("https://mirror.example.org/128.png")?)
+        );
+
+        component.push_icon(Icon::Local {
+            path: "/usr/share/pixmaps/maps.png".into(),
+            width: None,
+            height: None,
+        });
+        component.push_icon(Icon::Stock("org.gnome.Maps".into()));
+        let (_, source) = component.icon_for(48, 1, &resolver).ok_or("no icon")?;
+        assert_eq!(source, IconSource::Stock("org.gnome.Maps".into()));
+        let (_, source) = component.icon_for(32, 1, &resolver).ok_or("no icon")?;
+        assert_eq!(
+            source,
+            IconSource::Url(Url::parse("https://mirror.example.org/32.png")?)
+        );
+
+        component.clear_icons();
+        assert_eq!(component.icon_for(64, 1, &resolver), None);
+        Ok(())
+    }
+
+    #[test]
+    fn ordered_screenshots() -> Result<(), Box<dyn Error>> {
+        let component = Component::try_from(&xmltree::Element::parse(
+            r#"<component>
+                <id>org.kde.kate</id>
+                <name>Kate</name>
+                <screenshots>
+                    <screenshot environment="plasma"><image>https://example.org/a.png</image></screenshot>
+                    <screenshot type="default" environment="plasma:dark"><image>https://example.org/b.png</image></screenshot>
+                    <screenshot><image>https://example.org/c.png</image></screenshot>
+                    <screenshot environment="gnome:dark"><image>https://example.org/d.png</image></screenshot>
+                    <screenshot environment="gnome"><image>https://example.org/e.png</image></screenshot>
+                </screenshots>
+            </component>"#
+                .as_bytes(),
+        )?)?;
+        let order = |environment| {
+            component
+                .screenshots_ordered(environment)
+                .iter()
+                .map(|s| s.images[0].url.path().to_string())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            order(None),
+            vec!["/b.png", "/a.png", "/c.png", "/d.png", "/e.png"]
+        );
+        assert_eq!(
+            order(Some("gnome")),
+            vec!["/e.png", "/d.png", "/c.png", "/b.png", "/a.png"]
+        );
+        assert_eq!(
+            order(Some("plasma")),
+            vec!["/a.png", "/b.png", "/c.png", "/d.png", "/e.png"]
+        );
+        assert_eq!(
+            component.screenshots[1].environment.as_deref(),
+            Some("plasma:dark")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn classification() -> Result<(), Box<dyn Error>> {
+        let parse = |xml: &str| -> Result<Component, Box<dyn Error>> {
+            Ok(Component::try_from(&xmltree::Element::parse(
+                xml.as_bytes(),
+            )?)?)
+        };
+        let app = parse(
+            r#"<component type="desktop-application"><id>org.gnome.Maps</id><name>Maps</name></component>"#,
+        )?;
+        assert!(app.is_gui_application() && app.is_displayable_in_store());
+        assert!(!app.is_addon() && !app.is_font());
+
+        let merge = parse(
+            r#"<component type="desktop-application" merge="remove-component"><id>org.gnome.Maps</id></component>"#,
+        )?;
+        assert_eq!(merge.merge, Some(crate::enums::MergeKind::RemoveComponent));
+        assert!(merge.is_gui_application() && !merge.is_displayable_in_store());
+
+        let font = parse(
+            r#"<component type="font"><id>org.example.Font</id><name>Font</name></component>"#,
+        )?;
+        assert!(font.is_font() && !font.is_displayable_in_store());
+        let addon = parse(
+            r#"<component type="addon"><id>org.example.Addon</id><name>Addon</name></component>"#,
+        )?;
+        assert!(addon.is_addon() && !addon.is_gui_application());
+        let generic = parse(r#"<component><id>org.example.Lib</id><name>Lib</name></component>"#)?;
+        assert!(!generic.is_displayable_in_store());
+        assert!(
+            parse(r#"<component merge="prepend"><id>org.example.Lib</id></component>"#).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn latest_releases() -> Result<(), Box<dyn Error>> {
+        let component = Component::try_from(&xmltree::Element::parse(
+            r#"<component>
+                <id>org.gnome.Maps</id>
+                <name>Maps</name>
+                <releases>
+                    <release version="40.1" date="2021-04-20"/>
+                    <release version="41~beta" type="development" date="2021-08-10"/>
+                    <release version="1:3.38" date="2020-09-10"/>
+                    <release version="1:3.38" date="2020-10-02"/>
+                    <release version="1:3.38.1" type="snapshot" date="2020-10-03"/>
+                </releases>
+            </component>"#
+                .as_bytes(),
+        )?)?;
+        let latest = component.latest_release().ok_or("no release")?;
+        assert_eq!(latest.version, "1:3.38.1");
+        let stable = component.latest_stable_release().ok_or("no release")?;
+        assert_eq!(stable.version, "1:3.38");
+        assert_eq!(stable.date, Some("2020-10-02".parse::<Timestamp>()?));
+
+        let mut sorted = component.clone();
+        sorted.sort_releases();
+        let versions = sorted
+            .releases
+            .iter()
+            .map(|r| r.version.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            versions,
+            vec!["1:3.38.1", "1:3.38", "1:3.38", "41~beta", "40.1"]
+        );
+        assert_eq!(sorted.releases[1].date, stable.date);
+        Ok(())
+    }
+
+    #[test]
+    fn from_reader() -> Result<(), Box<dyn Error>> {
+        let path = "./tests/app-org.gnome.design.Contrast.xml";
+        let component = Component::from_reader(std::fs::File::open(path)?)?;
+        assert_eq!(component, Component::from_path(path.into())?);
+
+        let catalog = std::fs::File::open("./tests/collections/spec_example.xml")?;
+        assert!(matches!(
+            Component::from_reader(catalog),
+            Err(ParseError::InvalidTag(tag)) if tag == "components"
+        ));
+        assert!(Component::from_reader("<component><id>".as_bytes()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn metainfo_xml() -> Result<(), Box<dyn Error>> {
+        for entry in std::fs::read_dir("./tests")? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != "xml") {
+                continue;
+            }
+            let component = Component::from_path(path.clone())?;
+            let metainfo = component.to_metainfo_xml();
+            let parsed = Component::try_from(&xmltree::Element::parse(metainfo.as_bytes())?)?;
+
+            let mut expected = component.clone();
+            expected.bundles.clear();
+            expected
+                .icons
+                .retain(|icon| !matches!(icon, Icon::Cached { .. }));
+            assert_eq!(parsed, expected, "{}", path.display());
+        }
+
+        let component = Component::from_path("./tests/app-org.gnome.design.Contrast.xml".into())?;
+        let metainfo = component.to_metainfo_xml();
+        assert!(metainfo.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+        assert!(!metainfo.contains("<bundle"));
+        assert!(!metainfo.contains("type=\"cached\""));
+        Ok(())
+    }
 }
@@ -0,0 +1,607 @@
+use super::builders::CollectionBuilder;
+#[cfg(feature = "image")]
+use super::enums::Icon;
+use super::enums::Launchable;
+use super::error::ParseError;
+#[cfg(feature = "image")]
+use super::icons::safe_path;
+#[cfg(feature = "image")]
+use super::image_size;
+use super::validation::ValidationIssue;
+use super::{AppId, Collection, Component, DesktopEntry};
+use std::collections::BTreeMap;
+use std::fs;
+#[cfg(feature = "image")]
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "image")]
+/// The sizes of the cached icons generated by default.
+pub const DEFAULT_ICON_SIZES: &[u32] = &[48, 64, 128];
+
+#[cfg(feature = "image")]
+/// Renders an icon at a given size.
+///
+/// The crate doesn't ship any image decoder, the icons already available at the
+/// requested size are copied as is and the other ones are scaled through this trait.
+pub trait IconScaler {
+    /// Renders the image at `source`, a PNG or an SVG file, as a `size`x`size` PNG image.
+    fn scale(&self, source: &Path, size: u32) -> io::Result<Vec<u8>>;
+}
+
+#[cfg(feature = "image")]
+struct IconCache {
+    output: PathBuf,
+    sizes: Vec<u32>,
+    scaler: Box<dyn IconScaler>,
+}
+
+#[cfg(feature = "image")]
+impl std::fmt::Debug for IconCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IconCache")
+            .field("output", &self.output)
+            .field("sizes", &self.sizes)
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// The result of `Composer::compose`.
+pub struct ComposeResult {
+    /// The composed catalog.
+    pub collection: Collection,
+    /// The problems found on the way, the faulty components are left out of the
+    /// collection when those are errors.
+    pub hints: Vec<ValidationIssue>,
+}
+
+#[derive(Debug)]
+/// Builds a catalog `Collection` out of the metainfo and desktop files installed
+/// in a directory tree, like the payload of a package or a `DESTDIR`.
+///
+/// The metainfo files (`*.metainfo.xml` and the legacy `*.appdata.xml`) are
+/// parsed and completed with the data of the desktop file they launch.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::compose::Composer;
+///
+/// let result = Composer::new("my-distro")
+///     .media_base_url("https://appstream.example.org/media")
+///     .compose("/tmp/payload".as_ref())
+///     .unwrap();
+/// for hint in result.hints {
+///     eprintln!("{}", hint);
+/// }
+/// println!("{} components", result.collection.components.len());
+/// ```
+pub struct Composer {
+    origin: String,
+    version: String,
+    media_base_url: Option<String>,
+    architecture: Option<String>,
+    #[cfg(feature = "image")]
+    icon_cache: Option<IconCache>,
+}
+
+impl Composer {
+    /// Creates a new `Composer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The origin of the produced collection, like `flathub`.
+    pub fn new(origin: &str) -> Self {
+        Self {
+            origin: origin.to_string(),
+            version: "0.14".to_string(),
+            media_base_url: None,
+            architecture: None,
+            #[cfg(feature = "image")]
+            icon_cache: None,
+        }
+    }
+
+    /// Sets the specification version of the produced collection, `0.14` by default.
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    /// Sets the base URL the media of the collection are served from.
+    pub fn media_base_url(mut self, media_base_url: &str) -> Self {
+        self.media_base_url = Some(media_base_url.to_string());
+        self
+    }
+
+    /// Sets the CPU architecture targeted by the collection.
+    pub fn architecture(mut self, architecture: &str) -> Self {
+        self.architecture = Some(architecture.to_string());
+        self
+    }
+
+    #[cfg(feature = "image")]
+    /// Generates the cached icons of the components.
+    ///
+    /// The stock and local icons are looked up in the composed tree, rendered at
+    /// the `DEFAULT_ICON_SIZES` and written to `<output>/<origin>/<size>x<size>/`.
+    /// The local icons of the components are then replaced by `Icon::Cached` ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - The root of the icons tree, like `/usr/share/swcatalog/icons`.
+    /// * `scaler` - Used to render the icons not available at the right size.
+    pub fn icons<S: IconScaler + 'static>(mut self, output: &Path, scaler: S) -> Self {
+        self.icon_cache = Some(IconCache {
+            output: output.to_path_buf(),
+            sizes: DEFAULT_ICON_SIZES.to_vec(),
+            scaler: Box::new(scaler),
+        });
+        self
+    }
+
+    #[cfg(feature = "image")]
+    /// Overrides the sizes of the generated icons, only used along with `icons`.
+    pub fn icon_sizes(mut self, sizes: &[u32]) -> Self {
+        if let Some(cache) = self.icon_cache.as_mut() {
+            cache.sizes = sizes.to_vec();
+        }
+        self
+    }
+
+    /// Walks `root` and composes the collection of the components found in it.
+    ///
+    /// Only failures to read the directory tree are returned as errors, invalid
+    /// files are reported in `ComposeResult::hints`.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The directory to look for metainfo and desktop files in.
+    pub fn compose(&self, root: &Path) -> Result<ComposeResult, ParseError> {
+        let mut metainfo_files = Vec::new();
+        let mut desktop_files = BTreeMap::new();
+        walk(root, &mut |path| {
+            let file_name = match path.file_name().and_then(|f| f.to_str()) {
+                Some(f) => f,
+                None => return,
+            };
+            if file_name.ends_with(".metainfo.xml") || file_name.ends_with(".appdata.xml") {
+                metainfo_files.push(path.to_path_buf());
+            } else if file_name.ends_with(".desktop")
+                && path.parent().and_then(|p| p.file_name()) == Some("applications".as_ref())
+            {
+                desktop_files.insert(file_name.to_string(), path.to_path_buf());
+            }
+        })?;
+        metainfo_files.sort();
+
+        let mut hints = Vec::new();
+        let mut components: BTreeMap<AppId, Component> = BTreeMap::new();
+        for path in metainfo_files {
+            let mut component = match Component::from_path(path.clone()) {
+                Ok(c) => c,
+                Err(e) => {
+                    hints.push(ValidationIssue::error(
+                        "metainfo-parsing-error",
+                        &format!("Failed to parse {}: {}", path.display(), e),
+                    ));
+                    continue;
+                }
+            };
+
+            let desktop_id = desktop_id(&component);
+            match desktop_files.get(&desktop_id) {
+                Some(desktop_path) => match DesktopEntry::from_path(desktop_path) {
+                    Ok(entry) => component.merge_desktop_entry(&entry),
+                    Err(e) => hints.push(
+                        ValidationIssue::error(
+                            "desktop-file-error",
+                            &format!("Failed to parse {}: {}", desktop_path.display(), e),
+                        )
+                        .for_component(&component.id),
+                    ),
+                },
+                None if has_desktop_launchable(&component) => hints.push(
+                    ValidationIssue::warning(
+                        "desktop-file-not-found",
+                        &format!("The desktop file {} was not found", desktop_id),
+                    )
+                    .for_component(&component.id),
+                ),
+                None => (),
+            }
+
+            if components.contains_key(&component.id) {
+                hints.push(
+                    ValidationIssue::error(
+                        "duplicate-component",
+                        &format!("{} uses an id that is already taken", path.display()),
+                    )
+                    .for_component(&component.id),
+                );
+                continue;
+            }
+            #[cfg(feature = "image")]
+            if let Some(cache) = &self.icon_cache {
+                cache.process(root, &self.origin, &mut component, &mut hints)?;
+            }
+            components.insert(component.id.clone(), component);
+        }
+
+        let mut collection = CollectionBuilder::new(&self.version).origin(&self.origin);
+        if let Some(media_base_url) = &self.media_base_url {
+            collection = collection.media_base_url(media_base_url);
+        }
+        if let Some(architecture) = &self.architecture {
+            collection = collection.architecture(architecture);
+        }
+        for component in components.into_values() {
+            collection = collection.component(component);
+        }
+
+        Ok(ComposeResult {
+            collection: collection.build(),
+            hints,
+        })
+    }
+}
+
+#[cfg(feature = "image")]
+impl IconCache {
+    /// Generates the cached icons of `component` and rewrites its icons.
+    fn process(
+        &self,
+        root: &Path,
+        origin: &str,
+        component: &mut Component,
+        hints: &mut Vec<ValidationIssue>,
+    ) -> Result<(), ParseError> {
+        let icon = component
+            .icons
+            .iter()
+            .find(|i| matches!(i, Icon::Stock(_) | Icon::Local { .. }));
+        // The names and paths come from the metainfo, they must stay inside the
+        // tree for reading and inside the output for writing.
+        let (name, sources) = match icon {
+            None => return Ok(()),
+            Some(Icon::Stock(name)) if is_safe_icon_name(name) => {
+                (name.clone(), stock_icon_sources(root, name))
+            }
+            Some(Icon::Local { path, .. }) => match path
+                .to_str()
+                .and_then(|p| safe_path(p.trim_start_matches('/')).ok())
+            {
+                Some(path) => {
+                    let path = root.join(path);
+                    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+                    (name.to_string(), vec![icon_source(path)])
+                }
+                None => {
+                    skip_icon(path, hints);
+                    return Ok(());
+                }
+            },
+            Some(Icon::Stock(name)) => {
+                skip_icon(Path::new(name), hints);
+                return Ok(());
+            }
+            Some(_) => return Ok(()),
+        };
+
+        let file_name = format!("{}.png", name);
+        let mut cached = Vec::new();
+        for size in self.sizes.iter().copied() {
+            let exact = sources.iter().find(|(_, s)| *s == Some(size));
+            let data = match exact {
+                Some((path, _)) => fs::read(path)?,
+                None => {
+                    // Prefer scaling down the smallest bigger image, vector ones otherwise.
+                    let larger = sources
+                        .iter()
+                        .filter(|(_, s)| s.is_some_and(|s| s > size))
+                        .min_by_key(|(_, s)| *s)
+                        .or_else(|| sources.iter().find(|(_, s)| s.is_none()));
+                    let source = match larger {
+                        Some((path, _)) => path,
+                        None => continue,
+                    };
+                    match self.scaler.scale(source, size) {
+                        Ok(data) => data,
+                        Err(e) => {
+                            hints.push(ValidationIssue::error(
+                                "icon-scaling-failed",
+                                &format!(
+                                    "Failed to scale {} to {}px: {}",
+                                    source.display(),
+                                    size,
+                                    e
+                                ),
+                            ));
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let dir = self.output.join(origin).join(format!("{}x{}", size, size));
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join(&file_name), data)?;
+            cached.push(Icon::Cached {
+                path: file_name.clone().into(),
+                width: Some(size),
+                height: Some(size),
+            });
+        }
+
+        if cached.is_empty() {
+            hints.push(ValidationIssue::warning(
+                "icon-not-found",
+                &format!("No usable image was found for the icon {}", name),
+            ));
+            return Ok(());
+        }
+        // The paths of the local icons are only valid in the composed tree.
+        component.icons.retain(|i| !matches!(i, Icon::Local { .. }));
+        component.icons.extend(cached);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "image")]
+/// Whether `name` is a file name, with neither a directory nor a parent in it.
+fn is_safe_icon_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains('/') && !name.contains("..")
+}
+
+#[cfg(feature = "image")]
+/// Reports an icon whose name or path would go outside of the tree.
+fn skip_icon(path: &Path, hints: &mut Vec<ValidationIssue>) {
+    hints.push(ValidationIssue::warning(
+        "icon-invalid-path",
+        &format!(
+            "The icon {} is outside of the tree, skipped",
+            path.display()
+        ),
+    ));
+}
+
+#[cfg(feature = "image")]
+/// An icon file and its size, `None` for vector images.
+fn icon_source(path: PathBuf) -> (PathBuf, Option<u32>) {
+    let is_vector = path.extension().is_some_and(|e| e == "svg" || e == "svgz");
+    let size = if is_vector {
+        None
+    } else {
+        fs::read(&path)
+            .ok()
+            .and_then(|data| image_size::dimensions(&data))
+            .map(|(width, _)| width)
+    };
+    (path, size)
+}
+
+#[cfg(feature = "image")]
+/// The files of the stock icon `name` in the hicolor theme and the pixmaps directory.
+fn stock_icon_sources(root: &Path, name: &str) -> Vec<(PathBuf, Option<u32>)> {
+    let mut candidates = Vec::new();
+    let hicolor = root.join("usr/share/icons/hicolor");
+    if let Ok(entries) = fs::read_dir(&hicolor) {
+        for entry in entries.flatten() {
+            let apps = entry.path().join("apps");
+            candidates.push(apps.join(format!("{}.png", name)));
+            candidates.push(apps.join(format!("{}.svg", name)));
+            candidates.push(apps.join(format!("{}.svgz", name)));
+        }
+    }
+    let pixmaps = root.join("usr/share/pixmaps");
+    candidates.push(pixmaps.join(format!("{}.png", name)));
+    candidates.push(pixmaps.join(format!("{}.svg", name)));
+
+    let mut sources = candidates
+        .into_iter()
+        .filter(|p| p.is_file())
+        .map(icon_source)
+        .filter(|(path, size)| size.is_some() || path.extension().is_some_and(|e| e != "png"))
+        .collect::<Vec<_>>();
+    sources.sort();
+    sources
+}
+
+/// Calls `f` on every file found under `dir`, without following symlinks.
+fn walk(dir: &Path, f: &mut dyn FnMut(&Path)) -> Result<(), ParseError> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path: PathBuf = entry.path();
+        if file_type.is_dir() {
+            walk(&path, f)?;
+        } else {
+            f(&path);
+        }
+    }
+    Ok(())
+}
+
+fn has_desktop_launchable(component: &Component) -> bool {
+    component
+        .launchables
+        .iter()
+        .any(|l| matches!(l, Launchable::DesktopId(_)))
+}
+
+/// The file name of the desktop file associated with the component.
+fn desktop_id(component: &Component) -> String {
+    component
+        .launchables
+        .iter()
+        .find_map(|l| match l {
+            Launchable::DesktopId(id) => Some(id.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| {
+            let id = component.id.as_ref();
+            if id.ends_with(".desktop") {
+                id.to_string()
+            } else {
+                format!("{}.desktop", id)
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "image")]
+    use crate::builders::ComponentBuilder;
+    use crate::enums::{Category, Icon};
+    use crate::TranslatableString;
+    use std::error::Error;
+
+    #[test]
+    fn compose_tree() -> Result<(), Box<dyn Error>> {
+        let result = Composer::new("test")
+            .media_base_url("https://example.org/media")
+            .compose(Path::new("./tests/compose"))?;
+
+        let collection = result.collection;
+        assert_eq!(collection.origin, Some("test".into()));
+        assert_eq!(
+            collection.media_base_url,
+            Some("https://example.org/media".into())
+        );
+        let ids = collection
+            .components
+            .iter()
+            .map(|c| c.id.as_ref())
+            .collect::<Vec<&str>>();
+        assert_eq!(ids, vec!["org.example.Tool", "org.gnome.design.Contrast"]);
+
+        let contrast = &collection.components[1];
+        assert_eq!(
+            contrast.name,
+            TranslatableString::with_default("Contrast").and_locale("fr", "Contraste")
+        );
+        assert_eq!(
+            contrast.summary,
+            Some(TranslatableString::with_default(
+                "Check contrast between two colors"
+            ))
+        );
+        assert_eq!(
+            contrast.icons,
+            vec![Icon::Stock("org.gnome.design.Contrast".into())]
+        );
+        assert_eq!(
+            contrast.categories,
+            vec![Category::Utility, Category::Graphics]
+        );
+
+        let tags = result
+            .hints
+            .iter()
+            .map(|h| h.tag.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(tags, vec!["metainfo-parsing-error"]);
+        Ok(())
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn cached_icons() -> Result<(), Box<dyn Error>> {
+        struct FakeScaler;
+
+        impl IconScaler for FakeScaler {
+            fn scale(&self, source: &Path, size: u32) -> io::Result<Vec<u8>> {
+                let dir = source.parent().and_then(|p| p.parent()).unwrap();
+                let from = dir.file_name().unwrap().to_string_lossy();
+                Ok(format!("{}px from {}", size, from).into_bytes())
+            }
+        }
+
+        let dir = tempfile::tempdir()?;
+        let output = dir.path().join("compose-icons");
+        let result = Composer::new("test")
+            .icons(&output, FakeScaler)
+            .compose(Path::new("./tests/compose"))?;
+
+        let contrast = &result.collection.components[1];
+        let cached = |size| Icon::Cached {
+            path: "org.gnome.design.Contrast.png".into(),
+            width: Some(size),
+            height: Some(size),
+        };
+        assert_eq!(
+            contrast.icons,
+            vec![
+                Icon::Stock("org.gnome.design.Contrast".into()),
+                cached(48),
+                cached(64),
+                cached(128)
+            ]
+        );
+        let icon = |size| {
+            output.join(format!(
+                "test/{}x{}/org.gnome.design.Contrast.png",
+                size, size
+            ))
+        };
+        assert_eq!(fs::read(icon(48))?, b"48px from 64x64");
+        assert_eq!(
+            fs::read(icon(64))?,
+            fs::read(
+                "./tests/compose/usr/share/icons/hicolor/64x64/apps/org.gnome.design.Contrast.png"
+            )?
+        );
+        assert_eq!(fs::read(icon(128))?, b"128px from 256x256");
+
+        // Tool doesn't have any icon
+        assert!(result.collection.components[0].icons.is_empty());
+        assert_eq!(result.hints.len(), 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn icon_traversal() -> Result<(), Box<dyn Error>> {
+        struct FakeScaler;
+
+        impl IconScaler for FakeScaler {
+            fn scale(&self, _source: &Path, size: u32) -> io::Result<Vec<u8>> {
+                Ok(format!("{}px", size).into_bytes())
+            }
+        }
+
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().join("root");
+        fs::create_dir_all(root.join("usr/share/pixmaps"))?;
+        fs::write(root.join("usr/secret.svg"), "<svg/>")?;
+        fs::write(dir.path().join("outside.svg"), "<svg/>")?;
+        let cache = IconCache {
+            output: dir.path().join("output"),
+            sizes: vec![48],
+            scaler: Box::new(FakeScaler),
+        };
+
+        for icon in [
+            Icon::Stock("../../secret".into()),
+            Icon::Local {
+                path: "/usr/share/../../../outside.svg".into(),
+                width: None,
+                height: None,
+            },
+        ] {
+            let mut component = ComponentBuilder::default()
+                .id("org.example.App".into())
+                .name(TranslatableString::with_default("App"))
+                .icon(icon.clone())
+                .build();
+            let mut hints = Vec::new();
+            cache.process(&root, "test", &mut component, &mut hints)?;
+            assert_eq!(component.icons, vec![icon]);
+            assert_eq!(hints[0].tag, "icon-invalid-path");
+        }
+        assert!(!dir.path().join("output").exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use super::error::{ParseError, ParseWarning};
+use super::warnings;
+use super::{Collection, Component};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use xmltree::{Element, XMLNode};
+
+#[derive(Clone, Debug, Default, Serialize, PartialEq)]
+/// What the parser made of the tags and attributes of an XML file: the ones it
+/// recognized, the ones it ignored and the ones whose value it failed to parse.
+///
+/// The tags are keyed as `parent/tag`, like `component/name`, and the attributes
+/// as `tag@attribute`, like `icon@width`, with the number of occurrences. A tag is
+/// recognized unless the parser reported it, the children of the ignored tags
+/// aren't counted.
+///
+/// # Example
+///
+/// ```
+/// use appstream::coverage::CoverageReport;
+/// use xmltree::Element;
+///
+/// let xml = r#"<component importance="10">
+///     <id>org.example.App</id>
+///     <name>App</name>
+///     <icon type="stock" width="big">app</icon>
+///     <branding><colour type="primary">#ff0000</colour></branding>
+/// </component>"#;
+/// let report = CoverageReport::from_element(&Element::parse(xml.as_bytes()).unwrap());
+/// assert_eq!(report.recognized["component/name"], 1);
+/// assert_eq!(report.ignored["branding/colour"], 1);
+/// assert_eq!(report.ignored["component@importance"], 1);
+/// assert_eq!(report.failed["icon@width"], 1);
+/// assert!(!report.is_clean());
+/// ```
+pub struct CoverageReport {
+    /// The tags and attributes the parser used.
+    pub recognized: BTreeMap<String, usize>,
+    /// The tags and attributes the parser skipped.
+    pub ignored: BTreeMap<String, usize>,
+    /// The attributes whose value is invalid, replaced by a fallback.
+    pub failed: BTreeMap<String, usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The error that stopped the parsing, if any.
+    pub error: Option<String>,
+}
+
+impl CoverageReport {
+    /// Parses an XML metainfo file or catalog and reports its coverage.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the XML file.
+    pub fn from_path(path: &Path) -> Result<Self, ParseError> {
+        let element = Element::parse(BufReader::new(File::open(path)?))?;
+        Ok(Self::from_element(&element))
+    }
+
+    /// Parses a `<component>` or a `<components>` element and reports its
+    /// coverage.
+    ///
+    /// # Arguments
+    ///
+    /// * `element` - The root element.
+    pub fn from_element(element: &Element) -> Self {
+        let (result, warnings) = warnings::collect(|| {
+            if element.name == "components" {
+                Collection::try_from(element).map(|_| ())
+            } else {
+                Component::try_from(element).map(|_| ())
+            }
+        });
+
+        let mut report = Self {
+            error: result.err().map(|e| e.to_string()),
+            ..Self::default()
+        };
+        for warning in warnings.iter() {
+            let (map, key) = match warning {
+                ParseWarning::UnknownTag(tag, parent) => {
+                    (&mut report.ignored, format!("{}/{}", parent, tag))
+                }
+                ParseWarning::IgnoredAttribute(attr, tag) => {
+                    (&mut report.ignored, format!("{}@{}", tag, attr))
+                }
+                ParseWarning::RecoveredValue(_, attr, tag, _) => {
+                    (&mut report.failed, format!("{}@{}", tag, attr))
+                }
+            };
+            *map.entry(key).or_insert(0) += 1;
+        }
+
+        let mut seen = BTreeMap::new();
+        count(element, None, &report.ignored, &mut seen);
+        for (key, total) in seen {
+            let reported = report.ignored.get(&key).copied().unwrap_or(0)
+                + report.failed.get(&key).copied().unwrap_or(0);
+            if total > reported {
+                report.recognized.insert(key, total - reported);
+            }
+        }
+        report
+    }
+
+    /// Whether the parser used all the tags and attributes, and parsed all the
+    /// values.
+    pub fn is_clean(&self) -> bool {
+        self.ignored.is_empty() && self.failed.is_empty() && self.error.is_none()
+    }
+}
+
+/// Counts the occurrences of the tags and attributes, without descending into
+/// the ignored tags.
+fn count(
+    element: &Element,
+    parent: Option<&str>,
+    ignored: &BTreeMap<String, usize>,
+    seen: &mut BTreeMap<String, usize>,
+) {
+    if let Some(parent) = parent {
+        let key = format!("{}/{}", parent, element.name);
+        let skip = ignored.contains_key(&key);
+        *seen.entry(key).or_insert(0) += 1;
+        if skip {
+            return;
+        }
+    }
+    for attr in element.attributes.keys() {
+        *seen
+            .entry(format!("{}@{}", element.name, attr))
+            .or_insert(0) += 1;
+    }
+    for child in element.children.iter() {
+        if let XMLNode::Element(child) = child {
+            count(child, Some(&element.name), ignored, seen);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn coverage_report() -> Result<(), Box<dyn Error>> {
+        let report = CoverageReport::from_path(Path::new("./tests/collections/gnome-apps.xml"))?;
+        assert!(report.is_clean());
+        assert!(report.recognized["components/component"] > 1);
+        assert_eq!(
+            report.recognized["components/component"],
+            report.recognized["component/id"]
+        );
+
+        let xml = r#"<components version="0.14" media_baseurl="https://example.org">
+            <info><generator>asgen</generator></info>
+            <component type="desktop-application">
+                <id>org.example.App</id>
+                <name>App</name>
+                <launchable type="snap">app</launchable>
+            </component>
+            <component type="desktop-application">
+                <id>org.example.Other</id>
+                <name>Other</name>
+                <launchable type="desktop-id">other.desktop</launchable>
+            </component>
+        </components>"#;
+        let report = CoverageReport::from_element(&Element::parse(xml.as_bytes())?);
+        assert_eq!(report.ignored["components@media_baseurl"], 1);
+        assert_eq!(report.ignored["components/info"], 1);
+        assert!(!report.recognized.contains_key("info/generator"));
+        assert_eq!(report.failed["launchable@type"], 1);
+        assert_eq!(report.recognized["launchable@type"], 1);
+        assert_eq!(report.recognized["component@type"], 2);
+        assert_eq!(report.error, None);
+
+        let xml = r#"<component><name>App</name></component>"#;
+        let report = CoverageReport::from_element(&Element::parse(xml.as_bytes())?);
+        assert!(report.error.is_some());
+        assert!(!report.is_clean());
+        Ok(())
+    }
+}
@@ -0,0 +1,768 @@
+use super::digest::{self, Digest, HashingReader};
+use super::enums::Checksum;
+use super::error::ParseError;
+use super::net::{self, HttpClient};
+use super::Collection;
+#[cfg(feature = "gzip")]
+use flate2::read::GzDecoder;
+use std::convert::TryFrom;
+#[cfg(feature = "package")]
+use std::io;
+use std::io::Read;
+use std::str::FromStr;
+use std::sync::Arc;
+use url::Url;
+use yaml_rust::YamlLoader;
+
+/// The compression suffixes of the files that can be read, in order of preference.
+#[cfg(feature = "package")]
+const COMPRESSIONS: &[&str] = &[".xz", ".gz", ""];
+#[cfg(all(feature = "gzip", not(feature = "package")))]
+const COMPRESSIONS: &[&str] = &[".gz", ""];
+#[cfg(not(feature = "gzip"))]
+const COMPRESSIONS: &[&str] = &[""];
+
+#[derive(Clone, Debug, PartialEq)]
+/// A `deb` entry of an APT sources list.
+///
+/// # Example
+///
+/// ```
+/// use appstream::debian::SourcesEntry;
+/// use std::str::FromStr;
+///
+/// let entry = SourcesEntry::from_str("deb [arch=amd64] http://deb.debian.org/debian bookworm main contrib").unwrap();
+/// assert_eq!(entry.suites, vec!["bookworm"]);
+/// assert_eq!(entry.components, vec!["main", "contrib"]);
+/// assert_eq!(entry.architectures, vec!["amd64"]);
+/// ```
+pub struct SourcesEntry {
+    /// The mirrors of the repository.
+    pub uris: Vec<Url>,
+    /// The suites, like `bookworm` or `stable`.
+    pub suites: Vec<String>,
+    /// The archive components, like `main`.
+    pub components: Vec<String>,
+    /// The architectures, the one of the `Dep11Fetcher` is used if empty.
+    pub architectures: Vec<String>,
+}
+
+impl SourcesEntry {
+    /// Creates an entry for the `main` component of a mirror.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The mirror URL, like `http://deb.debian.org/debian`.
+    /// * `suite` - The suite, like `bookworm`.
+    pub fn new(uri: Url, suite: &str) -> Self {
+        Self {
+            uris: vec![uri],
+            suites: vec![suite.to_string()],
+            components: vec!["main".to_string()],
+            architectures: vec![],
+        }
+    }
+
+    /// Sets the archive components.
+    pub fn components(mut self, components: &[&str]) -> Self {
+        self.components = components.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// Sets the architectures.
+    pub fn architectures(mut self, architectures: &[&str]) -> Self {
+        self.architectures = architectures.iter().map(|a| a.to_string()).collect();
+        self
+    }
+
+    /// Parses the `deb` entries of a deb822-style `.sources` file, the `deb-src`
+    /// and disabled ones are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The content of the `.sources` file.
+    pub fn from_deb822(text: &str) -> Result<Vec<Self>, ParseError> {
+        let mut entries = Vec::new();
+        for stanza in parse_deb822(text) {
+            let field = |name: &str| {
+                stanza
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                    .map(|(_, v)| v.split_whitespace().map(String::from).collect())
+                    .unwrap_or_else(Vec::new)
+            };
+            let types: Vec<String> = field("Types");
+            let enabled: Vec<String> = field("Enabled");
+            if !types.iter().any(|t| t == "deb") || enabled.iter().any(|e| e == "no") {
+                continue;
+            }
+            let uris = field("URIs")
+                .iter()
+                .map(|u| Url::parse(u))
+                .collect::<Result<Vec<Url>, url::ParseError>>()?;
+            if uris.is_empty() {
+                return Err(ParseError::missing_value("URIs"));
+            }
+            let suites: Vec<String> = field("Suites");
+            if suites.is_empty() {
+                return Err(ParseError::missing_value("Suites"));
+            }
+            entries.push(Self {
+                uris,
+                suites,
+                components: field("Components"),
+                architectures: field("Architectures"),
+            });
+        }
+        Ok(entries)
+    }
+}
+
+impl FromStr for SourcesEntry {
+    type Err = ParseError;
+
+    /// Parses a one-line-style `deb` entry, like the ones of `/etc/apt/sources.list`.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let rest = line
+            .strip_prefix("deb ")
+            .ok_or_else(|| ParseError::invalid_value(line, "type", "deb"))?
+            .trim_start();
+
+        let mut architectures = vec![];
+        let rest = if let Some(options) = rest.strip_prefix('[') {
+            let end = options
+                .find(']')
+                .ok_or_else(|| ParseError::invalid_value(rest, "options", "deb"))?;
+            for option in options[..end].split_whitespace() {
+                if let Some(values) = option.strip_prefix("arch=") {
+                    architectures = values.split(',').map(String::from).collect();
+                }
+            }
+            &options[end + 1..]
+        } else {
+            rest
+        };
+
+        let mut fields = rest.split_whitespace();
+        let uri = fields
+            .next()
+            .ok_or_else(|| ParseError::missing_value("uri"))?;
+        let suite = fields
+            .next()
+            .ok_or_else(|| ParseError::missing_value("suite"))?;
+        Ok(Self {
+            uris: vec![Url::parse(uri)?],
+            suites: vec![suite.to_string()],
+            components: fields.map(String::from).collect(),
+            architectures,
+        })
+    }
+}
+
+/// Splits a deb822 document into its stanzas of `(field, value)` pairs, the
+/// continuation lines are joined to their field value.
+fn parse_deb822(text: &str) -> Vec<Vec<(String, String)>> {
+    let mut stanzas = vec![];
+    let mut stanza: Vec<(String, String)> = vec![];
+    for line in text.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.trim().is_empty() {
+            if !stanza.is_empty() {
+                stanzas.push(std::mem::take(&mut stanza));
+            }
+        } else if line.starts_with(' ') || line.starts_with('\t') {
+            if let Some((_, value)) = stanza.last_mut() {
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+        } else if let Some(colon) = line.find(':') {
+            stanza.push((
+                line[..colon].trim().to_string(),
+                line[colon + 1..].trim().to_string(),
+            ));
+        }
+    }
+    if !stanza.is_empty() {
+        stanzas.push(stanza);
+    }
+    stanzas
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A file listed in a repository's `Release` file.
+pub struct ReleaseFileEntry {
+    /// The path of the file, relative to the suite directory.
+    pub path: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The checksum of the file.
+    pub checksum: Checksum,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// The `Release` (or `InRelease`) file of a repository suite, listing the
+/// checksums of its index files.
+///
+/// The OpenPGP signature of an `InRelease` file is stripped, not verified, that
+/// is left to the application's keyring handling.
+pub struct ReleaseFile {
+    /// The origin of the repository, like `Debian`.
+    pub origin: Option<String>,
+    /// The suite, like `stable`.
+    pub suite: Option<String>,
+    /// The codename of the suite, like `bookworm`.
+    pub codename: Option<String>,
+    /// The files of the suite, with their SHA-256 checksum, or their SHA-1 one
+    /// for old repositories.
+    pub files: Vec<ReleaseFileEntry>,
+}
+
+impl ReleaseFile {
+    /// Finds the entry of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, relative to the suite directory.
+    pub fn find(&self, path: &str) -> Option<&ReleaseFileEntry> {
+        self.files.iter().find(|f| f.path == path)
+    }
+}
+
+impl FromStr for ReleaseFile {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        // Strip the clearsign armor of InRelease files.
+        let text = match text.find("-----BEGIN PGP SIGNED MESSAGE-----") {
+            Some(start) => {
+                let text = &text[start..];
+                let body = text.find("\n\n").map(|i| i + 2).unwrap_or(text.len());
+                let end = text
+                    .find("-----BEGIN PGP SIGNATURE-----")
+                    .unwrap_or(text.len());
+                &text[body..end.max(body)]
+            }
+            None => text,
+        };
+
+        let stanza = parse_deb822(text).into_iter().next().unwrap_or_default();
+        let field = |name: &str| {
+            stanza
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.clone())
+        };
+
+        let (hashes, checksum): (String, fn(String) -> Checksum) = match field("SHA256") {
+            Some(hashes) => (hashes, Checksum::Sha256),
+            None => (field("SHA1").unwrap_or_default(), Checksum::Sha1),
+        };
+        let mut files = vec![];
+        for line in hashes.lines().filter(|l| !l.trim().is_empty()) {
+            let parts = line.split_whitespace().collect::<Vec<&str>>();
+            match parts.as_slice() {
+                [hash, size, path] => files.push(ReleaseFileEntry {
+                    path: path.to_string(),
+                    size: size
+                        .parse()
+                        .map_err(|_| ParseError::invalid_value(size, "size", "Release"))?,
+                    checksum: checksum(hash.to_string()),
+                }),
+                _ => return Err(ParseError::invalid_value(line, "checksums", "Release")),
+            }
+        }
+
+        Ok(Self {
+            origin: field("Origin"),
+            suite: field("Suite"),
+            codename: field("Codename"),
+            files,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A verified, still compressed, icon tarball of a DEP-11 catalog.
+pub struct IconTarball {
+    /// The origin of the catalog the icons belong to.
+    pub origin: Option<String>,
+    /// The size of the icons, like `64x64`.
+    pub size: String,
+    /// The path of the tarball, relative to the suite directory.
+    pub path: String,
+    /// The content of the tarball.
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// The DEP-11 metadata of one or more repositories.
+pub struct Dep11Catalog {
+    /// The components of all the catalogs, each one keeps the origin of its catalog.
+    pub collection: Collection,
+    /// The icon tarballs of the catalogs.
+    pub icon_tarballs: Vec<IconTarball>,
+}
+
+/// Downloads the DEP-11 metadata of Debian-like repositories.
+///
+/// The `Components-<arch>.yml` catalogs and the `icons-<size>.tar` tarballs of the
+/// `dists/<suite>/<component>/dep11/` directories are verified against the suite's
+/// `Release` file. The gzip-compressed files are only used with the `gzip`
+/// feature and the xz-compressed ones with the `package` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::debian::{Dep11Fetcher, SourcesEntry};
+/// use appstream::net::{HttpClient, Request, Response};
+/// use url::Url;
+/// # use std::io;
+/// # struct MyClient;
+/// # impl HttpClient for MyClient {
+/// #     fn send(&self, _request: &Request) -> io::Result<Response> { unimplemented!() }
+/// # }
+///
+/// let entry = SourcesEntry::new(Url::parse("http://deb.debian.org/debian").unwrap(), "bookworm")
+///     .components(&["main", "contrib"]);
+/// let catalog = Dep11Fetcher::new(MyClient, "amd64").fetch(&entry).unwrap();
+/// println!("{} components", catalog.collection.components.len());
+/// ```
+#[derive(Debug)]
+pub struct Dep11Fetcher<C: HttpClient> {
+    client: C,
+    architecture: String,
+    icon_sizes: Vec<String>,
+}
+
+impl<C: HttpClient> Dep11Fetcher<C> {
+    /// Creates a new fetcher.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to send the requests through.
+    /// * `architecture` - The architecture used for the entries without any.
+    pub fn new(client: C, architecture: &str) -> Self {
+        Self {
+            client,
+            architecture: architecture.to_string(),
+            icon_sizes: vec!["48x48".into(), "64x64".into(), "128x128".into()],
+        }
+    }
+
+    /// Sets the sizes of the icon tarballs to download, `48x48`, `64x64` and
+    /// `128x128` by default.
+    pub fn icon_sizes(mut self, sizes: &[&str]) -> Self {
+        self.icon_sizes = sizes.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Downloads the `InRelease` file of a suite, or its `Release` file if the
+    /// repository isn't signed.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The mirror URL.
+    /// * `suite` - The suite.
+    pub fn fetch_release(&self, uri: &Url, suite: &str) -> Result<ReleaseFile, ParseError> {
+        let base = suite_url(uri, suite)?;
+        let (in_release, release) = (base.join("InRelease")?, base.join("Release")?);
+        let data =
+            net::get(&self.client, &in_release).or_else(|_| net::get(&self.client, &release))?;
+        ReleaseFile::from_str(&String::from_utf8_lossy(&data))
+    }
+
+    /// Downloads the DEP-11 metadata of all the suites and components of an entry,
+    /// from its first reachable mirror.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The sources entry.
+    pub fn fetch(&self, entry: &SourcesEntry) -> Result<Dep11Catalog, ParseError> {
+        let mut error = None;
+        for uri in entry.uris.iter() {
+            match self.fetch_mirror(entry, uri) {
+                Ok(catalog) => return Ok(catalog),
+                Err(e) => error = Some(e),
+            }
+        }
+        Err(error.unwrap_or_else(|| ParseError::missing_value("URIs")))
+    }
+
+    fn fetch_mirror(&self, entry: &SourcesEntry, uri: &Url) -> Result<Dep11Catalog, ParseError> {
+        let default_architecture = [self.architecture.clone()];
+        let architectures = if entry.architectures.is_empty() {
+            &default_architecture[..]
+        } else {
+            &entry.architectures[..]
+        };
+
+        let mut collections = vec![];
+        let mut icon_tarballs = vec![];
+        for suite in entry.suites.iter() {
+            let release = self.fetch_release(uri, suite)?;
+            let base = suite_url(uri, suite)?;
+            for component in entry.components.iter() {
+                let mut origin = None;
+                for architecture in architectures.iter() {
+                    let path = format!("{}/dep11/Components-{}.yml", component, architecture);
+                    if let Some((path, data)) = self.download(&base, &release, &path)? {
+                        let collection = parse_catalog(&path, &data)?;
+                        origin = collection.origin.clone();
+                        collections.push(collection);
+                    }
+                }
+                for size in self.icon_sizes.iter() {
+                    let path = format!("{}/dep11/icons-{}.tar", component, size);
+                    if let Some((path, data)) = self.download(&base, &release, &path)? {
+                        icon_tarballs.push(IconTarball {
+                            origin: origin.clone(),
+                            size: size.clone(),
+                            path,
+                            data,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(Dep11Catalog {
+            collection: merge(collections, &self.architecture),
+            icon_tarballs,
+        })
+    }
+
+    /// Downloads and verifies the first available compressed variant of a file,
+    /// returns `None` if the suite doesn't have it.
+    fn download(
+        &self,
+        base: &Url,
+        release: &ReleaseFile,
+        path: &str,
+    ) -> Result<Option<(String, Vec<u8>)>, ParseError> {
+        let mut error = None;
+        for compression in COMPRESSIONS.iter() {
+            let path = format!("{}{}", path, compression);
+            let entry = match release.find(&path) {
+                Some(entry) => entry,
+                None => continue,
+            };
+            // The Release file also lists files only available compressed.
+            match net::get(&self.client, &base.join(&path)?) {
+                Ok(data) => {
+                    verify(entry, &data)?;
+                    return Ok(Some((path, data)));
+                }
+                Err(e) => error = Some(e),
+            }
+        }
+        match error {
+            Some(e) => Err(e.into()),
+            None => Ok(None),
+        }
+    }
+}
+
+/// The URL of the `dists/<suite>/` directory of a mirror.
+fn suite_url(uri: &Url, suite: &str) -> Result<Url, ParseError> {
+    if suite.ends_with('/') {
+        return Err(ParseError::invalid_value(suite, "suite", "deb"));
+    }
+    let mut uri = uri.clone();
+    if !uri.path().ends_with('/') {
+        uri.set_path(&format!("{}/", uri.path()));
+    }
+    Ok(uri.join(&format!("dists/{}/", suite))?)
+}
+
+/// Checks the size and checksum of a downloaded file.
+fn verify(entry: &ReleaseFileEntry, data: &[u8]) -> Result<(), ParseError> {
+    let (digest, expected) = Digest::for_checksum(&entry.checksum);
+    let actual = digest::to_hex(&HashingReader::new(data, digest).finish()?);
+    if data.len() as u64 != entry.size || actual != expected.to_lowercase() {
+        return Err(ParseError::ChecksumMismatch(
+            expected.to_lowercase(),
+            actual,
+        ));
+    }
+    Ok(())
+}
+
+/// Wraps a downloaded file in the decoder of its compression suffix.
+fn decompress<'a>(path: &str, data: &'a [u8]) -> Result<Box<dyn Read + 'a>, ParseError> {
+    match path.rsplit('.').next() {
+        #[cfg(feature = "gzip")]
+        Some("gz") => Ok(Box::new(GzDecoder::new(data))),
+        #[cfg(feature = "package")]
+        Some("xz") => {
+            // The xz decoder writes its output, it can't be read on the fly.
+            let mut decompressed = vec![];
+            lzma_rs::xz_decompress(&mut &data[..], &mut decompressed)
+                .map_err(|_| ParseError::invalid_value("xz", "compression", path))?;
+            Ok(Box::new(io::Cursor::new(decompressed)))
+        }
+        _ => Ok(Box::new(data)),
+    }
+}
+
+/// Decompresses and parses a `Components-<arch>.yml` catalog.
+fn parse_catalog(path: &str, data: &[u8]) -> Result<Collection, ParseError> {
+    let mut text = String::new();
+    decompress(path, data)?.read_to_string(&mut text)?;
+    let documents = YamlLoader::load_from_str(&text)
+        .map_err(|e| ParseError::invalid_value(&e.to_string(), "$value", path))?;
+    if documents.is_empty() {
+        return Err(ParseError::missing_tag("File"));
+    }
+    Collection::try_from(&documents)
+}
+
+/// Merges the catalogs, the components keep the origin of their catalog.
+fn merge(collections: Vec<Collection>, architecture: &str) -> Collection {
+    let same = |f: fn(&Collection) -> &Option<String>| {
+        let first = collections.first().and_then(|c| f(c).clone());
+        if collections.iter().all(|c| *f(c) == first) {
+            first
+        } else {
+            None
+        }
+    };
+    let origin = same(|c| &c.origin);
+    let media_base_url = same(|c| &c.media_base_url);
+
+    let mut merged = Collection {
+        version: collections
+            .first()
+            .map(|c| c.version.clone())
+            .unwrap_or_else(|| "0.8".to_string()),
+        origin,
+        media_base_url,
+        components: vec![],
+        architecture: Some(architecture.to_string()),
+        // The merged catalog is as old as its oldest part.
+        timestamp: collections.iter().filter_map(|c| c.timestamp).min(),
+        indices: Default::default(),
+    };
+    for collection in collections.into_iter() {
+        let origin = collection.origin.as_deref().map(Arc::<str>::from);
+        for mut component in collection.components.into_iter() {
+            if component.origin.is_none() {
+                component.origin = origin.clone();
+            }
+            merged.components.push(component);
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::{Request, Response};
+    use sha2::{Digest as _, Sha256};
+    use std::collections::HashMap;
+    use std::error::Error;
+    use std::io;
+
+    struct FakeMirror(HashMap<String, Vec<u8>>);
+
+    impl HttpClient for FakeMirror {
+        fn send(&self, request: &Request) -> io::Result<Response> {
+            match self.0.get(request.url.path()) {
+                Some(data) => {
+                    let mut response = Response::new(200);
+                    response.body = Box::new(io::Cursor::new(data.clone()));
+                    Ok(response)
+                }
+                None => Ok(Response::new(404)),
+            }
+        }
+    }
+
+    fn sha256(data: &[u8]) -> String {
+        let mut hasher = Sha256::default();
+        hasher.update(data);
+        digest::to_hex(&hasher.finalize())
+    }
+
+    #[test]
+    fn sources_entries() -> Result<(), Box<dyn Error>> {
+        let entries = SourcesEntry::from_deb822(
+            "Types: deb deb-src
+URIs: http://deb.debian.org/debian https://mirror.example.org/debian
+Suites: bookworm bookworm-updates
+Components: main contrib
+  non-free-firmware
+Signed-By: /usr/share/keyrings/debian-archive-keyring.gpg
+
+# Sources only
+Types: deb-src
+URIs: http://deb.debian.org/debian
+Suites: bookworm
+Components: main
+
+Types: deb
+URIs: http://security.debian.org/debian-security
+Suites: bookworm-security
+Components: main
+Architectures: arm64
+Enabled: no
+",
+        )?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].uris.len(), 2);
+        assert_eq!(entries[0].suites, vec!["bookworm", "bookworm-updates"]);
+        assert_eq!(
+            entries[0].components,
+            vec!["main", "contrib", "non-free-firmware"]
+        );
+        assert!(entries[0].architectures.is_empty());
+
+        let entry = SourcesEntry::from_str(
+            "deb [arch=amd64,i386 signed-by=/etc/keyring.gpg] http://deb.debian.org/debian bookworm main # comment",
+        )?;
+        assert_eq!(
+            entry,
+            SourcesEntry::new(Url::parse("http://deb.debian.org/debian")?, "bookworm")
+                .architectures(&["amd64", "i386"])
+        );
+        assert!(SourcesEntry::from_str("deb-src http://deb.debian.org/debian bookworm").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn release_file() -> Result<(), Box<dyn Error>> {
+        let release = ReleaseFile::from_str(
+            "-----BEGIN PGP SIGNED MESSAGE-----
+Hash: SHA512
+
+Origin: Debian
+Suite: stable
+Codename: bookworm
+MD5Sum:
+ 0ed6d4c8891eb86358b94bb35d9e4da4  1484322 contrib/Contents-all
+SHA256:
+ a2c9a2d6e5d4a3c0ab7c4fa7b1c7d5ffb2a9e2d4c1b0c8a3c4d5e6f7a8b9c0d1 29 main/dep11/Components-amd64.yml.gz
+-----BEGIN PGP SIGNATURE-----
+
+iQIzBAEBCgAdFiEE
+-----END PGP SIGNATURE-----
+",
+        )?;
+        assert_eq!(release.origin.as_deref(), Some("Debian"));
+        assert_eq!(release.codename.as_deref(), Some("bookworm"));
+        assert_eq!(
+            release.files,
+            vec![ReleaseFileEntry {
+                path: "main/dep11/Components-amd64.yml.gz".into(),
+                size: 29,
+                checksum: Checksum::Sha256(
+                    "a2c9a2d6e5d4a3c0ab7c4fa7b1c7d5ffb2a9e2d4c1b0c8a3c4d5e6f7a8b9c0d1".into()
+                ),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_dep11() -> Result<(), Box<dyn Error>> {
+        let catalog = std::fs::read("./tests/collections/spec_example.yaml")?;
+        let icons = b"not really a tarball".to_vec();
+        let release = format!(
+            "Origin: Tanglu\nSuite: chromodoris\nSHA256:\n {} {} main/dep11/Components-amd64.yml\n {} {} main/dep11/Components-amd64.yml.xz\n {} {} main/dep11/icons-64x64.tar\n",
+            sha256(&catalog),
+            catalog.len(),
+            sha256(b"xz"),
+            2,
+            sha256(&icons),
+            icons.len(),
+        );
+        let mut files = HashMap::new();
+        files.insert(
+            "/tanglu/dists/chromodoris/Release".to_string(),
+            release.into_bytes(),
+        );
+        files.insert(
+            "/tanglu/dists/chromodoris/main/dep11/Components-amd64.yml".to_string(),
+            catalog,
+        );
+        files.insert(
+            "/tanglu/dists/chromodoris/main/dep11/icons-64x64.tar".to_string(),
+            icons.clone(),
+        );
+
+        let entry = SourcesEntry::new(Url::parse("https://example.org/tanglu")?, "chromodoris")
+            .components(&["main", "contrib"]);
+        let fetcher = Dep11Fetcher::new(FakeMirror(files.clone()), "amd64");
+        let catalog = fetcher.fetch(&entry)?;
+        assert_eq!(
+            catalog.collection.origin.as_deref(),
+            Some("chromodoris-main")
+        );
+        assert_eq!(catalog.collection.architecture.as_deref(), Some("amd64"));
+        assert!(!catalog.collection.components.is_empty());
+        assert!(catalog
+            .collection
+            .components
+            .iter()
+            .all(|c| c.origin.as_deref() == Some("chromodoris-main")));
+        assert_eq!(
+            catalog.icon_tarballs,
+            vec![IconTarball {
+                origin: Some("chromodoris-main".into()),
+                size: "64x64".into(),
+                path: "main/dep11/icons-64x64.tar".into(),
+                data: icons,
+            }]
+        );
+
+        // A tampered file is rejected.
+        files.insert(
+            "/tanglu/dists/chromodoris/main/dep11/icons-64x64.tar".to_string(),
+            b"not really a tarbal!".to_vec(),
+        );
+        let fetcher = Dep11Fetcher::new(FakeMirror(files), "amd64");
+        assert!(matches!(
+            fetcher.fetch(&entry),
+            Err(ParseError::ChecksumMismatch(_, _))
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "package")]
+    #[test]
+    fn fetch_xz_catalog() -> Result<(), Box<dyn Error>> {
+        let catalog = std::fs::read("./tests/collections/spec_example.yaml")?;
+        let mut compressed = vec![];
+        lzma_rs::xz_compress(&mut &catalog[..], &mut compressed)?;
+        let release = format!(
+            "Origin: Tanglu\nSuite: chromodoris\nSHA256:\n {} {} main/dep11/Components-amd64.yml.xz\n {} {} main/dep11/Components-amd64.yml\n",
+            sha256(&compressed),
+            compressed.len(),
+            sha256(b"unused"),
+            6,
+        );
+        let mut files = HashMap::new();
+        files.insert(
+            "/tanglu/dists/chromodoris/Release".to_string(),
+            release.into_bytes(),
+        );
+        files.insert(
+            "/tanglu/dists/chromodoris/main/dep11/Components-amd64.yml.xz".to_string(),
+            compressed,
+        );
+
+        let entry = SourcesEntry::new(Url::parse("https://example.org/tanglu")?, "chromodoris");
+        let fetched = Dep11Fetcher::new(FakeMirror(files), "amd64").fetch(&entry)?;
+        let expected = parse_catalog("Components-amd64.yml", &catalog)?;
+        assert_eq!(
+            fetched.collection.components.len(),
+            expected.components.len()
+        );
+        assert!(!fetched.collection.components.is_empty());
+        assert!(parse_catalog("Components-amd64.yml.xz", b"not xz").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use super::{AppId, Collection, Component};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+/// The changes between two versions of a catalog, computed by `Collection::diff`.
+///
+/// The components are compared by id: when any component of an id changed, all
+/// the components of the new version with that id are part of the delta.
+///
+/// # Example
+///
+/// ```
+/// use appstream::Collection;
+///
+/// let old = Collection::from_path("./tests/collections/gnome-apps.xml".into()).unwrap();
+/// let mut new = old.clone();
+/// new.components[0].pkgname = Some("gnome-weather".into());
+///
+/// let delta = old.diff(&new);
+/// assert_eq!(delta.updated.len(), 1);
+///
+/// let mut patched = old.clone();
+/// patched.apply_delta(&delta);
+/// assert_eq!(patched, new);
+/// ```
+pub struct CollectionDelta {
+    /// The specification version of the new catalog.
+    pub version: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The origin of the new catalog.
+    pub origin: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The base URL for media of the new catalog.
+    pub media_base_url: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The targeted CPU architecture of the new catalog.
+    pub architecture: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// The ids whose components were removed.
+    pub removed: Vec<AppId>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// The added or changed components.
+    pub updated: Vec<Component>,
+}
+
+/// Groups the components by id, in order of first appearance.
+fn by_id(components: &[Component]) -> (Vec<&str>, HashMap<&str, Vec<&Component>>) {
+    let mut ids = Vec::new();
+    let mut groups: HashMap<&str, Vec<&Component>> = HashMap::new();
+    for component in components {
+        let id = component.id.as_ref();
+        groups
+            .entry(id)
+            .or_insert_with(|| {
+                ids.push(id);
+                Vec::new()
+            })
+            .push(component);
+    }
+    (ids, groups)
+}
+
+impl CollectionDelta {
+    pub(crate) fn new(old: &Collection, new: &Collection) -> Self {
+        let (old_ids, old_groups) = by_id(&old.components);
+        let (new_ids, new_groups) = by_id(&new.components);
+        let removed = old_ids
+            .into_iter()
+            .filter(|id| !new_groups.contains_key(id))
+            .map(AppId::from)
+            .collect();
+        let updated = new_ids
+            .into_iter()
+            .filter(|id| old_groups.get(id) != new_groups.get(id))
+            .flat_map(|id| new_groups[id].iter().map(|c| (*c).clone()))
+            .collect();
+        Self {
+            version: new.version.clone(),
+            origin: new.origin.clone(),
+            media_base_url: new.media_base_url.clone(),
+            architecture: new.architecture.clone(),
+            removed,
+            updated,
+        }
+    }
+
+    /// Whether the delta changes no components.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.updated.is_empty()
+    }
+
+    pub(crate) fn apply(&self, collection: &mut Collection) {
+        let removed = self
+            .removed
+            .iter()
+            .map(|id| id.as_ref())
+            .collect::<HashSet<_>>();
+        let (updated_ids, mut updated) = by_id(&self.updated);
+
+        let mut components = Vec::with_capacity(collection.components.len());
+        for component in collection.components.drain(..) {
+            let id = component.id.as_ref();
+            if removed.contains(id) {
+                continue;
+            }
+            // The updated components take the place of the first old one.
+            match updated.get_mut(id) {
+                Some(group) => components.extend(group.drain(..).cloned()),
+                None => components.push(component),
+            }
+        }
+        for id in updated_ids {
+            components.extend(updated[id].iter().map(|c| (*c).clone()));
+        }
+
+        collection.version = self.version.clone();
+        collection.origin = self.origin.clone();
+        collection.media_base_url = self.media_base_url.clone();
+        collection.architecture = self.architecture.clone();
+        collection.components = components;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn apply_delta() -> Result<(), Box<dyn Error>> {
+        let old = Collection::from_path("./tests/collections/gnome-apps.xml".into())?;
+        assert!(old.diff(&old).is_empty());
+
+        let mut new = old.clone();
+        let removed = new.components.remove(1);
+        new.components[3].pkgname = Some("gnome-weather".into());
+        let mut duplicate = new.components[5].clone();
+        duplicate.pkgname = Some("flatpak".into());
+        new.components.insert(6, duplicate);
+        let mut added = removed.clone();
+        added.id = "org.example.New".into();
+        new.components.push(added);
+        new.origin = Some("gnome".into());
+
+        let delta = old.diff(&new);
+        assert_eq!(delta.removed, vec![removed.id]);
+        assert_eq!(delta.updated.len(), 4);
+
+        let mut patched = old.clone();
+        patched.apply_delta(&delta);
+        assert_eq!(patched, new);
+        Ok(())
+    }
+}
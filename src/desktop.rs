@@ -0,0 +1,276 @@
+use super::error::ParseError;
+use super::translatable_string::DEFAULT_LOCALE;
+use super::{TranslatableList, TranslatableString};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+const DESKTOP_ENTRY_GROUP: &str = "Desktop Entry";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// The `[Desktop Entry]` group of a `.desktop` file.
+/// See the [Desktop Entry Specification](https://specifications.freedesktop.org/desktop-entry-spec/latest/).
+///
+/// Only the keys that matter to AppStream are kept, see `Component::merge_desktop_entry`.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::DesktopEntry;
+///
+/// let entry = DesktopEntry::from_path("/usr/share/applications/org.gnome.design.Contrast.desktop".as_ref()).unwrap();
+/// println!("{:?}", entry.name.get_default());
+/// ```
+pub struct DesktopEntry {
+    /// The entry type, usually `Application`.
+    pub kind: String,
+    /// The specific name of the application.
+    pub name: TranslatableString,
+    /// A tooltip for the entry.
+    pub comment: Option<TranslatableString>,
+    /// The icon name, or an absolute path to it.
+    pub icon: Option<String>,
+    /// The categories the entry should be shown in a menu.
+    pub categories: Vec<String>,
+    /// Additional words describing the entry.
+    pub keywords: Option<TranslatableList>,
+    /// The mimetypes supported by the application.
+    pub mimetypes: Vec<String>,
+    /// Whether the entry should not be displayed in menus.
+    pub no_display: bool,
+}
+
+impl DesktopEntry {
+    /// Create a new `DesktopEntry` from a `.desktop` file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the desktop file.
+    pub fn from_path(path: &Path) -> Result<Self, ParseError> {
+        fs::read_to_string(path)?.parse()
+    }
+}
+
+impl FromStr for DesktopEntry {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut entry = DesktopEntry::default();
+        let mut group: Option<&str> = None;
+        let mut found = false;
+
+        for line in s.lines().map(|l| l.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                group = Some(name);
+                found |= name == DESKTOP_ENTRY_GROUP;
+                continue;
+            }
+            if group != Some(DESKTOP_ENTRY_GROUP) {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| ParseError::invalid_value(line, "line", DESKTOP_ENTRY_GROUP))?;
+            let key = key.trim();
+            let value = value.trim();
+            let (key, locale) = match key.strip_suffix(']').and_then(|k| k.split_once('[')) {
+                Some((key, locale)) => (key, Some(locale)),
+                None => (key, None),
+            };
+
+            match key {
+                "Type" => entry.kind = unescape(value),
+                "Name" => entry.name.add_for_locale(locale, &unescape(value)),
+                "Comment" => entry
+                    .comment
+                    .get_or_insert_with(TranslatableString::default)
+                    .add_for_locale(locale, &unescape(value)),
+                "Icon" if locale.is_none() => entry.icon = Some(unescape(value)),
+                "Categories" if locale.is_none() => entry.categories = split_list(value),
+                "MimeType" if locale.is_none() => entry.mimetypes = split_list(value),
+                "Keywords" => {
+                    let keywords = entry.keywords.get_or_insert_with(TranslatableList::default);
+                    for keyword in split_list(value) {
+                        keywords.add_for_locale(locale, &keyword);
+                    }
+                }
+                "NoDisplay" => entry.no_display = value == "true",
+                _ => (),
+            }
+        }
+
+        if !found {
+            return Err(ParseError::missing_tag(DESKTOP_ENTRY_GROUP));
+        }
+        Ok(entry)
+    }
+}
+
+impl fmt::Display for DesktopEntry {
+    /// Formats the entry as the content of a `.desktop` file.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[{}]", DESKTOP_ENTRY_GROUP)?;
+        if !self.kind.is_empty() {
+            writeln!(f, "Type={}", escape(&self.kind))?;
+        }
+        write_translations(f, "Name", &self.name.0, |t| escape(t))?;
+        if let Some(comment) = &self.comment {
+            write_translations(f, "Comment", &comment.0, |t| escape(t))?;
+        }
+        if let Some(icon) = &self.icon {
+            writeln!(f, "Icon={}", escape(icon))?;
+        }
+        if !self.categories.is_empty() {
+            writeln!(f, "Categories={}", join_list(&self.categories))?;
+        }
+        if let Some(keywords) = &self.keywords {
+            write_translations(f, "Keywords", &keywords.0, |k| join_list(k))?;
+        }
+        if !self.mimetypes.is_empty() {
+            writeln!(f, "MimeType={}", join_list(&self.mimetypes))?;
+        }
+        if self.no_display {
+            writeln!(f, "NoDisplay=true")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the untranslated value of a key, then its translations.
+fn write_translations<T, F: Fn(&T) -> String>(
+    f: &mut fmt::Formatter<'_>,
+    key: &str,
+    translations: &BTreeMap<String, T>,
+    format: F,
+) -> fmt::Result {
+    if let Some(value) = translations.get(DEFAULT_LOCALE) {
+        writeln!(f, "{}={}", key, format(value))?;
+    }
+    for (locale, value) in translations.iter() {
+        if locale != DEFAULT_LOCALE {
+            writeln!(f, "{}[{}]={}", key, locale, format(value))?;
+        }
+    }
+    Ok(())
+}
+
+/// Adds the escape sequences of a value, the reverse of `unescape`.
+fn escape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            ' ' if i == 0 => result.push_str("\\s"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            '\\' => result.push_str("\\\\"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Joins the items of a `;` separated list, the reverse of `split_list`.
+fn join_list<S: AsRef<str>>(items: &[S]) -> String {
+    items
+        .iter()
+        .map(|i| format!("{};", escape(i.as_ref()).replace(';', "\\;")))
+        .collect()
+}
+
+/// Replaces the escape sequences of a value.
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// Splits a `;` separated list, `\;` being an escaped separator.
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(';') => current.push(';'),
+                Some(other) => {
+                    current.push('\\');
+                    current.push(other);
+                }
+                None => current.push('\\'),
+            },
+            ';' => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    items.push(current);
+    items
+        .iter()
+        .map(|i| unescape(i.trim()))
+        .filter(|i| !i.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn desktop_entry() -> Result<(), Box<dyn Error>> {
+        let entry = DesktopEntry::from_path(Path::new(
+            "./tests/compose/usr/share/applications/org.gnome.design.Contrast.desktop",
+        ))?;
+        assert_eq!(entry.kind, "Application");
+        assert_eq!(
+            entry.name,
+            TranslatableString::with_default("Contrast").and_locale("fr", "Contraste")
+        );
+        assert_eq!(
+            entry.comment,
+            Some(TranslatableString::with_default(
+                "Check contrast between two colors"
+            ))
+        );
+        assert_eq!(entry.icon, Some("org.gnome.design.Contrast".into()));
+        assert_eq!(entry.categories, vec!["Utility", "Graphics"]);
+        assert_eq!(
+            entry.keywords,
+            Some(
+                TranslatableList::with_default(vec!["Color", "Contrast"])
+                    .and_locale("fr", vec!["Couleur"])
+            )
+        );
+        assert!(!entry.no_display);
+
+        assert!("[Other Group]\nName=Foo".parse::<DesktopEntry>().is_err());
+        assert_eq!(split_list(r"a\;b;c;;"), vec!["a;b", "c"]);
+        assert_eq!(unescape(r"a\sb\\c"), "a b\\c");
+        assert_eq!(join_list(&["a;b", " c\\"]), r"a\;b;\sc\\;");
+
+        let written: DesktopEntry = entry.to_string().parse()?;
+        assert_eq!(written, entry);
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use super::enums::Checksum;
+use blake2::{Blake2b512, Blake2s256};
+use sha1::Sha1;
+use sha2::{Digest as _, Sha256};
+use std::io::{self, Read};
+
+/// One of the hashers a `Checksum` can be computed with.
+#[derive(Clone, Debug)]
+pub enum Digest {
+    /// SHA-1.
+    Sha1(Sha1),
+    /// SHA-256.
+    Sha256(Sha256),
+    /// BLAKE2b-512.
+    Blake2b(Blake2b512),
+    /// BLAKE2s-256.
+    Blake2s(Blake2s256),
+}
+
+impl Digest {
+    /// Creates the hasher a checksum was computed with, returns it alongside the
+    /// expected digest.
+    pub fn for_checksum(checksum: &Checksum) -> (Self, &str) {
+        match checksum {
+            Checksum::Sha1(value) => (Digest::Sha1(Sha1::default()), value),
+            Checksum::Sha256(value) => (Digest::Sha256(Sha256::default()), value),
+            Checksum::Blake2b(value) => (Digest::Blake2b(Blake2b512::default()), value),
+            Checksum::Blake2s(value) => (Digest::Blake2s(Blake2s256::default()), value),
+        }
+    }
+
+    /// Feeds `data` to the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Digest::Sha1(h) => h.update(data),
+            Digest::Sha256(h) => h.update(data),
+            Digest::Blake2b(h) => h.update(data),
+            Digest::Blake2s(h) => h.update(data),
+        }
+    }
+
+    /// Returns the digest of the data fed so far.
+    pub fn finish(self) -> Vec<u8> {
+        match self {
+            Digest::Sha1(h) => h.finalize().to_vec(),
+            Digest::Sha256(h) => h.finalize().to_vec(),
+            Digest::Blake2b(h) => h.finalize().to_vec(),
+            Digest::Blake2s(h) => h.finalize().to_vec(),
+        }
+    }
+}
+
+/// A reader hashing everything read through it.
+#[derive(Debug)]
+pub struct HashingReader<R> {
+    inner: R,
+    digest: Digest,
+}
+
+impl<R: Read> HashingReader<R> {
+    /// Wraps `inner`, feeding what is read from it to `digest`.
+    pub fn new(inner: R, digest: Digest) -> Self {
+        Self { inner, digest }
+    }
+
+    /// Reads the rest of the data, so trailing bytes the consumer didn't need are
+    /// hashed as well, and returns the digest.
+    pub fn finish(mut self) -> io::Result<Vec<u8>> {
+        io::copy(&mut self, &mut io::sink())?;
+        Ok(self.digest.finish())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.digest.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// Formats a digest as a lowercase hexadecimal string.
+pub fn to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_digests() {
+        let digest = |checksum: Checksum| {
+            let (mut digest, _) = Digest::for_checksum(&checksum);
+            digest.update(b"abc");
+            to_hex(&digest.finish())
+        };
+        assert_eq!(
+            digest(Checksum::Sha1(String::new())),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            digest(Checksum::Sha256(String::new())),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            digest(Checksum::Blake2b(String::new())),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+        );
+        assert_eq!(
+            digest(Checksum::Blake2s(String::new())),
+            "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"
+        );
+    }
+
+    #[test]
+    fn hashing_reader() -> io::Result<()> {
+        let (digest, _) = Digest::for_checksum(&Checksum::Sha256(String::new()));
+        let mut reader = HashingReader::new(&b"abc"[..], digest);
+        let mut first = [0u8; 1];
+        reader.read_exact(&mut first)?;
+        assert_eq!(
+            to_hex(&reader.finish()?),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        Ok(())
+    }
+}
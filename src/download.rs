@@ -0,0 +1,309 @@
+use super::digest::{self, Digest};
+use super::enums::{ArtifactKind, Size};
+use super::error::ParseError;
+use super::net::{self, FetchOptions, HttpClient, Method, Request};
+use super::{Artifact, Release};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Downloads release artifacts, like firmware cabinets or tarballs, and checks
+/// them against the sizes and the checksums of the catalog before keeping them.
+///
+/// The artifact is streamed to `<target>.part`, hashed on the fly, and only
+/// renamed to its target once verified, a failed download never leaves a file
+/// behind that could be mistaken for a good one.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::download::ArtifactDownloader;
+/// use appstream::enums::ArtifactKind;
+/// use appstream::net::{HttpClient, Request, Response};
+/// use appstream::Component;
+/// # struct MyClient;
+/// # impl HttpClient for MyClient {
+/// #     fn send(&self, _: &Request) -> std::io::Result<Response> { unimplemented!() }
+/// # }
+///
+/// let component = Component::from_path("/usr/share/metainfo/com.example.Device.firmware.metainfo.xml".into()).unwrap();
+/// let downloader = ArtifactDownloader::new(MyClient).platform("x86_64-linux-gnu");
+/// let release = component.latest_stable_release().unwrap();
+/// let artifact = downloader.select(release, ArtifactKind::Binary).unwrap();
+/// downloader
+///     .download(artifact, "/tmp/firmware.cab".as_ref(), |done, total| {
+///         println!("{} / {:?} bytes", done, total);
+///     })
+///     .unwrap();
+/// ```
+pub struct ArtifactDownloader<C: HttpClient> {
+    client: C,
+    options: FetchOptions,
+    platform: Option<String>,
+    require_checksum: bool,
+}
+
+impl<C: HttpClient> ArtifactDownloader<C> {
+    /// Creates a downloader requiring the artifacts to have a checksum.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to send the requests through.
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            options: FetchOptions::default(),
+            platform: None,
+            require_checksum: true,
+        }
+    }
+
+    /// Sets the redirect and timeout options of the downloads.
+    pub fn options(mut self, options: FetchOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets the platform triplet of the system, like `x86_64-linux-gnu`, the
+    /// artifacts built for other platforms aren't selected.
+    pub fn platform(mut self, platform: &str) -> Self {
+        self.platform = Some(platform.to_string());
+        self
+    }
+
+    /// Sets whether the artifacts without any checksum are refused, they are by
+    /// default.
+    pub fn require_checksum(mut self, require_checksum: bool) -> Self {
+        self.require_checksum = require_checksum;
+        self
+    }
+
+    /// The artifact of a release to download: the first one of the kind built for
+    /// the platform, or for any platform.
+    ///
+    /// # Arguments
+    ///
+    /// * `release` - The release.
+    /// * `kind` - The kind of artifact, binary or source.
+    pub fn select<'a>(&self, release: &'a Release, kind: ArtifactKind) -> Option<&'a Artifact> {
+        let artifacts = release.artifacts.iter().filter(|a| a.kind == kind);
+        let platform = self.platform.as_deref();
+        artifacts
+            .clone()
+            .find(|a| platform.is_some_and(|p| a.is_for_platform(p)))
+            .or_else(|| artifacts.clone().find(|a| a.platform.is_none()))
+    }
+
+    /// Downloads an artifact to `target` and verifies it.
+    ///
+    /// All the checksums of the artifact and its download size are checked, the
+    /// download stops as soon as it's larger than expected.
+    ///
+    /// # Arguments
+    ///
+    /// * `artifact` - The artifact to download.
+    /// * `target` - The path of the downloaded file.
+    /// * `progress` - Called after each chunk written to the disk, with the bytes
+    ///   received so far and the total size, when the catalog or the server
+    ///   tells it.
+    pub fn download<F: FnMut(u64, Option<u64>)>(
+        &self,
+        artifact: &Artifact,
+        target: &Path,
+        mut progress: F,
+    ) -> Result<(), ParseError> {
+        if self.require_checksum && artifact.checksums.is_empty() {
+            return Err(ParseError::missing_tag("checksum"));
+        }
+        let expected_size = artifact.sizes.iter().find_map(|s| match s {
+            Size::Download(size) => Some(*size),
+            _ => None,
+        });
+
+        let request = Request::new(Method::Get, artifact.url.clone());
+        let mut response = net::send_following(&self.client, request, &self.options)?;
+        if !(200..300).contains(&response.status) {
+            return Err(std::io::Error::other(format!(
+                "{} returned HTTP status {}",
+                artifact.url, response.status
+            ))
+            .into());
+        }
+        let total = expected_size.or_else(|| {
+            response
+                .header("content-length")
+                .and_then(|l| l.trim().parse().ok())
+        });
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut name = target.as_os_str().to_owned();
+        name.push(".part");
+        let partial = Path::new(&name);
+        let result = write(
+            artifact,
+            &mut response.body,
+            partial,
+            (expected_size, total),
+            &mut progress,
+        );
+        match result {
+            Ok(()) => Ok(fs::rename(partial, target)?),
+            Err(e) => {
+                let _ = fs::remove_file(partial);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Streams the body to `partial`, then checks its size and checksums.
+fn write(
+    artifact: &Artifact,
+    body: &mut dyn Read,
+    partial: &Path,
+    (expected_size, total): (Option<u64>, Option<u64>),
+    progress: &mut dyn FnMut(u64, Option<u64>),
+) -> Result<(), ParseError> {
+    let mut digests = artifact
+        .checksums
+        .iter()
+        .map(Digest::for_checksum)
+        .collect::<Vec<(Digest, &str)>>();
+    let mut file = File::create(partial)?;
+    let mut buffer = vec![0; 64 * 1024];
+    let mut written = 0u64;
+    loop {
+        let read = body.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        written += read as u64;
+        if let Some(expected) = expected_size {
+            if written > expected {
+                return Err(ParseError::limit_exceeded(
+                    "bytes in the artifact",
+                    expected,
+                ));
+            }
+        }
+        for (digest, _) in digests.iter_mut() {
+            digest.update(&buffer[..read]);
+        }
+        file.write_all(&buffer[..read])?;
+        progress(written, total);
+    }
+    file.sync_all()?;
+
+    if let Some(expected) = expected_size {
+        if written != expected {
+            return Err(ParseError::invalid_value(
+                &written.to_string(),
+                "download",
+                "size",
+            ));
+        }
+    }
+    for (digest, expected) in digests {
+        let actual = digest::to_hex(&digest.finish());
+        if actual != expected.to_lowercase() {
+            return Err(ParseError::ChecksumMismatch(
+                expected.to_lowercase(),
+                actual,
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::{ArtifactBuilder, ReleaseBuilder};
+    use crate::enums::Checksum;
+    use crate::net::Response;
+    use sha2::{Digest as _, Sha256};
+    use std::error::Error;
+    use std::io;
+    use url::Url;
+
+    const CONTENT: &[u8] = b"firmware payload";
+
+    struct FakeServer;
+
+    impl HttpClient for FakeServer {
+        fn send(&self, request: &Request) -> io::Result<Response> {
+            match request.url.path() {
+                "/firmware.cab" => {
+                    let mut response = Response::new(200);
+                    response.body = Box::new(io::Cursor::new(CONTENT));
+                    Ok(response)
+                }
+                _ => Ok(Response::new(404)),
+            }
+        }
+    }
+
+    fn artifact(
+        platform: Option<&str>,
+        checksum: &str,
+        size: u64,
+    ) -> Result<Artifact, Box<dyn Error>> {
+        let mut builder = ArtifactBuilder::default()
+            .url(Url::parse("https://example.com/firmware.cab")?)
+            .kind(ArtifactKind::Binary)
+            .checksum(Checksum::Sha256(checksum.into()))
+            .size(Size::Download(size));
+        if let Some(platform) = platform {
+            builder = builder.platform(platform);
+        }
+        Ok(builder.build())
+    }
+
+    #[test]
+    fn download_artifact() -> Result<(), Box<dyn Error>> {
+        let mut hasher = Sha256::default();
+        hasher.update(CONTENT);
+        let checksum = digest::to_hex(&hasher.finalize()).to_uppercase();
+        let size = CONTENT.len() as u64;
+
+        let release = ReleaseBuilder::new("1.2.3")
+            .artifact(artifact(Some("aarch64-linux-gnu"), "00", size)?)
+            .artifact(artifact(None, &checksum, size)?)
+            .build();
+        let downloader = ArtifactDownloader::new(FakeServer).platform("x86_64-linux-gnu");
+        let selected = downloader
+            .select(&release, ArtifactKind::Binary)
+            .ok_or("no artifact")?;
+        assert_eq!(selected.platform, None);
+        assert!(downloader.select(&release, ArtifactKind::Source).is_none());
+
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().join("download");
+        let target = root.join("firmware.cab");
+        let mut updates = vec![];
+        downloader.download(selected, &target, |done, total| updates.push((done, total)))?;
+        assert_eq!(fs::read(&target)?, CONTENT);
+        assert_eq!(updates.last(), Some(&(size, Some(size))));
+
+        let corrupted = artifact(None, &"0".repeat(64), size)?;
+        let target = root.join("corrupted.cab");
+        let result = downloader.download(&corrupted, &target, |_, _| ());
+        assert!(matches!(result, Err(ParseError::ChecksumMismatch(_, _))));
+        assert!(!target.exists());
+        assert_eq!(fs::read_dir(&root)?.count(), 1);
+
+        let truncated = artifact(None, &checksum, size - 1)?;
+        assert!(downloader.download(&truncated, &target, |_, _| ()).is_err());
+        let mut unverified = artifact(None, &checksum, size)?;
+        unverified.checksums.clear();
+        assert!(downloader
+            .download(&unverified, &target, |_, _| ())
+            .is_err());
+        let downloader = downloader.require_checksum(false);
+        downloader.download(&unverified, &target, |_, _| ())?;
+        assert!(target.exists());
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use super::error::ParseError;
-use super::AppId;
+use super::media::MediaResolver;
+use super::{vercmp, AppId};
 use serde::ser::{SerializeMap, SerializeStruct};
 use serde::{
     de::{self, Deserializer, MapAccess, Visitor},
@@ -17,6 +18,7 @@ use url::Url;
 #[strum(serialize_all = "lowercase")]
 #[non_exhaustive]
 /// The artifact type.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ArtifactKind {
     /// The artifact is distributed as source-code.
     Source,
@@ -398,6 +400,71 @@ pub enum Category {
     Unknown(String),
 }
 
+impl Category {
+    /// The category of a name of the `Categories` key of a desktop entry, for the
+    /// catalogs.
+    ///
+    /// The registered names are recognized, even the ones the parser doesn't
+    /// know like `2DGraphics` or `IDE`, and the legacy ones like `Internet` are
+    /// mapped to their registered category. The vendor extensions, the toolkit
+    /// and desktop categories like `GTK` or `KDE`, the reserved ones like
+    /// `TrayIcon` and the unknown ones are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name in the desktop entry, like `AudioVideo`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::enums::Category;
+    ///
+    /// assert_eq!(Category::from_desktop_name("2DGraphics"), Some(Category::TwoDGraphics));
+    /// assert_eq!(Category::from_desktop_name("Internet"), Some(Category::Network));
+    /// assert_eq!(Category::from_desktop_name("GTK"), None);
+    /// ```
+    pub fn from_desktop_name(name: &str) -> Option<Self> {
+        let category = match name.trim() {
+            "IDE" => Self::IDE,
+            "GUIDesigner" => Self::GUIDesigner,
+            "PDA" => Self::PDA,
+            "2DGraphics" => Self::TwoDGraphics,
+            "3DGraphics" => Self::ThreeDGraphics,
+            "OCR" => Self::OCR,
+            "IRCClient" => Self::IRCClient,
+            "P2P" => Self::P2P,
+            "TV" => Self::TV,
+            // Legacy names, from before the menu specification.
+            "Internet" | "Web" => Self::Network,
+            "Multimedia" => Self::AudioVideo,
+            "Sound" => Self::Audio,
+            "Games" => Self::Game,
+            "Utilities" | "Accessories" => Self::Utility,
+            "Programming" => Self::Development,
+            "Editor" => Self::TextEditor,
+            "Mail" => Self::Email,
+            name => Self::from_str(name).ok()?,
+        };
+        match category {
+            Self::Core
+            | Self::KDE
+            | Self::GNOME
+            | Self::XFCE
+            | Self::GTK
+            | Self::Qt
+            | Self::Motif
+            | Self::Java
+            | Self::ConsoleOnly
+            | Self::Screensaver
+            | Self::TrayIcon
+            | Self::Applet
+            | Self::Shell
+            | Self::Unknown(_) => None,
+            category => Some(category),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 #[serde(tag = "type", content = "$value")]
@@ -414,11 +481,133 @@ pub enum Checksum {
     Blake2s(String),
 }
 
+#[derive(
+    Clone, Copy, Debug, Default, AsRefStr, EnumString, ToString, Serialize, Deserialize, PartialEq,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+/// How a version is compared to the one of a requirement.
+/// See [\<requires\/\>](https://www.freedesktop.org/software/appstream/docs/chap-Metadata.html#tag-requires-recommends-supports).
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Compare {
+    /// Equal to.
+    Eq,
+    /// Not equal to.
+    Ne,
+    /// Less than.
+    Lt,
+    /// Greater than.
+    Gt,
+    /// Less than or equal to.
+    Le,
+    #[default]
+    /// Greater than or equal to, the default.
+    Ge,
+    /// Matching a glob pattern, with `*` and `?` wildcards.
+    Glob,
+    /// Matching a regular expression, which isn't supported: never satisfied.
+    Regex,
+}
+
+impl Compare {
+    /// Whether a version satisfies the comparison with the one of a requirement.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version being checked, like the installed one.
+    /// * `required` - The version, or the pattern, of the requirement.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::enums::Compare;
+    ///
+    /// assert!(Compare::Ge.matches("1.10", "1.9"));
+    /// assert!(Compare::Glob.matches("2.0.1", "2.0.*"));
+    /// assert!(!Compare::Lt.matches("1.0", "1.0"));
+    /// ```
+    pub fn matches(&self, version: &str, required: &str) -> bool {
+        let ordering = vercmp(version, required);
+        match self {
+            Compare::Eq => ordering == Ordering::Equal,
+            Compare::Ne => ordering != Ordering::Equal,
+            Compare::Lt => ordering == Ordering::Less,
+            Compare::Gt => ordering == Ordering::Greater,
+            Compare::Le => ordering != Ordering::Greater,
+            Compare::Ge => ordering != Ordering::Less,
+            Compare::Glob => glob_matches(version, required),
+            Compare::Regex => false,
+        }
+    }
+}
+
+/// Matches a text against a glob pattern, `*` matching any sequence and `?` any
+/// character.
+pub(crate) fn glob_matches(text: &str, pattern: &str) -> bool {
+    let (text, pattern) = (text.as_bytes(), pattern.as_bytes());
+    let (mut t, mut p) = (0, 0);
+    // The position of the last `*` and of the text it was matched at, to
+    // backtrack to when the rest of the pattern doesn't match.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == b'?' || *c == text[t] => {
+                t += 1;
+                p += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    star = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == b'*')
+}
+
+#[derive(
+    Clone, Copy, Debug, AsRefStr, EnumString, ToString, Serialize, Deserialize, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+#[non_exhaustive]
+/// An input method to control a component, or available on the system.
+/// See [\<control\/\>](https://www.freedesktop.org/software/appstream/docs/chap-Metadata.html#tag-relations-control).
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Control {
+    /// A mouse, touchpad or trackball.
+    Pointing,
+    /// A physical keyboard.
+    Keyboard,
+    /// A text console.
+    Console,
+    /// A touch screen.
+    Touch,
+    /// A gamepad or joystick.
+    Gamepad,
+    /// Voice commands.
+    Voice,
+    /// A camera recognizing gestures.
+    Vision,
+    /// A TV remote.
+    TvRemote,
+    /// A graphics tablet.
+    Tablet,
+}
+
 #[derive(Clone, Copy, Debug, AsRefStr, Serialize, ToString, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "kebab-case")]
 #[non_exhaustive]
 /// Defines the various types of a `Component`.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ComponentKind {
     /// A runtime.
     Runtime,
@@ -468,6 +657,23 @@ impl Default for ComponentKind {
     }
 }
 
+#[derive(
+    Clone, Copy, Debug, AsRefStr, EnumString, ToString, Serialize, Deserialize, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+/// How a merge component changes the component of the same id, set in its
+/// `merge` attribute.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum MergeKind {
+    /// Its values are added to the ones of the component.
+    Append,
+    /// Its values replace the ones of the component.
+    Replace,
+    /// The component is removed from the catalog.
+    RemoveComponent,
+}
+
 impl FromStr for ComponentKind {
     type Err = ParseError;
 
@@ -497,6 +703,7 @@ impl FromStr for ComponentKind {
 #[serde(tag = "id", content = "$value")]
 #[non_exhaustive]
 /// OARS attribute.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ContentAttribute {
     #[serde(rename = "violence-cartoon")]
     /// Defined as fictional characters depicted in an animated film or a comic strip which do not look human.
@@ -584,9 +791,95 @@ pub enum ContentAttribute {
     MoneyGambling(ContentState),
 }
 
+impl ContentAttribute {
+    /// The attribute of an OARS id, `None` if the id is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The OARS id, like `violence-cartoon`.
+    /// * `state` - The intensity of the content.
+    pub fn from_id(id: &str, state: ContentState) -> Option<Self> {
+        Some(match id {
+            "violence-cartoon" => ContentAttribute::ViolenceCartoon(state),
+            "violence-fantasy" => ContentAttribute::ViolenceFantasy(state),
+            "violence-realistic" => ContentAttribute::ViolenceRealistic(state),
+            "violence-bloodshed" => ContentAttribute::ViolenceBloodshed(state),
+            "violence-sexual" => ContentAttribute::ViolenceSexual(state),
+            "violence-desecration" => ContentAttribute::ViolenceDesecration(state),
+            "violence-slavery" => ContentAttribute::ViolenceSlavery(state),
+            "violence-worship" => ContentAttribute::ViolenceWorship(state),
+            "drugs-alcohol" => ContentAttribute::DrugsAlcohol(state),
+            "drugs-narcotics" => ContentAttribute::DrugsNarcotics(state),
+            "drugs-tobacco" => ContentAttribute::DrugsTobacco(state),
+            "sex-nudity" => ContentAttribute::SexNudity(state),
+            "sex-themes" => ContentAttribute::SexThemes(state),
+            "sex-homosexuality" => ContentAttribute::SexHomosexuality(state),
+            "sex-prostitution" => ContentAttribute::SexProstitution(state),
+            "sex-adultery" => ContentAttribute::SexAdultery(state),
+            "sex-appearance" => ContentAttribute::SexAppearance(state),
+            "language-profanity" => ContentAttribute::LanguageProfanity(state),
+            "language-humor" => ContentAttribute::LanguageHumor(state),
+            "language-discrimination" => ContentAttribute::LanguageDiscrimination(state),
+            "social-chat" => ContentAttribute::SocialChat(state),
+            "social-info" => ContentAttribute::SocialInfo(state),
+            "social-audio" => ContentAttribute::SocialAudio(state),
+            "social-location" => ContentAttribute::SocialLocation(state),
+            "social-contacts" => ContentAttribute::SocialContacts(state),
+            "money-advertising" => ContentAttribute::MoneyAdvertising(state),
+            "money-purchasing" => ContentAttribute::MoneyPurchasing(state),
+            "money-gambling" => ContentAttribute::MoneyGambling(state),
+            _ => return None,
+        })
+    }
+
+    /// The OARS id of the attribute, like `violence-cartoon`.
+    pub fn id(&self) -> &'static str {
+        self.parts().0
+    }
+
+    /// The intensity of the content.
+    pub fn state(&self) -> ContentState {
+        self.parts().1
+    }
+
+    fn parts(&self) -> (&'static str, ContentState) {
+        match self {
+            ContentAttribute::ViolenceCartoon(state) => ("violence-cartoon", *state),
+            ContentAttribute::ViolenceFantasy(state) => ("violence-fantasy", *state),
+            ContentAttribute::ViolenceRealistic(state) => ("violence-realistic", *state),
+            ContentAttribute::ViolenceBloodshed(state) => ("violence-bloodshed", *state),
+            ContentAttribute::ViolenceSexual(state) => ("violence-sexual", *state),
+            ContentAttribute::ViolenceDesecration(state) => ("violence-desecration", *state),
+            ContentAttribute::ViolenceSlavery(state) => ("violence-slavery", *state),
+            ContentAttribute::ViolenceWorship(state) => ("violence-worship", *state),
+            ContentAttribute::DrugsAlcohol(state) => ("drugs-alcohol", *state),
+            ContentAttribute::DrugsNarcotics(state) => ("drugs-narcotics", *state),
+            ContentAttribute::DrugsTobacco(state) => ("drugs-tobacco", *state),
+            ContentAttribute::SexNudity(state) => ("sex-nudity", *state),
+            ContentAttribute::SexThemes(state) => ("sex-themes", *state),
+            ContentAttribute::SexHomosexuality(state) => ("sex-homosexuality", *state),
+            ContentAttribute::SexProstitution(state) => ("sex-prostitution", *state),
+            ContentAttribute::SexAdultery(state) => ("sex-adultery", *state),
+            ContentAttribute::SexAppearance(state) => ("sex-appearance", *state),
+            ContentAttribute::LanguageProfanity(state) => ("language-profanity", *state),
+            ContentAttribute::LanguageHumor(state) => ("language-humor", *state),
+            ContentAttribute::LanguageDiscrimination(state) => ("language-discrimination", *state),
+            ContentAttribute::SocialChat(state) => ("social-chat", *state),
+            ContentAttribute::SocialInfo(state) => ("social-info", *state),
+            ContentAttribute::SocialAudio(state) => ("social-audio", *state),
+            ContentAttribute::SocialLocation(state) => ("social-location", *state),
+            ContentAttribute::SocialContacts(state) => ("social-contacts", *state),
+            ContentAttribute::MoneyAdvertising(state) => ("money-advertising", *state),
+            ContentAttribute::MoneyPurchasing(state) => ("money-purchasing", *state),
+            ContentAttribute::MoneyGambling(state) => ("money-gambling", *state),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Eq, PartialEq, Deserialize, Serialize, Debug)]
 #[non_exhaustive]
 /// Defines the version of the OARS specification.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ContentRatingVersion {
     #[serde(rename = "oars-1.0")]
     /// OARS v1.0.
@@ -627,6 +920,7 @@ impl PartialOrd for ContentRatingVersion {
 #[serde(rename_all = "lowercase")]
 #[strum(serialize_all = "lowercase")]
 /// Defines the state of a `ContentAttribute`
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ContentState {
     /// No state is set.
     None,
@@ -648,6 +942,7 @@ impl Default for ContentState {
 #[serde(rename_all = "lowercase")]
 #[strum(serialize_all = "lowercase")]
 /// Defines the firmware type.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FirmwareKind {
     /// A flashed firmware.
     Flashed,
@@ -690,6 +985,17 @@ pub enum Icon {
     },
 }
 
+impl Icon {
+    /// The location a remote icon is served from according to `resolver`, `None`
+    /// for the other kinds of icons.
+    pub fn resolved_url(&self, resolver: &MediaResolver) -> Option<Url> {
+        match self {
+            Icon::Remote { url, .. } => Some(resolver.resolve(url)),
+            _ => None,
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for Icon {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -824,10 +1130,25 @@ impl Serialize for Icon {
     }
 }
 
+#[derive(
+    Clone, Copy, Debug, ToString, AsRefStr, Serialize, Deserialize, PartialEq, Eq, EnumString,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+/// The color scheme a branding color is meant for.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum ColorScheme {
+    /// A light color scheme.
+    Light,
+    /// A dark color scheme.
+    Dark,
+}
+
 #[derive(Clone, Copy, Debug, ToString, AsRefStr, Serialize, Deserialize, PartialEq, EnumString)]
 #[serde(rename_all = "lowercase")]
 #[strum(serialize_all = "lowercase")]
 /// The type of an image.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ImageKind {
     /// The source image.
     Source,
@@ -841,6 +1162,20 @@ impl Default for ImageKind {
     }
 }
 
+#[derive(
+    Clone, Copy, Debug, AsRefStr, EnumString, ToString, Serialize, Deserialize, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+/// The codec of a screenshot video, as set in its `codec` attribute.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum VideoCodec {
+    /// The VP9 codec.
+    Vp9,
+    /// The AV1 codec.
+    Av1,
+}
+
 #[derive(Clone, Debug, Deserialize, AsRefStr, ToString, Serialize, PartialEq, EnumString)]
 #[strum(serialize_all = "PascalCase")]
 #[non_exhaustive]
@@ -1025,13 +1360,16 @@ pub enum Provide {
 #[derive(Clone, Copy, Debug, ToString, EnumString, AsRefStr, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 #[strum(serialize_all = "lowercase")]
-/// Classifies the release into stable/development.
+/// Classifies the release into stable/development/snapshot.
 /// See [\<releases\/\>](https://www.freedesktop.org/software/appstream/docs/chap-Metadata.html#tag-releases).
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ReleaseKind {
     /// A stable release.
     Stable,
     /// A development release, not intended to be installed by users.
     Development,
+    /// A snapshot of the development, like a nightly build.
+    Snapshot,
 }
 
 impl Default for ReleaseKind {
@@ -1040,11 +1378,27 @@ impl Default for ReleaseKind {
     }
 }
 
+#[derive(
+    Clone, Copy, Debug, Default, AsRefStr, EnumString, ToString, Serialize, Deserialize, PartialEq,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+/// The type of an issue fixed by a release.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum IssueKind {
+    #[default]
+    /// An issue of the bug tracker of the project.
+    Generic,
+    /// A security vulnerability, identified by its CVE id.
+    Cve,
+}
+
 #[derive(Clone, Copy, Debug, AsRefStr, EnumString, ToString, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 #[strum(serialize_all = "lowercase")]
 /// Defines how important is to install the new release as un update.
 /// See [\<releases\/\>](https://www.freedesktop.org/software/appstream/docs/chap-Metadata.html#tag-releases).
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ReleaseUrgency {
     /// Low urgency.
     Low,
@@ -1066,6 +1420,7 @@ impl Default for ReleaseUrgency {
 #[serde(tag = "type", content = "$value", rename_all = "kebab-case")]
 #[non_exhaustive]
 /// Defines the download and installed size of a `Component` or `Artifact`.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Size {
     /// The downloaded size is bytes.
     Download(u64),
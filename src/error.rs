@@ -1,3 +1,5 @@
+#[cfg(feature = "schema")]
+use super::validation::ValidationIssue;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -7,14 +9,24 @@ pub enum ParseError {
     /// Xml error.
     XmlParserError(#[from] xmltree::ParseError),
 
+    #[error("XML reader error: {0}")]
+    /// quick-xml failed to read a document.
+    XmlReaderError(#[from] quick_xml::Error),
+
     #[error("URL parser error: {0}")]
     /// url failed to parse a URL.
     UrlParseError(#[from] url::ParseError),
 
+    #[cfg(feature = "chrono")]
     #[error("chrono parser error: {0}")]
     /// url failed to parse a URL.
     ChronoParseError(#[from] chrono::ParseError),
 
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    /// The SQLite store failed.
+    SqliteError(#[from] rusqlite::Error),
+
     #[error("Input/output error: {0} ")]
     /// IO.
     IOError(#[from] std::io::Error),
@@ -38,6 +50,20 @@ pub enum ParseError {
     #[error("Invalid value {0} passed to attribute {1} for tag {2}")]
     /// A value passed to an attribute for a specific tag is invalid.
     InvalidValue(String, String, String),
+
+    #[error("Checksum mismatch, expected {0} but got {1}")]
+    /// The digest of a file doesn't match the expected checksum.
+    ChecksumMismatch(String, String),
+
+    #[error("Limit exceeded: more than {1} {0}")]
+    /// The document goes over a limit set in the `ParseOptions`.
+    LimitExceeded(String, u64),
+
+    #[cfg(feature = "schema")]
+    #[error("The document doesn't follow the AppStream schema: {}", .0[0].message)]
+    /// The document doesn't follow the AppStream schema, see
+    /// `ParseOptions::validate_schema`. There is at least one issue.
+    SchemaViolation(Vec<ValidationIssue>),
 }
 
 impl ParseError {
@@ -65,4 +91,47 @@ impl ParseError {
     pub fn missing_value(tag: &str) -> Self {
         ParseError::MissingValue(tag.to_string())
     }
+
+    /// Creates a limit exceeded error.
+    pub fn limit_exceeded(what: &str, limit: u64) -> Self {
+        ParseError::LimitExceeded(what.to_string(), limit)
+    }
+}
+
+#[derive(Clone, Debug, Error, PartialEq)]
+/// Something the parser dropped or recovered from without failing.
+pub enum ParseWarning {
+    #[error("Unknown tag {0} in tag {1}")]
+    /// A tag that isn't supported, it was skipped.
+    UnknownTag(String, String),
+
+    #[error("Ignored attribute {0} of tag {1}")]
+    /// An attribute that isn't supported, it was skipped.
+    IgnoredAttribute(String, String),
+
+    #[error("Unexpected value {0} of attribute {1} for tag {2}, recovered as {3}")]
+    /// A value that isn't valid, replaced by a fallback.
+    RecoveredValue(String, String, String, String),
+}
+
+impl ParseWarning {
+    /// Creates an unknown tag warning.
+    pub fn unknown_tag(tag: &str, parent: &str) -> Self {
+        ParseWarning::UnknownTag(tag.to_string(), parent.to_string())
+    }
+
+    /// Creates an ignored attribute warning.
+    pub fn ignored_attribute(attr: &str, tag: &str) -> Self {
+        ParseWarning::IgnoredAttribute(attr.to_string(), tag.to_string())
+    }
+
+    /// Creates a recovered value warning.
+    pub fn recovered_value(val: &str, attr: &str, tag: &str, fallback: &str) -> Self {
+        ParseWarning::RecoveredValue(
+            val.to_string(),
+            attr.to_string(),
+            tag.to_string(),
+            fallback.to_string(),
+        )
+    }
 }
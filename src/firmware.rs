@@ -0,0 +1,262 @@
+use super::enums::{ArtifactKind, Compare, Provide};
+use super::{vercmp, Artifact, Component, Release};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// The target of the requirements on the bootloader of a device.
+const BOOTLOADER: &str = "bootloader";
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+/// A `<firmware>` requirement of a component, on the version of the firmware of
+/// the device or of another one.
+/// See [\<requires\/\>](https://www.freedesktop.org/software/appstream/docs/chap-Metadata.html#tag-requires-recommends-supports).
+pub struct FirmwareRequirement {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The firmware the version is compared to: `None` for the one of the
+    /// device, `bootloader` for its bootloader or the GUID of another device.
+    pub target: Option<String>,
+    #[serde(default)]
+    /// How the versions are compared.
+    pub compare: Compare,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The required version, without one the target only has to be present.
+    pub version: Option<String>,
+}
+
+impl FirmwareRequirement {
+    /// Whether a device satisfies the requirement.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The device to update.
+    pub fn is_satisfied(&self, device: &Device) -> bool {
+        let installed = match self.target.as_deref() {
+            None => Some(device.version.as_str()),
+            Some(BOOTLOADER) => device.bootloader_version.as_deref(),
+            Some(guid) => device
+                .others
+                .get(&guid.to_ascii_lowercase())
+                .map(String::as_str),
+        };
+        match (installed, &self.version) {
+            (Some(installed), Some(version)) => self.compare.matches(installed, version),
+            (installed, None) => installed.is_some(),
+            (None, Some(_)) => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// A device whose firmware can be updated, as reported by fwupd.
+///
+/// # Example
+///
+/// ```
+/// use appstream::firmware::Device;
+/// use appstream::Component;
+///
+/// let component = Component::from_path("./tests/firmware.xml".into()).unwrap();
+/// let device = Device::new("3.0.1").guid("84F40464-9272-4EF7-9399-CD95F12DA696");
+/// assert!(device.matches(&component));
+///
+/// let (release, artifact) = device.update(&component).unwrap();
+/// assert_eq!(release.version, "3.0.2");
+/// assert!(artifact.is_some());
+/// ```
+pub struct Device {
+    guids: Vec<String>,
+    version: String,
+    bootloader_version: Option<String>,
+    others: HashMap<String, String>,
+}
+
+impl Device {
+    /// Creates a device with its installed firmware version.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The installed firmware version.
+    pub fn new(version: &str) -> Self {
+        Self {
+            version: version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds a GUID of the device, it's compared case-insensitively.
+    pub fn guid(mut self, guid: &str) -> Self {
+        self.guids.push(guid.to_ascii_lowercase());
+        self
+    }
+
+    /// Sets the version of the bootloader of the device.
+    pub fn bootloader_version(mut self, version: &str) -> Self {
+        self.bootloader_version = Some(version.to_string());
+        self
+    }
+
+    /// Adds another device of the system, the requirements can target its GUID.
+    ///
+    /// # Arguments
+    ///
+    /// * `guid` - The GUID of the other device.
+    /// * `version` - Its installed firmware version.
+    pub fn other_device(mut self, guid: &str, version: &str) -> Self {
+        self.others
+            .insert(guid.to_ascii_lowercase(), version.to_string());
+        self
+    }
+
+    /// Whether a component provides a firmware for one of the GUIDs of the device.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - The firmware component.
+    pub fn matches(&self, component: &Component) -> bool {
+        component.provides.iter().any(|p| match p {
+            Provide::Firmware { item, .. } => self.guids.contains(&item.to_ascii_lowercase()),
+            _ => false,
+        })
+    }
+
+    /// Whether the device satisfies all the firmware requirements of a component.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - The firmware component.
+    pub fn satisfies(&self, component: &Component) -> bool {
+        component
+            .firmware_requirements
+            .iter()
+            .all(|r| r.is_satisfied(self))
+    }
+
+    /// The newest release of a component that updates the device, with its binary
+    /// artifact when it has one.
+    ///
+    /// There's none when the component doesn't match the device, when its
+    /// requirements aren't satisfied or when no release is newer than the
+    /// installed firmware.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - The firmware component.
+    pub fn update<'a>(
+        &self,
+        component: &'a Component,
+    ) -> Option<(&'a Release, Option<&'a Artifact>)> {
+        if !self.matches(component) || !self.satisfies(component) {
+            return None;
+        }
+        let release = component
+            .releases
+            .iter()
+            .filter(|r| vercmp(&r.version, &self.version) == Ordering::Greater)
+            .max_by(|a, b| vercmp(&a.version, &b.version))?;
+        let artifact = release
+            .artifacts
+            .iter()
+            .find(|a| a.kind == ArtifactKind::Binary);
+        Some((release, artifact))
+    }
+
+    /// The newest update of the device among several components, like the ones
+    /// of an LVFS catalog where each release is a component of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `components` - The firmware components.
+    pub fn best_update<'a, I: IntoIterator<Item = &'a Component>>(
+        &self,
+        components: I,
+    ) -> Option<(&'a Component, &'a Release, Option<&'a Artifact>)> {
+        components
+            .into_iter()
+            .filter_map(|c| {
+                let (release, artifact) = self.update(c)?;
+                Some((c, release, artifact))
+            })
+            .max_by(|(_, a, _), (_, b, _)| vercmp(&a.version, &b.version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::{ComponentBuilder, ReleaseBuilder};
+    use crate::enums::FirmwareKind;
+    use crate::TranslatableString;
+    use std::convert::TryFrom;
+    use std::error::Error;
+
+    fn firmware(version: &str, requirements: Vec<FirmwareRequirement>) -> Component {
+        let mut builder = ComponentBuilder::default()
+            .id("com.example.Device.firmware".into())
+            .name(TranslatableString::with_default("Firmware"))
+            .provide(Provide::Firmware {
+                kind: FirmwareKind::Flashed,
+                item: "2082b5e0-7a64-478a-b1b2-e3404fab6dad".into(),
+            })
+            .release(ReleaseBuilder::new(version).build());
+        for requirement in requirements {
+            builder = builder.require_firmware(requirement);
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn match_device() -> Result<(), Box<dyn Error>> {
+        let element = xmltree::Element::parse(
+            r#"<requires>
+                <id compare="ge" version="1.0.1">org.freedesktop.fwupd</id>
+                <firmware compare="ge" version="1.2.0"/>
+                <firmware compare="glob" version="0.3.*">bootloader</firmware>
+            </requires>"#
+                .as_bytes(),
+        )?;
+        let requirements = element
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|c| c.name == "firmware")
+            .map(FirmwareRequirement::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            requirements[1],
+            FirmwareRequirement {
+                target: Some("bootloader".into()),
+                compare: Compare::Glob,
+                version: Some("0.3.*".into()),
+            }
+        );
+
+        let device = Device::new("1.2.3")
+            .guid("2082B5E0-7A64-478A-B1B2-E3404FAB6DAD")
+            .bootloader_version("0.3.4");
+        let components = vec![
+            firmware("1.2.4", requirements.clone()),
+            firmware("1.3.0", requirements.clone()),
+            firmware("1.1.0", vec![]),
+        ];
+        let (component, release, artifact) = device.best_update(&components).ok_or("no update")?;
+        assert_eq!(release.version, "1.3.0");
+        assert_eq!(component, &components[1]);
+        assert!(artifact.is_none());
+
+        let old_bootloader = device.clone().bootloader_version("0.2.0");
+        assert!(!old_bootloader.satisfies(&components[0]));
+        assert!(old_bootloader.best_update(&components).is_none());
+
+        let other = FirmwareRequirement {
+            target: Some("4B6F8CE8-B8A4-4D1E-A5A5-7E3C5E7F4A1B".into()),
+            compare: Compare::Eq,
+            version: Some("2.0".into()),
+        };
+        assert!(!other.is_satisfied(&device));
+        let device = device.other_device("4b6f8ce8-b8a4-4d1e-a5a5-7e3c5e7f4a1b", "2.0");
+        assert!(other.is_satisfied(&device));
+        assert!(!Device::new("1.0").matches(&components[0]));
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+use super::digest;
+use super::error::ParseError;
+use super::gvariant;
+use super::net::{self, HttpClient};
+use super::Collection;
+use flate2::read::{DeflateDecoder, GzDecoder};
+use sha2::{Digest as _, Sha256};
+use std::convert::TryFrom;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use url::Url;
+use xmltree::Element;
+
+/// The OSTree object types fetched from a remote.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ObjectKind {
+    Commit,
+    DirTree,
+    File,
+}
+
+impl ObjectKind {
+    fn extension(self) -> &'static str {
+        match self {
+            ObjectKind::Commit => "commit",
+            ObjectKind::DirTree => "dirtree",
+            ObjectKind::File => "filez",
+        }
+    }
+}
+
+/// The files and sub-directories of an OSTree directory, with their checksums.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct DirTree {
+    files: Vec<(String, String)>,
+    dirs: Vec<(String, String)>,
+}
+
+/// The AppStream data of a flatpak remote, the `appstream/<arch>` branch of its
+/// OSTree repository.
+///
+/// Only the static `archive` repositories served over HTTP are supported, which
+/// is what Flathub and most remotes are. The commit and directory objects are
+/// verified against their checksum, the OpenPGP signatures are not.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::flatpak::FlatpakRemote;
+/// use appstream::net::{HttpClient, Request, Response};
+/// use url::Url;
+/// # use std::io;
+/// # struct MyClient;
+/// # impl HttpClient for MyClient {
+/// #     fn send(&self, _request: &Request) -> io::Result<Response> { unimplemented!() }
+/// # }
+///
+/// let remote = FlatpakRemote::new(MyClient, Url::parse("https://dl.flathub.org/repo/").unwrap())
+///     .architecture("x86_64");
+/// let collection = remote.fetch_collection().unwrap();
+/// remote.fetch_icons(&["64x64"], "/tmp/flathub/icons".as_ref()).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct FlatpakRemote<C: HttpClient> {
+    client: C,
+    url: Url,
+    architecture: String,
+}
+
+impl<C: HttpClient> FlatpakRemote<C> {
+    /// Creates a new remote for the architecture of the running system.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to send the requests through.
+    /// * `url` - The URL of the OSTree repository, like `https://dl.flathub.org/repo/`.
+    pub fn new(client: C, mut url: Url) -> Self {
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        let architecture = match std::env::consts::ARCH {
+            "x86" => "i386",
+            arch => arch,
+        };
+        Self {
+            client,
+            url,
+            architecture: architecture.to_string(),
+        }
+    }
+
+    /// Sets the architecture, as named by flatpak, like `x86_64` or `aarch64`.
+    pub fn architecture(mut self, architecture: &str) -> Self {
+        self.architecture = architecture.to_string();
+        self
+    }
+
+    /// Resolves the commit of the `appstream/<arch>` branch, from the `refs/heads`
+    /// directory of the repository or from its summary file.
+    pub fn commit(&self) -> Result<String, ParseError> {
+        let branch = format!("appstream/{}", self.architecture);
+        if let Ok(data) = net::get(&self.client, &self.url.join("refs/heads/")?.join(&branch)?) {
+            let commit = String::from_utf8_lossy(&data).trim().to_string();
+            if is_checksum(&commit) {
+                return Ok(commit);
+            }
+        }
+        let summary = net::get(&self.client, &self.url.join("summary")?)?;
+        summary_refs(&summary)?
+            .into_iter()
+            .find(|(name, _)| *name == branch)
+            .map(|(_, commit)| commit)
+            .ok_or_else(|| ParseError::missing_value(&branch))
+    }
+
+    /// Downloads the catalog of the remote, its `appstream.xml.gz` file.
+    pub fn fetch_collection(&self) -> Result<Collection, ParseError> {
+        let root = self.root()?;
+        let (name, checksum) = root
+            .files
+            .iter()
+            .find(|(name, _)| name == "appstream.xml.gz")
+            .or_else(|| root.files.iter().find(|(name, _)| name == "appstream.xml"))
+            .ok_or_else(|| ParseError::missing_tag("appstream.xml.gz"))?;
+        let data = self.fetch_file(checksum)?;
+        let element = if name.ends_with(".gz") {
+            Element::parse(GzDecoder::new(&data[..]))?
+        } else {
+            Element::parse(&data[..])?
+        };
+        Collection::try_from(&element)
+    }
+
+    /// Downloads the cached icons of the remote to `<output>/<size>/`, the layout
+    /// flatpak uses in its `active/icons` directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `sizes` - The icon sizes, like `64x64` or `128x128`.
+    /// * `output` - The directory to write the icons to.
+    pub fn fetch_icons(&self, sizes: &[&str], output: &Path) -> Result<(), ParseError> {
+        let root = self.root()?;
+        let icons = match root.dirs.iter().find(|(name, _)| name == "icons") {
+            Some((_, checksum)) => self.fetch_dirtree(checksum)?,
+            None => return Ok(()),
+        };
+        for (size, checksum) in icons.dirs.iter() {
+            if !sizes.contains(&size.as_str()) {
+                continue;
+            }
+            let directory = output.join(size);
+            fs::create_dir_all(&directory)?;
+            for (name, checksum) in self.fetch_dirtree(checksum)?.files.iter() {
+                // Names come from the network, don't let them escape the directory.
+                if name.contains('/') || name.starts_with('.') {
+                    continue;
+                }
+                fs::write(directory.join(name), self.fetch_file(checksum)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The root directory of the appstream branch.
+    fn root(&self) -> Result<DirTree, ParseError> {
+        let commit = self.fetch_object(&self.commit()?, ObjectKind::Commit)?;
+        self.fetch_dirtree(&commit_root(&commit)?)
+    }
+
+    fn fetch_dirtree(&self, checksum: &str) -> Result<DirTree, ParseError> {
+        parse_dirtree(&self.fetch_object(checksum, ObjectKind::DirTree)?)
+    }
+
+    /// Downloads and decompresses the content of a file object.
+    fn fetch_file(&self, checksum: &str) -> Result<Vec<u8>, ParseError> {
+        let data = self.fetch_object(checksum, ObjectKind::File)?;
+        let mut content = Vec::new();
+        DeflateDecoder::new(filez_content(&data)?).read_to_end(&mut content)?;
+        Ok(content)
+    }
+
+    /// Downloads an object, the metadata ones are verified against their checksum.
+    fn fetch_object(&self, checksum: &str, kind: ObjectKind) -> Result<Vec<u8>, ParseError> {
+        if !is_checksum(checksum) {
+            return Err(ParseError::invalid_value(checksum, "checksum", "ostree"));
+        }
+        let path = format!(
+            "objects/{}/{}.{}",
+            &checksum[..2],
+            &checksum[2..],
+            kind.extension()
+        );
+        let data = net::get(&self.client, &self.url.join(&path)?)?;
+        // File checksums cover the uncompressed content and its metadata.
+        if kind != ObjectKind::File {
+            let mut hasher = Sha256::default();
+            hasher.update(&data);
+            let actual = digest::to_hex(&hasher.finalize());
+            if actual != checksum {
+                return Err(ParseError::ChecksumMismatch(checksum.to_string(), actual));
+            }
+        }
+        Ok(data)
+    }
+}
+
+fn is_checksum(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// The compressed content of an archive file object, after its size-prefixed
+/// header.
+fn filez_content(data: &[u8]) -> Result<&[u8], ParseError> {
+    let invalid = || ParseError::invalid_value("filez", "header", "ostree");
+    if data.len() < 8 {
+        return Err(invalid());
+    }
+    let size = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    data.get(8 + size..).ok_or_else(invalid)
+}
+
+/// The checksum of the root directory of a `(a{sv}aya(say)sstayay)` commit.
+fn commit_root(data: &[u8]) -> Result<String, ParseError> {
+    let members = gvariant::tuple(data, &["a{sv}", "ay", "a(say)", "s", "s", "t", "ay", "ay"])
+        .ok_or_else(|| ParseError::invalid_value("commit", "format", "ostree"))?;
+    Ok(digest::to_hex(members[6]))
+}
+
+/// Parses a `(a(say)a(sayay))` directory tree.
+fn parse_dirtree(data: &[u8]) -> Result<DirTree, ParseError> {
+    let invalid = || ParseError::invalid_value("dirtree", "format", "ostree");
+    let members = gvariant::tuple(data, &["a(say)", "a(sayay)"]).ok_or_else(invalid)?;
+    let mut tree = DirTree::default();
+    for file in gvariant::array(members[0], "(say)").ok_or_else(invalid)? {
+        let file = gvariant::tuple(file, &["s", "ay"]).ok_or_else(invalid)?;
+        tree.files.push((
+            gvariant::string(file[0]).ok_or_else(invalid)?,
+            digest::to_hex(file[1]),
+        ));
+    }
+    for dir in gvariant::array(members[1], "(sayay)").ok_or_else(invalid)? {
+        let dir = gvariant::tuple(dir, &["s", "ay", "ay"]).ok_or_else(invalid)?;
+        tree.dirs.push((
+            gvariant::string(dir[0]).ok_or_else(invalid)?,
+            digest::to_hex(dir[1]),
+        ));
+    }
+    Ok(tree)
+}
+
+/// The refs of a `(a(s(taya{sv}))a{sv})` summary file, with their commit.
+fn summary_refs(data: &[u8]) -> Result<Vec<(String, String)>, ParseError> {
+    let invalid = || ParseError::invalid_value("summary", "format", "ostree");
+    let members = gvariant::tuple(data, &["a(s(taya{sv}))", "a{sv}"]).ok_or_else(invalid)?;
+    let mut refs = vec![];
+    for entry in gvariant::array(members[0], "(s(taya{sv}))").ok_or_else(invalid)? {
+        let entry = gvariant::tuple(entry, &["s", "(taya{sv})"]).ok_or_else(invalid)?;
+        let target = gvariant::tuple(entry[1], &["t", "ay", "a{sv}"]).ok_or_else(invalid)?;
+        refs.push((
+            gvariant::string(entry[0]).ok_or_else(invalid)?,
+            digest::to_hex(target[1]),
+        ));
+    }
+    Ok(refs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serializes a `(say)`-like tuple of a string followed by byte arrays.
+    fn entry(name: &str, checksums: &[&[u8]]) -> Vec<u8> {
+        let mut data = name.as_bytes().to_vec();
+        data.push(0);
+        let mut offsets = vec![data.len() as u8];
+        for (i, checksum) in checksums.iter().enumerate() {
+            data.extend_from_slice(checksum);
+            if i < checksums.len() - 1 {
+                offsets.push(data.len() as u8);
+            }
+        }
+        offsets.reverse();
+        data.extend(offsets);
+        data
+    }
+
+    /// Serializes an array of variable-size elements.
+    fn array(elements: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = vec![];
+        let mut offsets = vec![];
+        for element in elements {
+            data.extend_from_slice(element);
+            offsets.push(data.len() as u8);
+        }
+        data.extend(offsets);
+        data
+    }
+
+    #[test]
+    fn dirtree() -> Result<(), ParseError> {
+        let files = array(&[
+            entry("appstream.xml.gz", &[&[0xab; 32]]),
+            entry("appstream.xml", &[&[0x01; 32]]),
+        ]);
+        let dirs = array(&[entry("icons", &[&[0xcd; 32], &[0xef; 32]])]);
+        let mut data = files.clone();
+        data.extend_from_slice(&dirs);
+        data.push(files.len() as u8);
+
+        let tree = parse_dirtree(&data)?;
+        assert_eq!(
+            tree.files,
+            vec![
+                ("appstream.xml.gz".to_string(), "ab".repeat(32)),
+                ("appstream.xml".to_string(), "01".repeat(32)),
+            ]
+        );
+        assert_eq!(tree.dirs, vec![("icons".to_string(), "cd".repeat(32))]);
+
+        assert!(parse_dirtree(&[])?.files.is_empty());
+        assert!(parse_dirtree(&[0, 0, 0, 0]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn filez_header() -> Result<(), ParseError> {
+        let data = [0, 0, 0, 2, 0, 0, 0, 0, 0xaa, 0xbb, 0x01, 0x02];
+        assert_eq!(filez_content(&data)?, &[0x01, 0x02]);
+        assert!(filez_content(&[0, 0, 1, 0, 0, 0, 0, 0]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use super::error::ParseError;
+use super::gvariant;
+use super::{Collection, Component};
+use flate2::read::GzDecoder;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use xmltree::Element;
+
+#[derive(Clone, Debug, PartialEq)]
+/// The metadata of a `.flatpak` single-file bundle, as made by
+/// `flatpak build-bundle`.
+///
+/// A bundle is an OSTree static delta, its superblock starts with a dictionary
+/// holding the ref, the flatpak metadata and the compressed AppStream data of the
+/// application. Only that dictionary is read, not the content of the bundle, and
+/// its signatures aren't checked.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::flatpak_bundle::FlatpakBundle;
+///
+/// let bundle = FlatpakBundle::from_path("./org.gnome.design.Contrast.flatpak".as_ref()).unwrap();
+/// println!("{}", bundle.reference);
+/// if let Some(component) = bundle.component {
+///     println!("{:?}", component.name.get_default());
+/// }
+/// ```
+pub struct FlatpakBundle {
+    /// The ref of the bundle, like `app/org.gnome.design.Contrast/x86_64/stable`.
+    pub reference: String,
+    /// The name of the remote the bundle was built from, if set.
+    pub origin: Option<String>,
+    /// The location of a `.flatpakrepo` file providing the runtime, if set.
+    pub runtime_repo: Option<String>,
+    /// The flatpak metadata keyfile, with the runtime and the permissions.
+    pub metadata: Option<String>,
+    /// The component of the application, when the bundle embeds its AppStream data.
+    pub component: Option<Component>,
+}
+
+impl FlatpakBundle {
+    /// Reads the metadata of a bundle file, without loading the rest of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the `.flatpak` file.
+    pub fn from_path(path: &Path) -> Result<Self, ParseError> {
+        let invalid = || ParseError::invalid_value(&path.display().to_string(), "path", "bundle");
+        let mut file = File::open(path)?;
+        let length = file.seek(SeekFrom::End(0))? as usize;
+        // The superblock is a tuple, the end of its first member is the last
+        // framing offset of the file.
+        let size = gvariant::offset_size(length);
+        let mut offset = vec![0; size];
+        file.seek(SeekFrom::End(-(size as i64)))?;
+        file.read_exact(&mut offset)?;
+        let end = gvariant::read_offset(&offset, 0, size).ok_or_else(invalid)?;
+        if end > length - size {
+            return Err(invalid());
+        }
+
+        let mut metadata = vec![0; end];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut metadata)?;
+        Self::from_metadata(&metadata)
+    }
+
+    /// Reads the metadata of a bundle loaded in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The content of the `.flatpak` file.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ParseError> {
+        let size = gvariant::offset_size(data.len());
+        let metadata = data
+            .len()
+            .checked_sub(size)
+            .and_then(|at| gvariant::read_offset(data, at, size))
+            .and_then(|end| data.get(..end))
+            .ok_or_else(|| ParseError::invalid_value("superblock", "format", "bundle"))?;
+        Self::from_metadata(metadata)
+    }
+
+    /// Reads the `a{sv}` metadata dictionary of the superblock.
+    fn from_metadata(dict: &[u8]) -> Result<Self, ParseError> {
+        let string = |key: &str| -> Result<Option<String>, ParseError> {
+            match gvariant::lookup(dict, key) {
+                Some((value, "s")) => {
+                    let value = gvariant::string(value)
+                        .ok_or_else(|| ParseError::invalid_value(key, "key", "bundle"))?;
+                    Ok(Some(value))
+                }
+                Some(_) => Err(ParseError::invalid_value(key, "key", "bundle")),
+                None => Ok(None),
+            }
+        };
+
+        let component = match gvariant::lookup(dict, "appdata") {
+            Some((data, "ay")) => {
+                let element = Element::parse(GzDecoder::new(data))?;
+                let collection = Collection::try_from(&element)?;
+                collection.components.into_iter().next()
+            }
+            Some(_) => return Err(ParseError::invalid_value("appdata", "key", "bundle")),
+            None => None,
+        };
+
+        Ok(Self {
+            reference: string("ref")?.ok_or_else(|| ParseError::missing_value("ref"))?,
+            origin: string("origin")?,
+            runtime_repo: string("runtime-repo")?,
+            metadata: string("metadata")?,
+            component,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::error::Error;
+    use std::fs;
+    use std::io::Write;
+
+    /// Appends the framing offsets of a container, sized for its total length.
+    fn frame(mut data: Vec<u8>, offsets: &[usize]) -> Vec<u8> {
+        let size = [1, 2, 4, 8]
+            .iter()
+            .copied()
+            .find(|&size| gvariant::offset_size(data.len() + offsets.len() * size) == size)
+            .unwrap_or(8);
+        for offset in offsets {
+            data.extend_from_slice(&offset.to_le_bytes()[..size]);
+        }
+        data
+    }
+
+    fn pad(data: &mut Vec<u8>) {
+        while !data.len().is_multiple_of(8) {
+            data.push(0);
+        }
+    }
+
+    /// Serializes a `{sv}` dictionary entry.
+    fn entry(key: &str, value: &[u8], signature: &str) -> Vec<u8> {
+        let mut data = key.as_bytes().to_vec();
+        data.push(0);
+        let key_end = data.len();
+        pad(&mut data);
+        data.extend_from_slice(value);
+        data.push(0);
+        data.extend_from_slice(signature.as_bytes());
+        frame(data, &[key_end])
+    }
+
+    /// Serializes a bundle superblock with a metadata dictionary.
+    fn superblock(entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut dict = vec![];
+        let mut ends = vec![];
+        for entry in entries {
+            pad(&mut dict);
+            dict.extend_from_slice(entry);
+            ends.push(dict.len());
+        }
+        let mut data = frame(dict, &ends);
+        let dict_end = data.len();
+        pad(&mut data);
+        // The timestamp, then empty checksums, commit and part lists.
+        data.extend_from_slice(&[0; 8]);
+        frame(data, &[dict_end])
+    }
+
+    #[test]
+    fn bundle_metadata() -> Result<(), Box<dyn Error>> {
+        let xml = fs::read("./tests/collections/spec_example.xml")?;
+        let mut appdata = GzEncoder::new(vec![], Compression::default());
+        appdata.write_all(&xml)?;
+        let data = superblock(&[
+            entry("ref", b"app/org.mozilla.Firefox/x86_64/stable\0", "s"),
+            entry("origin", b"flathub\0", "s"),
+            entry("appdata", &appdata.finish()?, "ay"),
+        ]);
+
+        let bundle = FlatpakBundle::from_bytes(&data)?;
+        assert_eq!(bundle.reference, "app/org.mozilla.Firefox/x86_64/stable");
+        assert_eq!(bundle.origin.as_deref(), Some("flathub"));
+        assert_eq!(bundle.runtime_repo, None);
+        let component = bundle.component.as_ref().ok_or("no component")?;
+        assert_eq!(component.id, "org.mozilla.Firefox".into());
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("bundle.flatpak");
+        fs::write(&path, &data)?;
+        assert_eq!(FlatpakBundle::from_path(&path)?, bundle);
+
+        let data = superblock(&[entry("ref", &[1, 2], "ay")]);
+        assert!(FlatpakBundle::from_bytes(&data).is_err());
+        assert!(FlatpakBundle::from_bytes(&superblock(&[])).is_err());
+        Ok(())
+    }
+}
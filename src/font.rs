@@ -0,0 +1,133 @@
+use super::{AppId, Component};
+use serde::Serialize;
+
+/// The custom metadata key of the text a font is previewed with.
+pub const SAMPLE_TEXT_KEY: &str = "FontSampleText";
+/// The custom metadata key of the short text the icon of a font is rendered with.
+pub const ICON_TEXT_KEY: &str = "FontIconText";
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+/// A summary of a font component, to list its faces in a font manager.
+///
+/// The preview texts come from the `FontSampleText` and `FontIconText` custom
+/// metadata the upstream metainfo files may set, the languages from the
+/// `<languages>` tag.
+///
+/// # Example
+///
+/// ```
+/// use appstream::font::FontInfo;
+/// use appstream::Component;
+///
+/// let component = Component::from_path("./tests/font.xml".into()).unwrap();
+/// let font = FontInfo::from_component(&component).unwrap();
+/// assert_eq!(font.family, "Lato");
+/// assert_eq!(font.faces.len(), 5);
+/// assert_eq!(font.faces[2], "Lato Bold");
+/// ```
+pub struct FontInfo {
+    /// The id of the component.
+    pub id: AppId,
+    /// The untranslated name of the component, usually the font family.
+    pub family: String,
+    /// The names of the faces, like `Lato Bold`.
+    pub faces: Vec<String>,
+    /// The locales of the languages the font supports.
+    pub languages: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The text to preview the font with.
+    pub sample_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// The short text to render the icon of the font with, like `Aa`.
+    pub icon_text: Option<String>,
+}
+
+impl FontInfo {
+    /// Summarizes a font component, `None` if it's of another kind.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - The font component.
+    pub fn from_component(component: &Component) -> Option<Self> {
+        if !component.is_font() {
+            return None;
+        }
+        let custom = |key: &str| component.metadata.get(key).cloned().flatten();
+        Some(Self {
+            id: component.id.clone(),
+            family: component.name.get_default().cloned().unwrap_or_default(),
+            faces: component
+                .font_faces()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            languages: component
+                .languages
+                .iter()
+                .map(|l| l.locale.to_string())
+                .collect(),
+            sample_text: custom(SAMPLE_TEXT_KEY),
+            icon_text: custom(ICON_TEXT_KEY),
+        })
+    }
+
+    /// Whether the font supports a language, by its locale like `pt_BR` or its
+    /// language code like `pt`.
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - The locale of the language.
+    pub fn supports_language(&self, locale: &str) -> bool {
+        fn language(locale: &str) -> &str {
+            locale.split(&['_', '-', '@'][..]).next().unwrap_or(locale)
+        }
+        self.languages
+            .iter()
+            .any(|l| l == locale || language(l) == language(locale))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+    use std::error::Error;
+    use xmltree::Element;
+
+    #[test]
+    fn font_info() -> Result<(), Box<dyn Error>> {
+        let xml = r#"<component type="font">
+            <id>org.example.NotoSansJP</id>
+            <name>Noto Sans JP</name>
+            <provides>
+                <font>Noto Sans JP Regular</font>
+                <font>Noto Sans JP Bold</font>
+                <id>org.example.Noto</id>
+            </provides>
+            <languages>
+                <lang>ja</lang>
+                <lang>en_US</lang>
+            </languages>
+            <custom>
+                <value key="FontSampleText">いろはにほへと</value>
+                <value key="FontIconText">あ</value>
+            </custom>
+        </component>"#;
+        let component = Component::try_from(&Element::parse(xml.as_bytes())?)?;
+        assert_eq!(
+            component.font_faces(),
+            vec!["Noto Sans JP Regular", "Noto Sans JP Bold"]
+        );
+        let font = FontInfo::from_component(&component).unwrap();
+        assert_eq!(font.languages, vec!["ja", "en_US"]);
+        assert_eq!(font.sample_text.as_deref(), Some("いろはにほへと"));
+        assert_eq!(font.icon_text.as_deref(), Some("あ"));
+        assert!(font.supports_language("ja_JP"));
+        assert!(font.supports_language("en"));
+        assert!(!font.supports_language("fr"));
+
+        let component = Component::from_path("./tests/desktop.xml".into())?;
+        assert!(FontInfo::from_component(&component).is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,459 @@
+use super::builders::{
+    CollectionBuilder, ComponentBuilder, ImageBuilder, LanguageBuilder, ReleaseBuilder,
+    ScreenshotBuilder,
+};
+use super::enums::{
+    Bundle, Category, ComponentKind, ContentAttribute, ContentRatingVersion, FirmwareKind, Icon,
+    ImageKind, Kudo, Launchable, ProjectUrl, Provide, ReleaseKind, ReleaseUrgency, Size,
+    Translation,
+};
+use super::{
+    AppId, Collection, Component, ContentRating, Image, Language, License,
+    MarkupTranslatableString, Release, Screenshot, Timestamp, TranslatableList, TranslatableString,
+};
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::str::FromStr;
+use url::Url;
+
+const DOMAINS: &[&str] = &["org", "com", "io", "net"];
+const VENDORS: &[&str] = &["gnome", "kde", "example", "mozilla", "flathub"];
+const WORDS: &[&str] = &[
+    "app", "editor", "player", "viewer", "notes", "mail", "terminal", "maps", "photos", "music",
+    "calendar", "clock", "weather", "games", "chess",
+];
+const LOCALES: &[&str] = &["de", "fr", "pt_BR", "zh_CN", "en_GB", "ar"];
+const LICENSES: &[&str] = &[
+    "CC0-1.0",
+    "MIT",
+    "GPL-3.0-or-later",
+    "GPL-2.0-only",
+    "LGPL-2.1+",
+    "Apache-2.0",
+    "MPL-2.0 AND CC-BY-SA-4.0",
+];
+const CATEGORIES: &[&str] = &[
+    "AudioVideo",
+    "Audio",
+    "Video",
+    "Development",
+    "Education",
+    "Game",
+    "Graphics",
+    "Network",
+    "Office",
+    "Science",
+    "Settings",
+    "System",
+    "Utility",
+    "TextEditor",
+    "WebBrowser",
+    "Email",
+    "Player",
+    "Photography",
+    "Calculator",
+    "Maps",
+];
+const KUDOS: &[&str] = &[
+    "HiDpiIcon",
+    "HighContrast",
+    "ModernToolkit",
+    "Notifications",
+    "SearchProvider",
+    "UserDocs",
+];
+
+fn word(u: &mut Unstructured<'_>) -> Result<&'static str> {
+    u.choose(WORDS).copied()
+}
+
+fn sentence(u: &mut Unstructured<'_>) -> Result<String> {
+    let len = u.int_in_range(1..=6)?;
+    let mut words = Vec::with_capacity(len);
+    for _ in 0..len {
+        words.push(word(u)?);
+    }
+    let mut sentence = words.join(" ");
+    sentence[..1].make_ascii_uppercase();
+    Ok(sentence)
+}
+
+fn url(u: &mut Unstructured<'_>) -> Result<Url> {
+    let url = format!(
+        "https://{}.{}/{}",
+        u.choose(VENDORS)?,
+        u.choose(DOMAINS)?,
+        word(u)?
+    );
+    Ok(Url::parse(&url).expect("the generated URLs are valid"))
+}
+
+/// Fills `f` between zero and `max` times.
+fn repeat(
+    u: &mut Unstructured<'_>,
+    max: usize,
+    mut f: impl FnMut(&mut Unstructured<'_>) -> Result<()>,
+) -> Result<()> {
+    for _ in 0..u.int_in_range(0..=max)? {
+        f(u)?;
+    }
+    Ok(())
+}
+
+impl<'a> Arbitrary<'a> for AppId {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let id = format!("{}.{}.{}", u.choose(DOMAINS)?, u.choose(VENDORS)?, word(u)?);
+        Ok(id.into())
+    }
+}
+
+impl<'a> Arbitrary<'a> for License {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(u.choose(LICENSES)?.to_string().into())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Timestamp {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let year = u.int_in_range(2000..=2030)?;
+        let month = u.int_in_range(1..=12)?;
+        let day = u.int_in_range(1..=28)?;
+        Ok(Timestamp::from_ymd(year, month, day).expect("the generated dates are valid"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for TranslatableString {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut string = TranslatableString::with_default(&sentence(u)?);
+        repeat(u, 2, |u| {
+            string.add_for_locale(Some(u.choose(LOCALES)?), &sentence(u)?);
+            Ok(())
+        })?;
+        Ok(string)
+    }
+}
+
+impl<'a> Arbitrary<'a> for MarkupTranslatableString {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut string =
+            MarkupTranslatableString::with_default(&format!("<p>{}.</p>", sentence(u)?));
+        repeat(u, 2, |u| {
+            string.add_for_locale(
+                Some(u.choose(LOCALES)?),
+                &format!("<p>{}.</p>", sentence(u)?),
+            );
+            Ok(())
+        })?;
+        Ok(string)
+    }
+}
+
+impl<'a> Arbitrary<'a> for TranslatableList {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut list = TranslatableList::default();
+        repeat(u, 4, |u| {
+            list.add_for_locale(None, word(u)?);
+            Ok(())
+        })?;
+        Ok(list)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Category {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Category::from_str(u.choose(CATEGORIES)?).expect("the categories are registered"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Kudo {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Kudo::from_str(u.choose(KUDOS)?).expect("the kudos are known"))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Launchable {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=2)? {
+            0 => Launchable::DesktopId(format!("{}.desktop", AppId::arbitrary(u)?.0)),
+            1 => Launchable::Service(format!("{}.service", word(u)?)),
+            _ => Launchable::Url(url(u)?),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for ProjectUrl {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let url = url(u)?;
+        Ok(match u.int_in_range(0..=5)? {
+            0 => ProjectUrl::Homepage(url),
+            1 => ProjectUrl::BugTracker(url),
+            2 => ProjectUrl::Donation(url),
+            3 => ProjectUrl::Translate(url),
+            4 => ProjectUrl::Help(url),
+            _ => ProjectUrl::Faq(url),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Icon {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let size = *u.choose(&[64, 128, 256])?;
+        Ok(match u.int_in_range(0..=2)? {
+            0 => Icon::Stock(word(u)?.to_string()),
+            1 => Icon::Cached {
+                path: format!("{}.png", AppId::arbitrary(u)?.0).into(),
+                width: Some(size),
+                height: Some(size),
+            },
+            _ => Icon::Remote {
+                url: url(u)?,
+                width: Some(size),
+                height: Some(size),
+            },
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Provide {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let name = word(u)?;
+        Ok(match u.int_in_range(0..=4)? {
+            0 => Provide::Binary(name.to_string()),
+            1 => Provide::Library(format!("lib{}.so.1", name).into()),
+            2 => Provide::DBus(format!("org.example.{}", name)),
+            3 => Provide::Id(AppId::arbitrary(u)?),
+            _ => Provide::Firmware {
+                kind: FirmwareKind::arbitrary(u)?,
+                item: format!("{}.bin", name),
+            },
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Bundle {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let id = AppId::arbitrary(u)?;
+        Ok(match u.int_in_range(0..=2)? {
+            0 => Bundle::Flatpak {
+                runtime: Some("org.freedesktop.Platform/x86_64/23.08".to_string()),
+                sdk: Some("org.freedesktop.Sdk/x86_64/23.08".to_string()),
+                reference: format!("app/{}/x86_64/stable", id.0),
+            },
+            1 => Bundle::Snap(word(u)?.to_string()),
+            _ => Bundle::AppImage(format!("{}.AppImage", id.0)),
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Translation {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let domain = word(u)?.to_string();
+        Ok(if bool::arbitrary(u)? {
+            Translation::Gettext(domain)
+        } else {
+            Translation::Qt(domain)
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for ContentRating {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut attributes = Vec::new();
+        repeat(u, 3, |u| {
+            attributes.push(ContentAttribute::arbitrary(u)?);
+            Ok(())
+        })?;
+        Ok(ContentRating {
+            version: *u.choose(&[ContentRatingVersion::Oars1_0, ContentRatingVersion::Oars1_1])?,
+            attributes,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Language {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(LanguageBuilder::new(u.choose(LOCALES)?)
+            .percentage(u.int_in_range(0..=100)?)
+            .build())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Image {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let (width, height) = *u.choose(&[(1920, 1080), (1280, 720), (624, 351)])?;
+        Ok(ImageBuilder::new(url(u)?)
+            .kind(ImageKind::arbitrary(u)?)
+            .width(width)
+            .height(height)
+            .build())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Screenshot {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut screenshot = ScreenshotBuilder::default()
+            .set_default(bool::arbitrary(u)?)
+            .image(Image::arbitrary(u)?);
+        if bool::arbitrary(u)? {
+            screenshot = screenshot.caption(TranslatableString::arbitrary(u)?);
+        }
+        Ok(screenshot.build())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Release {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let version = format!(
+            "{}.{}.{}",
+            u.int_in_range(0..=5)?,
+            u.int_in_range(0..=20)?,
+            u.int_in_range(0..=9)?
+        );
+        let mut release = ReleaseBuilder::new(&version)
+            .date(Timestamp::arbitrary(u)?)
+            .kind(ReleaseKind::arbitrary(u)?)
+            .urgency(ReleaseUrgency::arbitrary(u)?);
+        if bool::arbitrary(u)? {
+            release = release.description(MarkupTranslatableString::arbitrary(u)?);
+        }
+        if bool::arbitrary(u)? {
+            release = release.size(Size::Download(u.int_in_range(1..=1 << 30)?));
+        }
+        Ok(release.build())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Component {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let kind = ComponentKind::arbitrary(u)?;
+        let mut component = ComponentBuilder::default()
+            .id(AppId::arbitrary(u)?)
+            .kind(kind)
+            .name(TranslatableString::arbitrary(u)?)
+            .summary(TranslatableString::arbitrary(u)?)
+            .metadata_license(License::arbitrary(u)?);
+        if bool::arbitrary(u)? {
+            component = component.description(MarkupTranslatableString::arbitrary(u)?);
+        }
+        if bool::arbitrary(u)? {
+            component = component.project_license(License::arbitrary(u)?);
+        }
+        if bool::arbitrary(u)? {
+            component = component.developer_name(TranslatableString::arbitrary(u)?);
+        }
+        if bool::arbitrary(u)? {
+            component = component.keywords(TranslatableList::arbitrary(u)?);
+        }
+        if bool::arbitrary(u)? {
+            component = component.content_rating(ContentRating::arbitrary(u)?);
+        }
+        if kind == ComponentKind::Addon {
+            component = component.extend(AppId::arbitrary(u)?);
+        }
+        repeat(u, 3, |u| {
+            component = std::mem::take(&mut component).category(Category::arbitrary(u)?);
+            Ok(())
+        })?;
+        repeat(u, 2, |u| {
+            component = std::mem::take(&mut component).icon(Icon::arbitrary(u)?);
+            Ok(())
+        })?;
+        repeat(u, 3, |u| {
+            component = std::mem::take(&mut component).url(ProjectUrl::arbitrary(u)?);
+            Ok(())
+        })?;
+        repeat(u, 1, |u| {
+            component = std::mem::take(&mut component).launchable(Launchable::arbitrary(u)?);
+            Ok(())
+        })?;
+        repeat(u, 3, |u| {
+            component = std::mem::take(&mut component).screenshot(Screenshot::arbitrary(u)?);
+            Ok(())
+        })?;
+        repeat(u, 4, |u| {
+            component = std::mem::take(&mut component).release(Release::arbitrary(u)?);
+            Ok(())
+        })?;
+        repeat(u, 2, |u| {
+            component = std::mem::take(&mut component).provide(Provide::arbitrary(u)?);
+            Ok(())
+        })?;
+        repeat(u, 2, |u| {
+            component = std::mem::take(&mut component).kudo(Kudo::arbitrary(u)?);
+            Ok(())
+        })?;
+        repeat(u, 1, |u| {
+            component = std::mem::take(&mut component).bundle(Bundle::arbitrary(u)?);
+            Ok(())
+        })?;
+        repeat(u, 1, |u| {
+            component = std::mem::take(&mut component).translation(Translation::arbitrary(u)?);
+            Ok(())
+        })?;
+        repeat(u, 3, |u| {
+            component = std::mem::take(&mut component).language(Language::arbitrary(u)?);
+            Ok(())
+        })?;
+        Ok(component.build())
+    }
+}
+
+impl<'a> Arbitrary<'a> for Collection {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut collection = CollectionBuilder::new(u.choose(&["0.8", "0.14", "1.0"])?)
+            .origin(u.choose(VENDORS)?)
+            .timestamp(Timestamp::arbitrary(u)?);
+        repeat(u, 8, |u| {
+            let component = Component::arbitrary(u)?;
+            collection =
+                std::mem::replace(&mut collection, CollectionBuilder::new("")).component(component);
+            Ok(())
+        })?;
+        Ok(collection.build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::{RequiredFieldsRule, Validator};
+
+    /// Pseudo-random bytes, to run the same cases every time.
+    fn bytes(seed: u64) -> Vec<u8> {
+        let mut state = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (0..4096)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 33) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arbitrary_components() -> Result<()> {
+        let validator = Validator::empty().rule(RequiredFieldsRule);
+        for seed in 0..64 {
+            let data = bytes(seed);
+            let collection = Collection::arbitrary(&mut Unstructured::new(&data))?;
+            for component in collection.components.iter() {
+                assert!(validator.validate_component(component).is_empty());
+                assert_eq!(component.id.split('.').count(), 3);
+            }
+            #[cfg(feature = "json")]
+            {
+                // Only the flatpak bundles deserialize from JSON.
+                let mut collection = collection;
+                for component in collection.components.iter_mut() {
+                    component
+                        .bundles
+                        .retain(|b| matches!(b, Bundle::Flatpak { .. }));
+                }
+                let json = serde_json::to_string(&collection).unwrap();
+                let c2: Collection = serde_json::from_str(&json).unwrap();
+                assert_eq!(collection, c2);
+            }
+        }
+        Ok(())
+    }
+}
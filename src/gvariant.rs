@@ -0,0 +1,162 @@
+/// The length of the first complete type of a signature.
+fn type_length(signature: &str) -> Option<usize> {
+    let bytes = signature.as_bytes();
+    match bytes.first()? {
+        b'a' | b'm' => Some(1 + type_length(&signature[1..])?),
+        open @ (b'(' | b'{') => {
+            let close = if *open == b'(' { b')' } else { b'}' };
+            let mut i = 1;
+            while *bytes.get(i)? != close {
+                i += type_length(&signature[i..])?;
+            }
+            Some(i + 1)
+        }
+        _ => Some(1),
+    }
+}
+
+/// The member types of a tuple or dict entry type.
+fn members(signature: &str) -> Option<Vec<&str>> {
+    let inner = &signature[1..signature.len() - 1];
+    let mut members = vec![];
+    let mut i = 0;
+    while i < inner.len() {
+        let length = type_length(&inner[i..])?;
+        members.push(&inner[i..i + length]);
+        i += length;
+    }
+    Some(members)
+}
+
+fn alignment(signature: &str) -> usize {
+    match signature.as_bytes().first() {
+        Some(b'n') | Some(b'q') => 2,
+        Some(b'i') | Some(b'u') | Some(b'h') => 4,
+        Some(b'x') | Some(b't') | Some(b'd') | Some(b'v') => 8,
+        Some(b'a') | Some(b'm') => alignment(&signature[1..]),
+        Some(b'(') | Some(b'{') => members(signature)
+            .unwrap_or_default()
+            .iter()
+            .map(|m| alignment(m))
+            .max()
+            .unwrap_or(1),
+        _ => 1,
+    }
+}
+
+fn fixed_size(signature: &str) -> Option<usize> {
+    match signature.as_bytes().first()? {
+        b'y' | b'b' => Some(1),
+        b'n' | b'q' => Some(2),
+        b'i' | b'u' | b'h' => Some(4),
+        b'x' | b't' | b'd' => Some(8),
+        b'(' | b'{' => {
+            let members = members(signature)?;
+            if members.is_empty() {
+                return Some(1);
+            }
+            let mut size = 0;
+            for member in members.iter() {
+                size = align(size, alignment(member)) + fixed_size(member)?;
+            }
+            Some(align(size, alignment(signature)))
+        }
+        _ => None,
+    }
+}
+
+fn align(offset: usize, alignment: usize) -> usize {
+    offset.div_ceil(alignment) * alignment
+}
+
+/// The size of the framing offsets of a container of `length` bytes.
+pub(crate) fn offset_size(length: usize) -> usize {
+    match length {
+        0 => 0,
+        1..=0xff => 1,
+        0x100..=0xffff => 2,
+        0x1_0000..=0xffff_ffff => 4,
+        _ => 8,
+    }
+}
+
+/// Reads a little-endian framing offset.
+pub(crate) fn read_offset(data: &[u8], at: usize, size: usize) -> Option<usize> {
+    let mut value = 0usize;
+    for (i, byte) in data.get(at..at + size)?.iter().enumerate() {
+        value |= (*byte as usize) << (8 * i);
+    }
+    Some(value)
+}
+
+/// Splits a serialized tuple into its members.
+pub(crate) fn tuple<'a>(data: &'a [u8], members: &[&str]) -> Option<Vec<&'a [u8]>> {
+    let size = offset_size(data.len());
+    let mut offsets = data.len();
+    let mut start = 0;
+    let mut values = vec![];
+    for (i, member) in members.iter().enumerate() {
+        start = align(start, alignment(member));
+        let end = match fixed_size(member) {
+            Some(fixed) => start + fixed,
+            None if i == members.len() - 1 => offsets,
+            None => {
+                offsets = offsets.checked_sub(size)?;
+                read_offset(data, offsets, size)?
+            }
+        };
+        values.push(data.get(start..end)?);
+        start = end;
+    }
+    Some(values)
+}
+
+/// Splits a serialized array into its elements.
+pub(crate) fn array<'a>(data: &'a [u8], element: &str) -> Option<Vec<&'a [u8]>> {
+    if data.is_empty() {
+        return Some(vec![]);
+    }
+    if let Some(fixed) = fixed_size(element) {
+        return Some(data.chunks(fixed).collect());
+    }
+    let size = offset_size(data.len());
+    let elements_end = read_offset(data, data.len() - size, size)?;
+    let count = (data.len().checked_sub(elements_end)?) / size;
+    let mut start = 0;
+    let mut values = vec![];
+    for i in 0..count {
+        start = align(start, alignment(element));
+        let end = read_offset(data, elements_end + i * size, size)?;
+        values.push(data.get(start..end)?);
+        start = end;
+    }
+    Some(values)
+}
+
+/// Reads a nul-terminated string.
+pub(crate) fn string(data: &[u8]) -> Option<String> {
+    let (last, string) = data.split_last()?;
+    if *last != 0 {
+        return None;
+    }
+    String::from_utf8(string.to_vec()).ok()
+}
+
+/// Splits a serialized variant into its value and its type.
+pub(crate) fn variant(data: &[u8]) -> Option<(&[u8], &str)> {
+    let separator = data.iter().rposition(|b| *b == 0)?;
+    let signature = std::str::from_utf8(&data[separator + 1..]).ok()?;
+    Some((&data[..separator], signature))
+}
+
+/// Looks up the value of a key in a serialized `a{sv}` dictionary, with its type.
+pub(crate) fn lookup<'a>(dict: &'a [u8], key: &str) -> Option<(&'a [u8], &'a str)> {
+    array(dict, "{sv}")?.into_iter().find_map(|entry| {
+        let entry = tuple(entry, &["s", "v"])?;
+        if string(entry[0])? == key {
+            variant(entry[1])
+        } else {
+            None
+        }
+    })
+}
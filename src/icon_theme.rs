@@ -0,0 +1,451 @@
+use super::enums::Icon;
+use super::Component;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// The theme every other one falls back to.
+const FALLBACK_THEME: &str = "hicolor";
+/// The extensions of the icon files, by order of preference.
+const EXTENSIONS: [&str; 3] = ["png", "svg", "xpm"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// How the icons of a theme directory can be scaled.
+enum DirectoryKind {
+    Fixed,
+    Scalable { min: u32, max: u32 },
+    Threshold(u32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A directory of an icon theme, a group of its `index.theme`.
+struct Directory {
+    path: String,
+    size: u32,
+    scale: u32,
+    kind: DirectoryKind,
+}
+
+impl Directory {
+    /// Whether the icons of the directory fit the size exactly.
+    fn matches(&self, size: u32, scale: u32) -> bool {
+        if self.scale != scale {
+            return false;
+        }
+        match self.kind {
+            DirectoryKind::Fixed => self.size == size,
+            DirectoryKind::Scalable { min, max } => min <= size && size <= max,
+            DirectoryKind::Threshold(threshold) => {
+                self.size.saturating_sub(threshold) <= size && size <= self.size + threshold
+            }
+        }
+    }
+
+    /// How far the icons of the directory are from the size, in pixels.
+    fn distance(&self, size: u32, scale: u32) -> u32 {
+        let (min, max) = match self.kind {
+            DirectoryKind::Fixed => (self.size, self.size),
+            DirectoryKind::Scalable { min, max } => (min, max),
+            DirectoryKind::Threshold(threshold) => {
+                (self.size.saturating_sub(threshold), self.size + threshold)
+            }
+        };
+        let pixels = size * scale;
+        if pixels < min * self.scale {
+            min * self.scale - pixels
+        } else {
+            pixels.saturating_sub(max * self.scale)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// The `index.theme` of an icon theme.
+struct Theme {
+    inherits: Vec<String>,
+    directories: Vec<Directory>,
+}
+
+impl Theme {
+    /// Parses an `index.theme` keyfile.
+    fn parse(index: &str) -> Self {
+        let mut groups: HashMap<&str, HashMap<&str, &str>> = HashMap::new();
+        let mut group = None;
+        for line in index.lines().map(|l| l.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                group = Some(name);
+            } else if let (Some(group), Some((key, value))) = (group, line.split_once('=')) {
+                groups
+                    .entry(group)
+                    .or_default()
+                    .insert(key.trim(), value.trim());
+            }
+        }
+
+        let list = |group: &HashMap<&str, &str>, key: &str| {
+            group
+                .get(key)
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from)
+                        .collect::<Vec<String>>()
+                })
+                .unwrap_or_default()
+        };
+        let header = groups.get("Icon Theme").cloned().unwrap_or_default();
+        let mut names = list(&header, "Directories");
+        names.extend(list(&header, "ScaledDirectories"));
+
+        let directories = names
+            .into_iter()
+            .filter_map(|name| {
+                let group = groups.get(name.as_str())?;
+                let number = |key: &str| group.get(key).and_then(|v| v.parse::<u32>().ok());
+                let size = number("Size")?;
+                let kind = match group.get("Type").copied().unwrap_or("Threshold") {
+                    "Fixed" => DirectoryKind::Fixed,
+                    "Scalable" => DirectoryKind::Scalable {
+                        min: number("MinSize").unwrap_or(size),
+                        max: number("MaxSize").unwrap_or(size),
+                    },
+                    _ => DirectoryKind::Threshold(number("Threshold").unwrap_or(2)),
+                };
+                Some(Directory {
+                    path: name,
+                    size,
+                    scale: number("Scale").unwrap_or(1).max(1),
+                    kind,
+                })
+            })
+            .collect();
+
+        Self {
+            inherits: list(&header, "Inherits"),
+            directories,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Resolves stock icons to files of the installed freedesktop icon themes,
+/// following the [Icon Theme Specification](https://specifications.freedesktop.org/icon-theme-spec/latest/).
+///
+/// The icons are looked up in the theme, then in the themes it inherits from and
+/// in `hicolor`, picking the directory whose size is the closest to the requested
+/// one. The `index.theme` files are read once, when a theme is first needed.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::icon_theme::IconThemeResolver;
+/// use appstream::Collection;
+///
+/// let resolver = IconThemeResolver::new("Adwaita");
+/// let collection = Collection::from_path("/tmp/appstream.xml".into()).unwrap();
+/// for component in collection.components.iter() {
+///     if let Some(path) = resolver.best_icon(component, 48, 1) {
+///         println!("{}: {}", component.id.0, path.display());
+///     }
+/// }
+/// ```
+pub struct IconThemeResolver {
+    theme: String,
+    base_dirs: Vec<PathBuf>,
+    fallback_dirs: Vec<PathBuf>,
+    themes: Mutex<HashMap<String, Option<Arc<Theme>>>>,
+}
+
+impl IconThemeResolver {
+    /// Creates a resolver looking up the icons in the directories of the system:
+    /// `~/.icons`, then the `icons` directories of `$XDG_DATA_HOME` and
+    /// `$XDG_DATA_DIRS`, and `/usr/share/pixmaps` as a last resort.
+    ///
+    /// # Arguments
+    ///
+    /// * `theme` - The name of the icon theme, like `Adwaita`.
+    pub fn new(theme: &str) -> Self {
+        let home = env::var_os("HOME").map(PathBuf::from);
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|h| h.join(".local/share")));
+        let data_dirs = env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+
+        let mut base_dirs = vec![];
+        base_dirs.extend(home.map(|h| h.join(".icons")));
+        base_dirs.extend(data_home.map(|d| d.join("icons")));
+        base_dirs.extend(
+            data_dirs
+                .split(':')
+                .filter(|d| !d.is_empty())
+                .map(|d| Path::new(d).join("icons")),
+        );
+        Self::with_base_dirs(theme, base_dirs).fallback_dir("/usr/share/pixmaps".into())
+    }
+
+    /// Creates a resolver looking up the icons only in the given directories.
+    ///
+    /// # Arguments
+    ///
+    /// * `theme` - The name of the icon theme.
+    /// * `base_dirs` - The directories holding the themes, by order of preference.
+    pub fn with_base_dirs(theme: &str, base_dirs: Vec<PathBuf>) -> Self {
+        Self {
+            theme: theme.to_string(),
+            base_dirs,
+            fallback_dirs: vec![],
+            themes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Adds a directory of unthemed icons, looked up when none of the themes has
+    /// the icon.
+    pub fn fallback_dir(mut self, dir: PathBuf) -> Self {
+        self.fallback_dirs.push(dir);
+        self
+    }
+
+    /// The name of the icon theme.
+    pub fn theme(&self) -> &str {
+        &self.theme
+    }
+
+    /// Looks up the file of an icon name, the closest to a size.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the icon, like `org.gnome.Maps`.
+    /// * `size` - The preferred size of the icon, in logical pixels.
+    /// * `scale` - The scale factor of the display, `1` for the non-HiDPI ones.
+    pub fn lookup(&self, name: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        if name.is_empty() || name.contains('/') || name.starts_with('.') {
+            return None;
+        }
+        let scale = scale.max(1);
+        let mut visited = vec![];
+        self.lookup_in_theme(&self.theme, name, size, scale, &mut visited)
+            .or_else(|| self.lookup_in_theme(FALLBACK_THEME, name, size, scale, &mut visited))
+            .or_else(|| {
+                self.fallback_dirs
+                    .iter()
+                    .find_map(|dir| find_file(dir, name))
+            })
+    }
+
+    /// Resolves an icon to its file: the stock icons are looked up in the
+    /// themes, the local ones are returned if they exist, the others are `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `icon` - The icon.
+    /// * `size` - The preferred size of the icon, in logical pixels.
+    /// * `scale` - The scale factor of the display.
+    pub fn resolve(&self, icon: &Icon, size: u32, scale: u32) -> Option<PathBuf> {
+        match icon {
+            Icon::Stock(name) => self.lookup(name, size, scale),
+            Icon::Local { path, .. } if path.is_file() => Some(path.clone()),
+            _ => None,
+        }
+    }
+
+    /// The first icon of a component found in the themes or on the disk.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - The component.
+    /// * `size` - The preferred size of the icon, in logical pixels.
+    /// * `scale` - The scale factor of the display.
+    pub fn best_icon(&self, component: &Component, size: u32, scale: u32) -> Option<PathBuf> {
+        component
+            .icons
+            .iter()
+            .find_map(|icon| self.resolve(icon, size, scale))
+    }
+
+    /// Looks up an icon in a theme and the ones it inherits from.
+    fn lookup_in_theme(
+        &self,
+        theme: &str,
+        name: &str,
+        size: u32,
+        scale: u32,
+        visited: &mut Vec<String>,
+    ) -> Option<PathBuf> {
+        if visited.iter().any(|t| t == theme) {
+            return None;
+        }
+        visited.push(theme.to_string());
+        let index = self.load_theme(theme)?;
+
+        let exact = index
+            .directories
+            .iter()
+            .filter(|d| d.matches(size, scale))
+            .find_map(|d| self.find_in_directory(theme, d, name));
+        if exact.is_some() {
+            return exact;
+        }
+        let mut directories = index.directories.iter().collect::<Vec<&Directory>>();
+        directories.sort_by_key(|d| d.distance(size, scale));
+        let closest = directories
+            .into_iter()
+            .find_map(|d| self.find_in_directory(theme, d, name));
+        if closest.is_some() {
+            return closest;
+        }
+
+        index
+            .inherits
+            .iter()
+            .find_map(|parent| self.lookup_in_theme(parent, name, size, scale, visited))
+    }
+
+    /// The icon file in a directory of a theme, in any of the base directories.
+    fn find_in_directory(&self, theme: &str, directory: &Directory, name: &str) -> Option<PathBuf> {
+        self.base_dirs
+            .iter()
+            .find_map(|base| find_file(&base.join(theme).join(&directory.path), name))
+    }
+
+    /// The `index.theme` of a theme, from the first base directory having one.
+    fn load_theme(&self, theme: &str) -> Option<Arc<Theme>> {
+        let mut themes = self.themes.lock().ok()?;
+        themes
+            .entry(theme.to_string())
+            .or_insert_with(|| {
+                if theme.is_empty() || theme.contains('/') || theme.starts_with('.') {
+                    return None;
+                }
+                self.base_dirs.iter().find_map(|base| {
+                    let index = fs::read_to_string(base.join(theme).join("index.theme")).ok()?;
+                    Some(Arc::new(Theme::parse(&index)))
+                })
+            })
+            .clone()
+    }
+}
+
+/// The file of an icon in a directory, with any of the supported extensions.
+fn find_file(dir: &Path, name: &str) -> Option<PathBuf> {
+    EXTENSIONS
+        .iter()
+        .map(|extension| dir.join(format!("{}.{}", name, extension)))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::ComponentBuilder;
+    use crate::TranslatableString;
+    use std::error::Error;
+
+    #[test]
+    fn lookup_stock_icons() -> Result<(), Box<dyn Error>> {
+        let temp = tempfile::tempdir()?;
+        let root = temp.path().join("icon-theme");
+        let (system, user) = (root.join("system"), root.join("user"));
+        fs::create_dir_all(system.join("hicolor"))?;
+        fs::create_dir_all(system.join("Custom"))?;
+        fs::create_dir_all(root.join("pixmaps"))?;
+        fs::write(
+            system.join("hicolor/index.theme"),
+            "[Icon Theme]\nName=Hicolor\nDirectories=48x48/apps,128x128/apps,scalable/apps\n\
+             ScaledDirectories=48x48@2/apps\n\n\
+             [48x48/apps]\nSize=48\nType=Threshold\n\n\
+             [48x48@2/apps]\nSize=48\nScale=2\nType=Threshold\n\n\
+             [128x128/apps]\nSize=128\nType=Fixed\n\n\
+             [scalable/apps]\nSize=128\nMinSize=8\nMaxSize=512\nType=Scalable\n",
+        )?;
+        fs::write(
+            system.join("Custom/index.theme"),
+            "[Icon Theme]\nName=Custom\nInherits=Custom\nDirectories=32x32/apps\n\n\
+             [32x32/apps]\nSize=32\nType=Fixed\n",
+        )?;
+        for (dir, file) in [
+            (system.join("hicolor/48x48/apps"), "org.gnome.Maps.png"),
+            (system.join("hicolor/48x48@2/apps"), "org.gnome.Maps.png"),
+            (system.join("hicolor/128x128/apps"), "org.gnome.Maps.png"),
+            (
+                system.join("hicolor/scalable/apps"),
+                "org.gnome.Weather.svg",
+            ),
+            (system.join("Custom/32x32/apps"), "org.gnome.Maps.png"),
+            (user.join("hicolor/128x128/apps"), "org.gnome.Maps.png"),
+            (root.join("pixmaps"), "xterm.xpm"),
+        ] {
+            fs::create_dir_all(&dir)?;
+            fs::write(dir.join(file), "")?;
+        }
+
+        let resolver =
+            IconThemeResolver::with_base_dirs("Custom", vec![user.clone(), system.clone()])
+                .fallback_dir(root.join("pixmaps"));
+        let path = |p: &Path| Some(p.to_path_buf());
+        assert_eq!(
+            resolver.lookup("org.gnome.Maps", 32, 1),
+            path(&system.join("Custom/32x32/apps/org.gnome.Maps.png"))
+        );
+        assert_eq!(
+            resolver.lookup("org.gnome.Weather", 64, 1),
+            path(&system.join("hicolor/scalable/apps/org.gnome.Weather.svg"))
+        );
+        assert_eq!(
+            resolver.lookup("org.gnome.Maps", 128, 1),
+            path(&system.join("Custom/32x32/apps/org.gnome.Maps.png"))
+        );
+
+        let resolver =
+            IconThemeResolver::with_base_dirs("Missing", vec![user.clone(), system.clone()])
+                .fallback_dir(root.join("pixmaps"));
+        assert_eq!(resolver.theme(), "Missing");
+        assert_eq!(
+            resolver.lookup("org.gnome.Maps", 50, 1),
+            path(&system.join("hicolor/48x48/apps/org.gnome.Maps.png"))
+        );
+        assert_eq!(
+            resolver.lookup("org.gnome.Maps", 48, 2),
+            path(&system.join("hicolor/48x48@2/apps/org.gnome.Maps.png"))
+        );
+        assert_eq!(
+            resolver.lookup("org.gnome.Maps", 128, 1),
+            path(&user.join("hicolor/128x128/apps/org.gnome.Maps.png"))
+        );
+        assert_eq!(
+            resolver.lookup("org.gnome.Maps", 256, 1),
+            path(&user.join("hicolor/128x128/apps/org.gnome.Maps.png"))
+        );
+        assert_eq!(
+            resolver.resolve(&Icon::Stock("xterm".into()), 48, 1),
+            path(&root.join("pixmaps/xterm.xpm"))
+        );
+        assert_eq!(resolver.lookup("../pixmaps/xterm", 48, 1), None);
+        assert_eq!(resolver.lookup("org.gnome.Clocks", 48, 1), None);
+
+        let component = ComponentBuilder::default()
+            .id("org.gnome.Weather".into())
+            .name(TranslatableString::with_default("Weather"))
+            .icon(Icon::Remote {
+                url: url::Url::parse("https://example.com/weather.png")?,
+                width: None,
+                height: None,
+            })
+            .icon(Icon::Stock("org.gnome.Weather".into()))
+            .build();
+        assert_eq!(
+            resolver.best_icon(&component, 48, 1),
+            path(&system.join("hicolor/scalable/apps/org.gnome.Weather.svg"))
+        );
+        Ok(())
+    }
+}
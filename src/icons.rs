@@ -0,0 +1,296 @@
+#[cfg(feature = "net")]
+use super::debian::Dep11Catalog;
+use super::enums::Icon;
+use super::error::ParseError;
+#[cfg(feature = "net")]
+use super::net::{self, HttpClient};
+use super::tar;
+use super::Component;
+#[cfg(feature = "gzip")]
+use flate2::read::GzDecoder;
+use std::fs;
+#[cfg(feature = "gzip")]
+use std::io::Read;
+use std::path::{Component as PathComponent, Path, PathBuf};
+#[cfg(feature = "net")]
+use url::Url;
+
+#[derive(Clone, Debug, PartialEq)]
+/// A directory of cached icons, laid out like `/usr/share/swcatalog/icons`:
+/// `<root>/<origin>/<width>x<height>/<name>`.
+///
+/// The icons are either extracted from the `icons-<size>.tar.gz` tarballs of
+/// the catalogs or downloaded straight to `<root>/<origin>/<size>/`, like with
+/// `FlatpakRemote::fetch_icons`. Gzip-compressed tarballs need the `gzip` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::icons::IconStore;
+/// use appstream::Collection;
+///
+/// let store = IconStore::new("/var/cache/my-store/icons".into());
+/// let tarball = std::fs::read("/tmp/icons-64x64.tar.gz").unwrap();
+/// store.add_tarball("debian-bookworm-main", "64x64", &tarball).unwrap();
+///
+/// let collection = Collection::from_yaml_path("/tmp/Components-amd64.yml".into()).unwrap();
+/// for component in collection.components.iter() {
+///     if let Some(path) = store.best_icon(component, 64) {
+///         println!("{}: {}", component.id.0, path.display());
+///     }
+/// }
+/// ```
+pub struct IconStore {
+    root: PathBuf,
+}
+
+impl IconStore {
+    /// Creates a new store, the directory is created when icons are added.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The directory of the store.
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// The directory of the store.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Extracts an icon tarball, returns the number of extracted icons.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The origin of the catalog the icons belong to.
+    /// * `size` - The size of the icons, like `64x64`.
+    /// * `data` - The content of the tarball, optionally gzip-compressed.
+    pub fn add_tarball(&self, origin: &str, size: &str, data: &[u8]) -> Result<usize, ParseError> {
+        let directory = self.root.join(safe_path(origin)?).join(safe_path(size)?);
+        fs::create_dir_all(&directory)?;
+        let mut count = 0;
+        for (name, content) in tar::entries(&decompress(data)?[..], |_| true)? {
+            let target = directory.join(safe_path(&name)?);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(target, content)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    #[cfg(feature = "net")]
+    /// Downloads and extracts an icon tarball, returns the number of extracted icons.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to send the request through.
+    /// * `url` - The URL of the tarball.
+    /// * `origin` - The origin of the catalog the icons belong to.
+    /// * `size` - The size of the icons, like `64x64`.
+    pub fn fetch_tarball<C: HttpClient + ?Sized>(
+        &self,
+        client: &C,
+        url: &Url,
+        origin: &str,
+        size: &str,
+    ) -> Result<usize, ParseError> {
+        let data = net::get(client, url)?;
+        self.add_tarball(origin, size, &data)
+    }
+
+    #[cfg(feature = "net")]
+    /// Extracts the icon tarballs of DEP-11 catalogs, returns the number of
+    /// extracted icons.
+    ///
+    /// # Arguments
+    ///
+    /// * `catalog` - The catalogs, as returned by `Dep11Fetcher::fetch`.
+    pub fn add_dep11(&self, catalog: &Dep11Catalog) -> Result<usize, ParseError> {
+        let mut count = 0;
+        for tarball in catalog.icon_tarballs.iter() {
+            let origin = tarball
+                .origin
+                .as_deref()
+                .ok_or_else(|| ParseError::missing_value("Origin"))?;
+            count += self.add_tarball(origin, &tarball.size, &tarball.data)?;
+        }
+        Ok(count)
+    }
+
+    /// Resolves a cached icon to its file, `None` for the other icon types or
+    /// if the icon isn't in the store.
+    ///
+    /// Icons without a size are looked up in every size directory of the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `icon` - The icon.
+    /// * `origin` - The origin of the component, `None` for the icons stored
+    ///   directly under the root.
+    pub fn resolve(&self, icon: &Icon, origin: Option<&str>) -> Option<PathBuf> {
+        let (path, width, height) = match icon {
+            Icon::Cached {
+                path,
+                width,
+                height,
+            } => (path, width, height),
+            _ => return None,
+        };
+        let base = match origin {
+            Some(origin) => self.root.join(safe_path(origin).ok()?),
+            None => self.root.clone(),
+        };
+        let name = safe_path(&path.to_string_lossy()).ok()?;
+        let candidates = match (width, height) {
+            (Some(width), Some(height)) => vec![base.join(format!("{}x{}", width, height))],
+            _ => {
+                let mut dirs = fs::read_dir(&base)
+                    .ok()?
+                    .flatten()
+                    .map(|e| e.path())
+                    .collect::<Vec<PathBuf>>();
+                dirs.sort();
+                dirs
+            }
+        };
+        candidates
+            .into_iter()
+            .map(|dir| dir.join(&name))
+            .find(|p| p.is_file())
+    }
+
+    /// The cached icon of a component closest to `size`, preferring the larger
+    /// ones so they can be scaled down.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - The component, its `origin` is used to find its icons.
+    /// * `size` - The preferred icon width.
+    pub fn best_icon(&self, component: &Component, size: u32) -> Option<PathBuf> {
+        let mut icons = component
+            .icons
+            .iter()
+            .filter_map(|icon| {
+                let width = match icon {
+                    Icon::Cached { width, .. } => width.unwrap_or(0),
+                    _ => return None,
+                };
+                Some((width, self.resolve(icon, component.origin.as_deref())?))
+            })
+            .collect::<Vec<(u32, PathBuf)>>();
+        icons.sort_by_key(|(width, _)| (*width < size, width.max(&size) - width.min(&size)));
+        icons.into_iter().next().map(|(_, path)| path)
+    }
+}
+
+/// A relative path that stays inside the directory it is joined to.
+pub(crate) fn safe_path(path: &str) -> Result<PathBuf, ParseError> {
+    let mut safe = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            PathComponent::Normal(c) => safe.push(c),
+            PathComponent::CurDir => (),
+            _ => return Err(ParseError::invalid_value(path, "path", "icon")),
+        }
+    }
+    if safe.as_os_str().is_empty() {
+        return Err(ParseError::invalid_value(path, "path", "icon"));
+    }
+    Ok(safe)
+}
+
+fn decompress(data: &[u8]) -> Result<Vec<u8>, ParseError> {
+    if !data.starts_with(&[0x1f, 0x8b]) {
+        return Ok(data.to_vec());
+    }
+    #[cfg(feature = "gzip")]
+    {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(data).read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    }
+    #[cfg(not(feature = "gzip"))]
+    Err(ParseError::invalid_value("gzip", "compression", "tarball"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::ComponentBuilder;
+    use crate::TranslatableString;
+    use std::error::Error;
+
+    #[test]
+    fn extract_and_resolve() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().join("icon-store");
+        let store = IconStore::new(root.clone());
+
+        let count = store.add_tarball(
+            "bookworm-main",
+            "64x64",
+            &tar::archive(&[("./gimp_gimp.png", b"64"), ("foo_bar.png", b"bar")]),
+        )?;
+        assert_eq!(count, 2);
+        store.add_tarball(
+            "bookworm-main",
+            "128x128",
+            &tar::archive(&[("gimp_gimp.png", b"128")]),
+        )?;
+        assert!(store
+            .add_tarball(
+                "bookworm-main",
+                "64x64",
+                &tar::archive(&[("../escape.png", b"")])
+            )
+            .is_err());
+        assert!(store
+            .add_tarball("../other", "64x64", &tar::archive(&[]))
+            .is_err());
+
+        let cached = |width: Option<u32>| Icon::Cached {
+            path: "gimp_gimp.png".into(),
+            width,
+            height: width,
+        };
+        assert_eq!(
+            store.resolve(&cached(Some(64)), Some("bookworm-main")),
+            Some(root.join("bookworm-main/64x64/gimp_gimp.png"))
+        );
+        assert_eq!(
+            store.resolve(&cached(Some(48)), Some("bookworm-main")),
+            None
+        );
+        assert_eq!(store.resolve(&cached(Some(64)), Some("other")), None);
+        assert_eq!(
+            store.resolve(&cached(None), Some("bookworm-main")),
+            Some(root.join("bookworm-main/128x128/gimp_gimp.png"))
+        );
+
+        let mut component = ComponentBuilder::default()
+            .id("org.gimp.GIMP".into())
+            .name(TranslatableString::with_default("GIMP"))
+            .icon(Icon::Stock("gimp".into()))
+            .icon(cached(Some(64)))
+            .icon(cached(Some(128)))
+            .build();
+        component.origin = Some("bookworm-main".into());
+        assert_eq!(
+            store.best_icon(&component, 48),
+            Some(root.join("bookworm-main/64x64/gimp_gimp.png"))
+        );
+        assert_eq!(
+            store.best_icon(&component, 256),
+            Some(root.join("bookworm-main/128x128/gimp_gimp.png"))
+        );
+        assert_eq!(
+            fs::read(store.best_icon(&component, 128).ok_or("no icon")?)?,
+            b"128"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+/// The 8 bytes every PNG file starts with.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Reads the `(width, height)` of a PNG, JPEG, GIF or WebP image from its headers.
+///
+/// Only the headers are looked at, the image itself isn't decoded. Returns `None`
+/// if the format is not supported or the data is truncated.
+///
+/// # Arguments
+///
+/// * `data` - The content of the image file.
+pub fn dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    if data.starts_with(PNG_SIGNATURE) {
+        png_dimensions(data)
+    } else if data.starts_with(b"\xff\xd8") {
+        jpeg_dimensions(data)
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some((le_u16(data, 6)? as u32, le_u16(data, 8)? as u32))
+    } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+        webp_dimensions(data)
+    } else {
+        None
+    }
+}
+
+fn be_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*data.get(at)?, *data.get(at + 1)?]))
+}
+
+fn le_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes([*data.get(at)?, *data.get(at + 1)?]))
+}
+
+fn be_u32(data: &[u8], at: usize) -> Option<u32> {
+    let bytes = data.get(at..at + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn le_u24(data: &[u8], at: usize) -> Option<u32> {
+    let bytes = data.get(at..at + 3)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]))
+}
+
+fn png_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    // The IHDR chunk always comes first.
+    if data.get(12..16) != Some(b"IHDR") {
+        return None;
+    }
+    Some((be_u32(data, 16)?, be_u32(data, 20)?))
+}
+
+fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let mut pos = 2;
+    loop {
+        // Markers can be padded with any number of 0xff bytes.
+        while *data.get(pos)? == 0xff && *data.get(pos + 1)? == 0xff {
+            pos += 1;
+        }
+        if *data.get(pos)? != 0xff {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+        match marker {
+            // Standalone markers, without any payload.
+            0x01 | 0xd0..=0xd7 => pos += 2,
+            // Start of frame, DHT, JPG and DAC excluded.
+            0xc0..=0xcf if marker != 0xc4 && marker != 0xc8 && marker != 0xcc => {
+                let height = be_u16(data, pos + 5)?;
+                let width = be_u16(data, pos + 7)?;
+                return Some((width as u32, height as u32));
+            }
+            0xd9 | 0xda => return None,
+            _ => pos += 2 + be_u16(data, pos + 2)? as usize,
+        }
+    }
+}
+
+fn webp_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    match data.get(12..16)? {
+        b"VP8 " => {
+            // Lossy, the frame header is after a 3 bytes tag and a 3 bytes start code.
+            let width = le_u16(data, 26)? & 0x3fff;
+            let height = le_u16(data, 28)? & 0x3fff;
+            Some((width as u32, height as u32))
+        }
+        b"VP8L" => {
+            // Lossless, 14 bits for each dimension minus one after the signature byte.
+            let bits = u32::from_le_bytes([
+                *data.get(21)?,
+                *data.get(22)?,
+                *data.get(23)?,
+                *data.get(24)?,
+            ]);
+            Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+        }
+        b"VP8X" => Some((le_u24(data, 24)? + 1, le_u24(data, 27)? + 1)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png() {
+        let mut data = PNG_SIGNATURE.to_vec();
+        data.extend_from_slice(&[0, 0, 0, 13]);
+        data.extend_from_slice(b"IHDR");
+        data.extend_from_slice(&1920u32.to_be_bytes());
+        data.extend_from_slice(&1080u32.to_be_bytes());
+        assert_eq!(dimensions(&data), Some((1920, 1080)));
+        assert_eq!(dimensions(&data[..20]), None);
+    }
+
+    #[test]
+    fn jpeg() {
+        let mut data = vec![0xff, 0xd8];
+        // An APP0 segment to skip.
+        data.extend_from_slice(&[0xff, 0xe0, 0x00, 0x04, 0x00, 0x00]);
+        data.extend_from_slice(&[0xff, 0xc0, 0x00, 0x11, 0x08]);
+        data.extend_from_slice(&720u16.to_be_bytes());
+        data.extend_from_slice(&1280u16.to_be_bytes());
+        assert_eq!(dimensions(&data), Some((1280, 720)));
+        assert_eq!(dimensions(&[0xff, 0xd8, 0xff, 0xda]), None);
+    }
+
+    #[test]
+    fn gif_and_webp() {
+        let mut gif = b"GIF89a".to_vec();
+        gif.extend_from_slice(&[0x20, 0x03, 0x58, 0x02]);
+        assert_eq!(dimensions(&gif), Some((800, 600)));
+
+        let mut webp = b"RIFF\0\0\0\0WEBPVP8X".to_vec();
+        webp.extend_from_slice(&[0; 8]);
+        webp.extend_from_slice(&[0x7f, 0x07, 0x00, 0x37, 0x04, 0x00]);
+        assert_eq!(dimensions(&webp), Some((1920, 1080)));
+
+        assert_eq!(dimensions(b"not an image"), None);
+    }
+}
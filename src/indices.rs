@@ -0,0 +1,97 @@
+use super::search::Tokens;
+use super::Component;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+/// The indices of the components of a collection, each built by the first
+/// lookup needing it.
+///
+/// An index is rebuilt when components were added or removed since it was
+/// built, the methods of `Collection` modifying its components in place drop
+/// the indices. The clones of a collection start without indices, and the
+/// indices are left out of its equality and serialization.
+pub(crate) struct Indices {
+    pkgnames: Lazy<HashMap<String, Vec<usize>>>,
+    search: Lazy<Tokens>,
+}
+
+impl Indices {
+    /// The positions of the components shipped by each package.
+    pub(crate) fn pkgnames(&self, components: &[Component]) -> Arc<HashMap<String, Vec<usize>>> {
+        self.pkgnames.get(components, |components| {
+            let mut pkgnames: HashMap<String, Vec<usize>> = HashMap::new();
+            for (position, component) in components.iter().enumerate() {
+                if let Some(pkgname) = &component.pkgname {
+                    pkgnames.entry(pkgname.clone()).or_default().push(position);
+                }
+            }
+            pkgnames
+        })
+    }
+
+    /// The tokens of the components, to search them.
+    pub(crate) fn search(&self, components: &[Component]) -> Arc<Tokens> {
+        self.search.get(components, Tokens::new)
+    }
+
+    /// Drops the indices, after the components were modified.
+    pub(crate) fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Clone for Indices {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl PartialEq for Indices {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for Indices {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Indices").finish_non_exhaustive()
+    }
+}
+
+/// An index, built by the first lookup needing it.
+struct Lazy<T>(Mutex<Option<Built<T>>>);
+
+/// An index, along with the address and the number of the components it was
+/// built from.
+struct Built<T> {
+    stamp: (usize, usize),
+    index: Arc<T>,
+}
+
+impl<T> Default for Lazy<T> {
+    fn default() -> Self {
+        Self(Mutex::new(None))
+    }
+}
+
+impl<T> Lazy<T> {
+    fn get<F: FnOnce(&[Component]) -> T>(&self, components: &[Component], build: F) -> Arc<T> {
+        let stamp = (components.as_ptr() as usize, components.len());
+        // The index is only ever replaced as a whole, so it's still consistent
+        // if a builder panicked.
+        let mut built = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match &*built {
+            Some(built) if built.stamp == stamp => Arc::clone(&built.index),
+            _ => {
+                let index = Arc::new(build(components));
+                *built = Some(Built {
+                    stamp,
+                    index: Arc::clone(&index),
+                });
+                index
+            }
+        }
+    }
+}
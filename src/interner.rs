@@ -0,0 +1,70 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+thread_local! {
+    static STRINGS: RefCell<Option<HashSet<Arc<str>>>> = const { RefCell::new(None) };
+}
+
+/// Ends the scope started by `scope` when dropped, even if parsing panicked.
+struct Scope {
+    outermost: bool,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        if self.outermost {
+            STRINGS.with(|strings| strings.borrow_mut().take());
+        }
+    }
+}
+
+/// Runs `f` sharing the strings interned meanwhile, the strings repeated across
+/// the components of a catalog like the origin, the project group or the
+/// licenses are then only allocated once.
+///
+/// Nested scopes share the strings of the outermost one.
+pub(crate) fn scope<T>(f: impl FnOnce() -> T) -> T {
+    let outermost = STRINGS.with(|strings| {
+        let mut strings = strings.borrow_mut();
+        if strings.is_some() {
+            return false;
+        }
+        *strings = Some(HashSet::new());
+        true
+    });
+    let _scope = Scope { outermost };
+    f()
+}
+
+/// Returns the shared copy of `s` when called inside `scope`, a new one otherwise.
+pub(crate) fn intern(s: &str) -> Arc<str> {
+    STRINGS.with(|strings| match strings.borrow_mut().as_mut() {
+        Some(strings) => match strings.get(s) {
+            Some(shared) => shared.clone(),
+            None => {
+                let shared: Arc<str> = Arc::from(s);
+                strings.insert(shared.clone());
+                shared
+            }
+        },
+        None => Arc::from(s),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_in_scope() {
+        let (a, b) = scope(|| {
+            let a = intern("GNOME");
+            let b = scope(|| intern("GNOME"));
+            (a, b)
+        });
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&intern("GNOME"), &intern("GNOME")));
+        assert!(STRINGS.with(|strings| strings.borrow().is_none()));
+    }
+}
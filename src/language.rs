@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 /// Defines how well a language is supported by the component.
@@ -8,5 +9,5 @@ pub struct Language {
     /// The percentage of translation completion.
     pub percentage: Option<u32>,
     /// The language locale.
-    pub locale: String,
+    pub locale: Arc<str>,
 }
@@ -0,0 +1,244 @@
+use super::enums::Launchable;
+#[cfg(feature = "linux")]
+use std::env;
+#[cfg(feature = "linux")]
+use std::io;
+#[cfg(feature = "linux")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "linux")]
+use std::process::{Child, Command};
+use url::Url;
+
+/// The address of the Cockpit web service.
+#[cfg(feature = "linux")]
+const COCKPIT_URL: &str = "https://localhost:9090/";
+
+#[derive(Clone, Debug, PartialEq)]
+/// What to activate to launch a component, see `Component::launch_descriptor`.
+pub enum LaunchDescriptor {
+    /// The desktop file to activate, by its desktop file id like
+    /// `org.gnome.Maps.desktop`.
+    DesktopFile(String),
+    /// The systemd service to start, like `sshd.service`.
+    Service(String),
+    /// The Cockpit package whose page to open, like `machines`.
+    CockpitManifest(String),
+    /// The URL to open for a web application.
+    Url(Url),
+}
+
+impl LaunchDescriptor {
+    /// The descriptor of a launchable, `None` for the unknown kinds.
+    ///
+    /// The values come from the metadata, so the names with a `/` or a `..`
+    /// and the URLs other than `http` and `https` ones are rejected too.
+    ///
+    /// # Arguments
+    ///
+    /// * `launchable` - The launchable.
+    pub fn from_launchable(launchable: &Launchable) -> Option<Self> {
+        match launchable {
+            Launchable::DesktopId(id) if is_safe_name(id) => Some(Self::DesktopFile(id.clone())),
+            Launchable::Service(name) if is_safe_name(name) => Some(Self::Service(name.clone())),
+            Launchable::CockpitManifest(name) if is_safe_name(name) => {
+                Some(Self::CockpitManifest(name.clone()))
+            }
+            Launchable::Url(url) if matches!(url.scheme(), "http" | "https") => {
+                Some(Self::Url(url.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// The order of preference of the descriptors when a component has several.
+    pub(crate) fn preference(&self) -> u8 {
+        match self {
+            Self::DesktopFile(_) => 0,
+            Self::Url(_) => 1,
+            Self::CockpitManifest(_) => 2,
+            Self::Service(_) => 3,
+        }
+    }
+}
+
+#[cfg(feature = "linux")]
+impl LaunchDescriptor {
+    /// The path of the desktop file to activate, looked up in the `applications`
+    /// directories of `$XDG_DATA_HOME` then of `$XDG_DATA_DIRS`.
+    pub fn desktop_file(&self) -> Option<PathBuf> {
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|h| Path::new(&h).join(".local/share")));
+        let data_dirs = env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+        let mut dirs = vec![];
+        dirs.extend(data_home);
+        dirs.extend(
+            data_dirs
+                .split(':')
+                .filter(|d| !d.is_empty())
+                .map(PathBuf::from),
+        );
+        self.desktop_file_in(&dirs)
+    }
+
+    /// The path of the desktop file to activate, looked up in the `applications`
+    /// directory of some data directories.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_dirs` - The data directories, like `/usr/share`, by order of
+    ///   preference.
+    pub fn desktop_file_in(&self, data_dirs: &[PathBuf]) -> Option<PathBuf> {
+        match self {
+            Self::DesktopFile(id) => data_dirs
+                .iter()
+                .find_map(|d| find_desktop_file(&d.join("applications"), id)),
+            _ => None,
+        }
+    }
+
+    /// The command activating the descriptor: `gtk-launch` for desktop files,
+    /// `systemctl start` for services, and `xdg-open` for the web applications
+    /// and the Cockpit pages.
+    pub fn command(&self) -> Command {
+        // xdg-open rejects `--`, its arguments are always http(s) URLs anyway.
+        let (program, args) = match self {
+            Self::DesktopFile(id) => ("gtk-launch", vec!["--".into(), id.clone()]),
+            Self::Service(name) => ("systemctl", vec!["start".into(), "--".into(), name.clone()]),
+            Self::CockpitManifest(name) => ("xdg-open", vec![format!("{}{}", COCKPIT_URL, name)]),
+            Self::Url(url) => ("xdg-open", vec![url.to_string()]),
+        };
+        let mut command = Command::new(program);
+        command.args(args);
+        command
+    }
+
+    /// Launches the component, without waiting for it to exit.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use appstream::Component;
+    ///
+    /// let component = Component::from_path("/usr/share/metainfo/org.gnome.Maps.appdata.xml".into()).unwrap();
+    /// if let Some(descriptor) = component.launch_descriptor() {
+    ///     descriptor.activate().unwrap();
+    /// }
+    /// ```
+    pub fn activate(&self) -> io::Result<Child> {
+        self.command().spawn()
+    }
+}
+
+#[cfg(feature = "linux")]
+/// Finds a desktop file by its id, whose dashes stand for the subdirectories of
+/// `dir` it's in, like `kde-konsole.desktop` for `kde/konsole.desktop`.
+fn find_desktop_file(dir: &Path, id: &str) -> Option<PathBuf> {
+    if !is_safe_name(id) {
+        return None;
+    }
+    let path = dir.join(id);
+    if path.is_file() {
+        return Some(path);
+    }
+    id.match_indices('-').find_map(|(i, _)| {
+        let subdir = dir.join(&id[..i]);
+        if subdir.is_dir() {
+            find_desktop_file(&subdir, &id[i + 1..])
+        } else {
+            None
+        }
+    })
+}
+
+/// Whether `name` is a file name, with neither a directory nor a parent in it.
+fn is_safe_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains('/') && !name.contains("..")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Component;
+    use std::convert::TryFrom;
+    use std::error::Error;
+
+    #[test]
+    fn launch_descriptor() -> Result<(), Box<dyn Error>> {
+        let component = Component::try_from(&xmltree::Element::parse(
+            r#"<component type="desktop-application">
+                <id>org.kde.konsole</id>
+                <name>Konsole</name>
+                <launchable type="service">konsole.service</launchable>
+                <launchable type="url">https://konsole.kde.org/web</launchable>
+                <launchable type="desktop-id">kde-konsole.desktop</launchable>
+            </component>"#
+                .as_bytes(),
+        )?)?;
+        let descriptor = component.launch_descriptor().ok_or("no descriptor")?;
+        assert_eq!(
+            descriptor,
+            LaunchDescriptor::DesktopFile("kde-konsole.desktop".into())
+        );
+
+        let mut web = component.clone();
+        web.launchables.remove(2);
+        assert_eq!(
+            web.launch_descriptor(),
+            Some(LaunchDescriptor::Url(Url::parse(
+                "https://konsole.kde.org/web"
+            )?))
+        );
+        web.launchables.clear();
+        assert_eq!(web.launch_descriptor(), None);
+
+        for launchable in [
+            Launchable::DesktopId("../../evil.desktop".into()),
+            Launchable::DesktopId("kde/konsole.desktop".into()),
+            Launchable::Service("../x.service".into()),
+            Launchable::CockpitManifest("a/../b".into()),
+            Launchable::Url(Url::parse("file:///tmp/x.desktop")?),
+        ] {
+            assert_eq!(LaunchDescriptor::from_launchable(&launchable), None);
+        }
+
+        #[cfg(feature = "linux")]
+        {
+            let dir = tempfile::tempdir()?;
+            let root = dir.path().join("launch");
+            std::fs::create_dir_all(root.join("share/applications/kde"))?;
+            std::fs::write(root.join("share/applications/kde/konsole.desktop"), "")?;
+            assert_eq!(
+                descriptor.desktop_file_in(&[root.join("empty"), root.join("share")]),
+                Some(root.join("share/applications/kde/konsole.desktop"))
+            );
+
+            let command = descriptor.command();
+            assert_eq!(command.get_program(), "gtk-launch");
+            assert_eq!(
+                command.get_args().collect::<Vec<_>>(),
+                ["--", "kde-konsole.desktop"]
+            );
+            let command = LaunchDescriptor::Service("--user".into()).command();
+            assert_eq!(
+                command.get_args().collect::<Vec<_>>(),
+                ["start", "--", "--user"]
+            );
+            assert_eq!(
+                LaunchDescriptor::DesktopFile("../evil.desktop".into())
+                    .desktop_file_in(&[root.join("share")]),
+                None
+            );
+            let command = LaunchDescriptor::CockpitManifest("machines".into()).command();
+            assert_eq!(
+                command.get_args().collect::<Vec<_>>(),
+                ["https://localhost:9090/machines"]
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,416 @@
+use super::error::{ParseError, ParseWarning};
+use super::view::{self, ComponentRef};
+use super::warnings;
+use super::{AppId, Collection, Component, Timestamp};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+/// The document of a `LazyCollection`.
+enum Source {
+    Owned(String),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl Source {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Source::Owned(xml) => xml.as_bytes(),
+            #[cfg(feature = "mmap")]
+            Source::Mapped(map) => map,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LazyComponent {
+    id: AppId,
+    span: Range<usize>,
+}
+
+#[derive(Debug, Default)]
+/// The parsed components, by index, evicting the least recently used ones past
+/// the capacity.
+struct Cache {
+    capacity: Option<usize>,
+    tick: u64,
+    components: HashMap<usize, (Arc<Component>, u64)>,
+    recency: BTreeMap<u64, usize>,
+}
+
+impl Cache {
+    fn get(&mut self, index: usize) -> Option<Arc<Component>> {
+        let (component, used) = self.components.get_mut(&index)?;
+        self.recency.remove(used);
+        self.tick += 1;
+        *used = self.tick;
+        self.recency.insert(self.tick, index);
+        Some(Arc::clone(component))
+    }
+
+    fn insert(&mut self, index: usize, component: Arc<Component>) -> Arc<Component> {
+        // Another thread may have parsed it in the meantime.
+        if let Some(cached) = self.get(index) {
+            return cached;
+        }
+        self.tick += 1;
+        self.components
+            .insert(index, (Arc::clone(&component), self.tick));
+        self.recency.insert(self.tick, index);
+        self.shrink();
+        component
+    }
+
+    fn shrink(&mut self) {
+        let capacity = self.capacity.unwrap_or(usize::MAX);
+        while self.components.len() > capacity {
+            if let Some((_, index)) = self.recency.pop_first() {
+                self.components.remove(&index);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+/// A catalog whose components are only parsed when they are first accessed.
+///
+/// Loading it only records the ids of the components and where they are in the
+/// document, which is kept in memory. The parsed components are cached, so
+/// sessions that only look at a few components of a big catalog don't pay for
+/// parsing the others. The cache can be bounded with `with_cache_capacity`, the
+/// least recently used components are then dropped and parsed again from the
+/// document when needed.
+///
+/// # Example
+///
+/// ```
+/// use appstream::lazy::LazyCollection;
+///
+/// let collection =
+///     LazyCollection::from_path("./tests/collections/gnome-apps.xml".into()).unwrap();
+/// let characters = collection.find_by_id("org.gnome.Characters".into()).unwrap();
+/// assert_eq!(characters.len(), 1);
+/// ```
+pub struct LazyCollection {
+    /// The specification version used on the components.
+    pub version: String,
+    /// The origin of the collection, could be something like `flathub`.
+    pub origin: Option<String>,
+    /// The targeted CPU architecture of the collection.
+    pub architecture: Option<String>,
+    /// When the collection was generated.
+    pub timestamp: Option<Timestamp>,
+    source: Source,
+    components: Vec<LazyComponent>,
+    cache: Mutex<Cache>,
+}
+
+impl LazyCollection {
+    /// Loads a collection from an XML file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the collection.
+    pub fn from_path(path: PathBuf) -> Result<Self, ParseError> {
+        Self::from_xml(fs::read_to_string(path)?)
+    }
+
+    /// Loads a collection from an XML document.
+    ///
+    /// # Arguments
+    ///
+    /// * `xml` - The document, the components are parsed from it when accessed.
+    pub fn from_xml(xml: String) -> Result<Self, ParseError> {
+        Self::load(Source::Owned(xml))
+    }
+
+    #[cfg(feature = "mmap")]
+    /// Loads a collection from a memory-mapped XML file, the components are
+    /// parsed from the mapping when accessed so only the pages of the accessed
+    /// components are read.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the uncompressed collection.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified nor truncated, by this process or another
+    /// one, while the collection is in use, see `Collection::from_mapped_path`.
+    pub unsafe fn from_mapped_path(path: PathBuf) -> Result<Self, ParseError> {
+        let file = fs::File::open(path)?;
+        Self::load(Source::Mapped(memmap2::Mmap::map(&file)?))
+    }
+
+    fn load(source: Source) -> Result<Self, ParseError> {
+        let (version, origin, architecture, timestamp, components) = {
+            let (root, spans) = view::component_spans(utf8(source.bytes())?)?;
+            let version = root
+                .attribute("version")
+                .ok_or_else(|| ParseError::missing_attribute("version", "collection"))?
+                .to_string();
+            let origin = root
+                .attribute("origin")
+                .filter(|o| !o.is_empty())
+                .map(|o| o.to_string());
+            let architecture = root.attribute("architecture").map(|a| a.to_string());
+            let timestamp = root.attribute("time").and_then(|time| match time.parse() {
+                Ok(time) => Some(time),
+                Err(_) => {
+                    warnings::warn(ParseWarning::recovered_value(
+                        time,
+                        "time",
+                        "components",
+                        "none",
+                    ));
+                    None
+                }
+            });
+            let components = spans
+                .into_iter()
+                .map(|(span, id)| LazyComponent {
+                    id: id.into(),
+                    span,
+                })
+                .collect::<Vec<_>>();
+            (version, origin, architecture, timestamp, components)
+        };
+        Ok(Self {
+            version,
+            origin,
+            architecture,
+            timestamp,
+            source,
+            components,
+            cache: Mutex::default(),
+        })
+    }
+
+    /// Bounds the number of parsed components kept in memory, the least recently
+    /// used ones are dropped past it.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of cached components.
+    pub fn with_cache_capacity(self, capacity: usize) -> Self {
+        {
+            let mut cache = self.lock();
+            cache.capacity = Some(capacity);
+            cache.shrink();
+        }
+        self
+    }
+
+    /// The number of components.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Whether the collection has no components.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// The ids of the components, available without parsing them.
+    pub fn ids(&self) -> impl Iterator<Item = &AppId> {
+        self.components.iter().map(|c| &c.id)
+    }
+
+    /// The component at `index`, parsed on the first access.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The position of the component in the collection.
+    pub fn get(&self, index: usize) -> Option<Result<Arc<Component>, ParseError>> {
+        (index < self.components.len()).then(|| self.parse(index))
+    }
+
+    /// Find the components that corresponds to a specific `AppId`, like
+    /// `Collection::find_by_id` does. Only the matching components are parsed.
+    pub fn find_by_id(&self, id: AppId) -> Result<Vec<Arc<Component>>, ParseError> {
+        let alternative_id: AppId = format!("{}.desktop", id.0).into();
+        self.components
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.id == id || c.id == alternative_id)
+            .map(|(index, _)| self.parse(index))
+            .collect()
+    }
+
+    /// Iterates over the components, parsing the ones that aren't cached.
+    pub fn iter(&self) -> impl Iterator<Item = Result<Arc<Component>, ParseError>> + '_ {
+        (0..self.components.len()).map(move |index| self.parse(index))
+    }
+
+    /// Parses all the components into a `Collection`.
+    pub fn into_collection(self) -> Result<Collection, ParseError> {
+        let components = self
+            .iter()
+            .map(|c| c.map(|c| Arc::try_unwrap(c).unwrap_or_else(|c| (*c).clone())))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Collection {
+            version: self.version,
+            origin: self.origin,
+            media_base_url: None,
+            components,
+            architecture: self.architecture,
+            timestamp: self.timestamp,
+            indices: Default::default(),
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Cache> {
+        // The cache is always consistent between its calls.
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn parse(&self, index: usize) -> Result<Arc<Component>, ParseError> {
+        if let Some(component) = self.lock().get(index) {
+            return Ok(component);
+        }
+        // Parsed without holding the lock, so other threads aren't blocked.
+        let xml = utf8(&self.source.bytes()[self.components[index].span.clone()])?;
+        let component = ComponentRef::parse(xml)?.to_component()?;
+        Ok(self.lock().insert(index, Arc::new(component)))
+    }
+}
+
+fn utf8(bytes: &[u8]) -> Result<&str, ParseError> {
+    std::str::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn parse_on_access() -> Result<(), Box<dyn Error>> {
+        let path = "./tests/collections/gnome-apps.xml";
+        let lazy = LazyCollection::from_path(path.into())?;
+        let collection = Collection::from_path(path.into())?;
+        assert_eq!(lazy.len(), collection.components.len());
+        assert_eq!(lazy.origin, collection.origin);
+        assert!(lazy.lock().components.is_empty());
+
+        let characters = lazy.find_by_id("org.gnome.Characters".into())?;
+        assert_eq!(characters.len(), 1);
+        assert_eq!(
+            vec![&*characters[0]],
+            collection.find_by_id("org.gnome.Characters".into())
+        );
+        assert_eq!(lazy.lock().components.len(), 1);
+
+        assert!(Arc::ptr_eq(
+            &characters[0],
+            &lazy.find_by_id("org.gnome.Characters".into())?[0]
+        ));
+        assert_eq!(lazy.ids().next(), Some(&collection.components[0].id));
+        assert_eq!(
+            lazy.get(3).transpose()?.as_deref(),
+            collection.components.get(3)
+        );
+        assert!(lazy.get(lazy.len()).is_none());
+        assert_eq!(lazy.into_collection()?, collection);
+
+        let broken = LazyCollection::from_xml(
+            "<components version='0.14'><component type='foo'><id>a</id></component></components>"
+                .into(),
+        )?;
+        assert_eq!(broken.ids().collect::<Vec<_>>(), vec![&AppId::from("a")]);
+        assert!(broken.get(0).ok_or("no component")?.is_err());
+        assert!(LazyCollection::from_xml("<components><component/></components>".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn lazy_timestamp() -> Result<(), Box<dyn Error>> {
+        let lazy = LazyCollection::from_xml(
+            "<components version='0.14' time='2022-02-09T16:17:03Z'/>".into(),
+        )?;
+        let generated = Timestamp::from_ymd_hms(2022, 2, 9, 16, 17, 3);
+        assert_eq!(lazy.timestamp, generated);
+        let collection = lazy.into_collection()?;
+        assert_eq!(collection.timestamp, generated);
+        assert!(collection
+            .age(Timestamp::from_unix(1_700_000_000))
+            .is_some());
+
+        let (lazy, warnings) = warnings::collect(|| {
+            LazyCollection::from_xml("<components version='0.14' time='yesterday'/>".into())
+        });
+        assert_eq!(lazy?.timestamp, None);
+        assert_eq!(
+            warnings,
+            vec![ParseWarning::recovered_value(
+                "yesterday",
+                "time",
+                "components",
+                "none"
+            )]
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn map_files() -> Result<(), Box<dyn Error>> {
+        let path = "./tests/collections/gnome-apps.xml";
+        let collection = Collection::from_path(path.into())?;
+        // The test files aren't modified while they are mapped.
+        unsafe {
+            assert_eq!(Collection::from_mapped_path(path.into())?, collection);
+            let lazy = LazyCollection::from_mapped_path(path.into())?;
+            assert_eq!(lazy.into_collection()?, collection);
+        }
+
+        let dir = tempfile::tempdir()?;
+        let empty = dir.path().join("mmap-empty");
+        fs::write(&empty, b"")?;
+        assert!(unsafe { Collection::from_mapped_path(empty.clone()) }.is_err());
+
+        let missing = "./tests/collections/missing.xml";
+        assert!(unsafe { LazyCollection::from_mapped_path(missing.into()) }.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn evict_least_recently_used() -> Result<(), Box<dyn Error>> {
+        let path = "./tests/collections/gnome-apps.xml";
+        let lazy = LazyCollection::from_path(path.into())?.with_cache_capacity(2);
+        let collection = Collection::from_path(path.into())?;
+        let cached = |lazy: &LazyCollection| {
+            let mut indices = lazy.lock().components.keys().copied().collect::<Vec<_>>();
+            indices.sort_unstable();
+            indices
+        };
+
+        let first = lazy.get(0).ok_or("no component")??;
+        lazy.get(1).ok_or("no component")??;
+        lazy.get(0).ok_or("no component")??;
+        lazy.get(2).ok_or("no component")??;
+        assert_eq!(cached(&lazy), vec![0, 2]);
+
+        // Evicted components are parsed again, the handed out ones stay valid.
+        lazy.get(1).ok_or("no component")??;
+        lazy.get(3).ok_or("no component")??;
+        assert_eq!(cached(&lazy), vec![1, 3]);
+        assert_eq!(&*first, &collection.components[0]);
+        assert_eq!(
+            lazy.get(0).transpose()?.as_deref(),
+            collection.components.first()
+        );
+
+        assert_eq!(lazy.iter().count(), collection.components.len());
+        assert_eq!(lazy.lock().components.len(), 2);
+        let lazy = lazy.with_cache_capacity(0);
+        assert!(lazy.lock().components.is_empty());
+        assert_eq!(lazy.into_collection()?, collection);
+        Ok(())
+    }
+}
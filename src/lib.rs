@@ -16,10 +16,9 @@
 //! ```
 //! use appstream::{Component, ParseError};
 //! use appstream::builders::{ComponentBuilder, ReleaseBuilder};
-//! use appstream::TranslatableString;
+//! use appstream::{Timestamp, TranslatableString};
 //! use appstream::enums::{Provide, ProjectUrl};
 //! use url::Url;
-//! use chrono::{Utc, TimeZone};
 //! use std::convert::TryFrom;
 //!
 //! fn main() -> Result<(), ParseError> {
@@ -59,7 +58,7 @@
 //!         .provide(Provide::Binary("foobar".into()))
 //!         .release(
 //!             ReleaseBuilder::new("1.2")
-//!                 .date(Utc.ymd(2015, 2, 16).and_hms_milli(0, 0, 0, 0))
+//!                 .date(Timestamp::from_ymd(2015, 2, 16).unwrap())
 //!                 .build(),
 //!         )
 //!         .build();
@@ -82,7 +81,7 @@
 //!     println!("{:#?}", collection.find_by_id("org.gnome.design.Contrast".into()));
 //!
 //!     // Find the list of gedit plugins
-//!     collection.components.iter()
+//!     collection.iter()
 //!         .filter(|c| c.extends.contains(&"org.gnome.gedit".into()))
 //!         .collect::<Vec<&Component>>();
 //!
@@ -93,31 +92,131 @@
 #![deny(missing_docs)]
 
 mod app_id;
+#[cfg(feature = "arrow")]
+/// Export of component fields to Arrow record batches and Parquet files.
+pub mod arrow;
+#[cfg(feature = "json")]
+/// Results and hints exported by appstream-generator.
+pub mod asgen;
+#[cfg(feature = "async")]
+mod blocking;
+mod branding;
 /// Various helpers to build any appstream type.
 pub mod builders;
+mod canonical;
+/// Parsing and matching of the GStreamer codecs the codec components provide.
+pub mod codec;
 mod collection;
 mod component;
+/// Generation of catalogs out of the metainfo and desktop files of a directory tree.
+pub mod compose;
 mod content_rating;
+/// Reports of the tags and attributes the parser recognized, ignored or failed on.
+pub mod coverage;
+#[cfg(feature = "net")]
+/// DEP-11 metadata of Debian-like repositories.
+pub mod debian;
+mod delta;
+mod desktop;
+mod digest;
+#[cfg(feature = "net")]
+/// Verified downloads of release artifacts.
+pub mod download;
 /// Various enumerations used in the appstream types.
 pub mod enums;
 mod error;
+/// Matching of firmware components to devices, like fwupd does.
+pub mod firmware;
+#[cfg(all(feature = "net", feature = "gzip"))]
+/// AppStream data of flatpak remotes, fetched without flatpak.
+pub mod flatpak;
+#[cfg(feature = "bundle")]
+/// Metadata of flatpak single-file bundles, read without installing them.
+pub mod flatpak_bundle;
+/// Summaries of the font components, their faces and preview texts.
+pub mod font;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+#[cfg(any(all(feature = "net", feature = "gzip"), feature = "bundle"))]
+mod gvariant;
+/// Lookup of stock icons in the installed freedesktop icon themes.
+pub mod icon_theme;
+/// Stores of cached icons, filled from the icon tarballs of catalogs.
+pub mod icons;
+#[cfg(feature = "image")]
+mod image_size;
+mod indices;
+mod interner;
 mod language;
+/// Launching of the components from their launchables.
+pub mod launch;
+/// Catalogs whose components are only parsed when they are first accessed.
+pub mod lazy;
 mod license;
+/// DEP-11 media pool layout and export.
+pub mod media;
+#[cfg(feature = "linux")]
+/// Hardware modaliases of the running Linux system, read from sysfs.
+pub mod modalias;
+#[cfg(feature = "json")]
+mod ndjson;
+#[cfg(feature = "net")]
+/// Network helpers, the HTTP transport is provided by the application.
+pub mod net;
+/// Conversion between changelogs and releases.
+pub mod news;
+mod options;
+#[cfg(feature = "package")]
+/// Metainfo files read straight from `.deb` and `.rpm` packages.
+pub mod package;
+/// Thread-safe, indexed pools of components loaded from several catalogs.
+pub mod pool;
+#[cfg(feature = "raw")]
+/// Unparsed access to the tags the crate doesn't model.
+pub mod raw;
+mod reader;
 mod release;
+#[cfg(feature = "schema")]
+/// Partial structural validation of XML files against the AppStream schema.
+pub mod schema;
 mod screenshot;
+/// Full-text search of components over their id, name, keywords, summary and description.
+pub mod search;
+mod spdx;
+mod spdx_list;
+#[cfg(feature = "sqlite")]
+/// Persistence of pools into SQLite databases, queried without loading them.
+pub mod sqlite;
+/// The hardware and network state of a system, probed on Linux with the `linux` feature.
+pub mod system;
+mod tar;
+mod timestamp;
 mod translatable_string;
+/// Validation of components and collections against the specification and custom rules.
+pub mod validation;
+/// Borrowed, read-only views of components, parsed without allocating their strings.
+pub mod view;
+mod warnings;
+/// Helpers for the web applications: their URL, icon and browser profile.
+pub mod webapp;
 mod xml;
+mod xml_writer;
 mod yaml;
 
-pub use app_id::AppId;
+pub use app_id::{AppId, AppIdAliases};
+pub use branding::BrandingColor;
 pub use collection::Collection;
 pub use component::Component;
 pub use content_rating::ContentRating;
-pub use error::ParseError;
+pub use delta::CollectionDelta;
+pub use desktop::DesktopEntry;
+pub use error::{ParseError, ParseWarning};
 pub use language::Language;
-pub use license::License;
-pub use release::{Artifact, Release};
+pub use license::{License, LicenseExpression};
+pub use options::{Fields, ParseOptions};
+pub use release::{vercmp, Artifact, Changelog, Issue, Release};
 pub use screenshot::{Image, Screenshot, Video};
+pub use timestamp::{Precision, Timestamp};
 pub use translatable_string::{MarkupTranslatableString, TranslatableList, TranslatableString};
 pub use url;
 pub use xmltree;
@@ -1,23 +1,554 @@
+use super::error::ParseError;
+use super::interner;
+use super::spdx;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
 /// A SPDX license.
 /// See the list of commonly found licenses [https://spdx.org/licenses/](https://spdx.org/licenses/).
-pub struct License(pub String);
+///
+/// Legacy identifiers are replaced by their modern forms when the license is
+/// constructed, `GPL-2.0+` becomes `GPL-2.0-or-later`, `LGPL-2.1` becomes
+/// `LGPL-2.1-only` and so on. The original text is kept for round-tripping the
+/// metadata, see `License::original`.
+pub struct License(pub Arc<str>, Option<Arc<str>>);
 
-impl From<String> for License {
-    fn from(l: String) -> Self {
-        Self(l)
+impl License {
+    /// Parses the license into a SPDX expression tree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::{License, LicenseExpression};
+    ///
+    /// let license = License::from("GPL-3.0-or-later WITH Classpath-exception-2.0 OR MIT");
+    /// let expression = license.expression().unwrap();
+    /// assert_eq!(expression.license_ids(), vec!["GPL-3.0-or-later", "MIT"]);
+    /// ```
+    pub fn expression(&self) -> Result<LicenseExpression, ParseError> {
+        LicenseExpression::from_str(&self.0)
+    }
+
+    /// Whether the license is a valid SPDX expression, only made of known
+    /// license and exception identifiers or `LicenseRef-` references.
+    pub fn is_valid_spdx(&self) -> bool {
+        self.expression().map(|e| e.is_valid()).unwrap_or(false)
+    }
+
+    /// Whether the license is a free license, like libappstream's `as_license_is_free_license`.
+    ///
+    /// A license is free when it's OSI approved or considered free by the FSF.
+    /// For expressions, at least one side of an `OR` and both sides of an `AND`
+    /// have to be free. Licenses that can't be parsed are considered as non-free.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::License;
+    ///
+    /// assert!(License::from("GPL-3.0-or-later OR LicenseRef-proprietary").is_free());
+    /// assert!(!License::from("LicenseRef-proprietary:NVIDIA").is_free());
+    /// ```
+    pub fn is_free(&self) -> bool {
+        self.expression()
+            .map(|e| e.evaluate(&spdx::is_free_license_id))
+            .unwrap_or(false)
+    }
+
+    /// The license as it was written, before its legacy identifiers were replaced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::License;
+    ///
+    /// let license = License::from("GPL-2.0+ AND lgpl-2.1");
+    /// assert_eq!(&*license.0, "GPL-2.0-or-later AND LGPL-2.1-only");
+    /// assert_eq!(license.original(), "GPL-2.0+ AND lgpl-2.1");
+    /// ```
+    pub fn original(&self) -> &str {
+        self.1.as_deref().unwrap_or(&self.0)
     }
 }
 
 impl From<&str> for License {
     fn from(l: &str) -> Self {
-        Self(l.to_string())
+        match LicenseExpression::from_str(l) {
+            Ok(e) => {
+                let canonical = e.canonicalize().to_string();
+                if canonical == l {
+                    Self(interner::intern(l), None)
+                } else {
+                    Self(interner::intern(&canonical), Some(interner::intern(l)))
+                }
+            }
+            Err(_) => Self(interner::intern(l), None),
+        }
+    }
+}
+
+impl From<String> for License {
+    fn from(l: String) -> Self {
+        Self::from(l.as_str())
+    }
+}
+
+impl From<License> for String {
+    fn from(l: License) -> Self {
+        l.original().to_string()
+    }
+}
+
+impl fmt::Display for License {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A parsed SPDX license expression.
+/// See [SPDX license expressions](https://spdx.github.io/spdx-spec/v2.3/SPDX-license-expressions/).
+pub enum LicenseExpression {
+    /// A license identifier like `MIT`.
+    License {
+        /// The license identifier.
+        id: String,
+        /// Whether the `+` operator was used, meaning "this version or any later version".
+        or_later: bool,
+    },
+    /// A user defined license reference like `LicenseRef-proprietary`.
+    Reference(String),
+    /// A license with an exception, like `GPL-2.0-or-later WITH Classpath-exception-2.0`.
+    With {
+        /// The license the exception applies to.
+        license: Box<LicenseExpression>,
+        /// The exception identifier.
+        exception: String,
+    },
+    /// Both expressions apply.
+    And(Box<LicenseExpression>, Box<LicenseExpression>),
+    /// A choice between both expressions.
+    Or(Box<LicenseExpression>, Box<LicenseExpression>),
+}
+
+impl LicenseExpression {
+    /// Whether every license and exception identifier of the expression is a known
+    /// SPDX identifier. User defined references are always considered as valid.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            LicenseExpression::License { id, .. } => spdx::is_license_id(id),
+            LicenseExpression::Reference(_) => true,
+            LicenseExpression::With { license, exception } => {
+                license.is_valid() && spdx::is_exception_id(exception)
+            }
+            LicenseExpression::And(l, r) | LicenseExpression::Or(l, r) => {
+                l.is_valid() && r.is_valid()
+            }
+        }
+    }
+
+    /// The license identifiers and references used in the expression, in order.
+    pub fn license_ids(&self) -> Vec<&str> {
+        match self {
+            LicenseExpression::License { id, .. } => vec![id.as_str()],
+            LicenseExpression::Reference(r) => vec![r.as_str()],
+            LicenseExpression::With { license, .. } => license.license_ids(),
+            LicenseExpression::And(l, r) | LicenseExpression::Or(l, r) => {
+                let mut ids = l.license_ids();
+                ids.extend(r.license_ids());
+                ids
+            }
+        }
+    }
+
+    /// Evaluates the expression against a predicate on license identifiers.
+    ///
+    /// `AND` requires both sides to satisfy the predicate while `OR` only requires
+    /// one of them. Exceptions only relax a license, so the predicate is evaluated
+    /// on the license the exception applies to.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate` - Called with each license identifier or reference.
+    pub fn evaluate<F: Fn(&str) -> bool>(&self, predicate: &F) -> bool {
+        match self {
+            LicenseExpression::License { id, .. } => predicate(id),
+            LicenseExpression::Reference(r) => predicate(r),
+            LicenseExpression::With { license, .. } => license.evaluate(predicate),
+            LicenseExpression::And(l, r) => l.evaluate(predicate) && r.evaluate(predicate),
+            LicenseExpression::Or(l, r) => l.evaluate(predicate) || r.evaluate(predicate),
+        }
+    }
+}
+
+impl LicenseExpression {
+    /// Replaces the deprecated SPDX identifiers by their modern replacements and
+    /// normalizes the case of the known identifiers.
+    pub fn canonicalize(&self) -> LicenseExpression {
+        match self {
+            LicenseExpression::License { id, or_later } => {
+                match spdx::replacement_of(id, *or_later)
+                    .and_then(|r| LicenseExpression::from_str(&r).ok())
+                {
+                    Some(replacement) => replacement,
+                    None => LicenseExpression::License {
+                        id: spdx::canonical_license_id(id).to_string(),
+                        or_later: *or_later,
+                    },
+                }
+            }
+            LicenseExpression::Reference(r) => LicenseExpression::Reference(r.clone()),
+            LicenseExpression::With { license, exception } => LicenseExpression::With {
+                license: Box::new(license.canonicalize()),
+                exception: spdx::canonical_license_id(exception).to_string(),
+            },
+            LicenseExpression::And(l, r) => {
+                LicenseExpression::And(Box::new(l.canonicalize()), Box::new(r.canonicalize()))
+            }
+            LicenseExpression::Or(l, r) => {
+                LicenseExpression::Or(Box::new(l.canonicalize()), Box::new(r.canonicalize()))
+            }
+        }
+    }
+}
+
+impl fmt::Display for LicenseExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LicenseExpression::License { id, or_later } => {
+                write!(f, "{}{}", id, if *or_later { "+" } else { "" })
+            }
+            LicenseExpression::Reference(r) => f.write_str(r),
+            LicenseExpression::With { license, exception } => match license.as_ref() {
+                LicenseExpression::License { .. } | LicenseExpression::Reference(_) => {
+                    write!(f, "{} WITH {}", license, exception)
+                }
+                _ => write!(f, "({}) WITH {}", license, exception),
+            },
+            LicenseExpression::And(l, r) => {
+                for (i, e) in [l, r].iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" AND ")?;
+                    }
+                    match e.as_ref() {
+                        LicenseExpression::Or(_, _) => write!(f, "({})", e)?,
+                        _ => write!(f, "{}", e)?,
+                    }
+                }
+                Ok(())
+            }
+            LicenseExpression::Or(l, r) => write!(f, "{} OR {}", l, r),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Open,
+    Close,
+    And,
+    Or,
+    With,
+    Id(&'a str),
+}
+
+fn tokenize(expr: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for word in expr.split_whitespace() {
+        let mut rest = word;
+        while !rest.is_empty() {
+            if let Some(r) = rest.strip_prefix('(') {
+                tokens.push(Token::Open);
+                rest = r;
+                continue;
+            }
+            let end = rest.find(&['(', ')'][..]).unwrap_or(rest.len());
+            let (item, r) = rest.split_at(end);
+            if !item.is_empty() {
+                tokens.push(match item {
+                    "AND" | "and" | "&" => Token::And,
+                    "OR" | "or" | "|" => Token::Or,
+                    "WITH" | "with" => Token::With,
+                    id => Token::Id(id),
+                });
+            }
+            rest = match r.strip_prefix(')') {
+                Some(r) => {
+                    tokens.push(Token::Close);
+                    r
+                }
+                None => r,
+            };
+        }
+    }
+    tokens
+}
+
+/// The deepest nesting of parentheses parsed, the parser recurses on them.
+const MAX_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    expr: &'a str,
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self) -> ParseError {
+        ParseError::invalid_value(self.expr, "$value", "license")
+    }
+
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token<'a>> {
+        self.pos += 1;
+        self.tokens.get(self.pos - 1)
+    }
+
+    fn parse_or(&mut self) -> Result<LicenseExpression, ParseError> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = LicenseExpression::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<LicenseExpression, ParseError> {
+        let mut left = self.parse_with()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            let right = self.parse_with()?;
+            left = LicenseExpression::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_with(&mut self) -> Result<LicenseExpression, ParseError> {
+        let license = self.parse_primary()?;
+        if self.peek() != Some(&Token::With) {
+            return Ok(license);
+        }
+        self.pos += 1;
+        match self.next() {
+            Some(Token::Id(exception)) => Ok(LicenseExpression::With {
+                license: Box::new(license),
+                exception: exception.to_string(),
+            }),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<LicenseExpression, ParseError> {
+        match self.next() {
+            Some(Token::Open) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(ParseError::limit_exceeded(
+                        "nested parentheses in a license",
+                        MAX_DEPTH as u64,
+                    ));
+                }
+                self.depth += 1;
+                let expr = self.parse_or()?;
+                self.depth -= 1;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err(self.error()),
+                }
+            }
+            Some(Token::Id(id)) => {
+                let id = *id;
+                if id.starts_with("LicenseRef-") || id.starts_with("DocumentRef-") {
+                    Ok(LicenseExpression::Reference(id.to_string()))
+                } else if let Some(id) = id.strip_suffix('+') {
+                    Ok(LicenseExpression::License {
+                        id: id.to_string(),
+                        or_later: true,
+                    })
+                } else {
+                    Ok(LicenseExpression::License {
+                        id: id.to_string(),
+                        or_later: false,
+                    })
+                }
+            }
+            _ => Err(self.error()),
+        }
     }
 }
 
-impl ToString for License {
-    fn to_string(&self) -> String {
-        self.0.clone()
+impl FromStr for LicenseExpression {
+    type Err = ParseError;
+
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            expr,
+            tokens: tokenize(expr),
+            pos: 0,
+            depth: 0,
+        };
+        let expression = parser.parse_or()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(parser.error());
+        }
+        Ok(expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(id: &str) -> Box<LicenseExpression> {
+        Box::new(LicenseExpression::License {
+            id: id.to_string(),
+            or_later: false,
+        })
+    }
+
+    #[test]
+    fn parse_expressions() -> Result<(), ParseError> {
+        assert_eq!(
+            LicenseExpression::from_str("GPL-2.0+")?,
+            LicenseExpression::License {
+                id: "GPL-2.0".into(),
+                or_later: true
+            }
+        );
+        assert_eq!(
+            LicenseExpression::from_str("MIT OR Apache-2.0 AND Zlib")?,
+            LicenseExpression::Or(
+                id("MIT"),
+                Box::new(LicenseExpression::And(id("Apache-2.0"), id("Zlib")))
+            )
+        );
+        assert_eq!(
+            LicenseExpression::from_str("(MIT OR Apache-2.0) AND LicenseRef-proprietary")?,
+            LicenseExpression::And(
+                Box::new(LicenseExpression::Or(id("MIT"), id("Apache-2.0"))),
+                Box::new(LicenseExpression::Reference(
+                    "LicenseRef-proprietary".into()
+                ))
+            )
+        );
+        assert_eq!(
+            LicenseExpression::from_str("GPL-2.0-only WITH Classpath-exception-2.0")?,
+            LicenseExpression::With {
+                license: id("GPL-2.0-only"),
+                exception: "Classpath-exception-2.0".into()
+            }
+        );
+        assert_eq!(
+            LicenseExpression::from_str("GPL-3.0 and LGPL-2.1")?,
+            LicenseExpression::And(id("GPL-3.0"), id("LGPL-2.1"))
+        );
+
+        assert!(LicenseExpression::from_str("MIT AND").is_err());
+        assert!(LicenseExpression::from_str("(MIT OR GPL-3.0").is_err());
+        assert!(LicenseExpression::from_str("MIT GPL-3.0").is_err());
+        assert!(LicenseExpression::from_str("").is_err());
+
+        let nested = |depth| format!("{}MIT{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(LicenseExpression::from_str(&nested(MAX_DEPTH)).is_ok());
+        assert!(matches!(
+            LicenseExpression::from_str(&nested(100_000)),
+            Err(ParseError::LimitExceeded(_, limit)) if limit == MAX_DEPTH as u64
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn display_round_trip() -> Result<(), ParseError> {
+        for expr in &[
+            "MIT",
+            "GPL-2.0+",
+            "(MIT OR Apache-2.0) AND Zlib",
+            "MIT OR Apache-2.0 AND Zlib",
+            "GPL-2.0-or-later WITH Classpath-exception-2.0 OR LicenseRef-proprietary",
+            "(MIT OR GPL-2.0) WITH Classpath-exception-2.0",
+            "(MIT AND GPL-2.0) WITH Classpath-exception-2.0 OR Zlib",
+        ] {
+            let expression = LicenseExpression::from_str(expr)?;
+            assert_eq!(expression.to_string(), *expr);
+            assert_eq!(
+                LicenseExpression::from_str(&expression.to_string())?,
+                expression
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn validate_and_evaluate() -> Result<(), ParseError> {
+        assert!(License::from("GPL-3.0-or-later AND CC-BY-SA-4.0").is_valid_spdx());
+        assert!(License::from("LicenseRef-proprietary:NVIDIA").is_valid_spdx());
+        assert!(License::from("Zed OR Bitstream-Vera WITH Bison-exception-1.24").is_valid_spdx());
+        assert!(!License::from("GPL-2.0 WITH foo-exception").is_valid_spdx());
+        assert!(!License::from("Foo-1.0").is_valid_spdx());
+        assert!(!License::from("MIT AND AND").is_valid_spdx());
+
+        let expr = License::from("MIT OR LicenseRef-proprietary").expression()?;
+        assert!(expr.evaluate(&|l| l == "MIT"));
+        assert!(!expr.evaluate(&|l| l == "GPL-3.0"));
+
+        let expr = License::from("MIT AND LicenseRef-proprietary").expression()?;
+        assert!(!expr.evaluate(&|l| l == "MIT"));
+        Ok(())
+    }
+
+    #[test]
+    fn free_licenses() {
+        assert!(License::from("GPL-2.0+").is_free());
+        assert!(License::from("MPL-2.0 AND CC-BY-SA-4.0").is_free());
+        assert!(License::from("LicenseRef-public-domain").is_free());
+        assert!(License::from("CC-BY-NC-4.0 OR MIT").is_free());
+        assert!(License::from("GPL-2.0-or-later WITH Classpath-exception-2.0").is_free());
+        assert!(License::from("LicenseRef-free=https://example.com/license.html").is_free());
+        assert!(License::from("BSD-3-Clause-Clear").is_free());
+
+        assert!(!License::from("proprietary").is_free());
+        assert!(!License::from("LicenseRef-proprietary").is_free());
+        assert!(!License::from("MIT AND CC-BY-NC-SA-4.0").is_free());
+        assert!(!License::from("SSPL-1.0").is_free());
+        assert!(!License::from("CC-BY-SA-3.0").is_free());
+        assert!(!License::from("Zed").is_free());
+        assert!(!License::from("MIT AND").is_free());
+    }
+
+    #[test]
+    fn canonical_licenses() {
+        for (legacy, canonical) in &[
+            ("GPL-2.0+", "GPL-2.0-or-later"),
+            ("GPL-3.0", "GPL-3.0-only"),
+            ("LGPL-2.1+ AND mit", "LGPL-2.1-or-later AND MIT"),
+            ("GPL-2.0-only+", "GPL-2.0-or-later"),
+            ("AGPL-3.0-or-later", "AGPL-3.0-or-later"),
+            ("MPL-1.1+", "MPL-1.1+"),
+            (
+                "GPL-2.0-with-classpath-exception OR Apache-2.0",
+                "GPL-2.0-only WITH Classpath-exception-2.0 OR Apache-2.0",
+            ),
+            (
+                "GPL-2.0+ WITH classpath-exception-2.0",
+                "GPL-2.0-or-later WITH Classpath-exception-2.0",
+            ),
+            ("LicenseRef-proprietary", "LicenseRef-proprietary"),
+        ] {
+            let license = License::from(*legacy);
+            assert_eq!(&*license.0, *canonical);
+            assert_eq!(license.original(), *legacy);
+            assert_eq!(license, License::from(legacy.to_string()));
+        }
+        let license = License::from("not a license");
+        assert_eq!(&*license.0, "not a license");
+        assert_eq!(license.original(), "not a license");
     }
 }
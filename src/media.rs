@@ -0,0 +1,825 @@
+use super::digest;
+use super::enums::Icon;
+use super::error::ParseError;
+use super::icons::safe_path;
+#[cfg(feature = "image")]
+use super::image_size;
+#[cfg(feature = "net")]
+use super::net::{self, FetchOptions, HttpClient, Method, Request};
+use super::{AppId, Collection, Component};
+#[cfg(feature = "net")]
+use super::{Image, Video};
+use sha2::{Digest as _, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Computes the global component id of a component, the directory of the media
+/// pool its media are stored in.
+///
+/// Reverse-DNS ids are split on their first two dots, `org.gnome.Nautilus` becomes
+/// `org/gnome/Nautilus/<hash>`, the other ones are sorted by their first letters,
+/// `foobar` becomes `f/fo/foobar/<hash>`.
+///
+/// # Arguments
+///
+/// * `id` - The component id.
+/// * `hash` - A hash of the component media, so a new version gets a new directory.
+///
+/// # Example
+///
+/// ```
+/// use appstream::media::global_component_id;
+///
+/// assert_eq!(global_component_id(&"org.gnome.Nautilus".into(), "e7ed8f52"), "org/gnome/Nautilus/e7ed8f52");
+/// assert_eq!(global_component_id(&"foobar.desktop".into(), "e7ed8f52"), "f/fo/foobar.desktop/e7ed8f52");
+/// ```
+pub fn global_component_id(id: &AppId, hash: &str) -> String {
+    let parts = id.0.splitn(3, '.').collect::<Vec<&str>>();
+    if parts.len() == 3 && !id.0.ends_with(".desktop") {
+        format!(
+            "{}/{}/{}/{}",
+            parts[0].to_lowercase(),
+            parts[1],
+            parts[2],
+            hash
+        )
+    } else {
+        let first = id.0.chars().take(1).collect::<String>().to_lowercase();
+        let two = id.0.chars().take(2).collect::<String>().to_lowercase();
+        format!("{}/{}/{}/{}", first, two, id.0, hash)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Exports the media of components to a DEP-11 media pool.
+///
+/// The local screenshots (`file://` URLs) and cached icons of a component are
+/// copied to `<export_dir>/<gcid>/` and the component is rewritten to point to
+/// their public location under `media_base_url`. Only the files inside the
+/// directories set with `media_dir` and `icons_dir` are exported, the paths come
+/// from the metadata.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::media::MediaPool;
+/// use appstream::Collection;
+/// use url::Url;
+///
+/// let mut collection = Collection::from_path("/tmp/compose/appstream.xml".into()).unwrap();
+/// let pool = MediaPool::new(
+///     "/srv/appstream/media/bookworm".into(),
+///     Url::parse("https://appstream.debian.org/media/bookworm/").unwrap(),
+/// )
+/// .media_dir("/tmp/compose/media".into())
+/// .icons_dir("/tmp/compose/icons/main".into());
+/// pool.export_collection(&mut collection).unwrap();
+/// ```
+pub struct MediaPool {
+    export_dir: PathBuf,
+    media_base_url: Url,
+    media_dir: Option<PathBuf>,
+    icons_dir: Option<PathBuf>,
+    symlink: bool,
+}
+
+impl MediaPool {
+    /// Creates a new `MediaPool`.
+    ///
+    /// # Arguments
+    ///
+    /// * `export_dir` - The directory the media are exported to.
+    /// * `media_base_url` - The URL `export_dir` is served from.
+    pub fn new(export_dir: PathBuf, media_base_url: Url) -> Self {
+        Self {
+            export_dir,
+            media_base_url,
+            media_dir: None,
+            icons_dir: None,
+            symlink: false,
+        }
+    }
+
+    /// Sets the directory the local screenshots and videos are stored in. The
+    /// `file://` URLs outside of it are left as they are, and all of them when
+    /// it isn't set.
+    pub fn media_dir(mut self, media_dir: PathBuf) -> Self {
+        self.media_dir = Some(media_dir);
+        self
+    }
+
+    /// Sets the directory the cached icons are stored in, with a `<size>x<size>`
+    /// sub-directory per size. The cached icons are not exported otherwise.
+    pub fn icons_dir(mut self, icons_dir: PathBuf) -> Self {
+        self.icons_dir = Some(icons_dir);
+        self
+    }
+
+    /// Whether to symlink the media instead of copying them, `false` by default.
+    pub fn symlink(mut self, symlink: bool) -> Self {
+        self.symlink = symlink;
+        self
+    }
+
+    /// The local files of a component's media and their path in the pool,
+    /// relative to its global component id.
+    fn media_files(&self, component: &Component) -> Vec<(PathBuf, String)> {
+        let mut files = Vec::new();
+        if let Some(icons_dir) = &self.icons_dir {
+            for icon in component.icons.iter() {
+                if let Icon::Cached {
+                    path,
+                    width: Some(width),
+                    height: Some(height),
+                } = icon
+                {
+                    let path = match path.to_str().map(safe_path) {
+                        Some(Ok(path)) => path,
+                        _ => continue,
+                    };
+                    let size = format!("{}x{}", width, height);
+                    let source = icons_dir.join(&size).join(&path);
+                    if source.is_file() {
+                        files.push((source, format!("icons/{}/{}", size, path.display())));
+                    }
+                }
+            }
+        }
+        for (i, screenshot) in component.screenshots.iter().enumerate() {
+            for image in screenshot.images.iter() {
+                if let Some(source) = self.local_file(&image.url) {
+                    let size = match (image.width, image.height) {
+                        (Some(width), Some(height)) => format!("{}x{}", width, height),
+                        _ => "orig".to_string(),
+                    };
+                    files.push((
+                        source.clone(),
+                        format!(
+                            "screenshots/image-{}_{}.{}",
+                            i + 1,
+                            size,
+                            extension(&source)
+                        ),
+                    ));
+                }
+            }
+            for (j, video) in screenshot.videos.iter().enumerate() {
+                if let Some(source) = self.local_file(&video.url) {
+                    files.push((
+                        source.clone(),
+                        format!(
+                            "screenshots/video-{}-{}.{}",
+                            i + 1,
+                            j + 1,
+                            extension(&source)
+                        ),
+                    ));
+                }
+            }
+        }
+        files
+    }
+
+    /// The file of a `file://` URL, if it is inside `media_dir`.
+    fn local_file(&self, url: &Url) -> Option<PathBuf> {
+        let media_dir = fs::canonicalize(self.media_dir.as_ref()?).ok()?;
+        let source = url.to_file_path().ok()?;
+        let canonical = fs::canonicalize(&source).ok()?;
+        canonical.starts_with(media_dir).then_some(source)
+    }
+
+    /// Hashes the id and the media of a component.
+    fn media_hash(
+        component: &Component,
+        files: &[(PathBuf, String)],
+    ) -> Result<String, ParseError> {
+        let mut hasher = Sha256::default();
+        hasher.update(component.id.0.as_bytes());
+        for (source, path) in files.iter() {
+            hasher.update(path.as_bytes());
+            hasher.update(&fs::read(source)?);
+        }
+        Ok(digest::to_hex(&hasher.finalize()[..16]))
+    }
+
+    /// Exports the media of a component and rewrites its URLs.
+    ///
+    /// The local screenshots get their public URL and a `Icon::Remote` is added
+    /// for every exported cached icon. Returns the global component id, `None`
+    /// if there were no media to export.
+    pub fn export_component(
+        &self,
+        component: &mut Component,
+    ) -> Result<Option<String>, ParseError> {
+        let files = self.media_files(component);
+        if files.is_empty() {
+            return Ok(None);
+        }
+        let gcid = global_component_id(&component.id, &Self::media_hash(component, &files)?);
+
+        let mut urls = Vec::new();
+        for (source, path) in files.iter() {
+            let relative = format!("{}/{}", gcid, path);
+            let target = self.export_dir.join(&relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            if target.symlink_metadata().is_ok() {
+                fs::remove_file(&target)?;
+            }
+            self.export_file(source, &target)?;
+            urls.push((source, self.media_base_url.join(&relative)?));
+        }
+
+        for (source, url) in urls.into_iter() {
+            for screenshot in component.screenshots_mut().iter_mut() {
+                let images = screenshot.images.iter_mut().map(|i| &mut i.url);
+                let videos = screenshot.videos.iter_mut().map(|v| &mut v.url);
+                for media_url in images.chain(videos) {
+                    if media_url.to_file_path().ok().as_ref() == Some(source) {
+                        *media_url = url.clone();
+                    }
+                }
+            }
+            let icon = component.icons.iter().find_map(|i| match i {
+                Icon::Cached {
+                    path,
+                    width,
+                    height,
+                } if source.ends_with(path) => Some(Icon::Remote {
+                    url: url.clone(),
+                    width: *width,
+                    height: *height,
+                }),
+                _ => None,
+            });
+            if let Some(icon) = icon.filter(|i| !component.icons.contains(i)) {
+                component.icons.push(icon);
+            }
+        }
+        Ok(Some(gcid))
+    }
+
+    /// Exports the media of all the components of a collection and sets its
+    /// `media_base_url`.
+    pub fn export_collection(&self, collection: &mut Collection) -> Result<(), ParseError> {
+        for component in collection.components.iter_mut() {
+            self.export_component(component)?;
+        }
+        collection.media_base_url = Some(self.media_base_url.to_string());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn export_file(&self, source: &Path, target: &Path) -> Result<(), ParseError> {
+        if self.symlink {
+            std::os::unix::fs::symlink(fs::canonicalize(source)?, target)?;
+        } else {
+            fs::copy(source, target)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn export_file(&self, source: &Path, target: &Path) -> Result<(), ParseError> {
+        fs::copy(source, target)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// What a media URL points to.
+pub enum MediaKind {
+    /// A remote icon.
+    Icon,
+    /// A screenshot image.
+    Image,
+    /// A screenshot video.
+    Video,
+    /// A release artifact.
+    Artifact,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A media URL of a collection, as listed by `Collection::media_urls`.
+pub struct MediaUrl {
+    /// The URL of the media.
+    pub url: Url,
+    /// What the URL points to, the first time it is referenced.
+    pub kind: MediaKind,
+    /// The ids of the components referencing the URL.
+    pub components: Vec<AppId>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Where the icon picked by `Component::icon_for` is loaded from.
+pub enum IconSource {
+    /// A name to look up in the icon theme, like with
+    /// `icon_theme::IconThemeResolver::lookup`.
+    Stock(String),
+    /// A path relative to the icon cache directory, laid out like
+    /// `<origin>/<width>x<height>/<name>`, see `icons::IconStore`.
+    Cached(PathBuf),
+    /// The path of an icon file.
+    Path(PathBuf),
+    /// The URL a remote icon is served from, after the rules of the
+    /// `MediaResolver` are applied.
+    Url(Url),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// Rewrites media URLs to another location, like a mirror or a local copy of the
+/// media, without modifying the parsed data.
+///
+/// Each rule maps a URL prefix, usually the `media_base_url` of a collection, to
+/// the base URL the media are served from instead. The URLs matching no rule are
+/// returned unchanged. The rewritten URLs are computed on access, through
+/// `Image::resolved_url`, `Video::resolved_url` and `Icon::resolved_url`.
+///
+/// # Example
+///
+/// ```
+/// use appstream::media::MediaResolver;
+/// use url::Url;
+///
+/// let resolver = MediaResolver::new().rewrite(
+///     &Url::parse("https://dl.flathub.org/media/").unwrap(),
+///     Url::parse("https://mirror.example.org/flathub/media/").unwrap(),
+/// );
+/// assert_eq!(
+///     resolver.resolve(&Url::parse("https://dl.flathub.org/media/org/gnome/Maps/icon.png").unwrap()),
+///     Url::parse("https://mirror.example.org/flathub/media/org/gnome/Maps/icon.png").unwrap(),
+/// );
+/// ```
+pub struct MediaResolver {
+    rules: Vec<(String, String)>,
+}
+
+impl MediaResolver {
+    /// Creates a resolver without any rule.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a resolver serving the media of a collection from `target`
+    /// instead of its `media_base_url`. It has no rule if the collection doesn't
+    /// have a `media_base_url`.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The collection whose media are moved.
+    /// * `target` - The base URL of the media.
+    pub fn for_collection(collection: &Collection, target: Url) -> Self {
+        match collection
+            .media_base_url
+            .as_deref()
+            .and_then(|base| Url::parse(base).ok())
+        {
+            Some(base) => Self::new().rewrite(&base, target),
+            None => Self::new(),
+        }
+    }
+
+    /// Adds a rule serving the media under `prefix` from `target`. The rules are
+    /// tried in the order they are added.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The base URL the media are referenced from.
+    /// * `target` - The base URL the media are served from.
+    pub fn rewrite(mut self, prefix: &Url, target: Url) -> Self {
+        self.rules
+            .push((with_trailing_slash(prefix), with_trailing_slash(&target)));
+        self
+    }
+
+    /// Adds a rule serving the media under `prefix` from a local directory, for
+    /// deployments without network access.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The base URL the media are referenced from.
+    /// * `root` - The absolute path of the directory holding the media.
+    pub fn local_root(self, prefix: &Url, root: &Path) -> Result<Self, ParseError> {
+        let target = Url::from_directory_path(root)
+            .map_err(|_| ParseError::invalid_value(&root.display().to_string(), "root", "media"))?;
+        Ok(self.rewrite(prefix, target))
+    }
+
+    /// The location `url` is served from, following the first matching rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The media URL, as found in the metadata.
+    pub fn resolve(&self, url: &Url) -> Url {
+        self.rules
+            .iter()
+            .find_map(|(prefix, target)| {
+                let path = url.as_str().strip_prefix(prefix.as_str())?;
+                Url::parse(&format!("{}{}", target, path)).ok()
+            })
+            .unwrap_or_else(|| url.clone())
+    }
+}
+
+fn with_trailing_slash(url: &Url) -> String {
+    let url = url.as_str();
+    if url.ends_with('/') {
+        url.to_string()
+    } else {
+        format!("{}/", url)
+    }
+}
+
+#[cfg(feature = "net")]
+/// Downloads media on demand to a local directory, for the applications
+/// displaying icons and screenshots.
+///
+/// The files are named after the SHA-256 of their URL, `<root>/<ab>/<sha256>.<ext>`,
+/// so a media is only downloaded once whatever the component referencing it.
+/// Files are written under a temporary name first, an interrupted download
+/// never leaves a truncated file behind. With the `image` feature, the size of
+/// the downloaded images is checked against the one declared in the metadata.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::media::MediaCache;
+/// use appstream::net::{HttpClient, Request, Response};
+/// use appstream::Collection;
+/// # struct MyClient;
+/// # impl HttpClient for MyClient {
+/// #     fn send(&self, _: &Request) -> std::io::Result<Response> { unimplemented!() }
+/// # }
+///
+/// let cache = MediaCache::new(MyClient, "/var/cache/my-store/media".into()).max_file_size(10_000_000);
+/// let collection = Collection::from_path("/tmp/appstream.xml".into()).unwrap();
+/// for icon in collection.components[0].icons.iter() {
+///     if let Some(path) = cache.icon(icon).unwrap() {
+///         println!("{}", path.display());
+///     }
+/// }
+/// ```
+pub struct MediaCache<C: HttpClient> {
+    client: C,
+    root: PathBuf,
+    options: FetchOptions,
+    resolver: MediaResolver,
+    max_file_size: Option<u64>,
+}
+
+#[cfg(feature = "net")]
+impl<C: HttpClient> MediaCache<C> {
+    /// Creates a new cache, the directory is created when media are downloaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to send the requests through.
+    /// * `root` - The directory of the cache.
+    pub fn new(client: C, root: PathBuf) -> Self {
+        Self {
+            client,
+            root,
+            options: FetchOptions::default(),
+            resolver: MediaResolver::new(),
+            max_file_size: None,
+        }
+    }
+
+    /// Sets the redirect and timeout options of the downloads.
+    pub fn options(mut self, options: FetchOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Downloads the media from where `resolver` tells instead of their URL. The
+    /// files are still named after the original URL.
+    pub fn resolver(mut self, resolver: MediaResolver) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    /// Limits the size of the downloaded files.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum size, in bytes.
+    pub fn max_file_size(mut self, max: u64) -> Self {
+        self.max_file_size = Some(max);
+        self
+    }
+
+    /// The directory of the cache.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The path the media at `url` is stored at, whether it was downloaded or not.
+    pub fn path(&self, url: &Url) -> PathBuf {
+        let mut hasher = Sha256::default();
+        hasher.update(url.as_str().as_bytes());
+        let hash = digest::to_hex(&hasher.finalize());
+        let name = match Path::new(url.path()).extension() {
+            Some(extension) => format!("{}.{}", hash, extension.to_string_lossy()),
+            None => hash.clone(),
+        };
+        self.root.join(&hash[..2]).join(name)
+    }
+
+    /// Downloads the media at `url` unless it's already cached, returns its path.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the media.
+    /// * `size` - The expected `(width, height)` of the image, if any.
+    pub fn fetch(&self, url: &Url, size: Option<(u32, u32)>) -> Result<PathBuf, ParseError> {
+        let path = self.path(url);
+        if path.is_file() {
+            return Ok(path);
+        }
+        // The body is streamed up to the limit instead of being loaded whole.
+        let source = self.resolver.resolve(url);
+        let request = Request::new(Method::Get, source.clone());
+        let response = net::send_following(&self.client, request, &self.options)?;
+        let max = self.max_file_size.unwrap_or(u64::MAX);
+        let data = net::read_body(&source, response, max)?;
+        if data.len() as u64 > max {
+            return Err(ParseError::limit_exceeded("bytes in a media file", max));
+        }
+        #[cfg(feature = "image")]
+        {
+            if let (Some(expected), Some(actual)) = (size, image_size::dimensions(&data)) {
+                if expected != actual {
+                    return Err(ParseError::invalid_value(
+                        &format!("{}x{}", actual.0, actual.1),
+                        "size",
+                        url.as_str(),
+                    ));
+                }
+            }
+        }
+        #[cfg(not(feature = "image"))]
+        let _ = size;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let partial = path.with_extension("part");
+        fs::write(&partial, &data)?;
+        fs::rename(&partial, &path)?;
+        Ok(path)
+    }
+
+    /// Downloads a remote icon, `None` for the other icon types.
+    pub fn icon(&self, icon: &Icon) -> Result<Option<PathBuf>, ParseError> {
+        match icon {
+            Icon::Remote { url, width, height } => {
+                let size = width.zip(*height);
+                self.fetch(url, size).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Downloads a screenshot image.
+    pub fn image(&self, image: &Image) -> Result<PathBuf, ParseError> {
+        self.fetch(&image.url, image.width.zip(image.height))
+    }
+
+    /// Downloads a screenshot video.
+    pub fn video(&self, video: &Video) -> Result<PathBuf, ParseError> {
+        self.fetch(&video.url, None)
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_else(|| "png".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::{CollectionBuilder, ComponentBuilder, ImageBuilder, ScreenshotBuilder};
+    use crate::TranslatableString;
+    use std::error::Error;
+
+    #[test]
+    fn export_media() -> Result<(), Box<dyn Error>> {
+        let icons = fs::canonicalize("./tests/compose/usr/share/icons/hicolor")?;
+        let screenshot =
+            Url::from_file_path(icons.join("256x256/apps/org.gnome.design.Contrast.png"))
+                .map_err(|_| "invalid path")?;
+        let mut c = ComponentBuilder::default()
+            .id("org.gnome.design.Contrast".into())
+            .name(TranslatableString::with_default("Contrast"))
+            .icon(Icon::Cached {
+                path: "apps/org.gnome.design.Contrast.png".into(),
+                width: Some(64),
+                height: Some(64),
+            })
+            .screenshot(
+                ScreenshotBuilder::default()
+                    .image(ImageBuilder::new(screenshot).build())
+                    .build(),
+            )
+            .build();
+
+        let dir = tempfile::tempdir()?;
+        let export_dir = dir.path().join("media-pool");
+        let pool = MediaPool::new(
+            export_dir.clone(),
+            Url::parse("https://example.org/media/")?,
+        )
+        .media_dir(icons.clone())
+        .icons_dir(icons);
+        let gcid = pool.export_component(&mut c)?.ok_or("no media")?;
+        assert!(gcid.starts_with("org/gnome/design.Contrast/"));
+        assert_eq!(gcid.len(), "org/gnome/design.Contrast/".len() + 32);
+
+        let base = format!("https://example.org/media/{}", gcid);
+        assert_eq!(
+            c.screenshots[0].images[0].url.as_str(),
+            format!("{}/screenshots/image-1_orig.png", base)
+        );
+        assert_eq!(
+            c.icons[1],
+            Icon::Remote {
+                url: Url::parse(&format!(
+                    "{}/icons/64x64/apps/org.gnome.design.Contrast.png",
+                    base
+                ))?,
+                width: Some(64),
+                height: Some(64),
+            }
+        );
+        assert!(export_dir
+            .join(&gcid)
+            .join("icons/64x64/apps/org.gnome.design.Contrast.png")
+            .is_file());
+        assert!(export_dir
+            .join(&gcid)
+            .join("screenshots/image-1_orig.png")
+            .is_file());
+
+        Ok(())
+    }
+
+    #[test]
+    fn export_outside_media() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let secret = dir.path().join("secret.png");
+        fs::write(&secret, "secret")?;
+        fs::create_dir_all(dir.path().join("media"))?;
+        fs::create_dir_all(dir.path().join("icons/64x64"))?;
+        let screenshot = Url::from_file_path(&secret).map_err(|_| "invalid path")?;
+        let mut c = ComponentBuilder::default()
+            .id("org.example.App".into())
+            .name(TranslatableString::with_default("App"))
+            .icon(Icon::Cached {
+                path: "../../secret.png".into(),
+                width: Some(64),
+                height: Some(64),
+            })
+            .screenshot(
+                ScreenshotBuilder::default()
+                    .image(ImageBuilder::new(screenshot.clone()).build())
+                    .build(),
+            )
+            .build();
+
+        let export_dir = dir.path().join("export");
+        let base_url = Url::parse("https://example.org/media/")?;
+        let pool = MediaPool::new(export_dir.clone(), base_url.clone())
+            .icons_dir(dir.path().join("icons"));
+        assert_eq!(pool.export_component(&mut c)?, None);
+        let pool = pool.media_dir(dir.path().join("media"));
+        assert_eq!(pool.export_component(&mut c)?, None);
+        assert_eq!(c.screenshots[0].images[0].url, screenshot);
+        assert!(!export_dir.exists());
+
+        let pool = MediaPool::new(export_dir, base_url).media_dir(dir.path().into());
+        assert!(pool.export_component(&mut c)?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_media() -> Result<(), Box<dyn Error>> {
+        let collection =
+            Collection::from_yaml_path("./tests/collections/spec_example.yaml".into())?;
+        let resolver = MediaResolver::for_collection(
+            &collection,
+            Url::parse("https://mirror.example.org/media")?,
+        )
+        .local_root(
+            &Url::parse("https://cdn.example.org/")?,
+            Path::new("/srv/media"),
+        )?;
+
+        let icon = collection.components[0]
+            .icons
+            .iter()
+            .find_map(|i| i.resolved_url(&resolver))
+            .ok_or("no remote icon")?;
+        assert!(icon
+            .as_str()
+            .starts_with("https://mirror.example.org/media/"));
+        // The parsed data is left untouched
+        assert!(collection.components[0].icons.iter().all(|i| match i {
+            Icon::Remote { url, .. } => url.as_str().starts_with("https://metadata.tanglu.org/"),
+            _ => true,
+        }));
+
+        assert_eq!(
+            resolver.resolve(&Url::parse("https://cdn.example.org/a/b.png")?),
+            Url::parse("file:///srv/media/a/b.png")?
+        );
+        let other = Url::parse("https://metadata.tanglu.org/appstream/mediafoo/b.png")?;
+        assert_eq!(resolver.resolve(&other), other);
+        let empty = CollectionBuilder::new("0.14").build();
+        let resolver = MediaResolver::for_collection(&empty, Url::parse("https://example.org")?);
+        assert_eq!(resolver, MediaResolver::new());
+        Ok(())
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn media_cache() -> Result<(), Box<dyn Error>> {
+        use crate::net::Response;
+        use std::io;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct FakeServer(AtomicUsize);
+
+        impl HttpClient for FakeServer {
+            fn send(&self, request: &Request) -> io::Result<Response> {
+                if request.url.host_str() != Some("mirror.example.org") {
+                    return Ok(Response::new(404));
+                }
+                self.0.fetch_add(1, Ordering::SeqCst);
+                let mut response = Response::new(200);
+                if request.url.path().ends_with("endless.png") {
+                    response.body = Box::new(io::repeat(0));
+                    return Ok(response);
+                }
+                response.body = Box::new(fs::File::open(
+                    "./tests/compose/usr/share/icons/hicolor/256x256/apps/org.gnome.design.Contrast.png",
+                )?);
+                Ok(response)
+            }
+        }
+
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().join("media-cache");
+        let resolver = MediaResolver::new().rewrite(
+            &Url::parse("https://example.org/media/")?,
+            Url::parse("https://mirror.example.org/")?,
+        );
+        let cache = MediaCache::new(FakeServer::default(), root.clone()).resolver(resolver);
+
+        let url = Url::parse("https://example.org/media/contrast.png")?;
+        let icon = Icon::Remote {
+            url: url.clone(),
+            width: Some(256),
+            height: Some(256),
+        };
+        let path = cache.icon(&icon)?.ok_or("not a remote icon")?;
+        assert_eq!(path, cache.path(&url));
+        assert!(path.starts_with(&root) && path.is_file());
+        assert_eq!(cache.fetch(&url, None)?, path);
+        assert_eq!(cache.client.0.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.icon(&Icon::Stock("contrast".into()))?, None);
+        assert!(cache
+            .fetch(&Url::parse("https://example.org/other/contrast.png")?, None)
+            .is_err());
+
+        let small = Url::parse("https://example.org/media/small.png")?;
+        let cache = cache.max_file_size(10);
+        assert!(matches!(
+            cache.fetch(&small, None),
+            Err(ParseError::LimitExceeded(..))
+        ));
+        assert!(!cache.path(&small).exists());
+        let endless = Url::parse("https://example.org/media/endless.png")?;
+        assert!(matches!(
+            cache.fetch(&endless, None),
+            Err(ParseError::LimitExceeded(..))
+        ));
+
+        #[cfg(feature = "image")]
+        {
+            let cache = MediaCache::new(FakeServer::default(), root.clone()).resolver(
+                MediaResolver::new().rewrite(
+                    &Url::parse("https://example.org/")?,
+                    Url::parse("https://mirror.example.org/")?,
+                ),
+            );
+            assert!(cache.fetch(&small, Some((64, 64))).is_err());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use super::enums::glob_matches;
+use super::error::ParseError;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// The directory of the devices in sysfs.
+const SYS_DEVICES: &str = "/sys/devices";
+
+/// Whether a modalias matches the glob of a `<modalias>` provide.
+///
+/// # Arguments
+///
+/// * `pattern` - The glob of the provide, like `usb:v1130p0202d*`.
+/// * `modalias` - The modalias of a device.
+///
+/// # Example
+///
+/// ```
+/// use appstream::modalias;
+///
+/// assert!(modalias::matches("pci:v000010DEd*sv*sd*bc03sc*i*", "pci:v000010DEd00001C82sv00001043sd000085BEbc03sc00i00"));
+/// ```
+pub fn matches(pattern: &str, modalias: &str) -> bool {
+    glob_matches(modalias, pattern)
+}
+
+/// The modaliases of the devices of the running system, read from
+/// `/sys/devices`.
+pub fn system_modaliases() -> Result<Vec<String>, ParseError> {
+    read_modaliases(Path::new(SYS_DEVICES))
+}
+
+/// The modaliases of a sysfs devices tree, sorted and without duplicates.
+///
+/// The `modalias` files are looked for recursively, without following the
+/// symbolic links. The ones that can't be read are skipped.
+///
+/// # Arguments
+///
+/// * `root` - The root of the tree, usually `/sys/devices`.
+pub fn read_modaliases(root: &Path) -> Result<Vec<String>, ParseError> {
+    let mut modaliases = BTreeSet::new();
+    let mut directories = vec![root.to_path_buf()];
+    let mut first = true;
+    while let Some(directory) = directories.pop() {
+        let entries = match fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(e) if first => return Err(e.into()),
+            Err(_) => continue,
+        };
+        first = false;
+        for entry in entries.flatten() {
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(_) => continue,
+            };
+            if file_type.is_dir() {
+                directories.push(entry.path());
+            } else if file_type.is_file() && entry.file_name() == "modalias" {
+                if let Ok(modalias) = fs::read_to_string(entry.path()) {
+                    let modalias = modalias.trim();
+                    if !modalias.is_empty() {
+                        modaliases.insert(modalias.to_string());
+                    }
+                }
+            }
+        }
+    }
+    Ok(modaliases.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn sysfs_tree() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().join("sysfs");
+        let usb = root.join("pci0000:00/0000:00:14.0/usb1/1-1");
+        fs::create_dir_all(&usb)?;
+        fs::write(
+            root.join("pci0000:00/0000:00:14.0/modalias"),
+            "pci:v00008086d0000A36Dsv00001028sd0000085Abc0Csc03i30\n",
+        )?;
+        fs::write(
+            usb.join("modalias"),
+            "usb:v1130p0202d0100dc00dsc00dp00ic03isc01ip02in00\n",
+        )?;
+        fs::write(root.join("pci0000:00/modalias"), "")?;
+
+        let modaliases = read_modaliases(&root)?;
+        assert_eq!(modaliases.len(), 2);
+        assert!(modaliases[1].starts_with("usb:"));
+        assert!(matches("usb:v1130p0202d*", &modaliases[1]));
+        assert!(!matches("usb:v1130p0203d*", &modaliases[1]));
+        assert!(read_modaliases(&root.join("missing")).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use super::translatable_string::DEFAULT_LOCALE;
+use super::{Collection, Component};
+use serde_json::{Map, Value};
+use std::io::{self, Write};
+
+/// The translatable fields of a component.
+const TRANSLATABLE_FIELDS: [&str; 5] = [
+    "name",
+    "summary",
+    "description",
+    "developer_name",
+    "keywords",
+];
+
+/// Writes the components of a collection as JSON objects, one per line.
+///
+/// The components without an origin get the one of the collection. With a
+/// `locale`, the translatable fields are flattened to their text in that locale.
+pub(crate) fn write<W: Write>(
+    collection: &Collection,
+    mut writer: W,
+    locale: Option<&str>,
+) -> io::Result<()> {
+    for component in collection.components.iter() {
+        let mut value = to_value(component, collection.origin.as_deref())?;
+        if let Some(locale) = locale {
+            flatten(&mut value, locale);
+        }
+        serde_json::to_writer(&mut writer, &value)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+fn to_value(component: &Component, origin: Option<&str>) -> io::Result<Map<String, Value>> {
+    let mut value = match serde_json::to_value(component)? {
+        Value::Object(map) => map,
+        _ => unreachable!("components serialize to JSON objects"),
+    };
+    if let Some(origin) = origin {
+        value
+            .entry("origin")
+            .or_insert_with(|| Value::String(origin.to_string()));
+    }
+    Ok(value)
+}
+
+/// Replaces a locale map by its value for `locale`, falling back to the default
+/// locale.
+fn localize(value: &mut Value, locale: &str) {
+    if let Value::Object(map) = value {
+        let text = map
+            .remove(locale)
+            .or_else(|| map.remove(DEFAULT_LOCALE))
+            .unwrap_or(Value::Null);
+        *value = text;
+    }
+}
+
+fn flatten(component: &mut Map<String, Value>, locale: &str) {
+    for field in TRANSLATABLE_FIELDS.iter() {
+        if let Some(value) = component.get_mut(*field) {
+            localize(value, locale);
+        }
+    }
+    if let Some(Value::Array(categories)) = component.get_mut("categories") {
+        for category in categories.iter_mut() {
+            // Unknown categories serialize as `{"Unknown": "name"}`
+            if let Value::Object(map) = category {
+                if let Some(name) = map.remove("Unknown") {
+                    *category = name;
+                }
+            }
+        }
+    }
+    let children = [("releases", "description"), ("screenshots", "caption")];
+    for (list, field) in children.iter() {
+        if let Some(Value::Array(items)) = component.get_mut(*list) {
+            for item in items.iter_mut() {
+                if let Some(value) = item.get_mut(*field) {
+                    localize(value, locale);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,420 @@
+use super::error::ParseError;
+use super::Collection;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use url::Url;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The HTTP method of a `Request`.
+pub enum Method {
+    /// Only fetch the headers.
+    Head,
+    /// Fetch the whole resource.
+    Get,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// An HTTP request sent through an `HttpClient`.
+pub struct Request {
+    /// The request method.
+    pub method: Method,
+    /// The requested URL.
+    pub url: Url,
+    /// Additional headers, as `(name, value)` pairs.
+    pub headers: Vec<(String, String)>,
+    /// How long to wait for the response before giving up, if any.
+    pub timeout: Option<Duration>,
+}
+
+impl Request {
+    /// Creates a new request without any additional header.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The request method.
+    /// * `url` - The requested URL.
+    pub fn new(method: Method, url: Url) -> Self {
+        Self {
+            method,
+            url,
+            headers: vec![],
+            timeout: None,
+        }
+    }
+
+    /// Adds a header to the request.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sets how long to wait for the response.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// The response to a `Request`.
+pub struct Response {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The response headers, as `(name, value)` pairs.
+    pub headers: Vec<(String, String)>,
+    /// The response body, empty for `Method::Head` requests.
+    pub body: Box<dyn Read + Send>,
+}
+
+impl Response {
+    /// Creates a new response with an empty body.
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: Box::new(io::empty()),
+        }
+    }
+
+    /// The value of the header `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Whether the status code is in the `2xx` or `3xx` range.
+    pub fn is_success(&self) -> bool {
+        (200..400).contains(&self.status)
+    }
+}
+
+impl fmt::Debug for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish()
+    }
+}
+
+/// The transport used by the network helpers of the crate.
+///
+/// The crate doesn't ship an HTTP stack on its own, implement this trait on top
+/// of the client your application already uses. Implementations can either
+/// follow redirects on their own or return the `3xx` responses, which `fetch`
+/// follows. They are expected to honor the timeout of the request.
+///
+/// # Example
+///
+/// ```
+/// use appstream::net::{HttpClient, Request, Response};
+/// use std::io;
+///
+/// struct Offline;
+///
+/// impl HttpClient for Offline {
+///     fn send(&self, _request: &Request) -> io::Result<Response> {
+///         Err(io::Error::new(io::ErrorKind::NotConnected, "offline"))
+///     }
+/// }
+/// ```
+pub trait HttpClient {
+    /// Sends the request and returns the response.
+    ///
+    /// Transport failures (DNS, connection, TLS...) are reported as errors, HTTP
+    /// failures as a `Response` with the matching status code.
+    fn send(&self, request: &Request) -> io::Result<Response>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Options changing how resources are downloaded.
+///
+/// # Example
+///
+/// ```
+/// use appstream::net::FetchOptions;
+/// use std::time::Duration;
+///
+/// let options = FetchOptions::default()
+///     .max_redirects(3)
+///     .timeout(Duration::from_secs(30));
+/// ```
+pub struct FetchOptions {
+    max_redirects: usize,
+    timeout: Option<Duration>,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            max_redirects: 10,
+            timeout: None,
+        }
+    }
+}
+
+impl FetchOptions {
+    /// Limits the number of redirects followed, `0` makes redirects fail.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum number of redirects.
+    pub fn max_redirects(mut self, max: usize) -> Self {
+        self.max_redirects = max;
+        self
+    }
+
+    /// Sets how long to wait for each response, by default it's up to the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The timeout of each request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// Sends `request`, following the redirects returned by the client, and returns
+/// the final response whatever its status.
+pub(crate) fn send_following<C: HttpClient + ?Sized>(
+    client: &C,
+    mut request: Request,
+    options: &FetchOptions,
+) -> io::Result<Response> {
+    if let Some(timeout) = options.timeout {
+        request = request.timeout(timeout);
+    }
+    let mut redirects = 0;
+    loop {
+        let response = client.send(&request)?;
+        let location = match response.header("location") {
+            Some(location) if (300..400).contains(&response.status) && response.status != 304 => {
+                location
+            }
+            _ => return Ok(response),
+        };
+        if redirects == options.max_redirects {
+            return Err(io::Error::other(format!(
+                "{} redirected more than {} times",
+                request.url, options.max_redirects
+            )));
+        }
+        request.url = request
+            .url
+            .join(location)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        redirects += 1;
+    }
+}
+
+/// Reads the body of a `2xx` response, other statuses are reported as errors.
+/// Reading stops one byte after `limit`, for the callers to tell it was exceeded.
+pub(crate) fn read_body(url: &Url, response: Response, limit: u64) -> io::Result<Vec<u8>> {
+    if !(200..300).contains(&response.status) {
+        return Err(io::Error::other(format!(
+            "{} returned HTTP status {}",
+            url, response.status
+        )));
+    }
+    let mut body = Vec::new();
+    response
+        .body
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut body)?;
+    Ok(body)
+}
+
+/// Downloads the resource at `url`, following the redirects returned by the
+/// client. HTTP failures are reported as errors.
+///
+/// # Arguments
+///
+/// * `client` - The client to send the requests through.
+/// * `url` - The URL of the resource.
+/// * `options` - The redirect and timeout options.
+pub fn fetch<C: HttpClient + ?Sized>(
+    client: &C,
+    url: &Url,
+    options: &FetchOptions,
+) -> io::Result<Vec<u8>> {
+    let response = send_following(client, Request::new(Method::Get, url.clone()), options)?;
+    read_body(url, response, u64::MAX)
+}
+
+/// Downloads the resource at `url` with the default `FetchOptions`, HTTP failures
+/// are reported as errors.
+///
+/// # Arguments
+///
+/// * `client` - The client to send the request through.
+/// * `url` - The URL of the resource.
+pub fn get<C: HttpClient + ?Sized>(client: &C, url: &Url) -> io::Result<Vec<u8>> {
+    fetch(client, url, &FetchOptions::default())
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    collection: Arc<Collection>,
+}
+
+#[derive(Debug, Default)]
+/// Downloaded catalogs, along with their `ETag` and `Last-Modified` validators.
+///
+/// Catalogs are downloaded again only when the server doesn't answer the
+/// conditional request with `304 Not Modified`, otherwise the collection parsed
+/// the previous time is shared. Servers sending neither validator are always
+/// downloaded from.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::net::{FetchOptions, HttpCache, HttpClient, Request, Response};
+/// use url::Url;
+/// # struct MyClient;
+/// # impl HttpClient for MyClient {
+/// #     fn send(&self, _: &Request) -> std::io::Result<Response> { unimplemented!() }
+/// # }
+///
+/// let cache = HttpCache::new();
+/// let url = Url::parse("https://dl.flathub.org/repo/appstream/x86_64/appstream.xml.gz").unwrap();
+/// let collection = cache.collection(&MyClient, &url, &FetchOptions::default()).unwrap();
+/// // Only checks whether the catalog changed in the meantime
+/// let collection = cache.collection(&MyClient, &url, &FetchOptions::default()).unwrap();
+/// ```
+pub struct HttpCache {
+    entries: Mutex<HashMap<Url, Arc<CacheEntry>>>,
+}
+
+impl HttpCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The collection at `url`, like `Collection::from_url` but reusing the cached
+    /// one when the server reports it as unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to send the requests through.
+    /// * `url` - The URL of the catalog.
+    /// * `options` - The redirect and timeout options.
+    pub fn collection<C: HttpClient + ?Sized>(
+        &self,
+        client: &C,
+        url: &Url,
+        options: &FetchOptions,
+    ) -> Result<Arc<Collection>, ParseError> {
+        let cached = self.entries.lock().unwrap().get(url).cloned();
+        let mut request = Request::new(Method::Get, url.clone());
+        if let Some(entry) = &cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header("If-None-Match", etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header("If-Modified-Since", last_modified);
+            }
+        }
+
+        let response = send_following(client, request, options)?;
+        if let (304, Some(entry)) = (response.status, &cached) {
+            return Ok(entry.collection.clone());
+        }
+        let etag = response.header("etag").map(String::from);
+        let last_modified = response.header("last-modified").map(String::from);
+        let collection = Arc::new(Collection::from_download(
+            url,
+            &read_body(url, response, u64::MAX)?,
+        )?);
+
+        let mut entries = self.entries.lock().unwrap();
+        if etag.is_some() || last_modified.is_some() {
+            let entry = CacheEntry {
+                etag,
+                last_modified,
+                collection: collection.clone(),
+            };
+            entries.insert(url.clone(), Arc::new(entry));
+        } else {
+            entries.remove(url);
+        }
+        Ok(collection)
+    }
+
+    /// Forgets the cached collection at `url`, returns whether there was one.
+    pub fn invalidate(&self, url: &Url) -> bool {
+        self.entries.lock().unwrap().remove(url).is_some()
+    }
+
+    /// Forgets all the cached collections.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct FakeServer {
+        etag: Mutex<String>,
+        downloads: AtomicUsize,
+    }
+
+    impl HttpClient for FakeServer {
+        fn send(&self, request: &Request) -> io::Result<Response> {
+            let etag = self.etag.lock().unwrap().clone();
+            let matches = request
+                .headers
+                .iter()
+                .any(|(n, v)| n == "If-None-Match" && *v == etag);
+            if matches {
+                return Ok(Response::new(304));
+            }
+            self.downloads.fetch_add(1, Ordering::SeqCst);
+            let mut response = Response::new(200);
+            response.headers.push(("ETag".into(), etag));
+            response.body = Box::new(io::Cursor::new(fs::read(
+                "./tests/collections/gnome-apps.xml",
+            )?));
+            Ok(response)
+        }
+    }
+
+    #[test]
+    fn conditional_fetch() -> Result<(), Box<dyn Error>> {
+        let server = FakeServer::default();
+        *server.etag.lock().unwrap() = "\"v1\"".into();
+        let url = Url::parse("https://example.org/appstream.xml")?;
+        let options = FetchOptions::default();
+        let cache = HttpCache::new();
+
+        let c1 = cache.collection(&server, &url, &options)?;
+        let c2 = cache.collection(&server, &url, &options)?;
+        assert!(Arc::ptr_eq(&c1, &c2));
+        assert_eq!(server.downloads.load(Ordering::SeqCst), 1);
+
+        *server.etag.lock().unwrap() = "\"v2\"".into();
+        let c3 = cache.collection(&server, &url, &options)?;
+        assert!(!Arc::ptr_eq(&c1, &c3));
+        assert_eq!(c1, c3);
+        assert_eq!(server.downloads.load(Ordering::SeqCst), 2);
+
+        assert!(cache.invalidate(&url));
+        assert!(!cache.invalidate(&url));
+        cache.collection(&server, &url, &options)?;
+        assert_eq!(server.downloads.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+}
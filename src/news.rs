@@ -0,0 +1,825 @@
+use super::builders::ReleaseBuilder;
+use super::enums::ReleaseKind;
+use super::error::ParseError;
+use super::{Component, MarkupTranslatableString, Release, Timestamp};
+use xmltree::{Element, XMLNode};
+use yaml_rust::yaml::Hash;
+use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
+
+const FEATURES_INTRO: &str = "This release adds the following features:";
+const BUGFIXES_INTRO: &str = "This release fixes the following bugs:";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The formats a changelog can be written in.
+pub enum NewsFormat {
+    /// A GNOME-style `NEWS` file, with `Version x.y` headers underlined by `~`, `=`
+    /// or `-`, an optional `Released: yyyy-mm-dd` line and `Notes:`, `Features:`,
+    /// `Bugfixes:`... sections.
+    Text,
+    /// A [keep a changelog](https://keepachangelog.com/) Markdown file, with
+    /// `## [x.y] - yyyy-mm-dd` headers and `### Added`, `### Fixed`... sections.
+    Markdown,
+    /// The YAML news format of `appstreamcli`, one document per release with
+    /// `Version`, `Date`, `Type` and `Description` keys.
+    Yaml,
+}
+
+impl NewsFormat {
+    /// Guesses the format of a changelog from its content.
+    pub fn detect(text: &str) -> Self {
+        if text.trim_start().starts_with("---") {
+            NewsFormat::Yaml
+        } else if text.lines().any(|l| l.starts_with("## ")) {
+            NewsFormat::Markdown
+        } else {
+            NewsFormat::Text
+        }
+    }
+}
+
+/// A release being parsed.
+#[derive(Default)]
+struct Entry {
+    version: String,
+    date: Option<Timestamp>,
+    kind: ReleaseKind,
+    /// The sections, in order, with their paragraphs and list items.
+    sections: Vec<(Option<String>, Vec<Block>)>,
+}
+
+enum Block {
+    Paragraph(String),
+    Item(String),
+}
+
+impl Entry {
+    fn blocks(&mut self) -> &mut Vec<Block> {
+        if self.sections.is_empty() {
+            self.sections.push((None, vec![]));
+        }
+        &mut self.sections.last_mut().unwrap().1
+    }
+
+    /// Appends a line to the current paragraph or list item.
+    fn push_text(&mut self, text: &str, new_item: bool) {
+        let blocks = self.blocks();
+        match blocks.last_mut() {
+            Some(Block::Item(item)) if !new_item => {
+                item.push(' ');
+                item.push_str(text);
+            }
+            Some(Block::Paragraph(paragraph)) if !new_item => {
+                if !paragraph.is_empty() {
+                    paragraph.push(' ');
+                }
+                paragraph.push_str(text);
+            }
+            _ if new_item => blocks.push(Block::Item(text.to_string())),
+            _ => blocks.push(Block::Paragraph(text.to_string())),
+        }
+    }
+
+    /// Starts a new paragraph at the next line.
+    fn break_paragraph(&mut self) {
+        if let Some((_, blocks)) = self.sections.last_mut() {
+            match blocks.last() {
+                Some(Block::Paragraph(p)) if p.is_empty() => (),
+                Some(_) => blocks.push(Block::Paragraph(String::new())),
+                None => (),
+            }
+        }
+    }
+
+    /// Parses a line of the body of a release in the text format.
+    fn push_text_line(&mut self, line: &str) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            self.break_paragraph();
+        } else if !line.starts_with(' ') && trimmed.ends_with(':') && !trimmed.contains(' ') {
+            self.sections
+                .push((Some(trimmed.trim_end_matches(':').to_string()), vec![]));
+        } else if let Some(item) = trimmed
+            .strip_prefix("* ")
+            .or_else(|| trimmed.strip_prefix("- "))
+            .or_else(|| trimmed.strip_prefix("+ "))
+        {
+            self.push_text(item.trim(), true);
+        } else {
+            self.push_text(trimmed, false);
+        }
+    }
+
+    fn into_release(self) -> Release {
+        let mut markup = String::new();
+        for (title, blocks) in self.sections.into_iter() {
+            let is_notes = title.as_deref() == Some("Notes");
+            let blocks = blocks
+                .into_iter()
+                .filter(|b| !matches!(b, Block::Paragraph(p) if p.is_empty()))
+                .map(|b| match b {
+                    // Notes are free text, even when written as a list.
+                    Block::Item(item) if is_notes => Block::Paragraph(item),
+                    b => b,
+                })
+                .collect::<Vec<Block>>();
+            if blocks.is_empty() {
+                continue;
+            }
+            match title.as_deref() {
+                None | Some("Notes") => (),
+                Some("Features") => markup.push_str(&format!("<p>{}</p>", FEATURES_INTRO)),
+                Some("Bugfixes") => markup.push_str(&format!("<p>{}</p>", BUGFIXES_INTRO)),
+                Some(title) => markup.push_str(&format!("<p>{}:</p>", escape(title))),
+            }
+            let mut in_list = false;
+            for block in blocks {
+                match block {
+                    Block::Item(item) => {
+                        if !in_list {
+                            markup.push_str("<ul>");
+                            in_list = true;
+                        }
+                        markup.push_str(&format!("<li>{}</li>", escape(&item)));
+                    }
+                    Block::Paragraph(paragraph) => {
+                        if in_list {
+                            markup.push_str("</ul>");
+                            in_list = false;
+                        }
+                        markup.push_str(&format!("<p>{}</p>", escape(&paragraph)));
+                    }
+                }
+            }
+            if in_list {
+                markup.push_str("</ul>");
+            }
+        }
+
+        let mut release = ReleaseBuilder::new(&self.version).kind(self.kind);
+        if let Some(date) = self.date {
+            release = release.date(date);
+        }
+        if !markup.is_empty() {
+            release = release.description(MarkupTranslatableString::with_default(&markup));
+        }
+        release.build()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn parse_date(date: &str) -> Result<Timestamp, ParseError> {
+    let trimmed = date.trim();
+    // Only plain dates are accepted, a number would be taken for a Unix timestamp.
+    if trimmed.len() == 10 && trimmed.contains('-') {
+        if let Ok(date) = trimmed.parse() {
+            return Ok(date);
+        }
+    }
+    Err(ParseError::invalid_value(date, "date", "release"))
+}
+
+/// Whether the line only underlines the previous one.
+fn is_underline(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 3
+        && (line.chars().all(|c| c == '~')
+            || line.chars().all(|c| c == '=')
+            || line.chars().all(|c| c == '-'))
+}
+
+/// Parses a changelog into a list of releases, the newest first as they are written.
+///
+/// # Arguments
+///
+/// * `text` - The content of the changelog.
+/// * `format` - Its format, see `NewsFormat::detect`.
+///
+/// # Example
+///
+/// ```
+/// use appstream::news::{parse_news, NewsFormat};
+///
+/// let news = "Version 1.2
+/// ~~~~~~~~~~~
+/// Released: 2021-06-01
+///
+/// Features:
+///  * Dark mode support
+/// ";
+/// let releases = parse_news(news, NewsFormat::Text).unwrap();
+/// assert_eq!(releases[0].version, "1.2");
+/// ```
+pub fn parse_news(text: &str, format: NewsFormat) -> Result<Vec<Release>, ParseError> {
+    match format {
+        NewsFormat::Text => parse_text(text),
+        NewsFormat::Markdown => parse_markdown(text),
+        NewsFormat::Yaml => parse_yaml(text),
+    }
+}
+
+fn parse_text(text: &str) -> Result<Vec<Release>, ParseError> {
+    let lines = text.lines().collect::<Vec<&str>>();
+    let mut releases = Vec::new();
+    let mut current: Option<Entry> = None;
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim_end();
+        let trimmed = line.trim();
+        i += 1;
+
+        // A header is either `Version x.y` or a version underlined on the next line.
+        let underlined = lines.get(i).is_some_and(|l| is_underline(l));
+        let header = trimmed
+            .strip_prefix("Version ")
+            .map(|v| v.split_whitespace().next().unwrap_or(v))
+            .or_else(|| {
+                let starts_with_digit = trimmed.chars().next().is_some_and(|c| c.is_ascii_digit());
+                if underlined && starts_with_digit && !trimmed.contains(' ') {
+                    Some(trimmed)
+                } else {
+                    None
+                }
+            });
+        if is_underline(line) && current.as_ref().is_none_or(|c| c.sections.is_empty()) {
+            continue;
+        }
+        if let Some(version) = header {
+            if let Some(entry) = current.take() {
+                releases.push(entry.into_release());
+            }
+            current = Some(Entry {
+                version: version.to_string(),
+                ..Default::default()
+            });
+            if underlined {
+                i += 1;
+            }
+            continue;
+        }
+
+        let entry = match current.as_mut() {
+            Some(entry) => entry,
+            None => continue,
+        };
+        if let Some(date) = trimmed.strip_prefix("Released:") {
+            if !date.trim().is_empty() && !date.contains("xxxx") {
+                entry.date = Some(parse_date(date)?);
+            }
+        } else {
+            entry.push_text_line(line);
+        }
+    }
+    if let Some(entry) = current {
+        releases.push(entry.into_release());
+    }
+    Ok(releases)
+}
+
+/// Removes the Markdown inline markup that can't be represented in AppStream.
+fn strip_markdown(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        let (before, after) = rest.split_at(start);
+        result.push_str(before);
+        match after
+            .find("](")
+            .and_then(|m| after[m..].find(')').map(|e| (m, m + e)))
+        {
+            Some((middle, end)) => {
+                result.push_str(&after[1..middle]);
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push('[');
+                rest = &after[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result.replace('`', "").replace("**", "")
+}
+
+fn parse_markdown(text: &str) -> Result<Vec<Release>, ParseError> {
+    let mut releases = Vec::new();
+    let mut current: Option<Entry> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix("## ") {
+            if let Some(entry) = current.take() {
+                releases.push(entry.into_release());
+            }
+            // `[1.0.0] - 2017-06-20`, `1.0.0 (2017-06-20)` or `v1.0.0`
+            let mut words = header
+                .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+                .filter(|w| !w.is_empty() && *w != "-");
+            let version = words
+                .next()
+                .unwrap_or_default()
+                .trim_matches(|c| c == '[' || c == ']');
+            let version = version.strip_prefix('v').unwrap_or(version);
+            if version.eq_ignore_ascii_case("unreleased") {
+                continue;
+            }
+            let date = match words.next() {
+                Some(date) => Some(parse_date(date)?),
+                None => None,
+            };
+            current = Some(Entry {
+                version: version.to_string(),
+                date,
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let entry = match current.as_mut() {
+            Some(entry) => entry,
+            None => continue,
+        };
+        if let Some(title) = trimmed.strip_prefix("### ") {
+            entry
+                .sections
+                .push((Some(title.trim().to_string()), vec![]));
+        } else if trimmed.is_empty() {
+            entry.break_paragraph();
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            entry.push_text(&strip_markdown(item.trim()), true);
+        } else {
+            entry.push_text(&strip_markdown(trimmed), false);
+        }
+    }
+    if let Some(entry) = current {
+        releases.push(entry.into_release());
+    }
+    Ok(releases)
+}
+
+fn yaml_string(value: &Yaml) -> Option<String> {
+    match value {
+        Yaml::String(s) | Yaml::Real(s) => Some(s.clone()),
+        Yaml::Integer(i) => Some(i.to_string()),
+        _ => None,
+    }
+}
+
+fn parse_yaml(text: &str) -> Result<Vec<Release>, ParseError> {
+    let documents = YamlLoader::load_from_str(text)
+        .map_err(|e| ParseError::invalid_value(&e.to_string(), "$value", "news"))?;
+    let mut releases = Vec::new();
+    for document in documents.iter() {
+        let version = yaml_string(&document["Version"])
+            .ok_or_else(|| ParseError::missing_value("Version"))?;
+        let mut entry = Entry {
+            version,
+            ..Default::default()
+        };
+        if let Some(date) = yaml_string(&document["Date"]) {
+            entry.date = Some(parse_date(&date)?);
+        }
+        if document["Type"].as_str() == Some("development") {
+            entry.kind = ReleaseKind::Development;
+        }
+        match &document["Description"] {
+            Yaml::Array(items) => {
+                for item in items.iter().filter_map(yaml_string) {
+                    entry.push_text(&item, true);
+                }
+            }
+            Yaml::String(description) => {
+                for line in description.lines() {
+                    entry.push_text_line(line);
+                }
+            }
+            _ => (),
+        }
+        releases.push(entry.into_release());
+    }
+    Ok(releases)
+}
+
+/// The text content of an element and its children.
+fn element_text(element: &Element) -> String {
+    let mut text = String::new();
+    for node in element.children.iter() {
+        match node {
+            XMLNode::Text(t) | XMLNode::CData(t) => text.push_str(t),
+            XMLNode::Element(e) => text.push_str(&element_text(e)),
+            _ => (),
+        }
+    }
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Splits the description of a release back into sections.
+fn release_sections(release: &Release) -> Vec<(Option<String>, Vec<Block>)> {
+    let mut sections: Vec<(Option<String>, Vec<Block>)> = vec![(None, vec![])];
+    let markup = match release.description.as_ref().and_then(|d| d.get_default()) {
+        Some(markup) => markup,
+        None => return vec![],
+    };
+    let root = match Element::parse(format!("<root>{}</root>", markup).as_bytes()) {
+        Ok(root) => root,
+        Err(_) => return vec![],
+    };
+    let elements = root
+        .children
+        .iter()
+        .filter_map(|n| n.as_element())
+        .collect::<Vec<&Element>>();
+    for (i, element) in elements.iter().enumerate() {
+        let text = element_text(element);
+        match element.name.as_str() {
+            "p" => {
+                let before_list = elements
+                    .get(i + 1)
+                    .is_some_and(|n| n.name == "ul" || n.name == "ol");
+                let title = match text.as_str() {
+                    FEATURES_INTRO => Some("Features".to_string()),
+                    BUGFIXES_INTRO => Some("Bugfixes".to_string()),
+                    t if before_list && t.ends_with(':') => {
+                        Some(t.trim_end_matches(':').to_string())
+                    }
+                    _ => None,
+                };
+                match title {
+                    Some(title) => sections.push((Some(title), vec![])),
+                    None => sections.last_mut().unwrap().1.push(Block::Paragraph(text)),
+                }
+            }
+            "ul" | "ol" => {
+                let items = element
+                    .children
+                    .iter()
+                    .filter_map(|n| n.as_element())
+                    .map(|li| Block::Item(element_text(li)));
+                sections.last_mut().unwrap().1.extend(items);
+            }
+            _ => (),
+        }
+    }
+    sections.retain(|(_, blocks)| !blocks.is_empty());
+    sections
+}
+
+/// Wraps `text` at 80 columns, the first line prefixed by `first` and the
+/// following ones by `next`.
+fn wrap(text: &str, first: &str, next: &str) -> String {
+    let mut result = String::new();
+    let mut line = first.to_string();
+    let mut empty = true;
+    for word in text.split_whitespace() {
+        if !empty && line.chars().count() + word.chars().count() + 1 > 80 {
+            result.push_str(&line);
+            result.push('\n');
+            line = next.to_string();
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+    result.push_str(&line);
+    result.push('\n');
+    result
+}
+
+/// Renders the sections of a release in the text format.
+fn render_text_body(sections: &[(Option<String>, Vec<Block>)]) -> String {
+    let mut body = Vec::new();
+    for (title, blocks) in sections.iter() {
+        let mut section = String::new();
+        if let Some(title) = title {
+            section.push_str(&format!("{}:\n", title));
+        }
+        for block in blocks.iter() {
+            match block {
+                Block::Item(item) => section.push_str(&wrap(item, " * ", "   ")),
+                Block::Paragraph(paragraph) => section.push_str(&wrap(paragraph, "", "")),
+            }
+        }
+        body.push(section);
+    }
+    body.join("\n")
+}
+
+fn render_text(releases: &[Release]) -> String {
+    let mut news = Vec::new();
+    for release in releases.iter() {
+        let header = format!("Version {}", release.version);
+        let date = match release.date {
+            Some(date) => date.format_date(),
+            None => "xxxx-xx-xx".to_string(),
+        };
+        let mut text = format!(
+            "{}\n{}\nReleased: {}\n",
+            header,
+            "~".repeat(header.chars().count()),
+            date
+        );
+        let body = render_text_body(&release_sections(release));
+        if !body.is_empty() {
+            text.push('\n');
+            text.push_str(&body);
+        }
+        news.push(text);
+    }
+    news.join("\n")
+}
+
+fn render_markdown(releases: &[Release]) -> String {
+    let mut news = Vec::new();
+    for release in releases.iter() {
+        let mut text = match release.date {
+            Some(date) => format!("## [{}] - {}\n", release.version, date.format_date()),
+            None => format!("## [{}]\n", release.version),
+        };
+        for (title, blocks) in release_sections(release).iter() {
+            text.push('\n');
+            if let Some(title) = title {
+                text.push_str(&format!("### {}\n", title));
+            }
+            let mut previous_item = None;
+            for block in blocks.iter() {
+                let is_item = matches!(block, Block::Item(_));
+                // Only the items of a list are not separated by a blank line.
+                if previous_item.is_some() && !(is_item && previous_item == Some(true)) {
+                    text.push('\n');
+                }
+                match block {
+                    Block::Item(item) => text.push_str(&format!("- {}\n", item)),
+                    Block::Paragraph(paragraph) => text.push_str(&format!("{}\n", paragraph)),
+                }
+                previous_item = Some(is_item);
+            }
+        }
+        news.push(text);
+    }
+    news.join("\n")
+}
+
+fn render_yaml(releases: &[Release]) -> String {
+    let mut news = String::new();
+    for release in releases.iter() {
+        let mut document = Hash::new();
+        document.insert(
+            Yaml::String("Version".into()),
+            Yaml::String(release.version.clone()),
+        );
+        if let Some(date) = release.date {
+            document.insert(
+                Yaml::String("Date".into()),
+                Yaml::String(date.format_date()),
+            );
+        }
+        if release.kind == ReleaseKind::Development {
+            document.insert(
+                Yaml::String("Type".into()),
+                Yaml::String("development".into()),
+            );
+        }
+        let sections = release_sections(release);
+        let only_items = sections.iter().all(|(title, blocks)| {
+            title.is_none() && blocks.iter().all(|b| matches!(b, Block::Item(_)))
+        });
+        if !sections.is_empty() {
+            let description = if only_items {
+                Yaml::Array(
+                    sections
+                        .iter()
+                        .flat_map(|(_, blocks)| blocks.iter())
+                        .filter_map(|b| match b {
+                            Block::Item(item) => Some(Yaml::String(item.clone())),
+                            Block::Paragraph(_) => None,
+                        })
+                        .collect(),
+                )
+            } else {
+                Yaml::String(render_text_body(&sections))
+            };
+            document.insert(Yaml::String("Description".into()), description);
+        }
+
+        let mut out = String::new();
+        // Writing to a String can't fail.
+        YamlEmitter::new(&mut out)
+            .dump(&Yaml::Hash(document))
+            .expect("Failed to render the YAML news");
+        news.push_str(&out);
+        news.push('\n');
+    }
+    news
+}
+
+/// Renders releases as a changelog, the reverse of `parse_news`.
+///
+/// The releases are written in the given order, the description sections
+/// generated by `parse_news` (`Features`, `Bugfixes`...) are restored.
+///
+/// # Arguments
+///
+/// * `releases` - The releases to render, usually `Component::releases`.
+/// * `format` - The format of the changelog.
+///
+/// # Example
+///
+/// ```
+/// use appstream::builders::ReleaseBuilder;
+/// use appstream::news::{render_news, NewsFormat};
+/// use appstream::MarkupTranslatableString;
+///
+/// let release = ReleaseBuilder::new("1.2")
+///     .description(MarkupTranslatableString::with_default("<ul><li>Dark mode support</li></ul>"))
+///     .build();
+/// assert_eq!(
+///     render_news(&[release], NewsFormat::Yaml),
+///     "---\nVersion: \"1.2\"\nDescription:\n  - Dark mode support\n"
+/// );
+/// ```
+pub fn render_news(releases: &[Release], format: NewsFormat) -> String {
+    match format {
+        NewsFormat::Text => render_text(releases),
+        NewsFormat::Markdown => render_markdown(releases),
+        NewsFormat::Yaml => render_yaml(releases),
+    }
+}
+
+/// Adds the releases to a component.
+///
+/// The releases already defined with the same version are replaced, so the
+/// changelog stays the single source of truth. The releases are kept sorted, the
+/// newest first.
+///
+/// # Arguments
+///
+/// * `component` - The component to update.
+/// * `releases` - The releases, as returned by `parse_news`.
+pub fn merge_releases(component: &mut Component, releases: Vec<Release>) {
+    let existing_releases = component.releases_mut();
+    for release in releases {
+        match existing_releases
+            .iter_mut()
+            .find(|r| r.version == release.version)
+        {
+            Some(existing) => *existing = release,
+            None => existing_releases.push(release),
+        }
+    }
+    // Releases without a date are considered as the upcoming ones.
+    existing_releases.sort_by(|a, b| match (a.date, b.date) {
+        (Some(a), Some(b)) => b.cmp(&a),
+        (None, Some(_)) => std::cmp::Ordering::Less,
+        (Some(_), None) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::ComponentBuilder;
+    use crate::Timestamp;
+    use crate::TranslatableString;
+    use std::error::Error;
+    use std::fs;
+
+    #[test]
+    fn text_news() -> Result<(), Box<dyn Error>> {
+        let news = fs::read_to_string("./tests/news/NEWS")?;
+        assert_eq!(NewsFormat::detect(&news), NewsFormat::Text);
+        let releases = parse_news(&news, NewsFormat::Text)?;
+
+        assert_eq!(
+            releases,
+            vec![
+                ReleaseBuilder::new("0.12.1")
+                    .date(Timestamp::from_ymd(2020, 1, 21).unwrap())
+                    .description(MarkupTranslatableString::with_default(
+                        "<p>Small bugfix release, with a long note that wraps over two lines.</p>\
+                        <p>This release adds the following features:</p>\
+                        <ul><li>Support &lt;video&gt; in screenshots</li>\
+                        <li>New --details flag, that spans several lines</li></ul>\
+                        <p>This release fixes the following bugs:</p>\
+                        <ul><li>Don't crash on empty files</li></ul>"
+                    ))
+                    .build(),
+                ReleaseBuilder::new("0.12.0")
+                    .date(Timestamp::from_ymd(2019, 12, 2).unwrap())
+                    .description(MarkupTranslatableString::with_default(
+                        "<p>Translations:</p><ul><li>Updated French translation</li></ul>"
+                    ))
+                    .build(),
+                ReleaseBuilder::new("3.38.0").build(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn markdown_news() -> Result<(), Box<dyn Error>> {
+        let news = fs::read_to_string("./tests/news/CHANGELOG.md")?;
+        assert_eq!(NewsFormat::detect(&news), NewsFormat::Markdown);
+        let releases = parse_news(&news, NewsFormat::Markdown)?;
+
+        assert_eq!(
+            releases,
+            vec![
+                ReleaseBuilder::new("1.1.0")
+                    .date(Timestamp::from_ymd(2019, 2, 15).unwrap())
+                    .description(MarkupTranslatableString::with_default(
+                        "<p>Added:</p><ul><li>Danish translation by Foo</li></ul>\
+                        <p>Fixed:</p><ul><li>The README links</li></ul>"
+                    ))
+                    .build(),
+                ReleaseBuilder::new("1.0.0")
+                    .date(Timestamp::from_ymd(2017, 6, 20).unwrap())
+                    .description(MarkupTranslatableString::with_default(
+                        "<p>The first stable release.</p>"
+                    ))
+                    .build(),
+            ]
+        );
+        assert!(parse_news("## 1.0 - 2017-13-45", NewsFormat::Markdown).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn render_round_trip() -> Result<(), Box<dyn Error>> {
+        let releases = parse_news(&fs::read_to_string("./tests/news/NEWS")?, NewsFormat::Text)?;
+        for format in &[NewsFormat::Text, NewsFormat::Yaml] {
+            let news = render_news(&releases, *format);
+            assert_eq!(NewsFormat::detect(&news), *format);
+            assert_eq!(parse_news(&news, *format)?, releases);
+        }
+        assert_eq!(
+            render_news(&releases[..1], NewsFormat::Text),
+            "Version 0.12.1\n\
+            ~~~~~~~~~~~~~~\n\
+            Released: 2020-01-21\n\
+            \n\
+            Small bugfix release, with a long note that wraps over two lines.\n\
+            \n\
+            Features:\n\
+            \x20* Support <video> in screenshots\n\
+            \x20* New --details flag, that spans several lines\n\
+            \n\
+            Bugfixes:\n\
+            \x20* Don't crash on empty files\n"
+        );
+
+        let releases = parse_news(
+            &fs::read_to_string("./tests/news/CHANGELOG.md")?,
+            NewsFormat::Markdown,
+        )?;
+        let news = render_news(&releases, NewsFormat::Markdown);
+        assert_eq!(parse_news(&news, NewsFormat::Markdown)?, releases);
+
+        let development = ReleaseBuilder::new("2.0~beta")
+            .kind(ReleaseKind::Development)
+            .build();
+        let news = render_news(std::slice::from_ref(&development), NewsFormat::Yaml);
+        assert_eq!(news, "---\nVersion: 2.0~beta\nType: development\n");
+        assert_eq!(parse_news(&news, NewsFormat::Yaml)?, vec![development]);
+        Ok(())
+    }
+
+    #[test]
+    fn merge_into_component() -> Result<(), Box<dyn Error>> {
+        let mut component = ComponentBuilder::default()
+            .id("org.example.Foo".into())
+            .name(TranslatableString::with_default("Foo"))
+            .release(ReleaseBuilder::new("1.0.0").build())
+            .release(
+                ReleaseBuilder::new("0.9")
+                    .date(Timestamp::from_ymd(2016, 1, 1).unwrap())
+                    .build(),
+            )
+            .build();
+        let releases = parse_news(
+            &fs::read_to_string("./tests/news/CHANGELOG.md")?,
+            NewsFormat::Markdown,
+        )?;
+        merge_releases(&mut component, releases);
+
+        let versions = component
+            .releases
+            .iter()
+            .map(|r| r.version.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(versions, vec!["1.1.0", "1.0.0", "0.9"]);
+        assert!(component.releases[1].date.is_some());
+        Ok(())
+    }
+}
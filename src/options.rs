@@ -0,0 +1,281 @@
+use super::error::ParseError;
+#[cfg(feature = "schema")]
+use super::schema;
+use super::Component;
+use std::ops::{BitOr, BitOrAssign};
+use std::sync::Arc;
+use xmltree::Element;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// A set of component fields, combined with `|`.
+///
+/// # Example
+///
+/// ```
+/// use appstream::Fields;
+///
+/// let fields = Fields::DESCRIPTIONS | Fields::RELEASES;
+/// assert!(fields.contains(Fields::RELEASES));
+/// assert!(!fields.contains(Fields::SCREENSHOTS));
+/// ```
+pub struct Fields(u32);
+
+impl Fields {
+    /// The long descriptions, in every locale.
+    pub const DESCRIPTIONS: Fields = Fields(1);
+    /// The screenshots.
+    pub const SCREENSHOTS: Fields = Fields(1 << 1);
+    /// The releases.
+    pub const RELEASES: Fields = Fields(1 << 2);
+    /// The supported languages.
+    pub const LANGUAGES: Fields = Fields(1 << 3);
+    /// The keywords, in every locale.
+    pub const KEYWORDS: Fields = Fields(1 << 4);
+    /// The content rating.
+    pub const CONTENT_RATING: Fields = Fields(1 << 5);
+
+    /// The XML tag of each field.
+    const TAGS: [(Fields, &'static str); 6] = [
+        (Fields::DESCRIPTIONS, "description"),
+        (Fields::SCREENSHOTS, "screenshots"),
+        (Fields::RELEASES, "releases"),
+        (Fields::LANGUAGES, "languages"),
+        (Fields::KEYWORDS, "keywords"),
+        (Fields::CONTENT_RATING, "content_rating"),
+    ];
+
+    /// No fields.
+    pub fn empty() -> Self {
+        Fields(0)
+    }
+
+    /// Whether all the `other` fields are part of the set.
+    pub fn contains(&self, other: Fields) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether the set has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for Fields {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Fields(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for Fields {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// Options changing how catalogs are parsed.
+///
+/// # Example
+///
+/// ```
+/// use appstream::{Collection, Fields, ParseOptions};
+///
+/// let options = ParseOptions::skip(Fields::DESCRIPTIONS | Fields::SCREENSHOTS | Fields::RELEASES);
+/// let collection =
+///     Collection::from_path_with_options("./tests/collections/gnome-apps.xml".into(), &options)
+///         .unwrap();
+/// assert!(collection.components.iter().all(|c| c.releases.is_empty()));
+/// ```
+///
+/// Limits make parsing untrusted documents fail early with
+/// `ParseError::LimitExceeded` instead of using unbounded resources:
+///
+/// ```
+/// use appstream::{Collection, ParseError, ParseOptions};
+///
+/// let options = ParseOptions::default().max_components(10);
+/// let result =
+///     Collection::from_path_with_options("./tests/collections/gnome-apps.xml".into(), &options);
+/// assert!(matches!(result, Err(ParseError::LimitExceeded(..))));
+/// ```
+pub struct ParseOptions {
+    skipped: Fields,
+    max_components: Option<u64>,
+    max_string_length: Option<u64>,
+    max_screenshots: Option<u64>,
+    max_releases: Option<u64>,
+    max_decompressed_bytes: Option<u64>,
+    #[cfg(feature = "schema")]
+    validate_schema: bool,
+}
+
+impl ParseOptions {
+    /// Creates options skipping some fields entirely, they are neither parsed nor
+    /// stored, which makes loading catalogs for lightweight list views faster.
+    ///
+    /// # Arguments
+    ///
+    /// * `fields` - The fields to skip.
+    pub fn skip(fields: Fields) -> Self {
+        Self {
+            skipped: fields,
+            ..Self::default()
+        }
+    }
+
+    /// Limits the number of components of a catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum number of components.
+    pub fn max_components(mut self, max: u64) -> Self {
+        self.max_components = Some(max);
+        self
+    }
+
+    /// Limits the length in bytes of the texts and attribute values of the XML
+    /// documents, and of the strings of the YAML ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum length.
+    pub fn max_string_length(mut self, max: u64) -> Self {
+        self.max_string_length = Some(max);
+        self
+    }
+
+    /// Limits the number of screenshots of each component.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum number of screenshots.
+    pub fn max_screenshots(mut self, max: u64) -> Self {
+        self.max_screenshots = Some(max);
+        self
+    }
+
+    /// Limits the number of releases of each component.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum number of releases.
+    pub fn max_releases(mut self, max: u64) -> Self {
+        self.max_releases = Some(max);
+        self
+    }
+
+    /// Limits the size of the XML and YAML documents once decompressed, which
+    /// guards against compression bombs.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - The maximum size, in bytes.
+    pub fn max_decompressed_bytes(mut self, max: u64) -> Self {
+        self.max_decompressed_bytes = Some(max);
+        self
+    }
+
+    #[cfg(feature = "schema")]
+    /// Checks the structure of the XML documents against the partial AppStream
+    /// schema before parsing them, see `schema::validate`. The first component that doesn't follow it
+    /// fails the parsing with `ParseError::SchemaViolation`, the skipped fields
+    /// aren't validated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::{Component, ParseError, ParseOptions};
+    ///
+    /// let options = ParseOptions::default().validate_schema();
+    /// let result = Component::from_path_with_options("./tests/desktop.xml".into(), &options);
+    /// assert!(result.is_ok());
+    /// ```
+    pub fn validate_schema(mut self) -> Self {
+        self.validate_schema = true;
+        self
+    }
+
+    /// The skipped fields.
+    pub fn skipped(&self) -> Fields {
+        self.skipped
+    }
+
+    pub(crate) fn check_components(&self, count: usize) -> Result<(), ParseError> {
+        check(count, self.max_components, "components")
+    }
+
+    pub(crate) fn check_string(&self, s: &str) -> Result<(), ParseError> {
+        check(s.len(), self.max_string_length, "bytes in a string")
+    }
+
+    /// Checks the number of children of the component child named `tag`.
+    pub(crate) fn check_children(&self, tag: &str, count: usize) -> Result<(), ParseError> {
+        match tag {
+            "screenshots" => check(count, self.max_screenshots, "screenshots"),
+            "releases" => check(count, self.max_releases, "releases"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the limits that apply to an already parsed component.
+    pub(crate) fn check_component(&self, component: &Component) -> Result<(), ParseError> {
+        self.check_children("screenshots", component.screenshots.len())?;
+        self.check_children("releases", component.releases.len())
+    }
+
+    /// Checks an element against the schema, if asked to.
+    pub(crate) fn check_schema(&self, _element: &Element) -> Result<(), ParseError> {
+        #[cfg(feature = "schema")]
+        if self.validate_schema {
+            let issues = schema::validate(_element);
+            if !issues.is_empty() {
+                return Err(ParseError::SchemaViolation(issues));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn decompressed_bytes_limit(&self) -> Option<u64> {
+        self.max_decompressed_bytes
+    }
+
+    /// Whether the children of a component named `tag` are skipped.
+    pub(crate) fn skips_tag(&self, tag: &str) -> bool {
+        Fields::TAGS
+            .iter()
+            .any(|(field, name)| *name == tag && self.skipped.contains(*field))
+    }
+
+    /// Clears the skipped fields of an already parsed component, for the formats
+    /// that can't skip them while parsing.
+    pub(crate) fn strip(&self, component: &mut Component) {
+        let skipped = self.skipped;
+        if skipped.contains(Fields::DESCRIPTIONS) {
+            component.description = None;
+        }
+        if skipped.contains(Fields::SCREENSHOTS) {
+            component.screenshots = Arc::default();
+        }
+        if skipped.contains(Fields::RELEASES) {
+            component.releases = Arc::default();
+        }
+        if skipped.contains(Fields::LANGUAGES) {
+            component.languages = Arc::default();
+        }
+        if skipped.contains(Fields::KEYWORDS) {
+            component.keywords = None;
+        }
+        if skipped.contains(Fields::CONTENT_RATING) {
+            component.content_rating = None;
+        }
+    }
+}
+
+fn check(count: usize, limit: Option<u64>, what: &str) -> Result<(), ParseError> {
+    match limit {
+        Some(limit) if count as u64 > limit => Err(ParseError::limit_exceeded(what, limit)),
+        _ => Ok(()),
+    }
+}
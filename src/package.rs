@@ -0,0 +1,422 @@
+use super::error::ParseError;
+use super::tar;
+use super::Component;
+use flate2::read::GzDecoder;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read};
+use std::path::Path;
+use xmltree::Element;
+
+const AR_MAGIC: &[u8] = b"!<arch>\n";
+const RPM_MAGIC: &[u8] = &[0xed, 0xab, 0xee, 0xdb];
+const RPM_HEADER_MAGIC: &[u8] = &[0x8e, 0xad, 0xe8, 0x01];
+const CPIO_TRAILER: &str = "TRAILER!!!";
+/// The longest cpio file name read, the headers' sizes are not trusted.
+const PATH_MAX: u64 = 4096;
+
+/// The directories metainfo files are installed to, the legacy one included.
+const METAINFO_DIRS: [&str; 2] = ["usr/share/metainfo/", "usr/share/appdata/"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The formats of binary packages.
+pub enum PackageFormat {
+    /// A Debian package, an `ar` archive with a `data.tar` member.
+    Deb,
+    /// An RPM package, with a `cpio` payload.
+    Rpm,
+}
+
+impl PackageFormat {
+    /// Detects the format of a package from its first bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The beginning of the package.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(AR_MAGIC) {
+            Some(PackageFormat::Deb)
+        } else if data.starts_with(RPM_MAGIC) {
+            Some(PackageFormat::Rpm)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// The metainfo files shipped by a `.deb` or `.rpm` package.
+///
+/// The payload is decompressed on the fly, gzip, xz and zstd are supported, and
+/// only the `usr/share/metainfo/*.xml` files, or the legacy
+/// `usr/share/appdata/*.xml` ones, are kept in memory. Payloads compressed with xz
+/// are the exception, they are decompressed at once.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::package::Package;
+///
+/// let package = Package::from_path("./gnome-contrast_0.0.5-1_amd64.deb".as_ref()).unwrap();
+/// for component in package.components().unwrap() {
+///     println!("{}", component.id.as_ref());
+/// }
+/// ```
+pub struct Package {
+    format: PackageFormat,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl Package {
+    /// Reads the metainfo files of a package file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the `.deb` or `.rpm` file.
+    pub fn from_path(path: &Path) -> Result<Self, ParseError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads the metainfo files of a package, its format being detected.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The content of the package.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, ParseError> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        let format = PackageFormat::detect(&magic)
+            .ok_or_else(|| ParseError::invalid_value("magic", "format", "package"))?;
+        let reader = Cursor::new(magic).chain(reader);
+        let files = match format {
+            PackageFormat::Deb => deb_files(reader)?,
+            PackageFormat::Rpm => rpm_files(reader)?,
+        };
+        Ok(Self { format, files })
+    }
+
+    /// The format of the package.
+    pub fn format(&self) -> PackageFormat {
+        self.format
+    }
+
+    /// The metainfo files, as `(path, content)` pairs, with their path relative to
+    /// the root directory.
+    pub fn metainfo_files(&self) -> &[(String, Vec<u8>)] {
+        &self.files
+    }
+
+    /// Parses the metainfo files, in the order they are stored in the package.
+    pub fn components(&self) -> Result<Vec<Component>, ParseError> {
+        self.files
+            .iter()
+            .map(|(_, content)| Component::try_from(&Element::parse(&content[..])?))
+            .collect()
+    }
+}
+
+/// The path of an archived file relative to the root directory, when it's a
+/// metainfo file.
+fn metainfo_path(name: &str) -> Option<String> {
+    let name = name.trim_start_matches("./").trim_start_matches('/');
+    METAINFO_DIRS.iter().find_map(|dir| {
+        let file = name.strip_prefix(dir)?;
+        if file.ends_with(".xml") && !file.contains('/') {
+            Some(name.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// Wraps a compressed stream in its decoder, detected from its first bytes.
+fn decompress<'a, R: Read + 'a>(mut reader: R) -> Result<Box<dyn Read + 'a>, ParseError> {
+    let mut magic = vec![];
+    (&mut reader).take(6).read_to_end(&mut magic)?;
+    let reader = Cursor::new(magic.clone()).chain(reader);
+    if magic.starts_with(&[0x1f, 0x8b]) {
+        Ok(Box::new(GzDecoder::new(reader)))
+    } else if magic.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0]) {
+        // The xz decoder writes its output, it can't be read on the fly.
+        let mut data = vec![];
+        lzma_rs::xz_decompress(&mut BufReader::new(reader), &mut data)
+            .map_err(|_| ParseError::invalid_value("xz", "compression", "package"))?;
+        Ok(Box::new(Cursor::new(data)))
+    } else if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        let decoder = ruzstd::decoding::StreamingDecoder::new(reader)
+            .map_err(|_| ParseError::invalid_value("zstd", "compression", "package"))?;
+        Ok(Box::new(decoder))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+fn skip<R: Read>(reader: &mut R, length: u64) -> Result<(), ParseError> {
+    if io::copy(&mut reader.take(length), &mut io::sink())? < length {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(())
+}
+
+/// Reads `length` bytes, without allocating them upfront.
+fn read<R: Read>(reader: &mut R, length: u64) -> Result<Vec<u8>, ParseError> {
+    let mut data = vec![];
+    if reader.take(length).read_to_end(&mut data)? < length as usize {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(data)
+}
+
+/// The metainfo files of the `data.tar` member of a Debian package.
+fn deb_files<R: Read>(mut reader: R) -> Result<Vec<(String, Vec<u8>)>, ParseError> {
+    let invalid = || ParseError::invalid_value("ar", "header", "deb");
+    skip(&mut reader, AR_MAGIC.len() as u64)?;
+    let mut header = [0u8; 60];
+    loop {
+        reader.read_exact(&mut header)?;
+        let name = String::from_utf8_lossy(&header[..16]);
+        let name = name.trim_end().trim_end_matches('/');
+        let size = String::from_utf8_lossy(&header[48..58])
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| invalid())?;
+        if name.starts_with("data.tar") {
+            let data = decompress((&mut reader).take(size))?;
+            return tar::entries(data, |name| metainfo_path(name).is_some())?
+                .into_iter()
+                .map(|(name, content)| Ok((metainfo_path(&name).ok_or_else(invalid)?, content)))
+                .collect();
+        }
+        // The members are aligned on two bytes.
+        skip(&mut reader, size + size % 2)?;
+    }
+}
+
+/// Skips a header structure of an RPM package, returns its length.
+fn skip_rpm_header<R: Read>(reader: &mut R) -> Result<u64, ParseError> {
+    let mut intro = [0u8; 16];
+    reader.read_exact(&mut intro)?;
+    if &intro[..4] != RPM_HEADER_MAGIC {
+        return Err(ParseError::invalid_value("header", "magic", "rpm"));
+    }
+    let count = u32::from_be_bytes([intro[8], intro[9], intro[10], intro[11]]) as u64;
+    let size = u32::from_be_bytes([intro[12], intro[13], intro[14], intro[15]]) as u64;
+    skip(reader, count * 16 + size)?;
+    Ok(16 + count * 16 + size)
+}
+
+/// The metainfo files of the payload of an RPM package.
+fn rpm_files<R: Read>(mut reader: R) -> Result<Vec<(String, Vec<u8>)>, ParseError> {
+    // The lead, then the signature header padded to eight bytes, then the header.
+    skip(&mut reader, 96)?;
+    let signature = skip_rpm_header(&mut reader)?;
+    skip(&mut reader, (8 - signature % 8) % 8)?;
+    skip_rpm_header(&mut reader)?;
+    cpio_files(decompress(reader)?)
+}
+
+/// The metainfo files of a `newc` cpio archive.
+fn cpio_files<R: Read>(mut reader: R) -> Result<Vec<(String, Vec<u8>)>, ParseError> {
+    let invalid = || ParseError::invalid_value("cpio", "header", "rpm");
+    let mut files = vec![];
+    let mut header = [0u8; 110];
+    loop {
+        reader.read_exact(&mut header)?;
+        if &header[..6] != b"070701" && &header[..6] != b"070702" {
+            return Err(invalid());
+        }
+        let field = |index: usize| {
+            let start = 6 + index * 8;
+            std::str::from_utf8(&header[start..start + 8])
+                .ok()
+                .and_then(|f| u64::from_str_radix(f, 16).ok())
+                .ok_or_else(invalid)
+        };
+        let (mode, size, name_size) = (field(1)?, field(6)?, field(11)?);
+
+        if name_size > PATH_MAX {
+            return Err(ParseError::limit_exceeded(
+                "bytes in a cpio file name",
+                PATH_MAX,
+            ));
+        }
+        let name = read(&mut reader, name_size)?;
+        skip(&mut reader, (4 - (110 + name_size) % 4) % 4)?;
+        let name = String::from_utf8_lossy(&name);
+        let name = name.trim_end_matches('\0');
+        if name == CPIO_TRAILER {
+            return Ok(files);
+        }
+
+        match metainfo_path(name) {
+            Some(path) if mode & 0o170000 == 0o100000 => {
+                files.push((path, read(&mut reader, size)?));
+            }
+            _ => skip(&mut reader, size)?,
+        }
+        skip(&mut reader, (4 - size % 4) % 4)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use std::fs;
+
+    const METAINFO: &str =
+        "./tests/compose/usr/share/metainfo/org.gnome.design.Contrast.metainfo.xml";
+
+    fn ar(members: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut data = AR_MAGIC.to_vec();
+        for (name, content) in members {
+            let header = format!(
+                "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+                format!("{}/", name),
+                0,
+                0,
+                0,
+                100644,
+                content.len()
+            );
+            data.extend_from_slice(header.as_bytes());
+            data.extend_from_slice(content);
+            if content.len() % 2 == 1 {
+                data.push(b'\n');
+            }
+        }
+        data
+    }
+
+    fn cpio(files: &[(&str, u32, &[u8])]) -> Vec<u8> {
+        let mut data = vec![];
+        let entries = files
+            .iter()
+            .copied()
+            .chain(Some((CPIO_TRAILER, 0, &[][..])));
+        for (name, mode, content) in entries {
+            data.extend_from_slice(b"070701");
+            let fields = [0, mode, 0, 0, 1, 0, content.len() as u32];
+            let fields = fields.iter().chain(&[0, 0, 0, 0]);
+            for field in fields.chain(&[name.len() as u32 + 1, 0]) {
+                data.extend_from_slice(format!("{:08x}", field).as_bytes());
+            }
+            data.extend_from_slice(name.as_bytes());
+            data.push(0);
+            data.resize(data.len().div_ceil(4) * 4, 0);
+            data.extend_from_slice(content);
+            data.resize(data.len().div_ceil(4) * 4, 0);
+        }
+        data
+    }
+
+    fn rpm_header(count: u32, size: u32) -> Vec<u8> {
+        let mut data = RPM_HEADER_MAGIC.to_vec();
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&count.to_be_bytes());
+        data.extend_from_slice(&size.to_be_bytes());
+        data.resize(data.len() + (count * 16 + size) as usize, 0);
+        data
+    }
+
+    #[test]
+    fn deb_metainfo() -> Result<(), Box<dyn Error>> {
+        let metainfo = fs::read(METAINFO)?;
+        let data = tar::archive(&[
+            ("./usr/bin/contrast", b"#!/bin/sh"),
+            (
+                "./usr/share/metainfo/org.gnome.design.Contrast.metainfo.xml",
+                &metainfo,
+            ),
+            ("./usr/share/metainfo/nested/ignored.xml", b"<component/>"),
+        ]);
+        let mut compressed = vec![];
+        lzma_rs::xz_compress(&mut &data[..], &mut compressed)?;
+        let deb = ar(&[
+            ("debian-binary", b"2.0\n"),
+            ("control.tar.gz", b"x"),
+            ("data.tar.xz", &compressed),
+        ]);
+
+        let package = Package::from_reader(&deb[..])?;
+        assert_eq!(package.format(), PackageFormat::Deb);
+        assert_eq!(
+            package.metainfo_files(),
+            &[(
+                "usr/share/metainfo/org.gnome.design.Contrast.metainfo.xml".to_string(),
+                metainfo
+            )]
+        );
+        let components = package.components()?;
+        assert_eq!(components[0].id, "org.gnome.design.Contrast".into());
+        Ok(())
+    }
+
+    #[test]
+    fn rpm_metainfo() -> Result<(), Box<dyn Error>> {
+        let metainfo = fs::read(METAINFO)?;
+        let payload = cpio(&[
+            ("./usr/share/appdata", 0o040755, b""),
+            (
+                "./usr/share/appdata/contrast.appdata.xml",
+                0o100644,
+                &metainfo,
+            ),
+            ("./usr/share/metainfo/readme.txt", 0o100644, b"text"),
+        ]);
+        let mut rpm = RPM_MAGIC.to_vec();
+        rpm.resize(96, 0);
+        rpm.extend(rpm_header(1, 5));
+        rpm.resize(rpm.len().div_ceil(8) * 8, 0);
+        rpm.extend(rpm_header(2, 30));
+        rpm.extend(ruzstd::encoding::compress_to_vec(
+            &payload[..],
+            ruzstd::encoding::CompressionLevel::Fastest,
+        ));
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("package.rpm");
+        fs::write(&path, &rpm)?;
+        let package = Package::from_path(&path)?;
+        assert_eq!(package.format(), PackageFormat::Rpm);
+        assert_eq!(package.metainfo_files().len(), 1);
+        assert_eq!(
+            package.metainfo_files()[0].0,
+            "usr/share/appdata/contrast.appdata.xml"
+        );
+        assert_eq!(package.components()?.len(), 1);
+
+        assert!(Package::from_reader(&b"not a package"[..]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_cpio() -> Result<(), Box<dyn Error>> {
+        let header = |size: u32, name_size: u32| {
+            let mut data = b"070701".to_vec();
+            let fields = [0, 0o100644, 0, 0, 1, 0, size, 0, 0, 0, 0, name_size, 0];
+            for field in fields.iter() {
+                data.extend_from_slice(format!("{:08x}", field).as_bytes());
+            }
+            data
+        };
+
+        let mut huge_name = header(0, u32::MAX);
+        huge_name.extend_from_slice(b"name");
+        assert!(matches!(
+            cpio_files(&huge_name[..]),
+            Err(ParseError::LimitExceeded(_, PATH_MAX))
+        ));
+
+        let name = "usr/share/metainfo/a.xml\0";
+        let mut huge_content = header(u32::MAX, name.len() as u32);
+        huge_content.extend_from_slice(name.as_bytes());
+        huge_content.resize(huge_content.len().div_ceil(4) * 4, 0);
+        huge_content.extend_from_slice(b"<component/>");
+        assert!(matches!(
+            cpio_files(&huge_content[..]),
+            Err(ParseError::IOError(e)) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+        Ok(())
+    }
+}
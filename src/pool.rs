@@ -0,0 +1,795 @@
+use super::enums::{glob_matches, Provide};
+#[cfg(feature = "linux")]
+use super::error::ParseError;
+#[cfg(feature = "linux")]
+use super::modalias;
+use super::{AppId, AppIdAliases, Collection, Component, Release};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+#[derive(Debug, Default)]
+/// An immutable state of a `Pool`, with its indices.
+///
+/// Queries on a snapshot don't take any lock, and it stays valid, unchanged, when
+/// the pool is refreshed in the meantime.
+pub struct Snapshot {
+    components: Vec<Arc<Component>>,
+    by_id: HashMap<AppId, usize>,
+    by_mimetype: HashMap<String, Vec<usize>>,
+    by_pkgname: HashMap<String, Vec<usize>>,
+    shadowed: HashMap<AppId, Vec<Arc<Component>>>,
+    replaced_by: HashMap<AppId, usize>,
+    aliases: AppIdAliases,
+}
+
+impl Snapshot {
+    fn new(collections: Vec<Collection>, aliases: &AppIdAliases) -> Self {
+        let mut snapshot = Self {
+            aliases: aliases.clone(),
+            ..Self::default()
+        };
+        for collection in collections {
+            let origin = collection.origin.as_deref().map(Arc::<str>::from);
+            for mut component in collection.components {
+                if component.origin.is_none() {
+                    component.origin = origin.clone();
+                }
+                let component = Arc::new(component);
+                // The component of highest priority wins, then the one of the
+                // first catalog loaded. Renamed components are deduplicated with
+                // the ones using their new id.
+                let id = match aliases.resolve(&component.id) {
+                    current if current == &*component.id => component.id.clone(),
+                    current => AppId::from(current),
+                };
+                match snapshot.by_id.get(&id) {
+                    Some(&index) => {
+                        let kept = &mut snapshot.components[index];
+                        let loser = if priority(&component) > priority(kept) {
+                            std::mem::replace(kept, component)
+                        } else {
+                            component
+                        };
+                        snapshot.shadowed.entry(id).or_default().push(loser);
+                    }
+                    None => {
+                        let index = snapshot.components.len();
+                        snapshot.by_id.insert(id, index);
+                        snapshot.components.push(component);
+                    }
+                }
+            }
+        }
+        for shadowed in snapshot.shadowed.values_mut() {
+            shadowed.sort_by_key(|c| std::cmp::Reverse(priority(c)));
+        }
+        for (index, component) in snapshot.components.iter().enumerate() {
+            for replaced in &component.replaces {
+                if !snapshot.by_id.contains_key(aliases.resolve(replaced)) {
+                    snapshot
+                        .replaced_by
+                        .entry(replaced.clone())
+                        .or_insert(index);
+                }
+            }
+            for mimetype in &component.mimetypes {
+                snapshot
+                    .by_mimetype
+                    .entry(mimetype.to_ascii_lowercase())
+                    .or_default()
+                    .push(index);
+            }
+            if let Some(pkgname) = &component.pkgname {
+                snapshot
+                    .by_pkgname
+                    .entry(pkgname.clone())
+                    .or_default()
+                    .push(index);
+            }
+        }
+        snapshot
+    }
+
+    /// The number of components.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Whether the snapshot has no components.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// The components, in the order they were loaded.
+    pub fn components(&self) -> &[Arc<Component>] {
+        &self.components
+    }
+
+    /// The component with a specific `AppId`, or with the same id suffixed by
+    /// `.desktop`. When none has it, the component replacing it is returned, so
+    /// renamed components are still found by their former id.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id to look for.
+    pub fn get(&self, id: &AppId) -> Option<&Arc<Component>> {
+        self.lookup(id).map(|(component, _)| component)
+    }
+
+    /// Like `get`, also telling whether the lookup was redirected to a component
+    /// with another id, the one replacing `id` or the one `id` was renamed to in
+    /// the aliases of the pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id to look for.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::{CollectionBuilder, ComponentBuilder};
+    /// use appstream::pool::Pool;
+    /// use appstream::TranslatableString;
+    ///
+    /// let component = ComponentBuilder::default()
+    ///     .id("org.gnome.TextEditor".into())
+    ///     .name(TranslatableString::with_default("Text Editor"))
+    ///     .replace("org.gnome.gedit".into())
+    ///     .build();
+    /// let collection = CollectionBuilder::new("0.14").component(component).build();
+    /// let pool = Pool::from_collections(vec![collection]);
+    ///
+    /// let snapshot = pool.snapshot();
+    /// let (component, redirected) = snapshot.lookup(&"org.gnome.gedit".into()).unwrap();
+    /// assert_eq!(component.id, "org.gnome.TextEditor".into());
+    /// assert!(redirected);
+    /// ```
+    pub fn lookup(&self, id: &AppId) -> Option<(&Arc<Component>, bool)> {
+        let desktop = format!("{}.desktop", id.as_ref());
+        let find = |ids: &HashMap<AppId, usize>, aliases: Option<&AppIdAliases>| {
+            [id.as_ref(), desktop.as_str()]
+                .iter()
+                .find_map(|&id| ids.get(aliases.map_or(id, |a| a.resolve(id))))
+                .map(|&index| &self.components[index])
+        };
+        let component =
+            find(&self.by_id, Some(&self.aliases)).or_else(|| find(&self.replaced_by, None))?;
+        let redirected = component.id != *id && *component.id != desktop;
+        Some((component, redirected))
+    }
+
+    /// The components with a specific `AppId` that lost to the one of the
+    /// snapshot, from the highest priority to the lowest, to find out which
+    /// catalog provided the component.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the component.
+    pub fn shadowed_components(&self, id: &AppId) -> &[Arc<Component>] {
+        self.shadowed
+            .get(self.aliases.resolve(id))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The components suggested or recommended by a component which are in the
+    /// snapshot, the suggestions first, like for a "You might also like" section.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - The component whose suggestions to look for.
+    pub fn resolve_suggestions(&self, component: &Component) -> Vec<&Arc<Component>> {
+        let mut found: Vec<&Arc<Component>> = vec![];
+        for id in component
+            .suggestions
+            .iter()
+            .chain(&component.recommendations)
+        {
+            if let Some(suggested) = self.get(id) {
+                let duplicate = found.iter().any(|c| Arc::ptr_eq(c, suggested));
+                if suggested.id != component.id && !duplicate {
+                    found.push(suggested);
+                }
+            }
+        }
+        found
+    }
+
+    /// Find the components that provide a specific public interface.
+    ///
+    /// # Arguments
+    ///
+    /// * `provide` - The provided item to look for.
+    pub fn find_by_provide(&self, provide: &Provide) -> Vec<&Arc<Component>> {
+        self.components
+            .iter()
+            .filter(|c| c.provides.contains(provide))
+            .collect()
+    }
+
+    /// Find the components that can handle a specific MIME type.
+    ///
+    /// # Arguments
+    ///
+    /// * `mimetype` - The MIME type, like `text/html`.
+    pub fn find_by_mimetype(&self, mimetype: &str) -> Vec<&Arc<Component>> {
+        self.by_mimetype
+            .get(&mimetype.to_ascii_lowercase())
+            .map(|indices| indices.iter().map(|&i| &self.components[i]).collect())
+            .unwrap_or_default()
+    }
+
+    /// Find the components shipped by a distribution package, to map the
+    /// installed packages to their components.
+    ///
+    /// # Arguments
+    ///
+    /// * `pkgname` - The name of the package, like `gnome-weather`.
+    pub fn find_by_pkgname(&self, pkgname: &str) -> Vec<&Arc<Component>> {
+        self.by_pkgname
+            .get(pkgname)
+            .map(|indices| indices.iter().map(|&i| &self.components[i]).collect())
+            .unwrap_or_default()
+    }
+
+    /// The available updates of installed components, with the release to update
+    /// to, see `Component::update_available`. The components missing from the
+    /// snapshot, or only replaced by another one, are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `installed` - The ids of the installed components, with their version.
+    pub fn updates_for<S: AsRef<str>>(
+        &self,
+        installed: &[(AppId, S)],
+    ) -> Vec<(&Arc<Component>, &Release)> {
+        installed
+            .iter()
+            .filter_map(|(id, version)| {
+                let (component, redirected) = self.lookup(id)?;
+                if redirected {
+                    return None;
+                }
+                Some((component, component.update_available(version.as_ref())?))
+            })
+            .collect()
+    }
+
+    /// Find the components, usually drivers, providing a `<modalias>` glob that
+    /// matches one of the modaliases of some devices.
+    ///
+    /// # Arguments
+    ///
+    /// * `modaliases` - The modaliases of the devices, like
+    ///   `usb:v1130p0202d0100dc00dsc00dp00ic03isc01ip02in00`.
+    pub fn find_by_modaliases<S: AsRef<str>>(&self, modaliases: &[S]) -> Vec<&Arc<Component>> {
+        self.components
+            .iter()
+            .filter(|c| {
+                c.provides.iter().any(|p| match p {
+                    Provide::Modalias(pattern) => {
+                        modaliases.iter().any(|m| glob_matches(m.as_ref(), pattern))
+                    }
+                    _ => false,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The priority of a component, `0` when unset.
+fn priority(component: &Component) -> i32 {
+    component.priority.unwrap_or_default()
+}
+
+#[derive(Debug, Default)]
+/// The components of several catalogs, indexed for lookups and shareable between
+/// threads.
+///
+/// A pool is `Send` and `Sync`: queries go through a `Snapshot`, taken under a
+/// read lock held only for cloning an `Arc`, and `refresh` builds the new indices
+/// without any lock before swapping them in at once. Readers thus never block
+/// each other and always see either the old or the new catalogs, never a mix.
+///
+/// # Example
+///
+/// ```
+/// use appstream::pool::Pool;
+/// use appstream::Collection;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into()).unwrap();
+/// let pool = Arc::new(Pool::from_collections(vec![collection]));
+///
+/// let reader = Arc::clone(&pool);
+/// let found = thread::spawn(move || reader.get(&"org.gnome.Characters".into()).is_some());
+/// assert!(found.join().unwrap());
+/// ```
+pub struct Pool {
+    snapshot: RwLock<Arc<Snapshot>>,
+    aliases: AppIdAliases,
+}
+
+impl Pool {
+    /// Creates an empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a pool from catalogs.
+    ///
+    /// # Arguments
+    ///
+    /// * `collections` - The catalogs, from the most to the least preferred
+    ///   origin. When several components share an id the one of highest
+    ///   priority is kept, then the one of the first catalog.
+    pub fn from_collections(collections: Vec<Collection>) -> Self {
+        Self::from_collections_with_aliases(collections, AppIdAliases::default())
+    }
+
+    /// Creates a pool from catalogs, with a table of renamed ids applied to the
+    /// lookups and the deduplication, also when the pool is refreshed.
+    ///
+    /// # Arguments
+    ///
+    /// * `collections` - The catalogs, see `from_collections`.
+    /// * `aliases` - The renamed ids.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use appstream::pool::Pool;
+    /// use appstream::{AppIdAliases, Collection};
+    ///
+    /// let aliases = AppIdAliases::from_path("/usr/share/app-info/renames.txt".into()).unwrap();
+    /// let flathub = Collection::from_path("/var/lib/flatpak/appstream/flathub/x86_64/active/appstream.xml".into()).unwrap();
+    /// let pool = Pool::from_collections_with_aliases(vec![flathub], aliases);
+    /// assert!(pool.get(&"gedit.desktop".into()).is_some());
+    /// ```
+    pub fn from_collections_with_aliases(
+        collections: Vec<Collection>,
+        aliases: AppIdAliases,
+    ) -> Self {
+        Self {
+            snapshot: RwLock::new(Arc::new(Snapshot::new(collections, &aliases))),
+            aliases,
+        }
+    }
+
+    /// The current state of the pool, unaffected by later refreshes.
+    pub fn snapshot(&self) -> Arc<Snapshot> {
+        // The snapshot is only ever replaced as a whole, so it's still
+        // consistent if a writer panicked.
+        let snapshot = self.snapshot.read().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(&snapshot)
+    }
+
+    /// Replaces the components of the pool with the ones of new catalogs,
+    /// atomically for the readers, and returns the previous state.
+    ///
+    /// # Arguments
+    ///
+    /// * `collections` - The new catalogs.
+    pub fn refresh(&self, collections: Vec<Collection>) -> Arc<Snapshot> {
+        let snapshot = Arc::new(Snapshot::new(collections, &self.aliases));
+        let mut current = self.snapshot.write().unwrap_or_else(|e| e.into_inner());
+        std::mem::replace(&mut current, snapshot)
+    }
+
+    /// The number of components.
+    pub fn len(&self) -> usize {
+        self.snapshot().len()
+    }
+
+    /// Whether the pool has no components.
+    pub fn is_empty(&self) -> bool {
+        self.snapshot().is_empty()
+    }
+
+    /// The component with a specific `AppId`, see `Snapshot::get`.
+    pub fn get(&self, id: &AppId) -> Option<Arc<Component>> {
+        self.snapshot().get(id).cloned()
+    }
+
+    /// The component with a specific `AppId`, telling whether the lookup was
+    /// redirected to the component replacing it, see `Snapshot::lookup`.
+    pub fn lookup(&self, id: &AppId) -> Option<(Arc<Component>, bool)> {
+        self.snapshot()
+            .lookup(id)
+            .map(|(component, redirected)| (Arc::clone(component), redirected))
+    }
+
+    /// The components with a specific `AppId` that lost to the one of the pool,
+    /// see `Snapshot::shadowed_components`.
+    pub fn shadowed_components(&self, id: &AppId) -> Vec<Arc<Component>> {
+        self.snapshot().shadowed_components(id).to_vec()
+    }
+
+    /// The components suggested or recommended by a component, see
+    /// `Snapshot::resolve_suggestions`.
+    pub fn resolve_suggestions(&self, component: &Component) -> Vec<Arc<Component>> {
+        self.snapshot()
+            .resolve_suggestions(component)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Find the components that provide a specific public interface, see
+    /// `Snapshot::find_by_provide`.
+    pub fn find_by_provide(&self, provide: &Provide) -> Vec<Arc<Component>> {
+        self.snapshot()
+            .find_by_provide(provide)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Find the components that can handle a specific MIME type, see
+    /// `Snapshot::find_by_mimetype`.
+    pub fn find_by_mimetype(&self, mimetype: &str) -> Vec<Arc<Component>> {
+        self.snapshot()
+            .find_by_mimetype(mimetype)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Find the components shipped by a distribution package, see
+    /// `Snapshot::find_by_pkgname`.
+    pub fn find_by_pkgname(&self, pkgname: &str) -> Vec<Arc<Component>> {
+        self.snapshot()
+            .find_by_pkgname(pkgname)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Find the components matching the modaliases of some devices, see
+    /// `Snapshot::find_by_modaliases`.
+    pub fn find_by_modaliases<S: AsRef<str>>(&self, modaliases: &[S]) -> Vec<Arc<Component>> {
+        self.snapshot()
+            .find_by_modaliases(modaliases)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// The available updates of installed components, see `Snapshot::updates_for`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::pool::Pool;
+    /// use appstream::Collection;
+    ///
+    /// let collection = Collection::from_path("./tests/collections/spec_example.xml".into()).unwrap();
+    /// let pool = Pool::from_collections(vec![collection]);
+    /// for (component, release) in pool.updates_for(&[("org.mozilla.Firefox".into(), "1.0")]) {
+    ///     println!("{} {}", component.id.as_ref(), release.version);
+    /// }
+    /// ```
+    pub fn updates_for<S: AsRef<str>>(
+        &self,
+        installed: &[(AppId, S)],
+    ) -> Vec<(Arc<Component>, Release)> {
+        self.snapshot()
+            .updates_for(installed)
+            .into_iter()
+            .map(|(component, release)| (Arc::clone(component), release.clone()))
+            .collect()
+    }
+
+    #[cfg(feature = "linux")]
+    /// Find the driver components for the hardware of the running system, whose
+    /// modaliases are read from `/sys/devices`.
+    pub fn find_drivers_for_system(&self) -> Result<Vec<Arc<Component>>, ParseError> {
+        Ok(self.find_by_modaliases(&modalias::system_modaliases()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::{CollectionBuilder, ComponentBuilder};
+    use crate::TranslatableString;
+    use std::convert::TryFrom;
+    use std::error::Error;
+    use std::thread;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn concurrent_reads() -> Result<(), Box<dyn Error>> {
+        assert_send_sync::<Pool>();
+        assert_send_sync::<Snapshot>();
+
+        let gnome = Collection::from_path("./tests/collections/gnome-apps.xml".into())?;
+        let mut flathub = gnome.clone();
+        flathub.origin = Some("flathub".into());
+        flathub.components.truncate(5);
+
+        let pool = Arc::new(Pool::from_collections(vec![gnome.clone(), flathub.clone()]));
+        assert_eq!(pool.len(), gnome.components.len());
+        let characters = pool
+            .get(&"org.gnome.Characters".into())
+            .ok_or("no component")?;
+        assert_eq!(characters.origin.as_deref(), gnome.origin.as_deref());
+        assert_eq!(
+            pool.find_by_mimetype("TEXT/PLAIN").len(),
+            gnome.find_by_mimetype("text/plain").len()
+        );
+
+        let readers = (0..4)
+            .map(|_| {
+                let pool = Arc::clone(&pool);
+                let (big, small) = (gnome.components.len(), flathub.components.len());
+                thread::spawn(move || {
+                    for _ in 0..200 {
+                        let snapshot = pool.snapshot();
+                        assert!(snapshot.len() == big || snapshot.len() == small);
+                        let origin = snapshot.components()[0].origin.clone();
+                        assert!(snapshot.components().iter().all(|c| c.origin == origin));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for i in 0..50 {
+            let collections = if i % 2 == 0 {
+                vec![flathub.clone()]
+            } else {
+                vec![gnome.clone()]
+            };
+            pool.refresh(collections);
+        }
+        for reader in readers {
+            reader.join().map_err(|_| "reader panicked")?;
+        }
+
+        let old = pool.refresh(Vec::new());
+        assert_eq!(old.len(), gnome.components.len());
+        assert!(pool.is_empty());
+        assert!(old.get(&"org.gnome.Characters".into()).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn find_by_pkgname() -> Result<(), Box<dyn Error>> {
+        let collection =
+            Collection::from_path("./tests/collections/fedora-other-repos.xml".into())?;
+        let pool = Pool::from_collections(vec![collection.clone()]);
+        for component in &collection.components {
+            let pkgname = component.pkgname.as_deref().ok_or("no package name")?;
+            let found = pool.find_by_pkgname(pkgname);
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].id, component.id);
+            assert_eq!(collection.find_by_pkgname(pkgname)[0].id, component.id);
+        }
+        assert!(pool.find_by_pkgname("rpmfusion").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn find_drivers() -> Result<(), Box<dyn Error>> {
+        let driver = Component::from_path("./tests/driver.xml".into())?;
+        let collection = CollectionBuilder::new("0.14").component(driver).build();
+        let pool = Pool::from_collections(vec![collection]);
+
+        let nvidia = "pci:v000010DEd00001C82sv00001043sd000085BEbc03sc00i00";
+        let intel = "pci:v00008086d0000A36Dsv00001028sd0000085Abc0Csc03i30";
+        assert_eq!(pool.find_by_modaliases(&[intel, nvidia]).len(), 1);
+        assert!(pool.find_by_modaliases(&[intel]).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn available_updates() -> Result<(), Box<dyn Error>> {
+        let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into())?;
+        let pool = Pool::from_collections(vec![collection]);
+        let (id, latest) = pool
+            .snapshot()
+            .components()
+            .iter()
+            .find_map(|c| Some((c.id.clone(), c.latest_stable_release()?.version.clone())))
+            .ok_or("no release")?;
+
+        let installed = vec![
+            (id.clone(), "0.0.1".to_string()),
+            ("org.example.Missing".into(), "0.0.1".to_string()),
+        ];
+        let updates = pool.updates_for(&installed);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].0.id, id);
+        assert_eq!(updates[0].1.version, latest);
+        assert!(pool.updates_for(&[(id, latest)]).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn priority_dedup() -> Result<(), Box<dyn Error>> {
+        let catalog = |origin: &str, priority: Option<i32>| {
+            let mut component = ComponentBuilder::default()
+                .id("org.example.App".into())
+                .name(TranslatableString::with_default(origin));
+            if let Some(priority) = priority {
+                component = component.priority(priority);
+            }
+            CollectionBuilder::new("0.14")
+                .origin(origin)
+                .component(component.build())
+                .build()
+        };
+        let id = AppId::from("org.example.App");
+        let origin = |c: &Arc<Component>| c.origin.as_deref().map(String::from);
+
+        let pool = Pool::from_collections(vec![
+            catalog("debian", None),
+            catalog("flathub", Some(10)),
+            catalog("flathub-beta", Some(10)),
+            catalog("local", Some(-5)),
+        ]);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(
+            pool.get(&id).as_ref().and_then(origin),
+            Some("flathub".into())
+        );
+        assert_eq!(
+            pool.shadowed_components(&id)
+                .iter()
+                .map(origin)
+                .collect::<Vec<_>>(),
+            vec![
+                Some("flathub-beta".into()),
+                Some("debian".into()),
+                Some("local".into())
+            ]
+        );
+
+        let pool =
+            Pool::from_collections(vec![catalog("debian", None), catalog("local", Some(-5))]);
+        assert_eq!(
+            pool.get(&id).as_ref().and_then(origin),
+            Some("debian".into())
+        );
+        assert!(pool
+            .shadowed_components(&"org.example.Missing".into())
+            .is_empty());
+
+        let parse = |xml: &str| Component::try_from(&xmltree::Element::parse(xml.as_bytes())?);
+        let component = parse(r#"<component priority="-3"><id>a</id><name>A</name></component>"#)?;
+        assert_eq!(component.priority, Some(-3));
+        let invalid = r#"<component priority="high"><id>a</id><name>A</name></component>"#;
+        assert!(parse(invalid).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn suggestions() -> Result<(), Box<dyn Error>> {
+        let component = Component::try_from(&xmltree::Element::parse(
+            r#"<component>
+                <id>org.gnome.Maps</id>
+                <name>Maps</name>
+                <suggests><id>org.gnome.Dictionary</id><id>org.example.Missing</id></suggests>
+                <recommends>
+                    <memory>2048</memory>
+                    <id>org.gnome.Characters</id>
+                    <id>org.gnome.Dictionary</id>
+                    <id>org.gnome.Maps</id>
+                </recommends>
+            </component>"#
+                .as_bytes(),
+        )?)?;
+        assert_eq!(
+            component.recommendations,
+            vec![
+                "org.gnome.Characters".into(),
+                "org.gnome.Dictionary".into(),
+                "org.gnome.Maps".into()
+            ]
+        );
+
+        let gnome = Collection::from_path("./tests/collections/gnome-apps.xml".into())?;
+        let catalog = CollectionBuilder::new("0.14")
+            .component(component.clone())
+            .build();
+        let pool = Pool::from_collections(vec![catalog, gnome]);
+        let ids = pool
+            .resolve_suggestions(&component)
+            .iter()
+            .map(|c| c.id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            vec![
+                "org.gnome.Dictionary.desktop".into(),
+                "org.gnome.Characters.desktop".into()
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn replaced_components() -> Result<(), Box<dyn Error>> {
+        let editor = Component::try_from(&xmltree::Element::parse(
+            r#"<component>
+                <id>org.gnome.TextEditor</id>
+                <name>Text Editor</name>
+                <replaces><id>org.gnome.gedit</id><id>org.gnome.Characters</id></replaces>
+                <releases><release version="45.0"/></releases>
+            </component>"#
+                .as_bytes(),
+        )?)?;
+        assert_eq!(
+            editor.replaces,
+            vec!["org.gnome.gedit".into(), "org.gnome.Characters".into()]
+        );
+        let mut gnome = Collection::from_path("./tests/collections/gnome-apps.xml".into())?;
+        gnome
+            .components
+            .retain(|c| c.id != "org.gnome.gedit.desktop".into());
+        let catalog = CollectionBuilder::new("0.14").component(editor).build();
+        let pool = Pool::from_collections(vec![catalog, gnome]);
+
+        let (found, redirected) = pool.lookup(&"org.gnome.gedit".into()).ok_or("not found")?;
+        assert_eq!(found.id, "org.gnome.TextEditor".into());
+        assert!(redirected);
+        let (found, redirected) = pool
+            .lookup(&"org.gnome.TextEditor".into())
+            .ok_or("not found")?;
+        assert_eq!(found.id, "org.gnome.TextEditor".into());
+        assert!(!redirected);
+        // An existing component is never shadowed by the one replacing it.
+        assert_eq!(
+            pool.get(&"org.gnome.Characters".into())
+                .map(|c| c.id.clone()),
+            Some("org.gnome.Characters.desktop".into())
+        );
+        assert!(pool
+            .updates_for(&[("org.gnome.gedit".into(), "3.0")])
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn aliased_components() -> Result<(), Box<dyn Error>> {
+        let component = |id: &str, priority: i32| {
+            ComponentBuilder::default()
+                .id(id.into())
+                .name(TranslatableString::with_default(id))
+                .priority(priority)
+                .build()
+        };
+        let debian = CollectionBuilder::new("0.14")
+            .origin("debian")
+            .component(component("gedit.desktop", 0))
+            .component(component("org.example.Old", 0))
+            .build();
+        let flathub = CollectionBuilder::new("0.14")
+            .origin("flathub")
+            .component(component("org.gnome.gedit", 10))
+            .build();
+        let aliases = "gedit.desktop org.gnome.gedit\norg.example.Old org.example.New\n".parse()?;
+        let pool = Pool::from_collections_with_aliases(vec![debian, flathub], aliases);
+        assert_eq!(pool.len(), 2);
+
+        let (found, redirected) = pool.lookup(&"gedit".into()).ok_or("not found")?;
+        assert_eq!(found.id, "org.gnome.gedit".into());
+        assert!(redirected);
+        assert_eq!(pool.shadowed_components(&"gedit.desktop".into()).len(), 1);
+        let (found, redirected) = pool.lookup(&"org.example.New".into()).ok_or("not found")?;
+        assert_eq!(found.id, "org.example.Old".into());
+        assert!(redirected);
+        let (_, redirected) = pool.lookup(&"org.example.Old".into()).ok_or("not found")?;
+        assert!(!redirected);
+
+        // The aliases are kept when refreshing.
+        let flathub = CollectionBuilder::new("0.14")
+            .component(component("org.example.New", 0))
+            .build();
+        pool.refresh(vec![flathub]);
+        let found = pool.get(&"org.example.Old".into()).ok_or("not found")?;
+        assert_eq!(found.id, "org.example.New".into());
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use xmltree::Element;
+use yaml_rust::Yaml;
+
+#[derive(Clone, Debug, PartialEq)]
+/// A child tag of a component, or a key of a DEP-11 component, that the crate
+/// doesn't model, kept unparsed to read the tags newer than the crate.
+///
+/// # Example
+///
+/// ```
+/// use appstream::raw::RawNode;
+/// use appstream::Component;
+/// use std::convert::TryFrom;
+///
+/// let xml = r#"<component>
+///     <id>org.example.App</id>
+///     <name>App</name>
+///     <sponsor>Example Foundation</sponsor>
+/// </component>"#;
+/// let element = appstream::xmltree::Element::parse(xml.as_bytes()).unwrap();
+/// let component = Component::try_from(&element).unwrap();
+/// match component.unknown_node("sponsor") {
+///     Some(RawNode::Xml(sponsor)) => {
+///         assert_eq!(sponsor.get_text().unwrap(), "Example Foundation")
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+pub enum RawNode {
+    /// A child element of a `<component>`.
+    Xml(Element),
+    /// A key of a DEP-11 component, with its value.
+    Yaml(String, Yaml),
+}
+
+impl RawNode {
+    /// The name of the tag, or the key.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Xml(element) => &element.name,
+            Self::Yaml(key, _) => key,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Collection;
+    use std::convert::TryFrom;
+    use std::error::Error;
+    use yaml_rust::YamlLoader;
+
+    #[test]
+    fn unknown_nodes() -> Result<(), Box<dyn Error>> {
+        let xml = r#"<components version="0.14">
+            <component>
+                <id>org.example.App</id>
+                <name>App</name>
+                <sponsor url="https://example.org">Example Foundation</sponsor>
+            </component>
+        </components>"#;
+        let collection = Collection::try_from(&Element::parse(xml.as_bytes())?)?;
+        let component = &collection.components[0];
+        assert_eq!(component.unknown_nodes.len(), 1);
+        match component.unknown_node("sponsor") {
+            Some(RawNode::Xml(e)) => {
+                assert_eq!(e.attributes["url"], "https://example.org");
+            }
+            node => panic!("Unexpected node {:?}", node),
+        }
+
+        let yaml = YamlLoader::load_from_str(
+            "---\nFile: DEP-11\nVersion: '0.14'\nOrigin: example\nMediaBaseUrl: ''\n\
+             ---\nID: org.example.App\nType: desktop-application\nName:\n  C: App\n\
+             Sponsor:\n  name: Example Foundation\n",
+        )?;
+        let collection = Collection::try_from(&yaml)?;
+        let component = &collection.components[0];
+        assert_eq!(component.unknown_nodes.len(), 1);
+        match component.unknown_node("Sponsor") {
+            Some(RawNode::Yaml(_, value)) => {
+                assert_eq!(value["name"].as_str(), Some("Example Foundation"));
+            }
+            node => panic!("Unexpected node {:?}", node),
+        }
+
+        // The unknown nodes aren't serialized, nor compared.
+        let mut stripped = component.clone();
+        stripped.unknown_nodes.clear();
+        assert_eq!(&stripped, component);
+        Ok(())
+    }
+}
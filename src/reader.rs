@@ -0,0 +1,567 @@
+use super::error::{ParseError, ParseWarning};
+use super::interner;
+use super::options::ParseOptions;
+use super::warnings;
+use super::{Collection, Component};
+use quick_xml::escape::{resolve_predefined_entity, unescape, EscapeError};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::NamespaceError;
+use quick_xml::Reader;
+use std::cell::Cell;
+use std::convert::TryFrom;
+use std::io::{self, BufRead, BufReader, Read};
+use std::rc::Rc;
+use xmltree::{Element, Namespace, XMLNode};
+use yaml_rust::{Yaml, YamlLoader};
+
+/// Reads a catalog one component at a time.
+///
+/// Only the tree of the component being converted is kept in memory instead of
+/// the tree of the whole catalog, which is what dominates the peak memory usage
+/// when loading big catalogs like Flathub's. The elements are built the same way
+/// `Element::parse` builds them, so the conversion code is shared, except that
+/// the prefixes of the attributes other than `xml:lang` are kept.
+///
+/// The children of the components skipped by `options` aren't built at all, and
+/// the limits of `options` are checked while reading, as well as the schema if
+/// `options` asks for it.
+pub(crate) fn read_collection<R: Read>(
+    reader: R,
+    options: &ParseOptions,
+) -> Result<Collection, ParseError> {
+    read_collection_filtered(reader, options, |_| true)
+}
+
+/// Like `read_collection`, but only converts the components whose element
+/// matches `filter`.
+pub(crate) fn read_collection_filtered<R: Read, F: Fn(&Element) -> bool>(
+    reader: R,
+    options: &ParseOptions,
+    filter: F,
+) -> Result<Collection, ParseError> {
+    limited(reader, options, |mut events| {
+        let root = root(&mut events)?;
+        options.check_schema(&root)?;
+        let mut collection = Collection::try_from(&root)?;
+        let mut count = 0;
+        interner::scope(|| -> Result<(), ParseError> {
+            loop {
+                match events.next()? {
+                    Node::Start(element) if element.name == "component" => {
+                        count += 1;
+                        options.check_components(count)?;
+                        let element = build(&mut events, element, options, 0)?;
+                        options.check_schema(&element)?;
+                        if filter(&element) {
+                            collection.components.push(Component::try_from(&element)?);
+                        }
+                    }
+                    Node::Start(element) => {
+                        let mut unexpected = root.clone();
+                        let name = element.name.clone();
+                        unexpected.children.push(XMLNode::Element(element));
+                        options.check_schema(&unexpected)?;
+                        warnings::warn(ParseWarning::unknown_tag(&name, "components"));
+                        events.skip()?
+                    }
+                    Node::End => return Ok(()),
+                    Node::Eof => return Err(xmltree::ParseError::CannotParse.into()),
+                    _ => (),
+                }
+            }
+        })?;
+        Ok(collection)
+    })
+}
+
+/// Reads a single component document, checking the limits of `options`, and the
+/// schema if it asks for it.
+pub(crate) fn read_component<R: Read>(
+    reader: R,
+    options: &ParseOptions,
+) -> Result<Component, ParseError> {
+    limited(reader, options, |mut events| {
+        let root = root(&mut events)?;
+        let root = build(&mut events, root, options, 0)?;
+        options.check_schema(&root)?;
+        Component::try_from(&root)
+    })
+}
+
+/// Fails the reads going over a number of bytes, and records it.
+struct Limited<R> {
+    inner: R,
+    remaining: u64,
+    exceeded: Rc<Cell<bool>>,
+}
+
+impl<R: Read> Read for Limited<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // One more byte is read to tell a document of exactly the limit apart.
+        let len = usize::try_from(self.remaining.saturating_add(1)).unwrap_or(usize::MAX);
+        let len = buf.len().min(len);
+        let n = self.inner.read(&mut buf[..len])?;
+        if n as u64 > self.remaining {
+            self.exceeded.set(true);
+            return Err(io::Error::other("decompressed bytes limit exceeded"));
+        }
+        self.remaining -= n as u64;
+        Ok(n)
+    }
+}
+
+/// Runs `f` on the nodes of `reader`, reporting the errors caused by going over
+/// the decompressed bytes limit of `options` as such, the XML parser hides them.
+fn limited<R: Read, T>(
+    reader: R,
+    options: &ParseOptions,
+    f: impl FnOnce(Events<'_, BufReader<Limited<R>>>) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    let limit = options.decompressed_bytes_limit();
+    let exceeded = Rc::new(Cell::new(false));
+    let reader = Limited {
+        inner: reader,
+        remaining: limit.unwrap_or(u64::MAX),
+        exceeded: Rc::clone(&exceeded),
+    };
+    f(Events::new(BufReader::new(reader), options)).map_err(|e| match limit {
+        Some(limit) if exceeded.get() => ParseError::limit_exceeded("decompressed bytes", limit),
+        _ => e,
+    })
+}
+
+/// Reads the YAML documents of a DEP-11 catalog, checking the limits of
+/// `options` on decompressed bytes and strings.
+pub(crate) fn read_yaml<R: Read>(
+    reader: R,
+    options: &ParseOptions,
+) -> Result<Vec<Yaml>, ParseError> {
+    let limit = options.decompressed_bytes_limit();
+    let mut reader = Limited {
+        inner: reader,
+        remaining: limit.unwrap_or(u64::MAX),
+        exceeded: Rc::new(Cell::new(false)),
+    };
+    let mut s = String::new();
+    if let Err(e) = reader.read_to_string(&mut s) {
+        return Err(match limit {
+            Some(limit) if reader.exceeded.get() => {
+                ParseError::limit_exceeded("decompressed bytes", limit)
+            }
+            _ => e.into(),
+        });
+    }
+    let documents = YamlLoader::load_from_str(&s)
+        .map_err(|e| ParseError::invalid_value(&e.to_string(), "$value", "collection"))?;
+
+    // The documents can be nested deeply, they are walked without recursing.
+    let mut values = documents.iter().collect::<Vec<&Yaml>>();
+    while let Some(value) = values.pop() {
+        match value {
+            Yaml::String(s) => options.check_string(s)?,
+            Yaml::Array(items) => values.extend(items),
+            Yaml::Hash(hash) => hash.iter().for_each(|(key, value)| {
+                values.push(key);
+                values.push(value);
+            }),
+            _ => (),
+        }
+    }
+    Ok(documents)
+}
+
+/// Reads up to the root element.
+fn root<R: BufRead>(events: &mut Events<'_, R>) -> Result<Element, ParseError> {
+    loop {
+        match events.next()? {
+            Node::Start(element) => return Ok(element),
+            Node::End | Node::Eof => return Err(xmltree::ParseError::CannotParse.into()),
+            _ => (),
+        }
+    }
+}
+
+/// A node of the document, as `Element::parse` keeps it.
+enum Node {
+    /// An element started, without its children yet.
+    Start(Element),
+    /// The last started element ended.
+    End,
+    /// Some text, with its references resolved and without the whitespace-only
+    /// runs between elements.
+    Text(String),
+    Comment(String),
+    ProcessingInstruction(String, Option<String>),
+    Eof,
+}
+
+/// Pulls the nodes of a document out of a quick-xml reader, resolving the
+/// namespaces and references it leaves to its users.
+struct Events<'a, R> {
+    reader: Reader<R>,
+    options: &'a ParseOptions,
+    buf: Vec<u8>,
+    /// The namespaces in scope of the open elements, and whether they only
+    /// have the default mappings.
+    namespaces: Vec<(Rc<Namespace>, bool)>,
+    /// The text read so far, resolved references included, and whether it is
+    /// only whitespace.
+    text: String,
+    whitespace: bool,
+    /// The node read after some text, returned after it.
+    pending: Option<Node>,
+}
+
+impl<'a, R: BufRead> Events<'a, R> {
+    fn new(reader: R, options: &'a ParseOptions) -> Self {
+        let mut reader = Reader::from_reader(reader);
+        reader.config_mut().expand_empty_elements = true;
+        let mut namespace = Namespace::empty();
+        namespace.put("", "");
+        namespace.put("xml", "http://www.w3.org/XML/1998/namespace");
+        namespace.put("xmlns", "http://www.w3.org/2000/xmlns/");
+        Self {
+            reader,
+            options,
+            buf: Vec::new(),
+            namespaces: vec![(Rc::new(namespace), true)],
+            text: String::new(),
+            whitespace: true,
+            pending: None,
+        }
+    }
+
+    fn next(&mut self) -> Result<Node, ParseError> {
+        if let Some(node) = self.pending.take() {
+            return Ok(node);
+        }
+        loop {
+            self.buf.clear();
+            let node = match self.reader.read_event_into(&mut self.buf)? {
+                Event::Text(e) => {
+                    let text = e.decode().map_err(quick_xml::Error::from)?;
+                    self.whitespace &= text.chars().all(char::is_whitespace);
+                    self.text.push_str(&text);
+                    continue;
+                }
+                Event::GeneralRef(e) => {
+                    let c = match e.resolve_char_ref()? {
+                        Some(c) => c.encode_utf8(&mut [0; 4]).to_owned(),
+                        None => {
+                            let name = e.decode().map_err(quick_xml::Error::from)?;
+                            match resolve_predefined_entity(&name) {
+                                Some(entity) => entity.to_owned(),
+                                None => {
+                                    let range = 0..name.len();
+                                    let e = EscapeError::UnrecognizedEntity(range, name.into());
+                                    return Err(quick_xml::Error::from(e).into());
+                                }
+                            }
+                        }
+                    };
+                    self.whitespace = false;
+                    self.text.push_str(&c);
+                    continue;
+                }
+                // CDATA sections are kept apart from the text around them.
+                Event::CData(e) => Node::Text(e.decode().map_err(quick_xml::Error::from)?.into()),
+                Event::Start(e) => {
+                    let element = new_element(&e, &mut self.namespaces, self.options)?;
+                    Node::Start(element)
+                }
+                Event::End(_) => {
+                    self.namespaces.pop();
+                    Node::End
+                }
+                Event::Comment(e) => {
+                    Node::Comment(e.decode().map_err(quick_xml::Error::from)?.into())
+                }
+                Event::PI(e) => {
+                    let decoder = self.reader.decoder();
+                    let name = decoder.decode(e.target()).map_err(quick_xml::Error::from)?;
+                    let data = decoder
+                        .decode(e.content())
+                        .map_err(quick_xml::Error::from)?;
+                    let data = data.trim_start();
+                    Node::ProcessingInstruction(
+                        name.into(),
+                        Some(data.to_owned()).filter(|d| !d.is_empty()),
+                    )
+                }
+                Event::Decl(_) | Event::DocType(_) => continue,
+                Event::Empty(_) => unreachable!("The empty elements are expanded"),
+                Event::Eof => Node::Eof,
+            };
+            if self.text.is_empty() {
+                return Ok(node);
+            }
+            let text = std::mem::take(&mut self.text);
+            let whitespace = std::mem::replace(&mut self.whitespace, true);
+            if whitespace {
+                return Ok(node);
+            }
+            self.pending = Some(node);
+            return Ok(Node::Text(text));
+        }
+    }
+
+    /// Reads up to the end of the element that just started.
+    fn skip(&mut self) -> Result<(), ParseError> {
+        let mut depth = 1;
+        while depth > 0 {
+            self.buf.clear();
+            match self.reader.read_event_into(&mut self.buf)? {
+                Event::Start(_) => depth += 1,
+                Event::End(_) => depth -= 1,
+                Event::Eof => return Err(xmltree::ParseError::CannotParse.into()),
+                _ => (),
+            }
+        }
+        self.namespaces.pop();
+        Ok(())
+    }
+}
+
+/// Builds the element of a start tag, pushing the namespaces in its scope.
+fn new_element(
+    start: &BytesStart<'_>,
+    namespaces: &mut Vec<(Rc<Namespace>, bool)>,
+    options: &ParseOptions,
+) -> Result<Element, ParseError> {
+    let decode = |bytes: &[u8]| -> Result<String, ParseError> {
+        let s = start
+            .decoder()
+            .decode(bytes)
+            .map_err(quick_xml::Error::from)?;
+        Ok(s.into_owned())
+    };
+
+    let (mut namespace, mut essentially_empty) = namespaces
+        .last()
+        .cloned()
+        .unwrap_or_else(|| (Rc::new(Namespace::empty()), true));
+    let mut attributes = Vec::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        let key = decode(attribute.key.as_ref())?;
+        let value = decode(&attribute.value)?;
+        let value = unescape(&value)
+            .map_err(quick_xml::Error::from)?
+            .into_owned();
+        options.check_string(&value)?;
+        match key.split_once(':') {
+            _ if key == "xmlns" => {
+                Rc::make_mut(&mut namespace).force_put("", value);
+                essentially_empty = false;
+            }
+            Some(("xmlns", prefix)) => {
+                Rc::make_mut(&mut namespace).force_put(prefix, value);
+                essentially_empty = false;
+            }
+            // `xml:lang` is looked up by its local name, like `Element::parse`
+            // keys it, the other prefixes are kept for the unknown elements.
+            Some(("xml", "lang")) => attributes.push(("lang".to_owned(), value)),
+            Some((prefix, _)) => {
+                if !namespace.contains(prefix) {
+                    let e = NamespaceError::UnknownPrefix(prefix.into());
+                    return Err(quick_xml::Error::from(e).into());
+                }
+                attributes.push((key, value))
+            }
+            None => attributes.push((key, value)),
+        }
+    }
+
+    let name = decode(start.name().as_ref())?;
+    let (prefix, name) = match name.split_once(':') {
+        Some((prefix, name)) => (Some(prefix.to_owned()), name.to_owned()),
+        None => (None, name),
+    };
+    let uri = match namespace.get(prefix.as_deref().unwrap_or("")) {
+        Some("") => None,
+        Some(uri) => Some(uri.to_owned()),
+        None => {
+            let e = NamespaceError::UnknownPrefix(prefix.unwrap_or_default().into());
+            return Err(quick_xml::Error::from(e).into());
+        }
+    };
+    let element = Element {
+        prefix,
+        namespace: uri,
+        namespaces: if essentially_empty {
+            None
+        } else {
+            Some(Namespace::clone(&namespace))
+        },
+        name,
+        attributes: attributes.into_iter().collect(),
+        children: Vec::new(),
+    };
+    namespaces.push((namespace, essentially_empty));
+    Ok(element)
+}
+
+/// Reads the children of `element` up to its end tag, `depth` levels below the
+/// component, leaving out the ones skipped by `options`.
+fn build<R: BufRead>(
+    events: &mut Events<'_, R>,
+    mut element: Element,
+    options: &ParseOptions,
+    depth: usize,
+) -> Result<Element, ParseError> {
+    let mut elements = 0;
+    loop {
+        match events.next()? {
+            Node::End => return Ok(element),
+            Node::Start(child) => {
+                if depth == 0 && options.skips_tag(&child.name) {
+                    events.skip()?;
+                    continue;
+                }
+                let child = build(events, child, options, depth + 1)?;
+                element.children.push(XMLNode::Element(child));
+                elements += 1;
+                if depth == 1 {
+                    options.check_children(&element.name, elements)?;
+                }
+            }
+            Node::Text(s) => {
+                options.check_string(&s)?;
+                element.children.push(XMLNode::Text(s))
+            }
+            Node::Comment(s) => element.children.push(XMLNode::Comment(s)),
+            Node::ProcessingInstruction(name, data) => element
+                .children
+                .push(XMLNode::ProcessingInstruction(name, data)),
+            Node::Eof => return Err(xmltree::ParseError::CannotParse.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    #[test]
+    fn same_as_tree() -> Result<(), Box<dyn Error>> {
+        for path in [
+            "./tests/collections/spec_example.xml",
+            "./tests/collections/gnome-apps.xml",
+            "./tests/collections/kde-apps.xml",
+            "./tests/collections/flathub-old.xml",
+        ]
+        .iter()
+        {
+            let tree = Collection::try_from(&Element::parse(BufReader::new(File::open(path)?))?)?;
+            let streamed =
+                read_collection(BufReader::new(File::open(path)?), &ParseOptions::default())?;
+            assert_eq!(tree, streamed, "{}", path);
+        }
+
+        assert!(read_collection(
+            "<components version='0.14'><component>".as_bytes(),
+            &ParseOptions::default(),
+        )
+        .is_err());
+        assert!(read_collection(
+            "<components><component/></components>".as_bytes(),
+            &ParseOptions::default(),
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn same_elements() -> Result<(), Box<dyn Error>> {
+        let xml = "<?xml version='1.0' encoding='UTF-8'?>
+            <!-- A comment -->
+            <component type='desktop' xmlns:ex='https://example.org/ns'>
+                <name xml:lang='fr'>Caf&#233; &amp; th&#xe9;</name>
+                <summary>  Some <![CDATA[<b>raw</b>]]> text\r\n  </summary>
+                <?app-hint data?>
+                <ex:extra key='a\tb &#9;c'><!-- note --><ex:empty/></ex:extra>
+                <description><p>Mixed <em>content</em> &lt;here&gt;</p></description>
+            </component>";
+        let tree = Element::parse(xml.as_bytes())?;
+        let options = ParseOptions::default();
+        let built = limited(xml.as_bytes(), &options, |mut events| {
+            let root = root(&mut events)?;
+            build(&mut events, root, &options, 0)
+        })?;
+        assert_eq!(built, tree);
+
+        for xml in [
+            "<component><name>A &unknown; entity</name></component>",
+            "<component><name>A</summary></component>",
+            "<component><ex:name>A</ex:name></component>",
+            "<component><name ex:lang='fr'>A</name></component>",
+        ] {
+            assert!(read_component(xml.as_bytes(), &options).is_err(), "{}", xml);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn limits() -> Result<(), Box<dyn Error>> {
+        let xml = "<components version='0.14'>
+            <component><id>a</id><name>A</name>
+                <releases><release version='2'/><release version='1'/></releases>
+            </component>
+            <component><id>b</id><name lang='fr'>Bé</name>
+                <screenshots><screenshot><image>https://example.org/b.png</image></screenshot></screenshots>
+            </component>
+        </components>";
+        let read = |options: ParseOptions| read_collection(xml.as_bytes(), &options);
+        let exceeded = |options: ParseOptions| match read(options) {
+            Err(ParseError::LimitExceeded(what, limit)) => Some((what, limit)),
+            _ => None,
+        };
+
+        assert_eq!(read(ParseOptions::default())?.components.len(), 2);
+        assert!(read(ParseOptions::default().max_components(2)).is_ok());
+        assert_eq!(
+            exceeded(ParseOptions::default().max_components(1)),
+            Some(("components".into(), 1))
+        );
+        assert!(read(ParseOptions::default().max_releases(2).max_screenshots(1)).is_ok());
+        assert_eq!(
+            exceeded(ParseOptions::default().max_releases(1)),
+            Some(("releases".into(), 1))
+        );
+        assert_eq!(
+            exceeded(ParseOptions::default().max_screenshots(0)),
+            Some(("screenshots".into(), 0))
+        );
+        assert!(read(ParseOptions::default().max_string_length(25)).is_ok());
+        assert_eq!(
+            exceeded(ParseOptions::default().max_string_length(24)),
+            Some(("bytes in a string".into(), 24))
+        );
+        assert_eq!(
+            exceeded(ParseOptions::default().max_string_length(3)),
+            Some(("bytes in a string".into(), 3))
+        );
+        let len = xml.len() as u64;
+        assert!(read(ParseOptions::default().max_decompressed_bytes(len)).is_ok());
+        assert_eq!(
+            exceeded(ParseOptions::default().max_decompressed_bytes(len - 1)),
+            Some(("decompressed bytes".into(), len - 1))
+        );
+
+        let path = "./tests/desktop.xml";
+        let component = Component::from_path(path.into())?;
+        assert_eq!(
+            Component::from_path_with_options(path.into(), &ParseOptions::default())?,
+            component
+        );
+        assert!(Component::from_path_with_options(
+            path.into(),
+            &ParseOptions::default().max_releases(component.releases.len() as u64 - 1),
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
-use super::enums::{ArtifactKind, Bundle, Checksum, ReleaseKind, ReleaseUrgency, Size};
-use super::MarkupTranslatableString;
-use chrono::{DateTime, Utc};
+use super::enums::{ArtifactKind, Bundle, Checksum, IssueKind, ReleaseKind, ReleaseUrgency, Size};
+use super::translatable_string::DEFAULT_LOCALE;
+use super::{MarkupTranslatableString, Timestamp};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use url::Url;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -10,11 +11,11 @@ use url::Url;
 pub struct Release {
     #[serde(default, alias = "timestamp", skip_serializing_if = "Option::is_none")]
     /// The release date.
-    pub date: Option<DateTime<Utc>>,
+    pub date: Option<Timestamp>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// The end-of-life date of the release.
-    pub date_eol: Option<DateTime<Utc>>,
+    pub date_eol: Option<Timestamp>,
     /// The release version
     pub version: String,
 
@@ -40,6 +41,24 @@ pub struct Release {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// A web page with the release changelog.
+    pub url: Option<Box<Url>>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// The issues fixed by the release.
+    pub issues: Vec<Issue>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+/// An issue fixed by a release, like a bug report or a CVE.
+/// See [\<releases\/\>](https://www.freedesktop.org/software/appstream/docs/chap-Metadata.html#tag-releases).
+pub struct Issue {
+    #[serde(default, rename = "type")]
+    /// The issue type.
+    pub kind: IssueKind,
+    /// The identifier of the issue, like `CVE-2021-1234` or `gnome/maps#42`.
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// A web page describing the issue.
     pub url: Option<Url>,
 }
 
@@ -71,14 +90,249 @@ pub struct Artifact {
     pub bundles: Vec<Bundle>,
 }
 
+impl Release {
+    /// The artifact to install on a platform: a binary built for it, else a
+    /// binary built for any platform, else the source code.
+    ///
+    /// # Arguments
+    ///
+    /// * `platform` - The platform triplet of the system, like `x86_64-linux-gnu`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::{ArtifactBuilder, ReleaseBuilder};
+    /// use appstream::enums::ArtifactKind;
+    /// use url::Url;
+    ///
+    /// let release = ReleaseBuilder::new("1.2")
+    ///     .artifact(
+    ///         ArtifactBuilder::default()
+    ///             .url(Url::parse("https://example.com/app.tar.xz").unwrap())
+    ///             .kind(ArtifactKind::Source)
+    ///             .build(),
+    ///     )
+    ///     .build();
+    /// let artifact = release.artifact_for_platform("x86_64-linux-gnu").unwrap();
+    /// assert_eq!(artifact.kind, ArtifactKind::Source);
+    /// ```
+    pub fn artifact_for_platform(&self, platform: &str) -> Option<&Artifact> {
+        let binaries = || {
+            self.artifacts
+                .iter()
+                .filter(|a| a.kind == ArtifactKind::Binary)
+        };
+        binaries()
+            .find(|a| a.is_for_platform(platform))
+            .or_else(|| binaries().find(|a| a.platform.is_none()))
+            .or_else(|| {
+                self.artifacts
+                    .iter()
+                    .find(|a| a.kind == ArtifactKind::Source)
+            })
+    }
+}
+
+impl Artifact {
+    /// Whether the artifact is built for a platform.
+    ///
+    /// The triplets are compared without their vendor, `x86_64-pc-linux-gnu` is
+    /// the same platform as `x86_64-linux-gnu`, and the artifacts without a
+    /// platform aren't built for any of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `platform` - The platform triplet, like `x86_64-linux-gnu`.
+    pub fn is_for_platform(&self, platform: &str) -> bool {
+        self.platform
+            .as_deref()
+            .is_some_and(|p| without_vendor(p) == without_vendor(platform))
+    }
+}
+
+/// The parts of a platform triplet, lowercased, without the vendor of the
+/// four parts ones, like `pc` in `x86_64-pc-linux-gnu`.
+fn without_vendor(platform: &str) -> Vec<String> {
+    let mut parts = platform
+        .split('-')
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    if parts.len() == 4 {
+        parts.remove(1);
+    }
+    parts
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// The changes between two versions of a component, see
+/// `Component::changelog_between`.
+pub struct Changelog<'a> {
+    /// The releases of the range, from the newest to the oldest, with their
+    /// description in the requested locale.
+    pub releases: Vec<(&'a Release, Option<&'a str>)>,
+    /// The issues fixed by the releases, without duplicates.
+    pub issues: Vec<&'a Issue>,
+}
+
+impl<'a> Changelog<'a> {
+    /// Collects the descriptions and the issues of some releases.
+    pub(crate) fn new(releases: Vec<&'a Release>, locale: Option<&str>) -> Self {
+        let mut issues: Vec<&Issue> = vec![];
+        for issue in releases.iter().flat_map(|r| r.issues.iter()) {
+            if !issues
+                .iter()
+                .any(|i| i.kind == issue.kind && i.id == issue.id)
+            {
+                issues.push(issue);
+            }
+        }
+        Self {
+            releases: releases
+                .into_iter()
+                .map(|r| (r, r.description.as_ref().and_then(|d| localized(d, locale))))
+                .collect(),
+            issues,
+        }
+    }
+
+    /// Whether no release is in the range.
+    pub fn is_empty(&self) -> bool {
+        self.releases.is_empty()
+    }
+}
+
+/// The text for `locale`, then for its language without the country, like `pt`
+/// for `pt_BR`, and for the default locale last.
+fn localized<'a>(text: &'a MarkupTranslatableString, locale: Option<&str>) -> Option<&'a str> {
+    let language = locale.and_then(|l| l.split(['_', '.', '@']).next());
+    locale
+        .into_iter()
+        .chain(language)
+        .chain(std::iter::once(DEFAULT_LOCALE))
+        .find_map(|l| text.get_for_locale(l))
+        .map(String::as_str)
+}
+
+/// Compares two version strings the way RPM and AppStream do.
+///
+/// An epoch, the number before a `:` like in `1:2.0`, is compared first, the
+/// versions without one have the epoch `0`. The versions are split into numeric and alphabetic segments, numeric segments
+/// are compared as numbers and are newer than alphabetic ones. A `~` sorts before
+/// anything, even the end of the version, so `1.2~rc1` is older than `1.2`, and a
+/// `^` sorts after the end of the version but before anything else.
+///
+/// # Arguments
+///
+/// * `a` - The first version.
+/// * `b` - The second version.
+///
+/// # Example
+///
+/// ```
+/// use appstream::vercmp;
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(vercmp("1.2~rc1", "1.2"), Ordering::Less);
+/// assert_eq!(vercmp("1.10", "1.9"), Ordering::Greater);
+/// assert_eq!(vercmp("1.0", "1.00"), Ordering::Equal);
+/// assert_eq!(vercmp("1:1.0", "2.0"), Ordering::Greater);
+/// ```
+pub fn vercmp(a: &str, b: &str) -> Ordering {
+    let ((a_epoch, a), (b_epoch, b)) = (split_epoch(a), split_epoch(b));
+    a_epoch.cmp(&b_epoch).then_with(|| compare_segments(a, b))
+}
+
+/// Splits the epoch of a version, `0` if it has none.
+fn split_epoch(version: &str) -> (u64, &str) {
+    match version.split_once(':') {
+        Some((epoch, rest)) if !epoch.is_empty() && epoch.bytes().all(|b| b.is_ascii_digit()) => {
+            (epoch.parse().unwrap_or(u64::MAX), rest)
+        }
+        _ => (0, version),
+    }
+}
+
+/// Compares the versions without their epoch, segment by segment.
+fn compare_segments(a: &str, b: &str) -> Ordering {
+    let is_separator = |c: char| !c.is_ascii_alphanumeric() && c != '~' && c != '^';
+    let (mut a, mut b) = (a, b);
+    loop {
+        a = a.trim_start_matches(is_separator);
+        b = b.trim_start_matches(is_separator);
+
+        match (a.starts_with('~'), b.starts_with('~')) {
+            (true, true) => {
+                a = &a[1..];
+                b = &b[1..];
+                continue;
+            }
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            _ => (),
+        }
+        match (a.starts_with('^'), b.starts_with('^')) {
+            (true, true) => {
+                a = &a[1..];
+                b = &b[1..];
+                continue;
+            }
+            (true, false) if b.is_empty() => return Ordering::Greater,
+            (true, false) => return Ordering::Less,
+            (false, true) if a.is_empty() => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            _ => (),
+        }
+        if a.is_empty() || b.is_empty() {
+            break;
+        }
+
+        let numeric = a.starts_with(|c: char| c.is_ascii_digit());
+        let segment = |s: &str| -> usize {
+            s.find(|c: char| {
+                if numeric {
+                    !c.is_ascii_digit()
+                } else {
+                    !c.is_ascii_alphabetic()
+                }
+            })
+            .unwrap_or(s.len())
+        };
+        let (a_len, b_len) = (segment(a), segment(b));
+        if b_len == 0 {
+            return if numeric {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+        let (a_segment, b_segment) = (&a[..a_len], &b[..b_len]);
+        let ordering = if numeric {
+            let a_segment = a_segment.trim_start_matches('0');
+            let b_segment = b_segment.trim_start_matches('0');
+            a_segment
+                .len()
+                .cmp(&b_segment.len())
+                .then_with(|| a_segment.cmp(b_segment))
+        } else {
+            a_segment.cmp(b_segment)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        a = &a[a_len..];
+        b = &b[b_len..];
+    }
+    a.is_empty().cmp(&b.is_empty()).reverse()
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        ArtifactKind, Checksum, MarkupTranslatableString, Release, ReleaseKind, ReleaseUrgency,
-        Size, Url,
+        vercmp, ArtifactKind, Checksum, Issue, IssueKind, MarkupTranslatableString, Ordering,
+        Release, ReleaseKind, ReleaseUrgency, Size, Url,
     };
     use crate::builders::{ArtifactBuilder, ReleaseBuilder};
-    use chrono::{TimeZone, Utc};
+    use crate::Timestamp;
     use std::convert::TryFrom;
 
     use std::error::Error;
@@ -132,8 +386,18 @@ mod tests {
                 .description(MarkupTranslatableString::with_default(
                     "<p>This stable release fixes bugs.</p>",
                 ))
-                .date(Utc.ymd(2014, 4, 12).and_hms_milli(0, 0, 0, 0))
+                .date(Timestamp::from_ymd(2014, 4, 12).unwrap())
                 .url(Url::parse("https://example.org/releases/version-1.2.html")?)
+                .issue(Issue {
+                    kind: IssueKind::Generic,
+                    id: "bz#12345".into(),
+                    url: Some(Url::parse("https://example.com/bugzilla/12345")?),
+                })
+                .issue(Issue {
+                    kind: IssueKind::Cve,
+                    id: "CVE-2019-123456".into(),
+                    url: None,
+                })
                 .artifact(
                     ArtifactBuilder::default()
                         .url(Url::parse("https://example.com/mytarball.bin.tar.xz")?)
@@ -162,13 +426,33 @@ mod tests {
                 .build(),
             ReleaseBuilder::new("1.1")
                 .kind(ReleaseKind::Development)
-                .date(Utc.ymd(2013, 10, 20).and_hms_milli(0, 0, 0, 0))
+                .date(Timestamp::from_ymd(2013, 10, 20).unwrap())
                 .build(),
             ReleaseBuilder::new("1.0")
-                .date(Utc.ymd(2012, 8, 26).and_hms_milli(0, 0, 0, 0))
+                .date(Timestamp::from_ymd(2012, 8, 26).unwrap())
                 .build(),
         ];
         assert_eq!(releases1, releases2);
+
+        let release = &releases1[0];
+        let artifact_url = |platform| {
+            release
+                .artifact_for_platform(platform)
+                .map(|a| a.url.as_str())
+        };
+        assert_eq!(
+            artifact_url("x86_64-pc-linux-gnu"),
+            Some("https://example.com/mytarball.bin.tar.xz")
+        );
+        assert_eq!(
+            artifact_url("WIN32"),
+            Some("https://example.com/mytarball.bin.exe")
+        );
+        assert_eq!(
+            artifact_url("aarch64-linux-gnu"),
+            Some("https://example.com/mytarball.tar.xz")
+        );
+        assert_eq!(releases1[1].artifact_for_platform("x86_64-linux-gnu"), None);
         Ok(())
     }
 
@@ -201,17 +485,110 @@ mod tests {
             vec![
                 ReleaseBuilder::new("1.8")
                     .description(MarkupTranslatableString::with_default("<p>This stable release fixes the following bug:</p><ul><li>CPU no longer overheats when you hold down spacebar</li></ul>"))
-                    .date(Utc.datetime_from_str("1424116753", "%s")?)
+                    .date(Timestamp::from_unix(1424116753))
                     .sizes(vec![Size::Download(12345678), Size::Installed(42424242)])
                     .build(),
                 ReleaseBuilder::new("1.2")
-                    .date(Utc.datetime_from_str("1397253600", "%s")?)
+                    .date(Timestamp::from_unix(1397253600))
                     .build(),
                 ReleaseBuilder::new("1.0")
-                    .date(Utc.datetime_from_str("1345932000", "%s")?)
+                    .date(Timestamp::from_unix(1345932000))
                     .build()
             ]
         );
         Ok(())
     }
+
+    #[test]
+    fn compare_versions() {
+        let ordered = [
+            "0.9",
+            "1.0~alpha",
+            "1.0~rc1",
+            "1.0",
+            "1.0^git1",
+            "1.0a",
+            "1.0.1",
+            "1.2",
+            "1.10",
+            "2",
+        ];
+        for (i, a) in ordered.iter().enumerate() {
+            for (j, b) in ordered.iter().enumerate() {
+                assert_eq!(vercmp(a, b), i.cmp(&j), "{} <=> {}", a, b);
+            }
+        }
+        assert_eq!(vercmp("1.01", "1.1"), Ordering::Equal);
+        assert_eq!(vercmp("1_0", "1.0"), Ordering::Equal);
+        assert_eq!(vercmp("2.0", "2.0b"), Ordering::Less);
+        assert_eq!(vercmp("1:0.9", "2.0"), Ordering::Greater);
+        assert_eq!(vercmp("0:1.0", "1.0"), Ordering::Equal);
+        assert_eq!(vercmp("1:1.0", "2:0.1"), Ordering::Less);
+        assert_eq!(vercmp("2.0-1ubuntu1:", "2.0-1"), Ordering::Greater);
+    }
+
+    #[test]
+    fn changelog_between() -> Result<(), Box<dyn Error>> {
+        let component = crate::Component::try_from(&xmltree::Element::parse(
+            r#"<component>
+                <id>org.gnome.Maps</id>
+                <name>Maps</name>
+                <releases>
+                    <release version="3.0">
+                        <description><p>Routing</p></description>
+                        <description xml:lang="pt"><p>Rotas</p></description>
+                        <issues>
+                            <issue url="https://gitlab.gnome.org/GNOME/gnome-maps/-/issues/42">gnome-maps#42</issue>
+                            <issue type="cve">CVE-2021-1234</issue>
+                        </issues>
+                    </release>
+                    <release version="2.1">
+                        <issues><issue type="cve">CVE-2021-1234</issue></issues>
+                    </release>
+                    <release version="2.0">
+                        <description><p>Search</p></description>
+                    </release>
+                    <release version="1.0"/>
+                </releases>
+            </component>"#
+                .as_bytes(),
+        )?)?;
+        assert_eq!(
+            component.releases[0].issues[0],
+            Issue {
+                kind: IssueKind::Generic,
+                id: "gnome-maps#42".into(),
+                url: Some(Url::parse(
+                    "https://gitlab.gnome.org/GNOME/gnome-maps/-/issues/42"
+                )?),
+            }
+        );
+
+        let changelog = component.changelog_between("1.0", "3.0", Some("pt_BR"));
+        let releases = changelog
+            .releases
+            .iter()
+            .map(|(r, d)| (r.version.as_str(), *d))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            releases,
+            vec![
+                ("3.0", Some("<p>Rotas</p>")),
+                ("2.1", None),
+                ("2.0", Some("<p>Search</p>"))
+            ]
+        );
+        let issues = changelog
+            .issues
+            .iter()
+            .map(|i| i.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(issues, vec!["gnome-maps#42", "CVE-2021-1234"]);
+
+        let changelog = component.changelog_between("2.0", "2.1", None);
+        assert_eq!(changelog.releases.len(), 1);
+        assert_eq!(changelog.issues[0].kind, IssueKind::Cve);
+        assert!(component.changelog_between("3.0", "3.0", None).is_empty());
+        Ok(())
+    }
 }
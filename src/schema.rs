@@ -0,0 +1,660 @@
+use super::error::ParseError;
+use super::validation::ValidationIssue;
+use super::AppId;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use xmltree::{Element, XMLNode};
+
+/// An element of the grammar.
+struct Rule {
+    name: &'static str,
+    /// The allowed attributes, with their allowed values if they are restricted.
+    attributes: &'static [(&'static str, Option<&'static [&'static str]>)],
+    required_attributes: &'static [&'static str],
+    children: &'static [&'static str],
+    required_children: &'static [&'static str],
+}
+
+const fn rule(
+    name: &'static str,
+    attributes: &'static [(&'static str, Option<&'static [&'static str]>)],
+    children: &'static [&'static str],
+) -> Rule {
+    Rule {
+        name,
+        attributes,
+        required_attributes: &[],
+        children,
+        required_children: &[],
+    }
+}
+
+impl Rule {
+    const fn requires(
+        self,
+        required_attributes: &'static [&'static str],
+        required_children: &'static [&'static str],
+    ) -> Self {
+        Rule {
+            required_attributes,
+            required_children,
+            ..self
+        }
+    }
+}
+
+/// The attributes of the translatable elements.
+const TRANSLATABLE: &[(&str, Option<&[&str]>)] = &[("lang", None), ("translate", Some(&["no"]))];
+/// The inline markup of the description paragraphs and list items.
+const INLINE: &[&str] = &["em", "code"];
+/// The items of the relations, like `<requires>`.
+const RELATION_ITEMS: &[&str] = &[
+    "id",
+    "modalias",
+    "kernel",
+    "memory",
+    "firmware",
+    "control",
+    "display_length",
+    "hardware",
+    "internet",
+];
+const COMPARE: (&str, Option<&[&str]>) = ("compare", Some(&["eq", "ne", "lt", "gt", "le", "ge"]));
+
+/// The structure of the AppStream metainfo files and XML catalogs: the allowed
+/// and required attributes and children of each element.
+///
+/// The table is hand-maintained from the specification, it is not generated
+/// from the official RELAX NG schema and only covers part of it: the contents
+/// of the text nodes and the order of the children aren't checked. An element
+/// name can appear under several parents, its rule is then the union of the uses.
+const PARTIAL_STRUCTURE: &[Rule] = &[
+    rule(
+        "components",
+        &[
+            ("version", None),
+            ("origin", None),
+            ("architecture", None),
+            ("media_baseurl", None),
+            ("time", None),
+            ("priority", None),
+            ("merge", Some(&["append", "replace", "remove-component"])),
+        ],
+        &["component"],
+    )
+    .requires(&["version"], &[]),
+    rule(
+        "component",
+        &[
+            (
+                "type",
+                Some(&[
+                    "generic",
+                    "desktop",
+                    "desktop-application",
+                    "console-application",
+                    "web-application",
+                    "service",
+                    "addon",
+                    "font",
+                    "codec",
+                    "inputmethod",
+                    "input-method",
+                    "firmware",
+                    "driver",
+                    "localization",
+                    "repository",
+                    "operating-system",
+                    "icon-theme",
+                    "runtime",
+                ]),
+            ),
+            ("merge", Some(&["append", "replace", "remove-component"])),
+            ("priority", None),
+            ("date_eol", None),
+        ],
+        &[
+            "id",
+            "name",
+            "summary",
+            "description",
+            "icon",
+            "categories",
+            "keywords",
+            "url",
+            "launchable",
+            "provides",
+            "releases",
+            "screenshots",
+            "content_rating",
+            "project_license",
+            "metadata_license",
+            "project_group",
+            "developer",
+            "developer_name",
+            "compulsory_for_desktop",
+            "extends",
+            "translation",
+            "bundle",
+            "suggests",
+            "requires",
+            "recommends",
+            "supports",
+            "replaces",
+            "languages",
+            "mimetypes",
+            "kudos",
+            "pkgname",
+            "source_pkgname",
+            "custom",
+            "metadata",
+            "agreement",
+            "branding",
+            "tags",
+            "references",
+            "update_contact",
+            "updatecontact",
+            "name_variant_suffix",
+        ],
+    )
+    .requires(&[], &["id"]),
+    rule("id", &[("type", None), COMPARE, ("version", None)], &[]),
+    rule("name", TRANSLATABLE, &[]),
+    rule("summary", TRANSLATABLE, &[]),
+    rule("developer_name", TRANSLATABLE, &[]),
+    rule("name_variant_suffix", TRANSLATABLE, &[]),
+    rule("description", TRANSLATABLE, &["p", "ul", "ol"]),
+    rule("p", TRANSLATABLE, INLINE),
+    rule("ul", TRANSLATABLE, &["li"]),
+    rule("ol", TRANSLATABLE, &["li"]),
+    rule("li", TRANSLATABLE, INLINE),
+    rule("em", &[], &[]),
+    rule("code", &[], &[]),
+    rule(
+        "icon",
+        &[
+            ("type", Some(&["stock", "cached", "local", "remote"])),
+            ("width", None),
+            ("height", None),
+            ("scale", None),
+        ],
+        &[],
+    )
+    .requires(&["type"], &[]),
+    rule("categories", &[], &["category"]),
+    rule("category", &[], &[]),
+    rule("keywords", TRANSLATABLE, &["keyword"]),
+    rule("keyword", TRANSLATABLE, &[]),
+    rule(
+        "url",
+        &[(
+            "type",
+            Some(&[
+                "homepage",
+                "bugtracker",
+                "faq",
+                "help",
+                "donation",
+                "translate",
+                "contact",
+                "vcs-browser",
+                "contribute",
+                "details",
+            ]),
+        )],
+        &[],
+    ),
+    rule(
+        "launchable",
+        &[(
+            "type",
+            Some(&["desktop-id", "service", "cockpit-manifest", "url"]),
+        )],
+        &[],
+    )
+    .requires(&["type"], &[]),
+    rule(
+        "provides",
+        &[],
+        &[
+            "library",
+            "binary",
+            "font",
+            "modalias",
+            "firmware",
+            "python2",
+            "python3",
+            "dbus",
+            "mediatype",
+            "mimetype",
+            "id",
+            "codec",
+        ],
+    ),
+    rule("library", &[], &[]),
+    rule("binary", &[], &[]),
+    rule("font", &[], &[]),
+    rule("modalias", &[], &[]),
+    rule(
+        "firmware",
+        &[
+            ("type", Some(&["runtime", "flashed"])),
+            COMPARE,
+            ("version", None),
+        ],
+        &[],
+    ),
+    rule("codec", &[], &[]),
+    rule("python2", &[], &[]),
+    rule("python3", &[], &[]),
+    rule("dbus", &[("type", Some(&["user", "system"]))], &[]),
+    rule("mediatype", &[], &[]),
+    rule("mimetypes", &[], &["mimetype"]),
+    rule("mimetype", &[], &[]),
+    rule(
+        "releases",
+        &[("type", Some(&["embedded", "external"])), ("url", None)],
+        &["release"],
+    ),
+    rule(
+        "release",
+        &[
+            ("version", None),
+            ("date", None),
+            ("timestamp", None),
+            ("date_eol", None),
+            ("type", Some(&["stable", "development", "snapshot"])),
+            ("urgency", Some(&["low", "medium", "high", "critical"])),
+        ],
+        &["description", "url", "issues", "artifacts", "tags"],
+    )
+    .requires(&["version"], &[]),
+    rule("issues", &[], &["issue"]),
+    rule(
+        "issue",
+        &[("type", Some(&["generic", "cve"])), ("url", None)],
+        &[],
+    ),
+    rule("artifacts", &[], &["artifact"]),
+    rule(
+        "artifact",
+        &[
+            ("type", Some(&["source", "binary"])),
+            ("platform", None),
+            ("bundle", None),
+        ],
+        &["location", "checksum", "size", "filename", "bundle"],
+    )
+    .requires(&["type"], &[]),
+    rule("location", &[], &[]),
+    rule("filename", &[], &[]),
+    rule(
+        "checksum",
+        &[
+            (
+                "type",
+                Some(&["sha1", "sha256", "sha512", "blake2b", "blake3"]),
+            ),
+            ("target", None),
+        ],
+        &[],
+    )
+    .requires(&["type"], &[]),
+    rule("size", &[("type", Some(&["download", "installed"]))], &[]).requires(&["type"], &[]),
+    rule("screenshots", &[], &["screenshot"]),
+    rule(
+        "screenshot",
+        &[("type", Some(&["default", "extra"])), ("environment", None)],
+        &["caption", "image", "video"],
+    ),
+    rule("caption", TRANSLATABLE, &[]),
+    rule(
+        "image",
+        &[
+            ("type", Some(&["source", "thumbnail"])),
+            ("width", None),
+            ("height", None),
+            ("scale", None),
+            ("lang", None),
+        ],
+        &[],
+    ),
+    rule(
+        "video",
+        &[
+            ("codec", None),
+            ("container", None),
+            ("width", None),
+            ("height", None),
+            ("lang", None),
+        ],
+        &[],
+    ),
+    rule(
+        "content_rating",
+        &[("type", Some(&["oars-1.0", "oars-1.1"]))],
+        &["content_attribute"],
+    ),
+    rule("content_attribute", &[("id", None)], &[]).requires(&["id"], &[]),
+    rule("project_license", &[], &[]),
+    rule("metadata_license", &[], &[]),
+    rule("project_group", &[], &[]),
+    rule("developer", &[("id", None)], &["name"]),
+    rule("compulsory_for_desktop", &[], &[]),
+    rule("extends", &[], &[]),
+    rule(
+        "translation",
+        &[("type", Some(&["gettext", "qt"])), ("source_locale", None)],
+        &[],
+    )
+    .requires(&["type"], &[]),
+    rule(
+        "bundle",
+        &[
+            (
+                "type",
+                Some(&[
+                    "package", "limba", "flatpak", "appimage", "snap", "tarball", "cabinet",
+                    "linglong",
+                ]),
+            ),
+            ("runtime", None),
+            ("sdk", None),
+        ],
+        &[],
+    ),
+    rule(
+        "suggests",
+        &[("type", Some(&["upstream", "heuristic"]))],
+        &["id"],
+    ),
+    rule("requires", &[], RELATION_ITEMS),
+    rule("recommends", &[], RELATION_ITEMS),
+    rule("supports", &[], RELATION_ITEMS),
+    rule("kernel", &[COMPARE, ("version", None)], &[]),
+    rule("memory", &[], &[]),
+    rule("control", &[], &[]),
+    rule("display_length", &[COMPARE, ("side", None)], &[]),
+    rule("hardware", &[], &[]),
+    rule("internet", &[("bandwidth_mbitps", None)], &[]),
+    rule("replaces", &[], &["id"]),
+    rule("languages", &[], &["lang"]),
+    rule("lang", &[("percentage", None)], &[]),
+    rule("kudos", &[], &["kudo"]),
+    rule("kudo", &[], &[]),
+    rule("pkgname", &[], &[]),
+    rule("source_pkgname", &[], &[]),
+    rule("custom", &[], &["value"]),
+    rule("metadata", &[], &["value"]),
+    rule("value", &[("key", None)], &[]).requires(&["key"], &[]),
+    rule(
+        "agreement",
+        &[("type", None), ("version_id", None)],
+        &["agreement_section"],
+    ),
+    rule(
+        "agreement_section",
+        &[("type", None)],
+        &["name", "description"],
+    ),
+    rule("branding", &[], &["color"]),
+    rule(
+        "color",
+        &[
+            ("type", Some(&["primary"])),
+            ("scheme_preference", Some(&["light", "dark"])),
+        ],
+        &[],
+    ),
+    rule("tags", &[], &["tag"]),
+    rule("tag", &[("namespace", None)], &[]),
+    rule("references", &[], &["doi", "citation_cff", "registry"]),
+    rule("doi", &[], &[]),
+    rule("citation_cff", &[], &[]),
+    rule("registry", &[("name", None)], &[]),
+    rule("update_contact", &[], &[]),
+    rule("updatecontact", &[], &[]),
+];
+
+fn find_rule(name: &str) -> Option<&'static Rule> {
+    PARTIAL_STRUCTURE.iter().find(|r| r.name == name)
+}
+
+/// Checks the structure of an XML metainfo file or catalog against a partial,
+/// hand-maintained version of the AppStream schema.
+///
+/// The check is stricter than the parsing, which skips what it doesn't know:
+/// unknown elements and attributes, values out of their allowed set and missing
+/// required elements and attributes are reported as errors. The issues found
+/// inside a component are attached to it. It isn't a full validation against
+/// the official RELAX NG schema, the text contents and the order of the
+/// children aren't checked.
+///
+/// # Arguments
+///
+/// * `element` - The root element, a `<component>` or `<components>`.
+///
+/// # Example
+///
+/// ```
+/// use appstream::schema;
+/// use xmltree::Element;
+///
+/// let xml = r#"<component type="desktop-application">
+///     <id>org.example.App</id>
+///     <name>App</name>
+///     <branding><colour type="primary">#ff0000</colour></branding>
+/// </component>"#;
+/// let issues = schema::validate(&Element::parse(xml.as_bytes()).unwrap());
+/// assert_eq!(issues[0].tag, "schema-unexpected-element");
+/// ```
+pub fn validate(element: &Element) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    match element.name.as_str() {
+        "component" => check_component(element, &mut issues),
+        "components" => check_element(element, &mut issues),
+        name => issues.push(ValidationIssue::error(
+            "schema-invalid-root",
+            &format!(
+                "The root element must be a component or components, not {}",
+                name
+            ),
+        )),
+    }
+    issues
+}
+
+/// Checks the structure of an XML metainfo file or catalog, see `validate`.
+///
+/// # Arguments
+///
+/// * `path` - The path to the XML file.
+pub fn validate_path(path: &Path) -> Result<Vec<ValidationIssue>, ParseError> {
+    let element = Element::parse(BufReader::new(File::open(path)?))?;
+    Ok(validate(&element))
+}
+
+fn check_component(component: &Element, issues: &mut Vec<ValidationIssue>) {
+    let mut found = Vec::new();
+    check_element(component, &mut found);
+    match component.get_child("id").and_then(|id| id.get_text()) {
+        Some(id) => {
+            let id = AppId::from(id.trim());
+            issues.extend(found.into_iter().map(|i| i.for_component(&id)));
+        }
+        None => issues.extend(found),
+    }
+}
+
+fn check_element(element: &Element, issues: &mut Vec<ValidationIssue>) {
+    // The children are only checked once allowed by their parent.
+    let rule = match find_rule(&element.name) {
+        Some(rule) => rule,
+        None => return,
+    };
+
+    let mut attributes = element.attributes.iter().collect::<Vec<_>>();
+    attributes.sort();
+    for (name, value) in attributes {
+        match rule.attributes.iter().find(|(a, _)| a == name) {
+            None => issues.push(ValidationIssue::error(
+                "schema-unknown-attribute",
+                &format!("The attribute {} is not allowed on {}", name, rule.name),
+            )),
+            Some((_, Some(values))) if !values.contains(&value.as_str()) => {
+                issues.push(ValidationIssue::error(
+                    "schema-invalid-attribute-value",
+                    &format!(
+                        "The value {} is not allowed for the attribute {} of {}",
+                        value, name, rule.name
+                    ),
+                ))
+            }
+            _ => (),
+        }
+    }
+    for name in rule.required_attributes {
+        if !element.attributes.contains_key(*name) {
+            issues.push(ValidationIssue::error(
+                "schema-missing-attribute",
+                &format!("The attribute {} is required on {}", name, rule.name),
+            ));
+        }
+    }
+    for name in rule.required_children {
+        if element.get_child(*name).is_none() {
+            issues.push(ValidationIssue::error(
+                "schema-missing-element",
+                &format!("The element {} is required in {}", name, rule.name),
+            ));
+        }
+    }
+
+    for child in element.children.iter() {
+        let child = match child {
+            XMLNode::Element(child) => child,
+            _ => continue,
+        };
+        if !rule.children.contains(&child.name.as_str()) {
+            issues.push(ValidationIssue::error(
+                "schema-unexpected-element",
+                &format!("The element {} is not allowed in {}", child.name, rule.name),
+            ));
+        } else if child.name == "component" {
+            check_component(child, issues);
+        } else {
+            check_element(child, issues);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn valid_files() -> Result<(), Box<dyn Error>> {
+        for path in [
+            "./tests/desktop.xml",
+            "./tests/firmware.xml",
+            "./tests/codec.xml",
+            "./tests/collections/spec_example.xml",
+        ] {
+            let issues = validate_path(Path::new(path))?;
+            assert!(issues.is_empty(), "{}: {:?}", path, issues);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn schema_violations() -> Result<(), Box<dyn Error>> {
+        let xml = r#"<components origin="example">
+            <component type="desktop-app" importance="10">
+                <id>org.example.App</id>
+                <name>App</name>
+                <icon>app</icon>
+                <description><p>An <b>app</b></p><li>Item</li></description>
+                <releases><release date="2020-01-01"/></releases>
+            </component>
+            <component><name>Nameless</name></component>
+        </components>"#;
+        let issues = validate(&Element::parse(xml.as_bytes())?);
+        let tags = issues
+            .iter()
+            .map(|i| (i.tag.as_str(), i.component.as_ref().map(|c| c.as_ref())))
+            .collect::<Vec<_>>();
+        let app = Some("org.example.App");
+        assert_eq!(
+            tags,
+            vec![
+                ("schema-missing-attribute", None),
+                ("schema-unknown-attribute", app),
+                ("schema-invalid-attribute-value", app),
+                ("schema-missing-attribute", app),
+                ("schema-unexpected-element", app),
+                ("schema-unexpected-element", app),
+                ("schema-missing-attribute", app),
+                ("schema-missing-element", None),
+            ]
+        );
+
+        let root = Element::parse("<application/>".as_bytes())?;
+        assert_eq!(validate(&root)[0].tag, "schema-invalid-root");
+        Ok(())
+    }
+
+    #[test]
+    fn validating_loaders() -> Result<(), Box<dyn Error>> {
+        use crate::{reader, ParseOptions};
+
+        let options = ParseOptions::default().validate_schema();
+        let tags = |result: Result<_, ParseError>| match result {
+            Err(ParseError::SchemaViolation(issues)) => {
+                issues.into_iter().map(|i| i.tag).collect::<Vec<_>>()
+            }
+            _ => Vec::new(),
+        };
+
+        let xml = r#"<components version="0.14">
+            <component><id>a</id><name>A</name></component>
+            <component><id>b</id><name>B</name><colour>red</colour></component>
+        </components>"#;
+        assert_eq!(
+            reader::read_collection(xml.as_bytes(), &Default::default())?
+                .components
+                .len(),
+            2
+        );
+        assert_eq!(
+            tags(reader::read_collection(xml.as_bytes(), &options).map(|_| ())),
+            vec!["schema-unexpected-element"]
+        );
+
+        let xml =
+            r#"<components version="0.14"><component><id>a</id></component><app/></components>"#;
+        assert!(reader::read_collection(xml.as_bytes(), &Default::default()).is_ok());
+        assert_eq!(
+            tags(reader::read_collection(xml.as_bytes(), &options).map(|_| ())),
+            vec!["schema-unexpected-element"]
+        );
+        let xml = r#"<components><component><id>a</id></component></components>"#;
+        assert_eq!(
+            tags(reader::read_collection(xml.as_bytes(), &options).map(|_| ())),
+            vec!["schema-missing-attribute"]
+        );
+
+        let xml = r#"<component importance="10"><id>a</id><name>A</name></component>"#;
+        assert!(reader::read_component(xml.as_bytes(), &Default::default()).is_ok());
+        assert_eq!(
+            tags(reader::read_component(xml.as_bytes(), &options).map(|_| ())),
+            vec!["schema-unknown-attribute"]
+        );
+        assert!(
+            crate::Component::from_path_with_options("./tests/desktop.xml".into(), &options)
+                .is_ok()
+        );
+        Ok(())
+    }
+}
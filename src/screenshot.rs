@@ -1,4 +1,5 @@
-use super::enums::ImageKind;
+use super::enums::{ImageKind, VideoCodec};
+use super::media::MediaResolver;
 use super::TranslatableString;
 use serde::{Deserialize, Serialize};
 use url::Url;
@@ -11,6 +12,11 @@ pub struct Screenshot {
     /// Whether the current screenshot is the default one.
     pub is_default: bool,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The desktop environment and style the screenshot was taken in, like
+    /// `gnome` or `plasma-mobile:dark`.
+    pub environment: Option<String>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     /// A translatable small description of the current screenshot.
     pub caption: Option<TranslatableString>,
@@ -30,6 +36,46 @@ pub struct Screenshot {
     pub videos: Vec<Video>,
 }
 
+impl Screenshot {
+    /// The video the client can play, the first one encoded with the most
+    /// preferred of its codecs.
+    ///
+    /// When no video has a supported codec, the first one without a `codec`
+    /// attribute is returned, it may still be playable.
+    ///
+    /// # Arguments
+    ///
+    /// * `codecs` - The codecs the client can decode, by order of preference.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::builders::{ScreenshotBuilder, VideoBuilder};
+    /// use appstream::enums::VideoCodec;
+    /// use url::Url;
+    ///
+    /// let screenshot = ScreenshotBuilder::default()
+    ///     .video(VideoBuilder::new(Url::parse("https://example.com/av1.mkv").unwrap()).codec("av1").build())
+    ///     .video(VideoBuilder::new(Url::parse("https://example.com/vp9.webm").unwrap()).codec("vp9").build())
+    ///     .build();
+    /// let video = screenshot.video_for(&[VideoCodec::Vp9]).unwrap();
+    /// assert_eq!(video.codec.as_deref(), Some("vp9"));
+    /// assert!(screenshot.video_for(&[]).is_none());
+    /// ```
+    pub fn video_for(&self, codecs: &[VideoCodec]) -> Option<&Video> {
+        codecs
+            .iter()
+            .find_map(|codec| {
+                self.videos.iter().find(|v| {
+                    v.codec
+                        .as_deref()
+                        .is_some_and(|c| c.eq_ignore_ascii_case(codec.as_ref()))
+                })
+            })
+            .or_else(|| self.videos.iter().find(|v| v.codec.is_none()))
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 /// A screenshot video.
 /// See [\<screenshots\/\>](https://www.freedesktop.org/software/appstream/docs/chap-Metadata.html#tag-screenshots).
@@ -54,6 +100,13 @@ pub struct Video {
     pub url: Url,
 }
 
+impl Video {
+    /// The location the video is served from according to `resolver`.
+    pub fn resolved_url(&self, resolver: &MediaResolver) -> Url {
+        resolver.resolve(&self.url)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 /// A screenshot image.
 /// See [\<screenshots\/\>](https://www.freedesktop.org/software/appstream/docs/chap-Metadata.html#tag-screenshots).
@@ -74,6 +127,13 @@ pub struct Image {
     pub url: Url,
 }
 
+impl Image {
+    /// The location the image is served from according to `resolver`.
+    pub fn resolved_url(&self, resolver: &MediaResolver) -> Url {
+        resolver.resolve(&self.url)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -167,4 +227,31 @@ mod tests {
         assert_eq!(s1, s2);
         Ok(())
     }
+
+    #[test]
+    fn video_for_codecs() -> Result<(), Box<dyn Error>> {
+        let video = |codec: Option<&str>, name: &str| -> Result<Video, url::ParseError> {
+            let mut video = VideoBuilder::new(Url::parse("https://example.com/")?.join(name)?);
+            if let Some(codec) = codec {
+                video = video.codec(codec);
+            }
+            Ok(video.build())
+        };
+        let screenshot = ScreenshotBuilder::default()
+            .video(video(Some("av1"), "av1.mkv")?)
+            .video(video(None, "unknown.webm")?)
+            .video(video(Some("VP9"), "vp9.webm")?)
+            .build();
+
+        let name = |codecs: &[VideoCodec]| screenshot.video_for(codecs).map(|v| v.url.path());
+        assert_eq!(name(&[VideoCodec::Av1, VideoCodec::Vp9]), Some("/av1.mkv"));
+        assert_eq!(name(&[VideoCodec::Vp9, VideoCodec::Av1]), Some("/vp9.webm"));
+        assert_eq!(name(&[]), Some("/unknown.webm"));
+
+        let screenshot = ScreenshotBuilder::default()
+            .video(video(Some("av1"), "av1.mkv")?)
+            .build();
+        assert_eq!(screenshot.video_for(&[VideoCodec::Vp9]), None);
+        Ok(())
+    }
 }
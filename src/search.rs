@@ -0,0 +1,219 @@
+use super::translatable_string::markup_tag_len;
+use super::Component;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+use std::sync::Arc;
+
+/// The weight of a token found in the description of a component.
+const DESCRIPTION: u32 = 1 << 3;
+/// The weight of a token found in the summary of a component.
+const SUMMARY: u32 = 1 << 4;
+/// The weight of a token found in the keywords of a component.
+const KEYWORD: u32 = 1 << 5;
+/// The weight of a token found in the name of a component.
+const NAME: u32 = 1 << 6;
+/// The weight of a token found in the id of a component.
+const ID: u32 = 1 << 7;
+
+#[derive(Clone, Debug)]
+/// A token index over the id, name, keywords, summary and description of
+/// components, in all their locales.
+///
+/// The index is built once, then each query only looks up its terms instead of
+/// scanning the text of every component. A term matches the tokens it is equal
+/// to or the beginning of, so the index can be queried as the user types.
+///
+/// # Example
+///
+/// ```
+/// use appstream::search::SearchIndex;
+/// use appstream::Collection;
+///
+/// let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into()).unwrap();
+/// let index = SearchIndex::new(&collection.components);
+/// let found = index.search("weath");
+/// assert_eq!(found[0].id, "org.gnome.Weather.Application.desktop".into());
+/// ```
+pub struct SearchIndex<'a> {
+    components: &'a [Component],
+    tokens: Arc<Tokens>,
+}
+
+#[derive(Debug, Default)]
+/// The tokens of some components, with the positions of the components they
+/// are found in and their weight there.
+pub(crate) struct Tokens(BTreeMap<String, Vec<(usize, u32)>>);
+
+impl Tokens {
+    pub(crate) fn new(components: &[Component]) -> Self {
+        let mut tokens: BTreeMap<String, Vec<(usize, u32)>> = BTreeMap::new();
+        for (position, component) in components.iter().enumerate() {
+            let mut weights: HashMap<String, u32> = HashMap::new();
+            let mut add = |text: &str, weight: u32| {
+                for token in tokenize(text) {
+                    *weights.entry(token).or_default() |= weight;
+                }
+            };
+            add(component.id.as_ref(), ID);
+            component.name.0.values().for_each(|name| add(name, NAME));
+            if let Some(keywords) = &component.keywords {
+                keywords.0.values().flatten().for_each(|k| add(k, KEYWORD));
+            }
+            if let Some(summary) = &component.summary {
+                summary.0.values().for_each(|s| add(s, SUMMARY));
+            }
+            if let Some(description) = &component.description {
+                description
+                    .0
+                    .values()
+                    .for_each(|d| add(&strip_markup(d), DESCRIPTION));
+            }
+            for (token, weight) in weights {
+                tokens.entry(token).or_default().push((position, weight));
+            }
+        }
+        Self(tokens)
+    }
+}
+
+impl<'a> SearchIndex<'a> {
+    /// Indexes the tokens of components.
+    ///
+    /// # Arguments
+    ///
+    /// * `components` - The components to search.
+    pub fn new(components: &'a [Component]) -> Self {
+        Self::with_tokens(components, Arc::new(Tokens::new(components)))
+    }
+
+    /// Wraps the tokens already indexed for some components.
+    pub(crate) fn with_tokens(components: &'a [Component], tokens: Arc<Tokens>) -> Self {
+        Self { components, tokens }
+    }
+
+    /// The components matching every term of a query, the most relevant first.
+    ///
+    /// A component scores the weight of the fields each term is found in: the
+    /// id weighs the most, then the name, the keywords, the summary and the
+    /// description. A token equal to a term counts twice as much as a token it
+    /// is only the beginning of. The components with the same score keep their
+    /// order.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The terms to look for, separated by spaces or punctuation.
+    pub fn search(&self, query: &str) -> Vec<&'a Component> {
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return vec![];
+        }
+        let mut scores: HashMap<usize, (usize, u32)> = HashMap::new();
+        for (n, term) in terms.iter().enumerate() {
+            let mut matches: HashMap<usize, u32> = HashMap::new();
+            let tokens = self
+                .tokens
+                .0
+                .range::<str, _>((Bound::Included(term.as_str()), Bound::Unbounded))
+                .take_while(|(token, _)| token.starts_with(term.as_str()));
+            for (token, postings) in tokens {
+                let factor = if token == term { 2 } else { 1 };
+                for &(position, weight) in postings {
+                    let score = matches.entry(position).or_default();
+                    *score = (*score).max(weight * factor);
+                }
+            }
+            // Only the components which matched the previous terms are kept.
+            for (position, score) in matches {
+                let entry = scores.entry(position).or_default();
+                if entry.0 == n {
+                    *entry = (n + 1, entry.1 + score);
+                }
+            }
+        }
+        let mut found = scores
+            .into_iter()
+            .filter(|(_, (matched, _))| *matched == terms.len())
+            .map(|(position, (_, score))| (position, score))
+            .collect::<Vec<_>>();
+        found.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        found
+            .into_iter()
+            .map(|(position, _)| &self.components[position])
+            .collect()
+    }
+}
+
+/// Splits a text into lowercase words.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Drops the tags of a description, keeping its words apart. Its text isn't
+/// escaped, see `markup_tag_len`.
+fn strip_markup(markup: &str) -> String {
+    let mut text = String::with_capacity(markup.len());
+    let mut chars = markup.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match markup_tag_len(&markup[i..]) {
+            Some(len) => {
+                // The tags are ASCII, one byte per char.
+                chars.nth(len - 2);
+                text.push(' ');
+            }
+            None => text.push(c),
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::ComponentBuilder;
+    use crate::{MarkupTranslatableString, TranslatableList, TranslatableString};
+
+    #[test]
+    fn search() {
+        let components = vec![
+            ComponentBuilder::default()
+                .id("org.example.Notes".into())
+                .name(TranslatableString::with_default("Notes").and_locale("fr", "Carnet"))
+                .summary(TranslatableString::with_default("Write down your ideas"))
+                .description(MarkupTranslatableString::with_default(
+                    "<p>Keeps a list of things to do & ideas, like <html> pages.</p>\
+                     <ul><li>Cut & paste your spreadsheets</li></ul>",
+                ))
+                .build(),
+            ComponentBuilder::default()
+                .id("org.example.Tasks".into())
+                .name(TranslatableString::with_default("Tasks"))
+                .summary(TranslatableString::with_default("Manage your to-do list"))
+                .keywords(TranslatableList::with_default(vec!["todo", "notes"]))
+                .build(),
+        ];
+        let index = SearchIndex::new(&components);
+        let ids = |query: &str| {
+            index
+                .search(query)
+                .iter()
+                .map(|c| c.id.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids("notes"), vec!["org.example.Notes", "org.example.Tasks"]);
+        assert_eq!(ids("todo"), vec!["org.example.Tasks"]);
+        assert_eq!(
+            ids("To-Do LIST"),
+            vec!["org.example.Tasks", "org.example.Notes"]
+        );
+        assert_eq!(ids("carn"), vec!["org.example.Notes"]);
+        assert_eq!(ids("ideas notes"), vec!["org.example.Notes"]);
+        assert_eq!(ids("spreadsheets"), vec!["org.example.Notes"]);
+        assert_eq!(ids("html"), vec!["org.example.Notes"]);
+        assert!(ids("ul").is_empty());
+        assert!(ids("notes nothing").is_empty());
+        assert!(ids(" - ").is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use super::spdx_list::{EXCEPTIONS, FSF_LIBRE, LICENSES, OSI_APPROVED};
+
+/// Whether `id` is a known SPDX license identifier.
+///
+/// SPDX identifiers are matched case-insensitively.
+pub fn is_license_id(id: &str) -> bool {
+    license_flags(id).is_some()
+}
+
+/// Whether `id` is a known SPDX license exception identifier.
+pub fn is_exception_id(id: &str) -> bool {
+    EXCEPTIONS.iter().any(|l| l.eq_ignore_ascii_case(id))
+}
+
+/// The flags of a SPDX license identifier, if it's a known one.
+fn license_flags(id: &str) -> Option<u8> {
+    LICENSES
+        .iter()
+        .find(|(l, _)| l.eq_ignore_ascii_case(id))
+        .map(|(_, flags)| *flags)
+}
+
+/// The user defined reference AppStream considers as public domain.
+const PUBLIC_DOMAIN_REF: &str = "LicenseRef-public-domain";
+/// The prefix of the user defined references AppStream considers as free, which
+/// can be followed by the URL of the license, like `LicenseRef-free=https://…`.
+const FREE_REF_PREFIX: &str = "LicenseRef-free";
+
+/// Whether `id` is a free license identifier, using the same classification as
+/// libappstream: a license is free if it's OSI approved or considered free by the FSF.
+///
+/// Unknown identifiers and user defined references, except the ones AppStream
+/// defines for public domain and free licenses, are considered as non-free.
+pub fn is_free_license_id(id: &str) -> bool {
+    if id.starts_with("LicenseRef-") || id.starts_with("DocumentRef-") {
+        return id.eq_ignore_ascii_case(PUBLIC_DOMAIN_REF)
+            || id
+                .get(..FREE_REF_PREFIX.len())
+                .is_some_and(|p| p.eq_ignore_ascii_case(FREE_REF_PREFIX));
+    }
+    license_flags(id).is_some_and(|flags| flags & (OSI_APPROVED | FSF_LIBRE) != 0)
+}
+
+/// Deprecated SPDX license identifiers and the expression replacing them.
+const DEPRECATED_LICENSE_IDS: &[(&str, &str)] = &[
+    ("AGPL-1.0", "AGPL-1.0-only"),
+    ("AGPL-3.0", "AGPL-3.0-only"),
+    ("GFDL-1.1", "GFDL-1.1-only"),
+    ("GFDL-1.2", "GFDL-1.2-only"),
+    ("GFDL-1.3", "GFDL-1.3-only"),
+    ("GPL-1.0", "GPL-1.0-only"),
+    ("GPL-1.0+", "GPL-1.0-or-later"),
+    ("GPL-2.0", "GPL-2.0-only"),
+    ("GPL-2.0+", "GPL-2.0-or-later"),
+    (
+        "GPL-2.0-with-autoconf-exception",
+        "GPL-2.0-only WITH Autoconf-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-bison-exception",
+        "GPL-2.0-or-later WITH Bison-exception-2.2",
+    ),
+    (
+        "GPL-2.0-with-classpath-exception",
+        "GPL-2.0-only WITH Classpath-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-font-exception",
+        "GPL-2.0-only WITH Font-exception-2.0",
+    ),
+    (
+        "GPL-2.0-with-GCC-exception",
+        "GPL-2.0-only WITH GCC-exception-2.0",
+    ),
+    ("GPL-3.0", "GPL-3.0-only"),
+    ("GPL-3.0+", "GPL-3.0-or-later"),
+    (
+        "GPL-3.0-with-autoconf-exception",
+        "GPL-3.0-only WITH Autoconf-exception-3.0",
+    ),
+    (
+        "GPL-3.0-with-GCC-exception",
+        "GPL-3.0-only WITH GCC-exception-3.1",
+    ),
+    ("LGPL-2.0", "LGPL-2.0-only"),
+    ("LGPL-2.0+", "LGPL-2.0-or-later"),
+    ("LGPL-2.1", "LGPL-2.1-only"),
+    ("LGPL-2.1+", "LGPL-2.1-or-later"),
+    ("LGPL-3.0", "LGPL-3.0-only"),
+    ("LGPL-3.0+", "LGPL-3.0-or-later"),
+];
+
+/// The GNU license families that have `-only` and `-or-later` variants.
+const GNU_LICENSE_PREFIXES: &[&str] = &["AGPL-", "GFDL-", "GPL-", "LGPL-"];
+
+/// The expression replacing a deprecated license identifier, if any.
+///
+/// # Arguments
+///
+/// * `id` - The license identifier, without the `+` operator.
+/// * `or_later` - Whether the `+` operator was used.
+pub fn replacement_of(id: &str, or_later: bool) -> Option<String> {
+    let base = id
+        .strip_suffix("-only")
+        .or_else(|| id.strip_suffix("-or-later"))
+        .unwrap_or(id);
+    let is_gnu = GNU_LICENSE_PREFIXES
+        .iter()
+        .any(|p| base.len() > p.len() && base[..p.len()].eq_ignore_ascii_case(p));
+    if or_later && is_gnu && base.len() != id.len() {
+        // GPL-2.0-only+ or GPL-2.0-or-later+
+        return Some(format!("{}-or-later", canonical_license_id(base)));
+    }
+
+    let lookup = if or_later {
+        format!("{}+", id)
+    } else {
+        id.to_string()
+    };
+    DEPRECATED_LICENSE_IDS
+        .iter()
+        .find(|(old, _)| old.eq_ignore_ascii_case(&lookup))
+        .map(|(_, new)| new.to_string())
+}
+
+/// The identifier with the case used by the SPDX list, if it's a known one.
+pub fn canonical_license_id(id: &str) -> &str {
+    LICENSES
+        .iter()
+        .map(|(l, _)| l)
+        .chain(EXCEPTIONS.iter())
+        .find(|l| l.eq_ignore_ascii_case(id))
+        .copied()
+        .unwrap_or(id)
+}
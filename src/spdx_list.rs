@@ -0,0 +1,778 @@
+// Generated by scripts/update-spdx.py from the SPDX license list 3.26.0, do not edit.
+
+/// The license is approved by the OSI.
+pub(crate) const OSI_APPROVED: u8 = 1;
+/// The license is considered free by the FSF.
+pub(crate) const FSF_LIBRE: u8 = 1 << 1;
+
+/// The SPDX license identifiers, deprecated ones included, with their flags.
+pub(crate) const LICENSES: &[(&str, u8)] = &[
+    ("0BSD", OSI_APPROVED),
+    ("3D-Slicer-1.0", 0),
+    ("AAL", OSI_APPROVED),
+    ("ADSL", 0),
+    ("AFL-1.1", OSI_APPROVED | FSF_LIBRE),
+    ("AFL-1.2", OSI_APPROVED | FSF_LIBRE),
+    ("AFL-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("AFL-2.1", OSI_APPROVED | FSF_LIBRE),
+    ("AFL-3.0", OSI_APPROVED | FSF_LIBRE),
+    ("AGPL-1.0", FSF_LIBRE),
+    ("AGPL-1.0-only", 0),
+    ("AGPL-1.0-or-later", 0),
+    ("AGPL-3.0", OSI_APPROVED | FSF_LIBRE),
+    ("AGPL-3.0-only", OSI_APPROVED | FSF_LIBRE),
+    ("AGPL-3.0-or-later", OSI_APPROVED | FSF_LIBRE),
+    ("AMD-newlib", 0),
+    ("AMDPLPA", 0),
+    ("AML", 0),
+    ("AML-glslang", 0),
+    ("AMPAS", 0),
+    ("ANTLR-PD", 0),
+    ("ANTLR-PD-fallback", 0),
+    ("APAFML", 0),
+    ("APL-1.0", OSI_APPROVED),
+    ("APSL-1.0", OSI_APPROVED),
+    ("APSL-1.1", OSI_APPROVED),
+    ("APSL-1.2", OSI_APPROVED),
+    ("APSL-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("ASWF-Digital-Assets-1.0", 0),
+    ("ASWF-Digital-Assets-1.1", 0),
+    ("Abstyles", 0),
+    ("AdaCore-doc", 0),
+    ("Adobe-2006", 0),
+    ("Adobe-Display-PostScript", 0),
+    ("Adobe-Glyph", 0),
+    ("Adobe-Utopia", 0),
+    ("Afmparse", 0),
+    ("Aladdin", 0),
+    ("Apache-1.0", FSF_LIBRE),
+    ("Apache-1.1", OSI_APPROVED | FSF_LIBRE),
+    ("Apache-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("App-s2p", 0),
+    ("Arphic-1999", 0),
+    ("Artistic-1.0", OSI_APPROVED),
+    ("Artistic-1.0-Perl", OSI_APPROVED),
+    ("Artistic-1.0-cl8", OSI_APPROVED),
+    ("Artistic-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("BSD-1-Clause", OSI_APPROVED),
+    ("BSD-2-Clause", OSI_APPROVED | FSF_LIBRE),
+    ("BSD-2-Clause-Darwin", 0),
+    ("BSD-2-Clause-FreeBSD", FSF_LIBRE),
+    ("BSD-2-Clause-NetBSD", FSF_LIBRE),
+    ("BSD-2-Clause-Patent", OSI_APPROVED),
+    ("BSD-2-Clause-Views", 0),
+    ("BSD-2-Clause-first-lines", 0),
+    ("BSD-3-Clause", OSI_APPROVED | FSF_LIBRE),
+    ("BSD-3-Clause-Attribution", 0),
+    ("BSD-3-Clause-Clear", FSF_LIBRE),
+    ("BSD-3-Clause-HP", 0),
+    ("BSD-3-Clause-LBNL", OSI_APPROVED),
+    ("BSD-3-Clause-Modification", 0),
+    ("BSD-3-Clause-No-Military-License", 0),
+    ("BSD-3-Clause-No-Nuclear-License", 0),
+    ("BSD-3-Clause-No-Nuclear-License-2014", 0),
+    ("BSD-3-Clause-No-Nuclear-Warranty", 0),
+    ("BSD-3-Clause-Open-MPI", 0),
+    ("BSD-3-Clause-Sun", 0),
+    ("BSD-3-Clause-acpica", 0),
+    ("BSD-3-Clause-flex", 0),
+    ("BSD-4-Clause", FSF_LIBRE),
+    ("BSD-4-Clause-Shortened", 0),
+    ("BSD-4-Clause-UC", 0),
+    ("BSD-4.3RENO", 0),
+    ("BSD-4.3TAHOE", 0),
+    ("BSD-Advertising-Acknowledgement", 0),
+    ("BSD-Attribution-HPND-disclaimer", 0),
+    ("BSD-Inferno-Nettverk", 0),
+    ("BSD-Protection", 0),
+    ("BSD-Source-Code", 0),
+    ("BSD-Source-beginning-file", 0),
+    ("BSD-Systemics", 0),
+    ("BSD-Systemics-W3Works", 0),
+    ("BSL-1.0", OSI_APPROVED | FSF_LIBRE),
+    ("BUSL-1.1", 0),
+    ("Baekmuk", 0),
+    ("Bahyph", 0),
+    ("Barr", 0),
+    ("Beerware", 0),
+    ("BitTorrent-1.0", 0),
+    ("BitTorrent-1.1", FSF_LIBRE),
+    ("Bitstream-Charter", 0),
+    ("Bitstream-Vera", 0),
+    ("BlueOak-1.0.0", OSI_APPROVED),
+    ("Boehm-GC", 0),
+    ("Boehm-GC-without-fee", 0),
+    ("Borceux", 0),
+    ("Brian-Gladman-2-Clause", 0),
+    ("Brian-Gladman-3-Clause", 0),
+    ("C-UDA-1.0", 0),
+    ("CAL-1.0", OSI_APPROVED),
+    ("CAL-1.0-Combined-Work-Exception", OSI_APPROVED),
+    ("CATOSL-1.1", OSI_APPROVED),
+    ("CC-BY-1.0", 0),
+    ("CC-BY-2.0", 0),
+    ("CC-BY-2.5", 0),
+    ("CC-BY-2.5-AU", 0),
+    ("CC-BY-3.0", 0),
+    ("CC-BY-3.0-AT", 0),
+    ("CC-BY-3.0-AU", 0),
+    ("CC-BY-3.0-DE", 0),
+    ("CC-BY-3.0-IGO", 0),
+    ("CC-BY-3.0-NL", 0),
+    ("CC-BY-3.0-US", 0),
+    ("CC-BY-4.0", FSF_LIBRE),
+    ("CC-BY-NC-1.0", 0),
+    ("CC-BY-NC-2.0", 0),
+    ("CC-BY-NC-2.5", 0),
+    ("CC-BY-NC-3.0", 0),
+    ("CC-BY-NC-3.0-DE", 0),
+    ("CC-BY-NC-4.0", 0),
+    ("CC-BY-NC-ND-1.0", 0),
+    ("CC-BY-NC-ND-2.0", 0),
+    ("CC-BY-NC-ND-2.5", 0),
+    ("CC-BY-NC-ND-3.0", 0),
+    ("CC-BY-NC-ND-3.0-DE", 0),
+    ("CC-BY-NC-ND-3.0-IGO", 0),
+    ("CC-BY-NC-ND-4.0", 0),
+    ("CC-BY-NC-SA-1.0", 0),
+    ("CC-BY-NC-SA-2.0", 0),
+    ("CC-BY-NC-SA-2.0-DE", 0),
+    ("CC-BY-NC-SA-2.0-FR", 0),
+    ("CC-BY-NC-SA-2.0-UK", 0),
+    ("CC-BY-NC-SA-2.5", 0),
+    ("CC-BY-NC-SA-3.0", 0),
+    ("CC-BY-NC-SA-3.0-DE", 0),
+    ("CC-BY-NC-SA-3.0-IGO", 0),
+    ("CC-BY-NC-SA-4.0", 0),
+    ("CC-BY-ND-1.0", 0),
+    ("CC-BY-ND-2.0", 0),
+    ("CC-BY-ND-2.5", 0),
+    ("CC-BY-ND-3.0", 0),
+    ("CC-BY-ND-3.0-DE", 0),
+    ("CC-BY-ND-4.0", 0),
+    ("CC-BY-SA-1.0", 0),
+    ("CC-BY-SA-2.0", 0),
+    ("CC-BY-SA-2.0-UK", 0),
+    ("CC-BY-SA-2.1-JP", 0),
+    ("CC-BY-SA-2.5", 0),
+    ("CC-BY-SA-3.0", 0),
+    ("CC-BY-SA-3.0-AT", 0),
+    ("CC-BY-SA-3.0-DE", 0),
+    ("CC-BY-SA-3.0-IGO", 0),
+    ("CC-BY-SA-4.0", FSF_LIBRE),
+    ("CC-PDDC", 0),
+    ("CC-PDM-1.0", 0),
+    ("CC-SA-1.0", 0),
+    ("CC0-1.0", FSF_LIBRE),
+    ("CDDL-1.0", OSI_APPROVED | FSF_LIBRE),
+    ("CDDL-1.1", 0),
+    ("CDL-1.0", 0),
+    ("CDLA-Permissive-1.0", 0),
+    ("CDLA-Permissive-2.0", 0),
+    ("CDLA-Sharing-1.0", 0),
+    ("CECILL-1.0", 0),
+    ("CECILL-1.1", 0),
+    ("CECILL-2.0", FSF_LIBRE),
+    ("CECILL-2.1", OSI_APPROVED),
+    ("CECILL-B", FSF_LIBRE),
+    ("CECILL-C", FSF_LIBRE),
+    ("CERN-OHL-1.1", 0),
+    ("CERN-OHL-1.2", 0),
+    ("CERN-OHL-P-2.0", OSI_APPROVED),
+    ("CERN-OHL-S-2.0", OSI_APPROVED),
+    ("CERN-OHL-W-2.0", OSI_APPROVED),
+    ("CFITSIO", 0),
+    ("CMU-Mach", 0),
+    ("CMU-Mach-nodoc", 0),
+    ("CNRI-Jython", 0),
+    ("CNRI-Python", OSI_APPROVED),
+    ("CNRI-Python-GPL-Compatible", 0),
+    ("COIL-1.0", 0),
+    ("CPAL-1.0", OSI_APPROVED | FSF_LIBRE),
+    ("CPL-1.0", OSI_APPROVED | FSF_LIBRE),
+    ("CPOL-1.02", 0),
+    ("CUA-OPL-1.0", OSI_APPROVED),
+    ("Caldera", 0),
+    ("Caldera-no-preamble", 0),
+    ("Catharon", 0),
+    ("ClArtistic", FSF_LIBRE),
+    ("Clips", 0),
+    ("Community-Spec-1.0", 0),
+    ("Condor-1.1", FSF_LIBRE),
+    ("Cornell-Lossless-JPEG", 0),
+    ("Cronyx", 0),
+    ("Crossword", 0),
+    ("CrystalStacker", 0),
+    ("Cube", 0),
+    ("D-FSL-1.0", 0),
+    ("DEC-3-Clause", 0),
+    ("DL-DE-BY-2.0", 0),
+    ("DL-DE-ZERO-2.0", 0),
+    ("DOC", 0),
+    ("DRL-1.0", 0),
+    ("DRL-1.1", 0),
+    ("DSDP", 0),
+    ("DocBook-Schema", 0),
+    ("DocBook-Stylesheet", 0),
+    ("DocBook-XML", 0),
+    ("Dotseqn", 0),
+    ("ECL-1.0", OSI_APPROVED),
+    ("ECL-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("EFL-1.0", OSI_APPROVED),
+    ("EFL-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("EPICS", 0),
+    ("EPL-1.0", OSI_APPROVED | FSF_LIBRE),
+    ("EPL-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("EUDatagrid", OSI_APPROVED | FSF_LIBRE),
+    ("EUPL-1.0", 0),
+    ("EUPL-1.1", OSI_APPROVED | FSF_LIBRE),
+    ("EUPL-1.2", OSI_APPROVED | FSF_LIBRE),
+    ("Elastic-2.0", 0),
+    ("Entessa", OSI_APPROVED),
+    ("ErlPL-1.1", 0),
+    ("Eurosym", 0),
+    ("FBM", 0),
+    ("FDK-AAC", 0),
+    ("FSFAP", FSF_LIBRE),
+    ("FSFAP-no-warranty-disclaimer", 0),
+    ("FSFUL", 0),
+    ("FSFULLR", 0),
+    ("FSFULLRWD", 0),
+    ("FTL", FSF_LIBRE),
+    ("Fair", OSI_APPROVED),
+    ("Ferguson-Twofish", 0),
+    ("Frameworx-1.0", OSI_APPROVED),
+    ("FreeBSD-DOC", 0),
+    ("FreeImage", 0),
+    ("Furuseth", 0),
+    ("GCR-docs", 0),
+    ("GD", 0),
+    ("GFDL-1.1", FSF_LIBRE),
+    ("GFDL-1.1-invariants", 0),
+    ("GFDL-1.1-invariants-only", 0),
+    ("GFDL-1.1-invariants-or-later", 0),
+    ("GFDL-1.1-no-invariants", 0),
+    ("GFDL-1.1-no-invariants-only", 0),
+    ("GFDL-1.1-no-invariants-or-later", 0),
+    ("GFDL-1.1-only", FSF_LIBRE),
+    ("GFDL-1.1-or-later", FSF_LIBRE),
+    ("GFDL-1.2", FSF_LIBRE),
+    ("GFDL-1.2-invariants", 0),
+    ("GFDL-1.2-invariants-only", 0),
+    ("GFDL-1.2-invariants-or-later", 0),
+    ("GFDL-1.2-no-invariants", 0),
+    ("GFDL-1.2-no-invariants-only", 0),
+    ("GFDL-1.2-no-invariants-or-later", 0),
+    ("GFDL-1.2-only", FSF_LIBRE),
+    ("GFDL-1.2-or-later", FSF_LIBRE),
+    ("GFDL-1.3", FSF_LIBRE),
+    ("GFDL-1.3-invariants", 0),
+    ("GFDL-1.3-invariants-only", 0),
+    ("GFDL-1.3-invariants-or-later", 0),
+    ("GFDL-1.3-no-invariants", 0),
+    ("GFDL-1.3-no-invariants-only", 0),
+    ("GFDL-1.3-no-invariants-or-later", 0),
+    ("GFDL-1.3-only", FSF_LIBRE),
+    ("GFDL-1.3-or-later", FSF_LIBRE),
+    ("GL2PS", 0),
+    ("GLWTPL", 0),
+    ("GPL-1.0", 0),
+    ("GPL-1.0+", 0),
+    ("GPL-1.0-only", 0),
+    ("GPL-1.0-or-later", 0),
+    ("GPL-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("GPL-2.0+", OSI_APPROVED | FSF_LIBRE),
+    ("GPL-2.0-only", OSI_APPROVED | FSF_LIBRE),
+    ("GPL-2.0-or-later", OSI_APPROVED | FSF_LIBRE),
+    ("GPL-2.0-with-GCC-exception", 0),
+    ("GPL-2.0-with-autoconf-exception", 0),
+    ("GPL-2.0-with-bison-exception", 0),
+    ("GPL-2.0-with-classpath-exception", 0),
+    ("GPL-2.0-with-font-exception", 0),
+    ("GPL-3.0", OSI_APPROVED | FSF_LIBRE),
+    ("GPL-3.0+", OSI_APPROVED | FSF_LIBRE),
+    ("GPL-3.0-only", OSI_APPROVED | FSF_LIBRE),
+    ("GPL-3.0-or-later", OSI_APPROVED | FSF_LIBRE),
+    ("GPL-3.0-with-GCC-exception", OSI_APPROVED),
+    ("GPL-3.0-with-autoconf-exception", 0),
+    ("Giftware", 0),
+    ("Glide", 0),
+    ("Glulxe", 0),
+    ("Graphics-Gems", 0),
+    ("Gutmann", 0),
+    ("HIDAPI", 0),
+    ("HP-1986", 0),
+    ("HP-1989", 0),
+    ("HPND", OSI_APPROVED | FSF_LIBRE),
+    ("HPND-DEC", 0),
+    ("HPND-Fenneberg-Livingston", 0),
+    ("HPND-INRIA-IMAG", 0),
+    ("HPND-Intel", 0),
+    ("HPND-Kevlin-Henney", 0),
+    ("HPND-MIT-disclaimer", 0),
+    ("HPND-Markus-Kuhn", 0),
+    ("HPND-Netrek", 0),
+    ("HPND-Pbmplus", 0),
+    ("HPND-UC", 0),
+    ("HPND-UC-export-US", 0),
+    ("HPND-doc", 0),
+    ("HPND-doc-sell", 0),
+    ("HPND-export-US", 0),
+    ("HPND-export-US-acknowledgement", 0),
+    ("HPND-export-US-modify", 0),
+    ("HPND-export2-US", 0),
+    ("HPND-merchantability-variant", 0),
+    ("HPND-sell-MIT-disclaimer-xserver", 0),
+    ("HPND-sell-regexpr", 0),
+    ("HPND-sell-variant", 0),
+    ("HPND-sell-variant-MIT-disclaimer", 0),
+    ("HPND-sell-variant-MIT-disclaimer-rev", 0),
+    ("HTMLTIDY", 0),
+    ("HaskellReport", 0),
+    ("Hippocratic-2.1", 0),
+    ("IBM-pibs", 0),
+    ("ICU", OSI_APPROVED),
+    ("IEC-Code-Components-EULA", 0),
+    ("IJG", FSF_LIBRE),
+    ("IJG-short", 0),
+    ("IPA", OSI_APPROVED | FSF_LIBRE),
+    ("IPL-1.0", OSI_APPROVED | FSF_LIBRE),
+    ("ISC", OSI_APPROVED | FSF_LIBRE),
+    ("ISC-Veillard", 0),
+    ("ImageMagick", 0),
+    ("Imlib2", FSF_LIBRE),
+    ("Info-ZIP", 0),
+    ("Inner-Net-2.0", 0),
+    ("InnoSetup", 0),
+    ("Intel", OSI_APPROVED | FSF_LIBRE),
+    ("Intel-ACPI", 0),
+    ("Interbase-1.0", 0),
+    ("JPL-image", 0),
+    ("JPNIC", 0),
+    ("JSON", 0),
+    ("Jam", OSI_APPROVED),
+    ("JasPer-2.0", 0),
+    ("Kastrup", 0),
+    ("Kazlib", 0),
+    ("Knuth-CTAN", 0),
+    ("LAL-1.2", 0),
+    ("LAL-1.3", 0),
+    ("LGPL-2.0", OSI_APPROVED),
+    ("LGPL-2.0+", OSI_APPROVED),
+    ("LGPL-2.0-only", OSI_APPROVED),
+    ("LGPL-2.0-or-later", OSI_APPROVED),
+    ("LGPL-2.1", OSI_APPROVED | FSF_LIBRE),
+    ("LGPL-2.1+", OSI_APPROVED | FSF_LIBRE),
+    ("LGPL-2.1-only", OSI_APPROVED | FSF_LIBRE),
+    ("LGPL-2.1-or-later", OSI_APPROVED | FSF_LIBRE),
+    ("LGPL-3.0", OSI_APPROVED | FSF_LIBRE),
+    ("LGPL-3.0+", OSI_APPROVED | FSF_LIBRE),
+    ("LGPL-3.0-only", OSI_APPROVED | FSF_LIBRE),
+    ("LGPL-3.0-or-later", OSI_APPROVED | FSF_LIBRE),
+    ("LGPLLR", 0),
+    ("LOOP", 0),
+    ("LPD-document", 0),
+    ("LPL-1.0", OSI_APPROVED),
+    ("LPL-1.02", OSI_APPROVED | FSF_LIBRE),
+    ("LPPL-1.0", 0),
+    ("LPPL-1.1", 0),
+    ("LPPL-1.2", FSF_LIBRE),
+    ("LPPL-1.3a", FSF_LIBRE),
+    ("LPPL-1.3c", OSI_APPROVED),
+    ("LZMA-SDK-9.11-to-9.20", 0),
+    ("LZMA-SDK-9.22", 0),
+    ("Latex2e", 0),
+    ("Latex2e-translated-notice", 0),
+    ("Leptonica", 0),
+    ("LiLiQ-P-1.1", OSI_APPROVED),
+    ("LiLiQ-R-1.1", OSI_APPROVED),
+    ("LiLiQ-Rplus-1.1", OSI_APPROVED),
+    ("Libpng", 0),
+    ("Linux-OpenIB", 0),
+    ("Linux-man-pages-1-para", 0),
+    ("Linux-man-pages-copyleft", 0),
+    ("Linux-man-pages-copyleft-2-para", 0),
+    ("Linux-man-pages-copyleft-var", 0),
+    ("Lucida-Bitmap-Fonts", 0),
+    ("MIPS", 0),
+    ("MIT", OSI_APPROVED | FSF_LIBRE),
+    ("MIT-0", OSI_APPROVED),
+    ("MIT-CMU", 0),
+    ("MIT-Click", 0),
+    ("MIT-Festival", 0),
+    ("MIT-Khronos-old", 0),
+    ("MIT-Modern-Variant", OSI_APPROVED),
+    ("MIT-Wu", 0),
+    ("MIT-advertising", 0),
+    ("MIT-enna", 0),
+    ("MIT-feh", 0),
+    ("MIT-open-group", 0),
+    ("MIT-testregex", 0),
+    ("MITNFA", 0),
+    ("MMIXware", 0),
+    ("MPEG-SSG", 0),
+    ("MPL-1.0", OSI_APPROVED),
+    ("MPL-1.1", OSI_APPROVED | FSF_LIBRE),
+    ("MPL-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("MPL-2.0-no-copyleft-exception", OSI_APPROVED),
+    ("MS-LPL", 0),
+    ("MS-PL", OSI_APPROVED | FSF_LIBRE),
+    ("MS-RL", OSI_APPROVED | FSF_LIBRE),
+    ("MTLL", 0),
+    ("Mackerras-3-Clause", 0),
+    ("Mackerras-3-Clause-acknowledgment", 0),
+    ("MakeIndex", 0),
+    ("Martin-Birgmeier", 0),
+    ("McPhee-slideshow", 0),
+    ("Minpack", 0),
+    ("MirOS", OSI_APPROVED),
+    ("Motosoto", OSI_APPROVED),
+    ("MulanPSL-1.0", 0),
+    ("MulanPSL-2.0", OSI_APPROVED),
+    ("Multics", OSI_APPROVED),
+    ("Mup", 0),
+    ("NAIST-2003", 0),
+    ("NASA-1.3", OSI_APPROVED),
+    ("NBPL-1.0", 0),
+    ("NCBI-PD", 0),
+    ("NCGL-UK-2.0", 0),
+    ("NCL", 0),
+    ("NCSA", OSI_APPROVED | FSF_LIBRE),
+    ("NGPL", OSI_APPROVED),
+    ("NICTA-1.0", 0),
+    ("NIST-PD", 0),
+    ("NIST-PD-fallback", 0),
+    ("NIST-Software", 0),
+    ("NLOD-1.0", 0),
+    ("NLOD-2.0", 0),
+    ("NLPL", 0),
+    ("NOASSERTION", 0),
+    ("NOSL", FSF_LIBRE),
+    ("NPL-1.0", FSF_LIBRE),
+    ("NPL-1.1", FSF_LIBRE),
+    ("NPOSL-3.0", OSI_APPROVED),
+    ("NRL", 0),
+    ("NTP", OSI_APPROVED),
+    ("NTP-0", 0),
+    ("Naumen", OSI_APPROVED),
+    ("Net-SNMP", 0),
+    ("NetCDF", 0),
+    ("Newsletr", 0),
+    ("Nokia", OSI_APPROVED | FSF_LIBRE),
+    ("Noweb", 0),
+    ("Nunit", FSF_LIBRE),
+    ("O-UDA-1.0", 0),
+    ("OAR", 0),
+    ("OCCT-PL", 0),
+    ("OCLC-2.0", OSI_APPROVED),
+    ("ODC-By-1.0", 0),
+    ("ODbL-1.0", FSF_LIBRE),
+    ("OFFIS", 0),
+    ("OFL-1.0", FSF_LIBRE),
+    ("OFL-1.0-RFN", 0),
+    ("OFL-1.0-no-RFN", 0),
+    ("OFL-1.1", OSI_APPROVED | FSF_LIBRE),
+    ("OFL-1.1-RFN", OSI_APPROVED),
+    ("OFL-1.1-no-RFN", OSI_APPROVED),
+    ("OGC-1.0", 0),
+    ("OGDL-Taiwan-1.0", 0),
+    ("OGL-Canada-2.0", 0),
+    ("OGL-UK-1.0", 0),
+    ("OGL-UK-2.0", 0),
+    ("OGL-UK-3.0", 0),
+    ("OGTSL", OSI_APPROVED),
+    ("OLDAP-1.1", 0),
+    ("OLDAP-1.2", 0),
+    ("OLDAP-1.3", 0),
+    ("OLDAP-1.4", 0),
+    ("OLDAP-2.0", 0),
+    ("OLDAP-2.0.1", 0),
+    ("OLDAP-2.1", 0),
+    ("OLDAP-2.2", 0),
+    ("OLDAP-2.2.1", 0),
+    ("OLDAP-2.2.2", 0),
+    ("OLDAP-2.3", FSF_LIBRE),
+    ("OLDAP-2.4", 0),
+    ("OLDAP-2.5", 0),
+    ("OLDAP-2.6", 0),
+    ("OLDAP-2.7", FSF_LIBRE),
+    ("OLDAP-2.8", OSI_APPROVED),
+    ("OLFL-1.3", OSI_APPROVED),
+    ("OML", 0),
+    ("OPL-1.0", 0),
+    ("OPL-UK-3.0", 0),
+    ("OPUBL-1.0", 0),
+    ("OSET-PL-2.1", OSI_APPROVED),
+    ("OSL-1.0", OSI_APPROVED | FSF_LIBRE),
+    ("OSL-1.1", FSF_LIBRE),
+    ("OSL-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("OSL-2.1", OSI_APPROVED | FSF_LIBRE),
+    ("OSL-3.0", OSI_APPROVED | FSF_LIBRE),
+    ("OpenPBS-2.3", 0),
+    ("OpenSSL", FSF_LIBRE),
+    ("OpenSSL-standalone", 0),
+    ("OpenVision", 0),
+    ("PADL", 0),
+    ("PDDL-1.0", 0),
+    ("PHP-3.0", OSI_APPROVED),
+    ("PHP-3.01", OSI_APPROVED | FSF_LIBRE),
+    ("PPL", 0),
+    ("PSF-2.0", 0),
+    ("Parity-6.0.0", 0),
+    ("Parity-7.0.0", 0),
+    ("Pixar", 0),
+    ("Plexus", 0),
+    ("PolyForm-Noncommercial-1.0.0", 0),
+    ("PolyForm-Small-Business-1.0.0", 0),
+    ("PostgreSQL", OSI_APPROVED),
+    ("Python-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("Python-2.0.1", 0),
+    ("QPL-1.0", OSI_APPROVED | FSF_LIBRE),
+    ("QPL-1.0-INRIA-2004", 0),
+    ("Qhull", 0),
+    ("RHeCos-1.1", 0),
+    ("RPL-1.1", OSI_APPROVED),
+    ("RPL-1.5", OSI_APPROVED),
+    ("RPSL-1.0", OSI_APPROVED | FSF_LIBRE),
+    ("RSA-MD", 0),
+    ("RSCPL", OSI_APPROVED),
+    ("Rdisc", 0),
+    ("Ruby", FSF_LIBRE),
+    ("Ruby-pty", 0),
+    ("SAX-PD", 0),
+    ("SAX-PD-2.0", 0),
+    ("SCEA", 0),
+    ("SGI-B-1.0", 0),
+    ("SGI-B-1.1", 0),
+    ("SGI-B-2.0", FSF_LIBRE),
+    ("SGI-OpenGL", 0),
+    ("SGP4", 0),
+    ("SHL-0.5", 0),
+    ("SHL-0.51", 0),
+    ("SISSL", OSI_APPROVED | FSF_LIBRE),
+    ("SISSL-1.2", 0),
+    ("SL", 0),
+    ("SMAIL-GPL", 0),
+    ("SMLNJ", FSF_LIBRE),
+    ("SMPPL", 0),
+    ("SNIA", 0),
+    ("SPL-1.0", OSI_APPROVED | FSF_LIBRE),
+    ("SSH-OpenSSH", 0),
+    ("SSH-short", 0),
+    ("SSLeay-standalone", 0),
+    ("SSPL-1.0", 0),
+    ("SWL", 0),
+    ("Saxpath", 0),
+    ("SchemeReport", 0),
+    ("Sendmail", 0),
+    ("Sendmail-8.23", 0),
+    ("Sendmail-Open-Source-1.1", 0),
+    ("SimPL-2.0", OSI_APPROVED),
+    ("Sleepycat", OSI_APPROVED | FSF_LIBRE),
+    ("Soundex", 0),
+    ("Spencer-86", 0),
+    ("Spencer-94", 0),
+    ("Spencer-99", 0),
+    ("StandardML-NJ", FSF_LIBRE),
+    ("SugarCRM-1.1.3", 0),
+    ("Sun-PPP", 0),
+    ("Sun-PPP-2000", 0),
+    ("SunPro", 0),
+    ("Symlinks", 0),
+    ("TAPR-OHL-1.0", 0),
+    ("TCL", 0),
+    ("TCP-wrappers", 0),
+    ("TGPPL-1.0", 0),
+    ("TMate", 0),
+    ("TORQUE-1.1", 0),
+    ("TOSL", 0),
+    ("TPDL", 0),
+    ("TPL-1.0", 0),
+    ("TTWL", 0),
+    ("TTYP0", 0),
+    ("TU-Berlin-1.0", 0),
+    ("TU-Berlin-2.0", 0),
+    ("TermReadKey", 0),
+    ("ThirdEye", 0),
+    ("TrustedQSL", 0),
+    ("UCAR", 0),
+    ("UCL-1.0", OSI_APPROVED),
+    ("UMich-Merit", 0),
+    ("UPL-1.0", OSI_APPROVED | FSF_LIBRE),
+    ("URT-RLE", 0),
+    ("Ubuntu-font-1.0", 0),
+    ("Unicode-3.0", OSI_APPROVED),
+    ("Unicode-DFS-2015", 0),
+    ("Unicode-DFS-2016", OSI_APPROVED),
+    ("Unicode-TOU", 0),
+    ("UnixCrypt", 0),
+    ("Unlicense", OSI_APPROVED | FSF_LIBRE),
+    ("VOSTROM", 0),
+    ("VSL-1.0", OSI_APPROVED),
+    ("Vim", FSF_LIBRE),
+    ("W3C", OSI_APPROVED | FSF_LIBRE),
+    ("W3C-19980720", 0),
+    ("W3C-20150513", OSI_APPROVED),
+    ("WTFPL", FSF_LIBRE),
+    ("Watcom-1.0", OSI_APPROVED),
+    ("Widget-Workshop", 0),
+    ("Wsuipa", 0),
+    ("X11", FSF_LIBRE),
+    ("X11-distribute-modifications-variant", 0),
+    ("X11-swapped", 0),
+    ("XFree86-1.1", FSF_LIBRE),
+    ("XSkat", 0),
+    ("Xdebug-1.03", 0),
+    ("Xerox", 0),
+    ("Xfig", 0),
+    ("Xnet", OSI_APPROVED),
+    ("YPL-1.0", 0),
+    ("YPL-1.1", FSF_LIBRE),
+    ("ZPL-1.1", 0),
+    ("ZPL-2.0", OSI_APPROVED | FSF_LIBRE),
+    ("ZPL-2.1", OSI_APPROVED | FSF_LIBRE),
+    ("Zed", 0),
+    ("Zeeff", 0),
+    ("Zend-2.0", FSF_LIBRE),
+    ("Zimbra-1.3", FSF_LIBRE),
+    ("Zimbra-1.4", 0),
+    ("Zlib", OSI_APPROVED | FSF_LIBRE),
+    ("any-OSI", 0),
+    ("any-OSI-perl-modules", 0),
+    ("bcrypt-Solar-Designer", 0),
+    ("blessing", 0),
+    ("bzip2-1.0.5", 0),
+    ("bzip2-1.0.6", 0),
+    ("check-cvs", 0),
+    ("checkmk", 0),
+    ("copyleft-next-0.3.0", 0),
+    ("copyleft-next-0.3.1", 0),
+    ("curl", 0),
+    ("cve-tou", 0),
+    ("diffmark", 0),
+    ("dtoa", 0),
+    ("dvipdfm", 0),
+    ("eCos-2.0", FSF_LIBRE),
+    ("eGenix", 0),
+    ("etalab-2.0", 0),
+    ("fwlw", 0),
+    ("gSOAP-1.3b", 0),
+    ("generic-xts", 0),
+    ("gnuplot", FSF_LIBRE),
+    ("gtkbook", 0),
+    ("hdparm", 0),
+    ("iMatix", FSF_LIBRE),
+    ("libpng-2.0", 0),
+    ("libselinux-1.0", 0),
+    ("libtiff", 0),
+    ("libutil-David-Nugent", 0),
+    ("lsof", 0),
+    ("magaz", 0),
+    ("mailprio", 0),
+    ("metamail", 0),
+    ("mpi-permissive", 0),
+    ("mpich2", 0),
+    ("mplus", 0),
+    ("pkgconf", 0),
+    ("pnmstitch", 0),
+    ("psfrag", 0),
+    ("psutils", 0),
+    ("python-ldap", 0),
+    ("radvd", 0),
+    ("snprintf", 0),
+    ("softSurfer", 0),
+    ("ssh-keyscan", 0),
+    ("swrule", 0),
+    ("threeparttable", 0),
+    ("ulem", 0),
+    ("w3m", 0),
+    ("wwl", 0),
+    ("wxWindows", OSI_APPROVED),
+    ("xinetd", FSF_LIBRE),
+    ("xkeyboard-config-Zinoviev", 0),
+    ("xlock", 0),
+    ("xpp", 0),
+    ("xzoom", 0),
+    ("zlib-acknowledgement", 0),
+];
+
+/// The SPDX license exception identifiers, deprecated ones included.
+pub(crate) const EXCEPTIONS: &[&str] = &[
+    "389-exception",
+    "Asterisk-exception",
+    "Asterisk-linking-protocols-exception",
+    "Autoconf-exception-2.0",
+    "Autoconf-exception-3.0",
+    "Autoconf-exception-generic",
+    "Autoconf-exception-generic-3.0",
+    "Autoconf-exception-macro",
+    "Bison-exception-1.24",
+    "Bison-exception-2.2",
+    "Bootloader-exception",
+    "CGAL-linking-exception",
+    "CLISP-exception-2.0",
+    "Classpath-exception-2.0",
+    "DigiRule-FOSS-exception",
+    "FLTK-exception",
+    "Fawkes-Runtime-exception",
+    "Font-exception-2.0",
+    "GCC-exception-2.0",
+    "GCC-exception-2.0-note",
+    "GCC-exception-3.1",
+    "GNAT-exception",
+    "GNOME-examples-exception",
+    "GNU-compiler-exception",
+    "GPL-3.0-389-ds-base-exception",
+    "GPL-3.0-interface-exception",
+    "GPL-3.0-linking-exception",
+    "GPL-3.0-linking-source-exception",
+    "GPL-CC-1.0",
+    "GStreamer-exception-2005",
+    "GStreamer-exception-2008",
+    "Gmsh-exception",
+    "Independent-modules-exception",
+    "KiCad-libraries-exception",
+    "LGPL-3.0-linking-exception",
+    "LLGPL",
+    "LLVM-exception",
+    "LZMA-exception",
+    "Libtool-exception",
+    "Linux-syscall-note",
+    "Nokia-Qt-exception-1.1",
+    "OCCT-exception-1.0",
+    "OCaml-LGPL-linking-exception",
+    "OpenJDK-assembly-exception-1.0",
+    "PCRE2-exception",
+    "PS-or-PDF-font-exception-20170817",
+    "QPL-1.0-INRIA-2004-exception",
+    "Qt-GPL-exception-1.0",
+    "Qt-LGPL-exception-1.1",
+    "Qwt-exception-1.0",
+    "RRDtool-FLOSS-exception-2.0",
+    "SANE-exception",
+    "SHL-2.0",
+    "SHL-2.1",
+    "SWI-exception",
+    "Swift-exception",
+    "Texinfo-exception",
+    "UBDL-exception",
+    "Universal-FOSS-exception-1.0",
+    "WxWindows-exception-3.1",
+    "cryptsetup-OpenSSL-exception",
+    "eCos-exception-2.0",
+    "erlang-otp-linking-exception",
+    "fmt-exception",
+    "freertos-exception-2.0",
+    "gnu-javamail-exception",
+    "harbour-exception",
+    "i2p-gpl-java-exception",
+    "libpri-OpenH323-exception",
+    "mif-exception",
+    "mxml-exception",
+    "openvpn-openssl-exception",
+    "romic-exception",
+    "stunnel-exception",
+    "u-boot-exception-2.0",
+    "vsftpd-openssl-exception",
+    "x11vnc-openssl-exception",
+];
@@ -0,0 +1,360 @@
+use super::enums::Provide;
+use super::error::ParseError;
+use super::pool::{Pool, Snapshot};
+use super::{AppId, Collection, Component};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS components (
+    rowid INTEGER PRIMARY KEY,
+    id TEXT NOT NULL UNIQUE,
+    kind TEXT NOT NULL,
+    origin TEXT,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS provides (
+    component INTEGER NOT NULL REFERENCES components(rowid) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    value TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS provides_value ON provides(kind, value);
+CREATE TABLE IF NOT EXISTS mimetypes (
+    component INTEGER NOT NULL REFERENCES components(rowid) ON DELETE CASCADE,
+    mimetype TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS mimetypes_mimetype ON mimetypes(mimetype);
+CREATE TABLE IF NOT EXISTS releases (
+    component INTEGER NOT NULL REFERENCES components(rowid) ON DELETE CASCADE,
+    version TEXT NOT NULL,
+    date TEXT,
+    kind TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS releases_component ON releases(component);
+CREATE TABLE IF NOT EXISTS translations (
+    component INTEGER NOT NULL REFERENCES components(rowid) ON DELETE CASCADE,
+    field TEXT NOT NULL,
+    locale TEXT NOT NULL,
+    value TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS translations_component ON translations(component, field);
+";
+
+/// The key of a provided item in the `provides` table: its kind, as serialized,
+/// and its value, the JSON text of the non-string ones like firmwares.
+fn provide_key(provide: &Provide) -> Result<(String, String), ParseError> {
+    let invalid = || ParseError::invalid_value(&format!("{:?}", provide), "provide", "provides");
+    match serde_json::to_value(provide).map_err(|_| invalid())? {
+        Value::Object(map) => {
+            let (kind, value) = map.into_iter().next().ok_or_else(invalid)?;
+            let value = match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            Ok((kind, value))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn decode(id: &str, data: &str) -> Result<Component, ParseError> {
+    serde_json::from_str(data).map_err(|_| ParseError::invalid_value(id, "data", "components"))
+}
+
+#[derive(Debug)]
+/// A pool of components persisted in a SQLite database.
+///
+/// The components are stored whole, as JSON, next to normalized tables of their
+/// provided items, MIME types, releases and translated texts. The lookups go
+/// through the indices of those tables and only decode the matching components,
+/// so a server can answer queries without keeping the catalogs in memory. The
+/// tables can be queried directly with SQL as well:
+///
+/// ```text
+/// components(rowid, id, kind, origin, data)
+/// provides(component, kind, value)
+/// mimetypes(component, mimetype)
+/// releases(component, version, date, kind)
+/// translations(component, field, locale, value)
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use appstream::pool::Pool;
+/// use appstream::sqlite::SqliteStore;
+/// use appstream::Collection;
+///
+/// let collection = Collection::from_path("./tests/collections/gnome-apps.xml".into()).unwrap();
+/// let store = SqliteStore::open_in_memory().unwrap();
+/// store.save(&Pool::from_collections(vec![collection]).snapshot()).unwrap();
+///
+/// let characters = store.get(&"org.gnome.Characters".into()).unwrap();
+/// assert!(characters.is_some());
+/// ```
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Opens a database file, creating it and its tables when needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the database.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ParseError> {
+        Self::new(Connection::open(path)?)
+    }
+
+    /// Opens a new database in memory.
+    pub fn open_in_memory() -> Result<Self, ParseError> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(conn: Connection) -> Result<Self, ParseError> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        // A panic can't leave a transaction half applied, it's rolled back
+        // when dropped.
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replaces the content of the database with the components of a pool, in
+    /// a single transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The state of the pool to persist.
+    pub fn save(&self, snapshot: &Snapshot) -> Result<(), ParseError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM components", [])?;
+        {
+            let mut component_stmt = tx.prepare(
+                "INSERT INTO components (rowid, id, kind, origin, data) VALUES (?1, ?2, ?3, ?4, ?5)",
+            )?;
+            let mut provide_stmt =
+                tx.prepare("INSERT INTO provides (component, kind, value) VALUES (?1, ?2, ?3)")?;
+            let mut mimetype_stmt =
+                tx.prepare("INSERT INTO mimetypes (component, mimetype) VALUES (?1, ?2)")?;
+            let mut release_stmt = tx.prepare(
+                "INSERT INTO releases (component, version, date, kind) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            let mut translation_stmt = tx.prepare(
+                "INSERT INTO translations (component, field, locale, value) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+
+            for (index, component) in snapshot.components().iter().enumerate() {
+                let rowid = index as i64 + 1;
+                let id = component.id.as_ref();
+                let data = serde_json::to_string(component.as_ref())
+                    .map_err(|_| ParseError::invalid_value(id, "data", "components"))?;
+                component_stmt.execute(params![
+                    rowid,
+                    id,
+                    component.kind.to_string(),
+                    component.origin.as_deref(),
+                    data
+                ])?;
+                for provide in component.provides.iter() {
+                    let (kind, value) = provide_key(provide)?;
+                    provide_stmt.execute(params![rowid, kind, value])?;
+                }
+                for mimetype in component.mimetypes.iter() {
+                    mimetype_stmt.execute(params![rowid, mimetype.to_ascii_lowercase()])?;
+                }
+                for release in component.releases.iter() {
+                    release_stmt.execute(params![
+                        rowid,
+                        release.version,
+                        release.date.as_ref().map(|d| d.to_string()),
+                        release.kind.to_string()
+                    ])?;
+                }
+                let texts = Some(("name", &component.name.0))
+                    .into_iter()
+                    .chain(component.summary.as_ref().map(|s| ("summary", &s.0)))
+                    .chain(
+                        component
+                            .description
+                            .as_ref()
+                            .map(|d| ("description", &d.0)),
+                    );
+                for (field, translations) in texts {
+                    for (locale, value) in translations.iter() {
+                        translation_stmt.execute(params![rowid, field, locale, value])?;
+                    }
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The number of components.
+    pub fn len(&self) -> Result<usize, ParseError> {
+        let count: i64 = self
+            .conn()
+            .query_row("SELECT COUNT(*) FROM components", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Whether the database has no components.
+    pub fn is_empty(&self) -> Result<bool, ParseError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// The component with a specific `AppId`, or with the same id suffixed by
+    /// `.desktop`, like `Snapshot::get`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id to look for.
+    pub fn get(&self, id: &AppId) -> Result<Option<Component>, ParseError> {
+        let data: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT data FROM components WHERE id = ?1 OR id = ?1 || '.desktop'
+                 ORDER BY id = ?1 DESC LIMIT 1",
+                params![id.as_ref()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        data.map(|data| decode(id.as_ref(), &data)).transpose()
+    }
+
+    fn query(
+        &self,
+        sql: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<Component>, ParseError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params, |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut components = Vec::new();
+        for row in rows {
+            let (id, data) = row?;
+            components.push(decode(&id, &data)?);
+        }
+        Ok(components)
+    }
+
+    /// Find the components that provide a specific public interface.
+    ///
+    /// # Arguments
+    ///
+    /// * `provide` - The provided item to look for.
+    pub fn find_by_provide(&self, provide: &Provide) -> Result<Vec<Component>, ParseError> {
+        let (kind, value) = provide_key(provide)?;
+        self.query(
+            "SELECT id, data FROM components WHERE rowid IN
+             (SELECT component FROM provides WHERE kind = ?1 AND value = ?2)
+             ORDER BY rowid",
+            &[&kind, &value],
+        )
+    }
+
+    /// Find the components that can handle a specific MIME type.
+    ///
+    /// # Arguments
+    ///
+    /// * `mimetype` - The MIME type, like `text/html`.
+    pub fn find_by_mimetype(&self, mimetype: &str) -> Result<Vec<Component>, ParseError> {
+        self.query(
+            "SELECT id, data FROM components WHERE rowid IN
+             (SELECT component FROM mimetypes WHERE mimetype = ?1)
+             ORDER BY rowid",
+            &[&mimetype.to_ascii_lowercase()],
+        )
+    }
+
+    /// Loads all the components back into an in-memory pool, in the order they
+    /// were saved.
+    pub fn load(&self) -> Result<Pool, ParseError> {
+        let components = self.query("SELECT id, data FROM components ORDER BY rowid", &[])?;
+        // The components already carry the origin of their catalog.
+        Ok(Pool::from_collections(vec![Collection {
+            version: String::new(),
+            origin: None,
+            media_base_url: None,
+            components,
+            architecture: None,
+            timestamp: None,
+            indices: Default::default(),
+        }]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn persist_pool() -> Result<(), Box<dyn Error>> {
+        let gnome = Collection::from_path("./tests/collections/gnome-apps.xml".into())?;
+        let spec = Collection::from_path("./tests/collections/spec_example.xml".into())?;
+        let pool = Pool::from_collections(vec![gnome.clone(), spec]);
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("pool.sqlite");
+        SqliteStore::open(&path)?.save(&pool.snapshot())?;
+
+        let store = SqliteStore::open(&path)?;
+        assert_eq!(store.len()?, pool.len());
+        let characters = store
+            .get(&"org.gnome.Characters".into())?
+            .ok_or("no component")?;
+        assert_eq!(
+            Some(&characters),
+            pool.get(&"org.gnome.Characters".into()).as_deref()
+        );
+        assert_eq!(characters.origin.as_deref(), gnome.origin.as_deref());
+        assert!(store.get(&"org.example.Missing".into())?.is_none());
+
+        assert_eq!(
+            store.find_by_mimetype("TEXT/PLAIN")?.len(),
+            pool.find_by_mimetype("text/plain").len()
+        );
+        let provide = pool
+            .snapshot()
+            .components()
+            .iter()
+            .find_map(|c| c.provides.first().cloned())
+            .ok_or("no provide")?;
+        assert_eq!(
+            store.find_by_provide(&provide)?.len(),
+            pool.find_by_provide(&provide).len()
+        );
+
+        let name: String = store.conn().query_row(
+            "SELECT value FROM translations JOIN components ON component = components.rowid
+             WHERE id = 'org.gnome.Characters.desktop' AND field = 'name' AND locale = 'C'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(Some(&name), characters.name.0.get("C"));
+
+        let loaded = store.load()?;
+        assert_eq!(loaded.len(), pool.len());
+        assert_eq!(
+            loaded.snapshot().components()[0],
+            pool.snapshot().components()[0]
+        );
+
+        store.save(&Pool::new().snapshot())?;
+        assert!(store.is_empty()?);
+        drop(store);
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+use super::enums::Control;
+#[cfg(feature = "linux")]
+use std::fs;
+#[cfg(feature = "linux")]
+use std::path::Path;
+
+/// `INPUT_PROP_POINTER`, set on touchpads.
+#[cfg(feature = "linux")]
+const PROP_POINTER: u64 = 1 << 0;
+/// `INPUT_PROP_DIRECT`, set on touch screens.
+#[cfg(feature = "linux")]
+const PROP_DIRECT: u64 = 1 << 1;
+/// `EV_REP`, the auto-repeat of keyboards.
+#[cfg(feature = "linux")]
+const EV_REP: u64 = 1 << 0x14;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// The hardware and the network state of a system, what the relations of the
+/// components, like `<requires><memory>`, `<display_length>` or `<control>`, are
+/// checked against.
+pub struct SystemProfile {
+    /// The physical memory, in MiB.
+    pub memory: Option<u64>,
+    /// The resolution of the connected displays, as `(width, height)` pixels.
+    pub displays: Vec<(u32, u32)>,
+    /// The input methods available.
+    pub controls: Vec<Control>,
+    /// Whether the system has a default network route.
+    pub online: bool,
+}
+
+impl SystemProfile {
+    /// The length of the shortest side of the largest display, in pixels.
+    pub fn display_length(&self) -> Option<u32> {
+        self.displays
+            .iter()
+            .max_by_key(|(width, height)| *width as u64 * *height as u64)
+            .map(|(width, height)| *width.min(height))
+    }
+
+    /// Whether an input method is available.
+    pub fn has_control(&self, control: Control) -> bool {
+        self.controls.contains(&control)
+    }
+
+    #[cfg(feature = "linux")]
+    /// Probes the running system, from `/proc` and `/sys`.
+    ///
+    /// The probing is best effort, what can't be read is left unknown.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::system::SystemProfile;
+    ///
+    /// let profile = SystemProfile::detect();
+    /// println!("{:?} MiB, online: {}", profile.memory, profile.online);
+    /// ```
+    pub fn detect() -> Self {
+        Self::detect_in(Path::new("/"))
+    }
+
+    #[cfg(feature = "linux")]
+    /// Probes a system whose `/proc` and `/sys` are mounted under `root`.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The root directory, `/` for the running system.
+    pub fn detect_in(root: &Path) -> Self {
+        let read = |path: &str| fs::read_to_string(root.join(path)).unwrap_or_default();
+        Self {
+            memory: parse_meminfo(&read("proc/meminfo")),
+            displays: read_displays(&root.join("sys/class/drm")),
+            controls: parse_input_devices(&read("proc/bus/input/devices")),
+            online: has_default_route(&read("proc/net/route"), &read("proc/net/ipv6_route")),
+        }
+    }
+}
+
+#[cfg(feature = "linux")]
+/// The `MemTotal` of `/proc/meminfo`, in MiB.
+fn parse_meminfo(meminfo: &str) -> Option<u64> {
+    meminfo.lines().find_map(|line| {
+        let kilobytes = line.strip_prefix("MemTotal:")?.trim().strip_suffix("kB")?;
+        Some(kilobytes.trim().parse::<u64>().ok()? / 1024)
+    })
+}
+
+#[cfg(feature = "linux")]
+/// The preferred modes of the connected DRM connectors.
+fn read_displays(drm: &Path) -> Vec<(u32, u32)> {
+    let mut connectors = match fs::read_dir(drm) {
+        Ok(entries) => entries.flatten().map(|e| e.path()).collect::<Vec<_>>(),
+        Err(_) => return vec![],
+    };
+    connectors.sort();
+    connectors
+        .iter()
+        .filter(|c| fs::read_to_string(c.join("status")).is_ok_and(|s| s.trim() == "connected"))
+        .filter_map(|c| {
+            // The preferred mode comes first, like `1920x1080`.
+            let modes = fs::read_to_string(c.join("modes")).ok()?;
+            let (width, height) = modes.lines().next()?.split_once('x')?;
+            let height = height.trim_end_matches(|c: char| !c.is_ascii_digit());
+            Some((width.parse().ok()?, height.parse().ok()?))
+        })
+        .collect()
+}
+
+#[cfg(feature = "linux")]
+/// The input methods of the devices of `/proc/bus/input/devices`.
+fn parse_input_devices(devices: &str) -> Vec<Control> {
+    let bits = |value: &str| u64::from_str_radix(value.trim(), 16).unwrap_or(0);
+    let mut controls = vec![];
+    for device in devices.split("\n\n") {
+        let (mut name, mut handlers, mut props, mut events) = ("", vec![], 0, 0);
+        for line in device.lines() {
+            if let Some(value) = line.strip_prefix("N: Name=") {
+                name = value.trim_matches('"');
+            } else if let Some(value) = line.strip_prefix("H: Handlers=") {
+                handlers = value.split_whitespace().collect();
+            } else if let Some(value) = line.strip_prefix("B: PROP=") {
+                props = bits(value);
+            } else if let Some(value) = line.strip_prefix("B: EV=") {
+                events = bits(value);
+            }
+        }
+
+        let is_pen = name
+            .split_whitespace()
+            .any(|w| w.eq_ignore_ascii_case("pen") || w.eq_ignore_ascii_case("stylus"));
+        let control = if handlers.iter().any(|h| h.starts_with("js")) {
+            Some(Control::Gamepad)
+        } else if is_pen {
+            Some(Control::Tablet)
+        } else if props & PROP_DIRECT != 0 {
+            Some(Control::Touch)
+        } else if props & PROP_POINTER != 0 || handlers.iter().any(|h| h.starts_with("mouse")) {
+            Some(Control::Pointing)
+        } else if handlers.contains(&"kbd") && events & EV_REP != 0 {
+            Some(Control::Keyboard)
+        } else {
+            None
+        };
+        if let Some(control) = control {
+            if !controls.contains(&control) {
+                controls.push(control);
+            }
+        }
+    }
+    controls
+}
+
+#[cfg(feature = "linux")]
+/// Whether the IPv4 or the IPv6 routing table has a default route.
+fn has_default_route(ipv4: &str, ipv6: &str) -> bool {
+    let ipv4 = ipv4.lines().skip(1).any(|line| {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        fields.len() > 1 && fields[0] != "lo" && fields[1] == "00000000"
+    });
+    let ipv6 = ipv6.lines().any(|line| {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        fields.len() > 9
+            && fields[0].bytes().all(|b| b == b'0')
+            && fields[1] == "00"
+            && fields[9] != "lo"
+    });
+    ipv4 || ipv6
+}
+
+#[cfg(all(test, feature = "linux"))]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn detect_profile() -> Result<(), Box<dyn Error>> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().join("system");
+        fs::create_dir_all(root.join("proc/bus/input"))?;
+        fs::create_dir_all(root.join("proc/net"))?;
+        fs::write(
+            root.join("proc/meminfo"),
+            "MemTotal:       16305448 kB\nMemFree:         1234567 kB\n",
+        )?;
+        fs::write(
+            root.join("proc/bus/input/devices"),
+            "I: Bus=0011 Vendor=0001 Product=0001 Version=ab83\n\
+             N: Name=\"AT Translated Set 2 keyboard\"\n\
+             H: Handlers=sysrq kbd leds event3\n\
+             B: PROP=0\n\
+             B: EV=120013\n\
+             \n\
+             I: Bus=0019 Vendor=0000 Product=0001 Version=0000\n\
+             N: Name=\"Power Button\"\n\
+             H: Handlers=kbd event0\n\
+             B: PROP=0\n\
+             B: EV=3\n\
+             \n\
+             I: Bus=0018 Vendor=06cb Product=7e7e Version=0100\n\
+             N: Name=\"SYNA7DB5:01 06CB:7E7E Touchpad\"\n\
+             H: Handlers=mouse0 event7\n\
+             B: PROP=5\n\
+             B: EV=1b\n",
+        )?;
+        fs::write(
+            root.join("proc/net/route"),
+            "Iface\tDestination\tGateway\nlo\t00000000\t00000000\n",
+        )?;
+        for (connector, status, modes) in [
+            ("card0-eDP-1", "connected", "2560x1600\n1920x1200\n"),
+            ("card0-HDMI-A-1", "disconnected", ""),
+        ] {
+            let connector = root.join("sys/class/drm").join(connector);
+            fs::create_dir_all(&connector)?;
+            fs::write(connector.join("status"), status)?;
+            fs::write(connector.join("modes"), modes)?;
+        }
+
+        let profile = SystemProfile::detect_in(&root);
+        assert_eq!(profile.memory, Some(15923));
+        assert_eq!(profile.displays, vec![(2560, 1600)]);
+        assert_eq!(profile.display_length(), Some(1600));
+        assert_eq!(profile.controls, vec![Control::Keyboard, Control::Pointing]);
+        assert!(profile.has_control(Control::Pointing));
+        assert!(!profile.online);
+
+        fs::write(
+            root.join("proc/net/route"),
+            "Iface\tDestination\tGateway\neth0\t00000000\t010200C0\n",
+        )?;
+        assert!(SystemProfile::detect_in(&root).online);
+        Ok(())
+    }
+}
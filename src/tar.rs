@@ -0,0 +1,112 @@
+use super::error::ParseError;
+use std::io::{self, Read};
+
+fn field(header: &[u8], start: usize, end: usize) -> String {
+    let field = &header[start..end];
+    let length = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..length]).to_string()
+}
+
+/// The regular files of a ustar archive, as `(path, content)` pairs.
+///
+/// Only the files whose path is accepted by `filter` are read, the content of the
+/// others is skipped without being buffered. The sizes of the headers aren't
+/// trusted, the content is buffered as it is read. The GNU long names aren't
+/// supported, the archives using them are rejected instead of having their files
+/// misnamed.
+pub(crate) fn entries<R: Read, F: FnMut(&str) -> bool>(
+    mut reader: R,
+    mut filter: F,
+) -> Result<Vec<(String, Vec<u8>)>, ParseError> {
+    let invalid = || ParseError::invalid_value("tar", "header", "tarball");
+    let mut entries = vec![];
+    let mut header = [0u8; 512];
+    loop {
+        match reader.read_exact(&mut header) {
+            Ok(()) => (),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        }
+        // The archive ends with empty blocks.
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+        let size =
+            u64::from_str_radix(field(&header, 124, 136).trim(), 8).map_err(|_| invalid())?;
+        let padded = size.div_ceil(512) * 512;
+        let mut name = field(&header, 0, 100);
+        if &header[257..262] == b"ustar" {
+            let prefix = field(&header, 345, 500);
+            if !prefix.is_empty() {
+                name = format!("{}/{}", prefix, name);
+            }
+        }
+
+        if header[156] == b'L' {
+            return Err(ParseError::invalid_value("L", "typeflag", "tarball"));
+        }
+
+        let mut block = (&mut reader).take(padded);
+        if matches!(header[156], b'0' | 0) && filter(&name) {
+            let mut content = vec![];
+            (&mut block).take(size).read_to_end(&mut content)?;
+            if (content.len() as u64) < size {
+                return Err(invalid());
+            }
+            io::copy(&mut block, &mut io::sink())?;
+            entries.push((name, content));
+        } else if io::copy(&mut block, &mut io::sink())? < size {
+            return Err(invalid());
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+/// Builds an uncompressed ustar archive.
+pub(crate) fn archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut data = vec![];
+    for (name, content) in files {
+        let mut header = [0u8; 512];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[100..107].copy_from_slice(b"0000644");
+        header[124..135].copy_from_slice(format!("{:011o}", content.len()).as_bytes());
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[148..156].copy_from_slice(b"        ");
+        let checksum: u32 = header.iter().map(|b| *b as u32).sum();
+        header[148..155].copy_from_slice(format!("{:06o}\0", checksum).as_bytes());
+        data.extend_from_slice(&header);
+        data.extend_from_slice(content);
+        data.resize(data.len().div_ceil(512) * 512, 0);
+    }
+    data.extend_from_slice(&[0u8; 1024]);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn malformed_archives() -> Result<(), Box<dyn Error>> {
+        let data = archive(&[("a.png", b"a"), ("b.png", b"bb")]);
+        let files = entries(&data[..], |name| name == "b.png")?;
+        assert_eq!(files, vec![("b.png".to_string(), b"bb".to_vec())]);
+
+        // A huge size is rejected once the data runs out, nothing is allocated
+        // upfront.
+        let mut huge = archive(&[("a.png", b"a")]);
+        huge[124..135].copy_from_slice(b"77777777777");
+        assert!(entries(&huge[..], |_| true).is_err());
+
+        let mut long_name = archive(&[("././@LongLink", b"a-very-long-name.png\0")]);
+        long_name[156] = b'L';
+        assert!(matches!(
+            entries(&long_name[..], |_| true),
+            Err(ParseError::InvalidValue(..))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+use super::error::ParseError;
+use serde::de::{self, Deserializer, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// How precise a `Timestamp` is.
+pub enum Precision {
+    /// A calendar date, without a time.
+    Date,
+    /// A date and a time.
+    DateTime,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A point in time, in seconds since the Unix epoch in UTC, like the dates of
+/// the releases.
+///
+/// Calendar dates like `2019-06-20` are kept as such: they are stored as the
+/// midnight UTC of the day, but formatted and serialized back without a time.
+/// Date times are formatted like `chrono::DateTime<Utc>` does, and converted
+/// from and to it when the `chrono` feature is enabled.
+///
+/// # Example
+///
+/// ```
+/// use appstream::Timestamp;
+///
+/// let date = Timestamp::from_ymd(2020, 1, 12).unwrap();
+/// assert_eq!(date, "2020-01-12".parse().unwrap());
+/// assert_eq!(date.to_string(), "2020-01-12");
+///
+/// let time: Timestamp = "1578787200".parse().unwrap();
+/// assert_eq!(time.as_unix(), date.as_unix());
+/// assert_eq!(time.to_string(), "2020-01-12T00:00:00Z");
+/// ```
+pub struct Timestamp {
+    seconds: i64,
+    precision: Precision,
+}
+
+impl Timestamp {
+    /// Creates a timestamp from a number of seconds since the Unix epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds` - The seconds since `1970-01-01T00:00:00Z`.
+    pub fn from_unix(seconds: i64) -> Self {
+        Self {
+            seconds,
+            precision: Precision::DateTime,
+        }
+    }
+
+    /// Creates a calendar date, `None` if it is invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - The year.
+    /// * `month` - The month, from 1 to 12.
+    /// * `day` - The day of the month, from 1.
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        let midnight = Self::from_ymd_hms(year, month, day, 0, 0, 0)?;
+        Some(Self {
+            precision: Precision::Date,
+            ..midnight
+        })
+    }
+
+    /// Creates a timestamp at a time of a day in UTC, `None` if it is invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - The year.
+    /// * `month` - The month, from 1 to 12.
+    /// * `day` - The day of the month, from 1.
+    /// * `hour` - The hour, from 0 to 23.
+    /// * `minute` - The minute, from 0 to 59.
+    /// * `second` - The second, from 0 to 59.
+    pub fn from_ymd_hms(
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+    ) -> Option<Self> {
+        if !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return None;
+        }
+        let seconds = i64::from(hour) * 3600 + i64::from(minute) * 60 + i64::from(second);
+        Some(Self::from_unix(
+            days_from_civil(year, month, day) * SECONDS_PER_DAY + seconds,
+        ))
+    }
+
+    /// The seconds since the Unix epoch, midnight UTC for calendar dates.
+    pub fn as_unix(&self) -> i64 {
+        self.seconds
+    }
+
+    /// Whether the timestamp is a calendar date or has a time.
+    pub fn precision(&self) -> Precision {
+        self.precision
+    }
+
+    /// The year, month and day.
+    pub fn ymd(&self) -> (i32, u32, u32) {
+        civil_from_days(self.seconds.div_euclid(SECONDS_PER_DAY))
+    }
+
+    /// The date formatted as `YYYY-MM-DD`.
+    pub fn format_date(&self) -> String {
+        let (year, month, day) = self.ymd();
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// Whether `year` is a leap year of the proleptic Gregorian calendar.
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The days since the Unix epoch of a date, see
+/// <http://howardhinnant.github.io/date_algorithms.html>.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The date of a number of days since the Unix epoch, the inverse of
+/// `days_from_civil`.
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year as i32, month as u32, day as u32)
+}
+
+/// Parses the `n` digits at the start of `s`.
+fn digits(s: &str, n: usize) -> Option<(u32, &str)> {
+    let (number, rest) = (s.get(..n)?, &s[n..]);
+    if !number.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((number.parse().ok()?, rest))
+}
+
+/// Parses the basic ISO 8601 `YYYYMMDDTHHMMSS` form, in UTC, of the `Time`
+/// header of the DEP-11 catalogs.
+fn parse_basic_date_time(s: &str) -> Option<Timestamp> {
+    let (year, s) = digits(s, 4)?;
+    let (month, s) = digits(s, 2)?;
+    let (day, s) = digits(s, 2)?;
+    let (hour, s) = digits(s.strip_prefix('T')?, 2)?;
+    let (minute, s) = digits(s, 2)?;
+    let (second, s) = digits(s, 2)?;
+    if !s.is_empty() && s != "Z" {
+        return None;
+    }
+    Timestamp::from_ymd_hms(year as i32, month, day, hour, minute, second)
+}
+
+/// Parses `YYYY-MM-DD`, followed by an optional RFC 3339 time.
+fn parse_date_time(s: &str) -> Option<Timestamp> {
+    let (year, s) = digits(s, 4)?;
+    let (month, s) = digits(s.strip_prefix('-')?, 2)?;
+    let (day, s) = digits(s.strip_prefix('-')?, 2)?;
+    if s.is_empty() {
+        return Timestamp::from_ymd(year as i32, month, day);
+    }
+
+    let s = s.strip_prefix(|c| c == 'T' || c == 't' || c == ' ')?;
+    let (hour, s) = digits(s, 2)?;
+    let (minute, s) = digits(s.strip_prefix(':')?, 2)?;
+    let (second, mut s) = digits(s.strip_prefix(':')?, 2)?;
+    // The fractions of seconds are dropped.
+    if let Some(fraction) = s.strip_prefix('.') {
+        s = fraction.trim_start_matches(|c: char| c.is_ascii_digit());
+        if s.len() == fraction.len() {
+            return None;
+        }
+    }
+    let offset = match s {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match s.get(..1)? {
+                "+" => 1,
+                "-" => -1,
+                _ => return None,
+            };
+            let (hours, s) = digits(&s[1..], 2)?;
+            let (minutes, s) = digits(s.strip_prefix(':')?, 2)?;
+            if !s.is_empty() || hours > 23 || minutes > 59 {
+                return None;
+            }
+            sign * (i64::from(hours) * 3600 + i64::from(minutes) * 60)
+        }
+    };
+    let local = Timestamp::from_ymd_hms(year as i32, month, day, hour, minute, second)?;
+    Some(Timestamp::from_unix(local.seconds - offset))
+}
+
+impl FromStr for Timestamp {
+    type Err = ParseError;
+
+    /// Parses the formats used in AppStream documents: a number of seconds since
+    /// the Unix epoch, a `YYYY-MM-DD` date, an RFC 3339 date and time, or a basic
+    /// `YYYYMMDDTHHMMSS` date and time.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if !s.is_empty()
+            && s.trim_start_matches('-')
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+        {
+            if let Ok(seconds) = s.parse() {
+                return Ok(Self::from_unix(seconds));
+            }
+        }
+        parse_date_time(s)
+            .or_else(|| parse_basic_date_time(s))
+            .ok_or_else(|| ParseError::invalid_value(s, "$value", "timestamp"))
+    }
+}
+
+impl fmt::Display for Timestamp {
+    /// Formats the calendar dates as `YYYY-MM-DD`, and the other timestamps as
+    /// RFC 3339 dates and times in UTC.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.precision == Precision::Date {
+            return f.write_str(&self.format_date());
+        }
+        let seconds = self.seconds.rem_euclid(SECONDS_PER_DAY);
+        write!(
+            f,
+            "{}T{:02}:{:02}:{:02}Z",
+            self.format_date(),
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TimestampVisitor;
+
+        impl<'de> Visitor<'de> for TimestampVisitor {
+            type Value = Timestamp;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a date or a Unix timestamp")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Timestamp, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Timestamp, E> {
+                Ok(Timestamp::from_unix(v))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Timestamp, E> {
+                i64::try_from(v)
+                    .map(Timestamp::from_unix)
+                    .map_err(|_| E::custom("timestamp out of range"))
+            }
+        }
+
+        deserializer.deserialize_any(TimestampVisitor)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for Timestamp {
+    /// Converts a chrono date time, dropping the fractions of seconds.
+    fn from(date: chrono::DateTime<chrono::Utc>) -> Self {
+        Self::from_unix(date.timestamp())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<Timestamp> for chrono::DateTime<chrono::Utc> {
+    /// Converts to a chrono date time, at midnight UTC for calendar dates.
+    fn from(timestamp: Timestamp) -> Self {
+        chrono::DateTime::from_timestamp(timestamp.seconds, 0).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn parse_and_format() -> Result<(), Box<dyn Error>> {
+        let date = Timestamp::from_ymd(2015, 2, 16).ok_or("invalid date")?;
+        assert_eq!(date.as_unix(), 1_424_044_800);
+        assert_eq!(date.ymd(), (2015, 2, 16));
+        assert_eq!(date.format_date(), "2015-02-16");
+        assert_eq!(date.precision(), Precision::Date);
+        assert_eq!(date.to_string(), "2015-02-16");
+        assert_eq!("2015-02-16".parse::<Timestamp>()?, date);
+
+        let midnight = Timestamp::from_unix(date.as_unix());
+        assert_ne!(midnight, date);
+        assert!(midnight > date);
+        assert_eq!(midnight.precision(), Precision::DateTime);
+        assert_eq!(midnight.to_string(), "2015-02-16T00:00:00Z");
+        assert_eq!("1424044800".parse::<Timestamp>()?, midnight);
+        assert_eq!("2015-02-16T00:00:00Z".parse::<Timestamp>()?, midnight);
+        assert_eq!(
+            "2015-02-16T01:30:00.25+01:30".parse::<Timestamp>()?,
+            midnight
+        );
+
+        let time = Timestamp::from_ymd_hms(1969, 12, 31, 23, 59, 59).ok_or("invalid date")?;
+        assert_eq!(time.as_unix(), -1);
+        assert_eq!(time.to_string(), "1969-12-31T23:59:59Z");
+        assert_eq!(time.to_string().parse::<Timestamp>()?, time);
+        assert_eq!(
+            Timestamp::from_ymd(2000, 2, 29).map(|d| d.ymd()),
+            Some((2000, 2, 29))
+        );
+        assert!(Timestamp::from_ymd(1900, 2, 29).is_none());
+        assert!(Timestamp::from_ymd(2021, 13, 1).is_none());
+        assert_eq!(
+            "20150216T013000".parse::<Timestamp>()?,
+            Timestamp::from_ymd_hms(2015, 2, 16, 1, 30, 0).ok_or("invalid date")?
+        );
+
+        for invalid in [
+            "",
+            "2015-02",
+            "2015-2-16",
+            "2015-02-16T25:00:00Z",
+            "16/02/2015",
+            "20150216T0130",
+        ]
+        .iter()
+        {
+            assert!(invalid.parse::<Timestamp>().is_err(), "{}", invalid);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_conversions() {
+        use chrono::{DateTime, TimeZone, Utc};
+
+        let date = Utc.with_ymd_and_hms(2020, 1, 12, 10, 30, 0).unwrap();
+        let timestamp = Timestamp::from(date);
+        assert_eq!(
+            timestamp.to_string(),
+            date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        );
+        assert_eq!(DateTime::<Utc>::from(timestamp), date);
+    }
+}
@@ -1,8 +1,57 @@
+use super::error::ParseError;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 pub const DEFAULT_LOCALE: &str = "C";
 
+/// The locales and values of a DEP-11 mapping of translations, an error if it's
+/// malformed.
+fn yaml_translations(
+    element: &yaml_rust::Yaml,
+) -> Result<Vec<(&str, &yaml_rust::Yaml)>, ParseError> {
+    element
+        .as_hash()
+        .ok_or_else(|| ParseError::invalid_tag("translations"))?
+        .iter()
+        .map(|(k, v)| {
+            let locale = k
+                .as_str()
+                .ok_or_else(|| ParseError::missing_attribute("lang", "translations"))?;
+            Ok((locale, v))
+        })
+        .collect()
+}
+
+/// The text of a translation, an error naming its locale if it isn't a string.
+fn yaml_text<'a>(locale: &str, value: &'a yaml_rust::Yaml) -> Result<&'a str, ParseError> {
+    value
+        .as_str()
+        .ok_or_else(|| ParseError::missing_value(locale))
+}
+
+/// The tags of the description markup.
+const MARKUP_TAGS: [&str; 6] = ["p", "ul", "ol", "li", "em", "code"];
+
+/// The length of the markup tag a description starts with, like `<p>` or
+/// `</em>`, `None` if it doesn't start with one.
+///
+/// The parsed descriptions keep the tags of the markup but not the escaping of
+/// their text, an `&lt;html&gt;` is read as `<html>` which isn't a tag.
+pub(crate) fn markup_tag_len(markup: &str) -> Option<usize> {
+    let rest = markup.strip_prefix('<')?;
+    let (closing, rest) = match rest.strip_prefix('/') {
+        Some(rest) => (1, rest),
+        None => (0, rest),
+    };
+    MARKUP_TAGS
+        .iter()
+        .find(|tag| {
+            rest.strip_prefix(*tag)
+                .is_some_and(|end| end.starts_with('>'))
+        })
+        .map(|tag| closing + tag.len() + 2)
+}
+
 fn element_to_xml(e: &xmltree::Element) -> String {
     e.children
         .iter()
@@ -67,11 +116,13 @@ impl MarkupTranslatableString {
     /// Adds a new string from a `yaml_rust::Yaml`
     ///
     ///
-    /// and can be used to feed the `MarkupTranslatableString`.
-    pub fn add_for_yaml_element(&mut self, element: &yaml_rust::Yaml) {
-        for (k, v) in element.as_hash().unwrap() {
-            self.add_for_locale(k.as_str(), v.as_str().unwrap());
+    /// and can be used to feed the `MarkupTranslatableString`. Fails if the
+    /// element isn't a mapping of locales to strings.
+    pub fn add_for_yaml_element(&mut self, element: &yaml_rust::Yaml) -> Result<(), ParseError> {
+        for (locale, v) in yaml_translations(element)? {
+            self.add_for_locale(Some(locale), yaml_text(locale, v)?);
         }
+        Ok(())
     }
 
     /// Adds a new translation for a speicifc locale.
@@ -158,14 +209,13 @@ impl TranslatableString {
     /// Adds a new string from a `yaml_rust::Yaml`
     ///
     ///
-    /// and can be used to feed the `TranslatableString`.
-    pub fn add_for_yaml_element(&mut self, element: &yaml_rust::Yaml) {
-        for (k, v) in element.as_hash().unwrap() {
-            self.add_for_locale(
-                k.as_str(),
-                v.as_str().unwrap(), // for some reason some description tags contains empty strings.
-            );
+    /// and can be used to feed the `TranslatableString`. Fails if the element
+    /// isn't a mapping of locales to strings.
+    pub fn add_for_yaml_element(&mut self, element: &yaml_rust::Yaml) -> Result<(), ParseError> {
+        for (locale, v) in yaml_translations(element)? {
+            self.add_for_locale(Some(locale), yaml_text(locale, v)?);
         }
+        Ok(())
     }
 
     /// Adds a new translation for a speicifc locale.
@@ -258,14 +308,18 @@ impl TranslatableList {
     /// Adds a new string from a `yaml_rust::Yaml`
     ///
     ///
-    /// and can be used to feed the `TranslatableList`.
-    pub fn add_for_yaml_element(&mut self, element: &yaml_rust::Yaml) {
-        for (k, v) in element.as_hash().unwrap() {
-            let keywords = v.as_vec().unwrap();
+    /// and can be used to feed the `TranslatableList`. Fails if the element
+    /// isn't a mapping of locales to lists of strings.
+    pub fn add_for_yaml_element(&mut self, element: &yaml_rust::Yaml) -> Result<(), ParseError> {
+        for (locale, v) in yaml_translations(element)? {
+            let keywords = v
+                .as_vec()
+                .ok_or_else(|| ParseError::missing_value(locale))?;
             for x in keywords {
-                self.add_for_locale(k.as_str(), x.as_str().unwrap());
+                self.add_for_locale(Some(locale), yaml_text(locale, x)?);
             }
         }
+        Ok(())
     }
 
     /// Adds a new string for a specific locale.
@@ -0,0 +1,1640 @@
+use super::enums::{ColorScheme, Icon, ImageKind};
+#[cfg(feature = "image")]
+use super::image_size;
+#[cfg(feature = "net")]
+use super::net::{HttpClient, Method, Request};
+#[cfg(feature = "image")]
+use super::Image;
+use super::{AppId, Collection, Component};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+#[cfg(feature = "image")]
+use std::io::Read;
+use url::Url;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+/// How serious a `ValidationIssue` is.
+///
+/// The ordering goes from the least to the most important one, so a minimum
+/// severity can be filtered with a simple comparison.
+pub enum IssueSeverity {
+    /// Nitpicks that are only reported on request.
+    Pedantic,
+    /// Informational hints that don't need to be fixed.
+    Info,
+    /// Something that should be fixed but won't break consumers.
+    Warning,
+    /// A spec violation, the metadata is not usable as is.
+    Error,
+}
+
+impl fmt::Display for IssueSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            IssueSeverity::Pedantic => "pedantic",
+            IssueSeverity::Info => "info",
+            IssueSeverity::Warning => "warning",
+            IssueSeverity::Error => "error",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+/// A problem found while validating a `Component` or a `Collection`.
+pub struct ValidationIssue {
+    /// A short, stable identifier of the issue, like `summary-missing`.
+    pub tag: String,
+
+    /// How serious the issue is.
+    pub severity: IssueSeverity,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The component the issue was found on, if any.
+    pub component: Option<AppId>,
+
+    /// A human-readable explanation.
+    pub message: String,
+}
+
+impl ValidationIssue {
+    /// Creates a new issue that is not attached to a component yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `severity` - How serious the issue is.
+    /// * `tag` - A short identifier of the issue, like `summary-missing`.
+    /// * `message` - A human-readable explanation.
+    pub fn new(severity: IssueSeverity, tag: &str, message: &str) -> Self {
+        Self {
+            tag: tag.to_string(),
+            severity,
+            component: None,
+            message: message.to_string(),
+        }
+    }
+
+    /// Creates a new error issue.
+    pub fn error(tag: &str, message: &str) -> Self {
+        Self::new(IssueSeverity::Error, tag, message)
+    }
+
+    /// Creates a new warning issue.
+    pub fn warning(tag: &str, message: &str) -> Self {
+        Self::new(IssueSeverity::Warning, tag, message)
+    }
+
+    /// Creates a new informational issue.
+    pub fn info(tag: &str, message: &str) -> Self {
+        Self::new(IssueSeverity::Info, tag, message)
+    }
+
+    /// Attach the issue to a specific component.
+    pub fn for_component(mut self, id: &AppId) -> Self {
+        self.component = Some(id.clone());
+        self
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.component {
+            Some(id) => write!(
+                f,
+                "{}: {} [{}] {}",
+                id.0, self.severity, self.tag, self.message
+            ),
+            None => write!(f, "{} [{}] {}", self.severity, self.tag, self.message),
+        }
+    }
+}
+
+/// A check that can be registered on a `Validator`.
+///
+/// Downstreams (stores, firmware services, distributions...) can implement
+/// their own rules and run them alongside the built-in ones, the issues
+/// are reported the same way.
+///
+/// # Example
+///
+/// ```
+/// use appstream::validation::{ValidationIssue, ValidationRule, Validator};
+/// use appstream::Component;
+///
+/// struct RequireDeveloperName;
+///
+/// impl ValidationRule for RequireDeveloperName {
+///     fn name(&self) -> &str {
+///         "require-developer-name"
+///     }
+///
+///     fn check_component(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+///         if component.developer_name.is_none() {
+///             issues.push(ValidationIssue::error(
+///                 "developer-name-missing",
+///                 "The store requires a developer name",
+///             ));
+///         }
+///     }
+/// }
+///
+/// let validator = Validator::default().rule(RequireDeveloperName);
+/// ```
+pub trait ValidationRule {
+    /// A unique name identifying the rule.
+    fn name(&self) -> &str;
+
+    /// Check a single component and push the found issues.
+    ///
+    /// The `Validator` takes care of attaching the component id to the issues.
+    fn check_component(&self, _component: &Component, _issues: &mut Vec<ValidationIssue>) {}
+
+    /// Check a whole collection and push the found issues.
+    ///
+    /// The components of the collection are already checked one by one through
+    /// `check_component`, this is only useful for checks that need to look at
+    /// several components at once.
+    fn check_collection(&self, _collection: &Collection, _issues: &mut Vec<ValidationIssue>) {}
+}
+
+/// Runs a set of `ValidationRule` against components and collections.
+///
+/// `Validator::default()` comes with the built-in rules, use `Validator::empty()`
+/// to only run your own ones.
+pub struct Validator {
+    rules: Vec<Box<dyn ValidationRule>>,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self::empty()
+            .rule(RequiredFieldsRule)
+            .rule(ComponentIdRule)
+            .rule(SummaryRule)
+            .rule(ScreenshotRule)
+            .rule(CollisionRule)
+    }
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Validator")
+            .field("rules", &self.rule_names())
+            .finish()
+    }
+}
+
+impl Validator {
+    /// Create a `Validator` without any rule.
+    pub fn empty() -> Self {
+        Self { rules: vec![] }
+    }
+
+    /// Registers a new rule.
+    pub fn rule<R: ValidationRule + 'static>(mut self, rule: R) -> Self {
+        self.add_rule(Box::new(rule));
+        self
+    }
+
+    /// Registers a new boxed rule.
+    pub fn add_rule(&mut self, rule: Box<dyn ValidationRule>) {
+        self.rules.push(rule);
+    }
+
+    /// Removes the rule registered under `name`, if any.
+    pub fn remove_rule(&mut self, name: &str) {
+        self.rules.retain(|r| r.name() != name);
+    }
+
+    /// The names of the registered rules.
+    pub fn rule_names(&self) -> Vec<&str> {
+        self.rules.iter().map(|r| r.name()).collect()
+    }
+
+    /// Validate a single component.
+    pub fn validate_component(&self, component: &Component) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        for rule in self.rules.iter() {
+            let mut found = Vec::new();
+            rule.check_component(component, &mut found);
+            issues.extend(found.into_iter().map(|i| i.for_component(&component.id)));
+        }
+        issues
+    }
+
+    /// Validate a collection and all of its components.
+    pub fn validate_collection(&self, collection: &Collection) -> Vec<ValidationIssue> {
+        let mut issues = collection
+            .components
+            .iter()
+            .flat_map(|c| self.validate_component(c))
+            .collect::<Vec<ValidationIssue>>();
+
+        for rule in self.rules.iter() {
+            rule.check_collection(collection, &mut issues);
+        }
+        issues
+    }
+}
+
+/// Ensures the fields required by the specification are set.
+pub struct RequiredFieldsRule;
+
+impl ValidationRule for RequiredFieldsRule {
+    fn name(&self) -> &str {
+        "required-fields"
+    }
+
+    fn check_component(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+        if component
+            .name
+            .get_default()
+            .is_none_or(|n| n.trim().is_empty())
+        {
+            issues.push(ValidationIssue::error(
+                "name-missing",
+                "The component is missing a name",
+            ));
+        }
+        if component.summary.is_none() {
+            issues.push(ValidationIssue::error(
+                "summary-missing",
+                "The component is missing a summary",
+            ));
+        }
+        if component.metadata_license.is_none() {
+            issues.push(ValidationIssue::warning(
+                "metadata-license-missing",
+                "The component is missing a metadata license",
+            ));
+        }
+    }
+}
+
+/// Ensures the component id is a valid reverse-DNS name.
+pub struct ComponentIdRule;
+
+impl ValidationRule for ComponentIdRule {
+    fn name(&self) -> &str {
+        "component-id"
+    }
+
+    fn check_component(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+        let id = component.id.as_ref();
+        if id.is_empty() {
+            issues.push(ValidationIssue::error(
+                "cid-empty",
+                "The component id is empty",
+            ));
+            return;
+        }
+        if id.split('.').count() < 3 {
+            issues.push(ValidationIssue::warning(
+                "cid-is-not-rdns",
+                "The component id is not a reverse-DNS name",
+            ));
+        }
+        if id
+            .chars()
+            .any(|c| !(c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-'))
+        {
+            issues.push(ValidationIssue::error(
+                "cid-invalid-character",
+                "The component id contains an invalid character",
+            ));
+        }
+        if id.ends_with(".desktop") {
+            issues.push(ValidationIssue::info(
+                "cid-desktopapp-is-not-rdns",
+                "The component id ends with .desktop, a legacy naming",
+            ));
+        }
+    }
+}
+
+/// Ensures the summary follows the style recommendations.
+pub struct SummaryRule;
+
+impl ValidationRule for SummaryRule {
+    fn name(&self) -> &str {
+        "summary-style"
+    }
+
+    fn check_component(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+        let summary = match component.summary.as_ref().and_then(|s| s.get_default()) {
+            Some(s) => s,
+            None => return,
+        };
+        if summary.ends_with('.') {
+            issues.push(ValidationIssue::info(
+                "summary-has-dot-suffix",
+                "The summary should not end with a dot",
+            ));
+        }
+        if summary.chars().count() > 80 {
+            issues.push(ValidationIssue::warning(
+                "summary-too-long",
+                "The summary should be shorter than 80 characters",
+            ));
+        }
+    }
+}
+
+/// Ensures every screenshot has exactly one source image.
+pub struct ScreenshotRule;
+
+impl ValidationRule for ScreenshotRule {
+    fn name(&self) -> &str {
+        "screenshots"
+    }
+
+    fn check_component(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+        for screenshot in component.screenshots.iter() {
+            if screenshot.images.is_empty() && screenshot.videos.is_empty() {
+                issues.push(ValidationIssue::error(
+                    "screenshot-no-media",
+                    "A screenshot doesn't have any image or video",
+                ));
+                continue;
+            }
+            let sources = screenshot
+                .images
+                .iter()
+                .filter(|i| i.kind == ImageKind::Source)
+                .count();
+            if !screenshot.images.is_empty() && sources != 1 {
+                issues.push(ValidationIssue::error(
+                    "screenshot-image-source-count",
+                    "A screenshot must have exactly one source image",
+                ));
+            }
+        }
+        if component
+            .screenshots
+            .iter()
+            .filter(|s| s.is_default)
+            .count()
+            > 1
+        {
+            issues.push(ValidationIssue::warning(
+                "screenshot-default-duplicated",
+                "Only one screenshot should be marked as the default one",
+            ));
+        }
+    }
+}
+
+/// Checks the quality guidelines of [Flathub](https://docs.flathub.org/docs/for-app-authors/metainfo-guidelines/quality-guidelines),
+/// on top of the specification.
+///
+/// The rule is opt-in, the store requirements go beyond the specification. Only
+/// the declared sizes of the icons and screenshots are checked, the
+/// `ImageDimensionsRule` downloads them to check their real size.
+///
+/// # Example
+///
+/// ```
+/// use appstream::validation::{FlathubQualityRule, Validator};
+/// use appstream::Component;
+///
+/// let component = Component::from_path("./tests/desktop.xml".into()).unwrap();
+/// let validator = Validator::default().rule(FlathubQualityRule);
+/// let issues = validator.validate_component(&component);
+/// assert!(issues.iter().any(|i| i.tag == "flathub-branding-colors-missing"));
+/// ```
+pub struct FlathubQualityRule;
+
+impl FlathubQualityRule {
+    /// The minimum size of the icon, in pixels.
+    pub const MIN_ICON_SIZE: u32 = 256;
+    /// The maximum length of the summary, in characters.
+    pub const MAX_SUMMARY_LENGTH: usize = 35;
+    /// The minimum width of the screenshots, in pixels.
+    pub const MIN_SCREENSHOT_WIDTH: u32 = 620;
+
+    fn check_icons(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+        if component.icons.is_empty() {
+            issues.push(ValidationIssue::error(
+                "flathub-icon-missing",
+                "The component has no icon",
+            ));
+            return;
+        }
+        let largest = component
+            .icons
+            .iter()
+            .filter_map(|icon| match icon {
+                Icon::Stock(_) => None,
+                Icon::Cached { width, .. }
+                | Icon::Remote { width, .. }
+                | Icon::Local { width, .. } => *width,
+            })
+            .max();
+        if largest.is_some_and(|w| w < Self::MIN_ICON_SIZE) {
+            issues.push(ValidationIssue::warning(
+                "flathub-icon-too-small",
+                &format!(
+                    "The largest icon should be at least {}x{} pixels",
+                    Self::MIN_ICON_SIZE,
+                    Self::MIN_ICON_SIZE
+                ),
+            ));
+        }
+    }
+
+    fn check_summary(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+        let summary = match component.summary.as_ref().and_then(|s| s.get_default()) {
+            Some(s) => s.trim(),
+            None => return,
+        };
+        if summary.chars().count() > Self::MAX_SUMMARY_LENGTH {
+            issues.push(ValidationIssue::warning(
+                "flathub-summary-too-long",
+                &format!(
+                    "The summary should be shorter than {} characters",
+                    Self::MAX_SUMMARY_LENGTH
+                ),
+            ));
+        }
+        let first_word = summary.split_whitespace().next().unwrap_or_default();
+        if ["A", "An", "The"].contains(&first_word) {
+            issues.push(ValidationIssue::warning(
+                "flathub-summary-starts-with-article",
+                "The summary should not start with an article",
+            ));
+        }
+        if let Some(name) = component.name.get_default() {
+            if !name.trim().is_empty() && summary.contains(name.trim()) {
+                issues.push(ValidationIssue::warning(
+                    "flathub-summary-repeats-name",
+                    "The summary should not repeat the name",
+                ));
+            }
+        }
+    }
+
+    fn check_screenshots(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+        if component.screenshots.is_empty() {
+            issues.push(ValidationIssue::error(
+                "flathub-screenshots-missing",
+                "The component has no screenshot",
+            ));
+            return;
+        }
+        for screenshot in component.screenshots.iter() {
+            if screenshot.caption.is_none() {
+                issues.push(ValidationIssue::warning(
+                    "flathub-screenshot-caption-missing",
+                    "Every screenshot should have a caption",
+                ));
+            }
+            let source = screenshot
+                .images
+                .iter()
+                .find(|i| i.kind == ImageKind::Source);
+            if let Some(width) = source.and_then(|i| i.width) {
+                if width < Self::MIN_SCREENSHOT_WIDTH {
+                    issues.push(ValidationIssue::warning(
+                        "flathub-screenshot-too-small",
+                        &format!(
+                            "The screenshots should be at least {} pixels wide",
+                            Self::MIN_SCREENSHOT_WIDTH
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check_branding(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+        let scheme = |scheme: ColorScheme| {
+            component
+                .branding
+                .iter()
+                .any(|c| c.scheme_preference == Some(scheme))
+        };
+        if component.branding.is_empty() {
+            issues.push(ValidationIssue::info(
+                "flathub-branding-colors-missing",
+                "Branding colors for the light and dark color schemes are recommended",
+            ));
+        } else if !scheme(ColorScheme::Light) || !scheme(ColorScheme::Dark) {
+            issues.push(ValidationIssue::info(
+                "flathub-branding-color-scheme-missing",
+                "A branding color for each of the light and dark color schemes is recommended",
+            ));
+        }
+    }
+}
+
+impl ValidationRule for FlathubQualityRule {
+    fn name(&self) -> &str {
+        "flathub-quality"
+    }
+
+    fn check_component(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+        self.check_icons(component, issues);
+        self.check_summary(component, issues);
+        self.check_screenshots(component, issues);
+        if component.content_rating.is_none() {
+            issues.push(ValidationIssue::error(
+                "flathub-content-rating-missing",
+                "The component has no OARS content rating",
+            ));
+        }
+        self.check_branding(component, issues);
+    }
+}
+
+/// The remote icons, screenshot images and videos URLs of a component, along
+/// with a short description of where they are used.
+pub(crate) fn remote_urls(component: &Component) -> Vec<(&'static str, &Url)> {
+    let mut urls = Vec::new();
+    for icon in component.icons.iter() {
+        if let Icon::Remote { url, .. } = icon {
+            urls.push(("icon", url));
+        }
+    }
+    for screenshot in component.screenshots.iter() {
+        urls.extend(
+            screenshot
+                .images
+                .iter()
+                .map(|i| ("screenshot image", &i.url)),
+        );
+        urls.extend(
+            screenshot
+                .videos
+                .iter()
+                .map(|v| ("screenshot video", &v.url)),
+        );
+    }
+    urls
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+/// A component id found more than once.
+pub struct DuplicateId {
+    /// The duplicated id.
+    pub id: AppId,
+    /// The origin of every occurrence, in the order they were found.
+    pub origins: Vec<Option<String>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+/// Two components whose ids only differ by the legacy `.desktop` suffix.
+pub struct SuffixCollision {
+    /// The id without the suffix.
+    pub id: AppId,
+    /// The id with the `.desktop` suffix.
+    pub legacy_id: AppId,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+/// A remote icon or screenshot URL used by unrelated components.
+pub struct SharedUrl {
+    /// The shared URL.
+    pub url: Url,
+    /// The components using it.
+    pub components: Vec<AppId>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+/// Collisions found between the components of one or several collections.
+///
+/// Those are usually packaging mistakes: the same application shipped by two
+/// origins, a component renamed without dropping the old `.desktop` id or
+/// screenshots copy-pasted from another project.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::validation::CollisionReport;
+/// use appstream::Collection;
+///
+/// let flathub = Collection::from_path("/var/lib/flatpak/appstream/flathub/x86_64/active/appstream.xml".into()).unwrap();
+/// let fedora = Collection::from_path("/usr/share/swcatalog/xml/fedora.xml".into()).unwrap();
+/// let report = CollisionReport::from_collections(&[&flathub, &fedora]);
+/// for duplicate in report.duplicate_ids {
+///     println!("{} is shipped by {:?}", duplicate.id.0, duplicate.origins);
+/// }
+/// ```
+pub struct CollisionReport {
+    /// The ids found more than once.
+    pub duplicate_ids: Vec<DuplicateId>,
+    /// The ids found both with and without the `.desktop` suffix.
+    pub suffix_collisions: Vec<SuffixCollision>,
+    /// The media URLs shared between unrelated components.
+    pub shared_urls: Vec<SharedUrl>,
+}
+
+impl CollisionReport {
+    /// Analyzes the components of a single collection.
+    pub fn from_collection(collection: &Collection) -> Self {
+        Self::from_collections(&[collection])
+    }
+
+    /// Analyzes the components of several collections at once.
+    ///
+    /// The origin of a component is its own one if set, the one of its collection
+    /// otherwise.
+    pub fn from_collections(collections: &[&Collection]) -> Self {
+        let components = collections
+            .iter()
+            .flat_map(|c| {
+                c.components.iter().map(move |component| {
+                    (
+                        component,
+                        component.origin.as_deref().or(c.origin.as_deref()),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut origins: BTreeMap<&str, Vec<Option<String>>> = BTreeMap::new();
+        for (component, origin) in components.iter() {
+            origins
+                .entry(component.id.as_ref())
+                .or_default()
+                .push(origin.map(str::to_string));
+        }
+
+        let duplicate_ids = origins
+            .iter()
+            .filter(|(_, o)| o.len() > 1)
+            .map(|(id, o)| DuplicateId {
+                id: AppId::from(*id),
+                origins: o.clone(),
+            })
+            .collect();
+
+        let suffix_collisions = origins
+            .keys()
+            .filter_map(|id| {
+                let short = id.strip_suffix(".desktop")?;
+                if origins.contains_key(short) {
+                    Some(SuffixCollision {
+                        id: AppId::from(short),
+                        legacy_id: AppId::from(*id),
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        // Addons legitimately reuse the media of the component they extend.
+        let family = |component: &Component| -> String {
+            let id = component.extends.first().unwrap_or(&component.id).as_ref();
+            id.strip_suffix(".desktop").unwrap_or(id).to_string()
+        };
+        let mut users: BTreeMap<&Url, Vec<(String, &AppId)>> = BTreeMap::new();
+        for (component, _) in components.iter() {
+            for (_, url) in remote_urls(component) {
+                let entries = users.entry(url).or_default();
+                let family = family(component);
+                if !entries.iter().any(|(f, _)| f == &family) {
+                    entries.push((family, &component.id));
+                }
+            }
+        }
+        let shared_urls = users
+            .into_iter()
+            .filter(|(_, entries)| entries.len() > 1)
+            .map(|(url, entries)| SharedUrl {
+                url: url.clone(),
+                components: entries.into_iter().map(|(_, id)| id.clone()).collect(),
+            })
+            .collect();
+
+        Self {
+            duplicate_ids,
+            suffix_collisions,
+            shared_urls,
+        }
+    }
+
+    /// Whether no collision was found.
+    pub fn is_empty(&self) -> bool {
+        self.duplicate_ids.is_empty()
+            && self.suffix_collisions.is_empty()
+            && self.shared_urls.is_empty()
+    }
+
+    /// The collisions as validation issues, attached to the involved components.
+    pub fn issues(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        for duplicate in self.duplicate_ids.iter() {
+            let origins = duplicate
+                .origins
+                .iter()
+                .map(|o| o.as_deref().unwrap_or("unknown"))
+                .collect::<Vec<&str>>();
+            issues.push(
+                ValidationIssue::error(
+                    "cid-duplicated",
+                    &format!(
+                        "The component id is used {} times, origins: {}",
+                        origins.len(),
+                        origins.join(", ")
+                    ),
+                )
+                .for_component(&duplicate.id),
+            );
+        }
+        for collision in self.suffix_collisions.iter() {
+            issues.push(
+                ValidationIssue::warning(
+                    "cid-desktop-suffix-collision",
+                    &format!(
+                        "The component id is also used with a .desktop suffix by {}",
+                        collision.legacy_id.0
+                    ),
+                )
+                .for_component(&collision.id),
+            );
+        }
+        for shared in self.shared_urls.iter() {
+            for (i, id) in shared.components.iter().enumerate() {
+                let others = shared
+                    .components
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, o)| o.as_ref())
+                    .collect::<Vec<&str>>();
+                issues.push(
+                    ValidationIssue::warning(
+                        "media-url-shared",
+                        &format!("{} is also used by {}", shared.url, others.join(", ")),
+                    )
+                    .for_component(id),
+                );
+            }
+        }
+        issues
+    }
+}
+
+/// Reports the collisions between the components of a collection.
+///
+/// See `CollisionReport` to analyze several collections at once.
+pub struct CollisionRule;
+
+impl ValidationRule for CollisionRule {
+    fn name(&self) -> &str {
+        "collisions"
+    }
+
+    fn check_collection(&self, collection: &Collection, issues: &mut Vec<ValidationIssue>) {
+        issues.extend(CollisionReport::from_collection(collection).issues());
+    }
+}
+
+#[cfg(feature = "net")]
+/// Ensures the remote icons, screenshots and videos can be reached.
+///
+/// Every URL is checked with a `HEAD` request, falling back to `GET` for the
+/// servers that don't implement it. This rule is not part of the default ones
+/// as it needs a working network connection.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::net::{HttpClient, Request, Response};
+/// use appstream::validation::{LinkCheckRule, Validator};
+/// use appstream::Collection;
+/// # use std::io;
+/// # struct MyClient;
+/// # impl HttpClient for MyClient {
+/// #     fn send(&self, _request: &Request) -> io::Result<Response> { unimplemented!() }
+/// # }
+///
+/// let collection = Collection::from_path("/var/lib/flatpak/appstream/flathub/x86_64/active/appstream.xml".into()).unwrap();
+/// let validator = Validator::empty().rule(LinkCheckRule::new(MyClient));
+/// for issue in validator.validate_collection(&collection) {
+///     println!("{}", issue);
+/// }
+/// ```
+pub struct LinkCheckRule<C: HttpClient> {
+    client: C,
+}
+
+#[cfg(feature = "net")]
+impl<C: HttpClient> LinkCheckRule<C> {
+    /// Creates a new rule sending its requests through `client`.
+    pub fn new(client: C) -> Self {
+        Self { client }
+    }
+
+    fn check_url(&self, url: &Url) -> Result<(), String> {
+        let response = self
+            .client
+            .send(&Request::new(Method::Head, url.clone()))
+            .map_err(|e| e.to_string())?;
+        // Some servers don't implement HEAD requests.
+        let response = if response.status == 405 || response.status == 501 {
+            self.client
+                .send(&Request::new(Method::Get, url.clone()))
+                .map_err(|e| e.to_string())?
+        } else {
+            response
+        };
+        if response.is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP status {}", response.status))
+        }
+    }
+}
+
+#[cfg(feature = "net")]
+impl<C: HttpClient> ValidationRule for LinkCheckRule<C> {
+    fn name(&self) -> &str {
+        "link-check"
+    }
+
+    fn check_component(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+        for (usage, url) in remote_urls(component) {
+            if let Err(reason) = self.check_url(url) {
+                issues.push(ValidationIssue::error(
+                    "url-not-reachable",
+                    &format!("The {} {} is not reachable: {}", usage, url, reason),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+/// Downloads the screenshot images and compares their real size with the declared
+/// one and the store requirements.
+///
+/// # Example
+///
+/// ```no_run
+/// use appstream::net::{HttpClient, Request, Response};
+/// use appstream::validation::{ImageDimensionsRule, Validator};
+/// # use std::io;
+/// # struct MyClient;
+/// # impl HttpClient for MyClient {
+/// #     fn send(&self, _request: &Request) -> io::Result<Response> { unimplemented!() }
+/// # }
+///
+/// // Flathub's requirements
+/// let rule = ImageDimensionsRule::new(MyClient)
+///     .min_width(620)
+///     .aspect_ratio(16, 9);
+/// let validator = Validator::default().rule(rule);
+/// ```
+pub struct ImageDimensionsRule<C: HttpClient> {
+    client: C,
+    min_width: Option<u32>,
+    aspect_ratio: Option<(u32, u32)>,
+    tolerance: f64,
+}
+
+#[cfg(feature = "image")]
+impl<C: HttpClient> ImageDimensionsRule<C> {
+    /// Creates a new rule downloading the images through `client`.
+    ///
+    /// Only the declared sizes are checked until a minimum width or an aspect
+    /// ratio is set.
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            min_width: None,
+            aspect_ratio: None,
+            tolerance: 0.01,
+        }
+    }
+
+    /// Requires the source images to be at least `width` pixels wide.
+    pub fn min_width(mut self, width: u32) -> Self {
+        self.min_width = Some(width);
+        self
+    }
+
+    /// Requires the source images to have a `width:height` aspect ratio.
+    pub fn aspect_ratio(mut self, width: u32, height: u32) -> Self {
+        self.aspect_ratio = Some((width, height));
+        self
+    }
+
+    /// How far the aspect ratio can be from the required one, `0.01` by default.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    fn fetch_dimensions(&self, url: &Url) -> Result<Option<(u32, u32)>, String> {
+        let mut response = self
+            .client
+            .send(&Request::new(Method::Get, url.clone()))
+            .map_err(|e| e.to_string())?;
+        if !response.is_success() {
+            return Err(format!("HTTP status {}", response.status));
+        }
+        let mut data = Vec::new();
+        response
+            .body
+            .read_to_end(&mut data)
+            .map_err(|e| e.to_string())?;
+        Ok(image_size::dimensions(&data))
+    }
+
+    fn check_image(&self, image: &Image, issues: &mut Vec<ValidationIssue>) {
+        let (width, height) = match self.fetch_dimensions(&image.url) {
+            Ok(Some(dimensions)) => dimensions,
+            Ok(None) => {
+                issues.push(ValidationIssue::warning(
+                    "screenshot-image-unknown-format",
+                    &format!("The format of the image {} is not supported", image.url),
+                ));
+                return;
+            }
+            Err(reason) => {
+                issues.push(ValidationIssue::error(
+                    "screenshot-download-failed",
+                    &format!(
+                        "The image {} could not be downloaded: {}",
+                        image.url, reason
+                    ),
+                ));
+                return;
+            }
+        };
+
+        if image.width.is_some_and(|w| w != width) || image.height.is_some_and(|h| h != height) {
+            issues.push(ValidationIssue::error(
+                "screenshot-size-mismatch",
+                &format!(
+                    "The image {} is {}x{} but declared as {}x{}",
+                    image.url,
+                    width,
+                    height,
+                    image
+                        .width
+                        .map(|w| w.to_string())
+                        .unwrap_or_else(|| "?".into()),
+                    image
+                        .height
+                        .map(|h| h.to_string())
+                        .unwrap_or_else(|| "?".into()),
+                ),
+            ));
+        }
+        if image.kind != ImageKind::Source {
+            return;
+        }
+        if let Some(min_width) = self.min_width.filter(|m| width < *m) {
+            issues.push(ValidationIssue::warning(
+                "screenshot-too-small",
+                &format!(
+                    "The image {} is {} pixels wide, at least {} are required",
+                    image.url, width, min_width
+                ),
+            ));
+        }
+        if let Some((ratio_width, ratio_height)) = self.aspect_ratio {
+            let expected = ratio_width as f64 / ratio_height as f64;
+            let actual = width as f64 / height.max(1) as f64;
+            if (actual - expected).abs() / expected > self.tolerance {
+                issues.push(ValidationIssue::warning(
+                    "screenshot-wrong-aspect-ratio",
+                    &format!(
+                        "The image {} is {}x{}, a {}:{} aspect ratio is required",
+                        image.url, width, height, ratio_width, ratio_height
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+impl<C: HttpClient> ValidationRule for ImageDimensionsRule<C> {
+    fn name(&self) -> &str {
+        "image-dimensions"
+    }
+
+    fn check_component(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+        for image in component.screenshots.iter().flat_map(|s| s.images.iter()) {
+            self.check_image(image, issues);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+/// The issues found in a file.
+pub struct FileReport {
+    /// The path or URI of the file.
+    pub path: String,
+    /// The issues found in the file.
+    pub issues: Vec<ValidationIssue>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+/// The issues found in a set of files, serializable as is, or as a SARIF log
+/// for code-review tools.
+///
+/// # Example
+///
+/// ```
+/// use appstream::validation::{ValidationReport, Validator};
+/// use appstream::Component;
+///
+/// let component = Component::from_path("./tests/desktop.xml".into()).unwrap();
+/// let mut report = ValidationReport::default();
+/// report.add("tests/desktop.xml", Validator::default().validate_component(&component));
+/// assert!(report.passed());
+/// assert_eq!(report.to_sarif().runs[0].results.len(), report.files[0].issues.len());
+/// ```
+pub struct ValidationReport {
+    /// The validated files.
+    pub files: Vec<FileReport>,
+}
+
+impl ValidationReport {
+    /// Adds the issues of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path or URI of the file.
+    /// * `issues` - The issues found in the file.
+    pub fn add(&mut self, path: &str, issues: Vec<ValidationIssue>) {
+        self.files.push(FileReport {
+            path: path.to_string(),
+            issues,
+        });
+    }
+
+    /// Whether none of the files has an error.
+    pub fn passed(&self) -> bool {
+        self.files
+            .iter()
+            .flat_map(|f| f.issues.iter())
+            .all(|i| i.severity < IssueSeverity::Error)
+    }
+
+    /// Converts the report to a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+    /// log, with one rule per issue tag.
+    pub fn to_sarif(&self) -> SarifLog {
+        let mut rules = self
+            .files
+            .iter()
+            .flat_map(|f| f.issues.iter().map(|i| i.tag.clone()))
+            .collect::<Vec<String>>();
+        rules.sort();
+        rules.dedup();
+
+        let results = self
+            .files
+            .iter()
+            .flat_map(|file| {
+                file.issues.iter().map(move |issue| SarifResult {
+                    rule_id: issue.tag.clone(),
+                    level: match issue.severity {
+                        IssueSeverity::Error => "error",
+                        IssueSeverity::Warning => "warning",
+                        IssueSeverity::Info | IssueSeverity::Pedantic => "note",
+                    },
+                    message: SarifMessage {
+                        text: issue.message.clone(),
+                    },
+                    locations: vec![SarifLocation {
+                        physical_location: SarifPhysicalLocation {
+                            artifact_location: SarifArtifactLocation {
+                                uri: file.path.clone(),
+                            },
+                        },
+                        logical_locations: issue
+                            .component
+                            .iter()
+                            .map(|id| SarifLogicalLocation {
+                                fully_qualified_name: id.to_string(),
+                            })
+                            .collect(),
+                    }],
+                })
+            })
+            .collect();
+
+        SarifLog {
+            schema: SARIF_SCHEMA,
+            version: "2.1.0",
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: env!("CARGO_PKG_NAME"),
+                        version: env!("CARGO_PKG_VERSION"),
+                        information_uri: env!("CARGO_PKG_REPOSITORY"),
+                        rules: rules.into_iter().map(|id| SarifRule { id }).collect(),
+                    },
+                },
+                results,
+            }],
+        }
+    }
+}
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+/// A SARIF log, see `ValidationReport::to_sarif`.
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    /// The URI of the SARIF JSON schema.
+    pub schema: &'static str,
+    /// The SARIF version.
+    pub version: &'static str,
+    /// The runs of the validator, there is only one.
+    pub runs: Vec<SarifRun>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+/// A run of the validator.
+pub struct SarifRun {
+    /// The validator.
+    pub tool: SarifTool,
+    /// The issues found.
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+/// The tool that produced a run.
+pub struct SarifTool {
+    /// The validator.
+    pub driver: SarifDriver,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// The validator and the rules it reported.
+pub struct SarifDriver {
+    /// The name of the validator.
+    pub name: &'static str,
+    /// The version of the validator.
+    pub version: &'static str,
+    /// The homepage of the validator.
+    pub information_uri: &'static str,
+    /// The rules the issues refer to.
+    pub rules: Vec<SarifRule>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+/// A rule, the tag of the issues.
+pub struct SarifRule {
+    /// The tag of the issues, like `summary-missing`.
+    pub id: String,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// An issue.
+pub struct SarifResult {
+    /// The tag of the issue.
+    pub rule_id: String,
+    /// `error`, `warning` or `note`.
+    pub level: &'static str,
+    /// The explanation of the issue.
+    pub message: SarifMessage,
+    /// The file and the component of the issue.
+    pub locations: Vec<SarifLocation>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+/// The message of an issue.
+pub struct SarifMessage {
+    /// A human-readable explanation.
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// Where an issue was found.
+pub struct SarifLocation {
+    /// The file.
+    pub physical_location: SarifPhysicalLocation,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// The component, if any.
+    pub logical_locations: Vec<SarifLogicalLocation>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// The file an issue was found in.
+pub struct SarifPhysicalLocation {
+    /// The file.
+    pub artifact_location: SarifArtifactLocation,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+/// The path or URI of a file.
+pub struct SarifArtifactLocation {
+    /// The path or URI of the file.
+    pub uri: String,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// The component an issue was found on.
+pub struct SarifLogicalLocation {
+    /// The component id.
+    pub fully_qualified_name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::ComponentBuilder;
+    use crate::TranslatableString;
+    use std::convert::TryFrom;
+    use std::error::Error;
+
+    struct NoConsoleApps;
+
+    impl ValidationRule for NoConsoleApps {
+        fn name(&self) -> &str {
+            "no-console-apps"
+        }
+
+        fn check_component(&self, component: &Component, issues: &mut Vec<ValidationIssue>) {
+            if component.kind == crate::enums::ComponentKind::ConsoleApplication {
+                issues.push(ValidationIssue::error(
+                    "console-app-rejected",
+                    "Console applications are not accepted",
+                ));
+            }
+        }
+
+        fn check_collection(&self, collection: &Collection, issues: &mut Vec<ValidationIssue>) {
+            if collection.origin.is_none() {
+                issues.push(ValidationIssue::warning(
+                    "collection-origin-missing",
+                    "The collection has no origin",
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn builtin_rules() -> Result<(), Box<dyn Error>> {
+        let c1 = Component::from_path("./tests/desktop.xml".into())?;
+        let issues = Validator::default().validate_component(&c1);
+        assert!(issues.iter().all(|i| i.severity < IssueSeverity::Error));
+
+        let c2 = ComponentBuilder::default()
+            .id("foo bar".into())
+            .name(TranslatableString::with_default("Foo"))
+            .summary(TranslatableString::with_default("A summary."))
+            .build();
+        let tags = Validator::default()
+            .validate_component(&c2)
+            .into_iter()
+            .map(|i| {
+                assert_eq!(i.component, Some("foo bar".into()));
+                i.tag
+            })
+            .collect::<Vec<String>>();
+        assert_eq!(
+            tags,
+            vec![
+                "metadata-license-missing",
+                "cid-is-not-rdns",
+                "cid-invalid-character",
+                "summary-has-dot-suffix"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn custom_rule() -> Result<(), Box<dyn Error>> {
+        let collection = Collection::from_path("./tests/collections/fedora-web-apps.xml".into())?;
+
+        let mut validator = Validator::empty().rule(NoConsoleApps);
+        assert_eq!(validator.rule_names(), vec!["no-console-apps"]);
+        let issues = validator.validate_collection(&collection);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].tag, "collection-origin-missing");
+        assert_eq!(issues[0].component, None);
+
+        validator.remove_rule("no-console-apps");
+        assert!(validator.validate_collection(&collection).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn collisions() -> Result<(), Box<dyn Error>> {
+        use crate::builders::{CollectionBuilder, ImageBuilder, ScreenshotBuilder};
+
+        let component = |id: &str, url: &str| -> Result<Component, Box<dyn Error>> {
+            Ok(ComponentBuilder::default()
+                .id(id.into())
+                .name(TranslatableString::with_default("Foo"))
+                .screenshot(
+                    ScreenshotBuilder::default()
+                        .image(ImageBuilder::new(Url::parse(url)?).build())
+                        .build(),
+                )
+                .build())
+        };
+
+        let flathub = CollectionBuilder::new("0.14")
+            .origin("flathub")
+            .component(component("org.example.Foo", "https://example.org/foo.png")?)
+            .component(component("org.example.Bar", "https://example.org/foo.png")?)
+            .build();
+        let fedora = CollectionBuilder::new("0.14")
+            .origin("fedora")
+            .component(component("org.example.Foo", "https://example.org/foo.png")?)
+            .component(component(
+                "org.example.Foo.desktop",
+                "https://example.org/foo.png",
+            )?)
+            .build();
+
+        let report = CollisionReport::from_collections(&[&flathub, &fedora]);
+        assert_eq!(
+            report.duplicate_ids,
+            vec![DuplicateId {
+                id: "org.example.Foo".into(),
+                origins: vec![Some("flathub".into()), Some("fedora".into())],
+            }]
+        );
+        assert_eq!(
+            report.suffix_collisions,
+            vec![SuffixCollision {
+                id: "org.example.Foo".into(),
+                legacy_id: "org.example.Foo.desktop".into(),
+            }]
+        );
+        assert_eq!(
+            report.shared_urls,
+            vec![SharedUrl {
+                url: Url::parse("https://example.org/foo.png")?,
+                components: vec!["org.example.Foo".into(), "org.example.Bar".into()],
+            }]
+        );
+
+        assert!(CollisionReport::from_collection(&fedora)
+            .shared_urls
+            .is_empty());
+        let tags = Validator::empty()
+            .rule(CollisionRule)
+            .validate_collection(&flathub)
+            .into_iter()
+            .map(|i| (i.tag, i.component.unwrap().to_string()))
+            .collect::<Vec<(String, String)>>();
+        assert_eq!(
+            tags,
+            vec![
+                ("media-url-shared".into(), "org.example.Foo".into()),
+                ("media-url-shared".into(), "org.example.Bar".into())
+            ]
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn link_check() -> Result<(), Box<dyn Error>> {
+        use crate::net::{HttpClient, Method, Request, Response};
+        use std::io;
+
+        struct FakeClient;
+
+        impl HttpClient for FakeClient {
+            fn send(&self, request: &Request) -> io::Result<Response> {
+                match (request.url.path(), request.method) {
+                    (p, _) if p.ends_with("missing.png") => Ok(Response::new(404)),
+                    (p, Method::Head) if p.ends_with("no-head.png") => Ok(Response::new(405)),
+                    (p, _) if p.ends_with("offline.png") => {
+                        Err(io::Error::new(io::ErrorKind::TimedOut, "timed out"))
+                    }
+                    _ => Ok(Response::new(200)),
+                }
+            }
+        }
+
+        let c = crate::builders::ComponentBuilder::default()
+            .id("org.example.Foo".into())
+            .name(TranslatableString::with_default("Foo"))
+            .screenshot(
+                crate::builders::ScreenshotBuilder::default()
+                    .image(
+                        crate::builders::ImageBuilder::new(Url::parse(
+                            "https://example.org/ok.png",
+                        )?)
+                        .build(),
+                    )
+                    .image(
+                        crate::builders::ImageBuilder::new(Url::parse(
+                            "https://example.org/missing.png",
+                        )?)
+                        .build(),
+                    )
+                    .image(
+                        crate::builders::ImageBuilder::new(Url::parse(
+                            "https://example.org/no-head.png",
+                        )?)
+                        .build(),
+                    )
+                    .image(
+                        crate::builders::ImageBuilder::new(Url::parse(
+                            "https://example.org/offline.png",
+                        )?)
+                        .build(),
+                    )
+                    .build(),
+            )
+            .build();
+        let issues = Validator::empty()
+            .rule(LinkCheckRule::new(FakeClient))
+            .validate_component(&c);
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|i| i.tag == "url-not-reachable"));
+        assert!(issues[0].message.contains("missing.png"));
+        assert!(issues[0].message.contains("404"));
+        assert!(issues[1].message.contains("timed out"));
+        Ok(())
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn image_dimensions() -> Result<(), Box<dyn Error>> {
+        use crate::builders::{ImageBuilder, ScreenshotBuilder};
+        use crate::net::{HttpClient, Request, Response};
+        use std::io;
+
+        struct FakeClient;
+
+        impl HttpClient for FakeClient {
+            fn send(&self, request: &Request) -> io::Result<Response> {
+                let (width, height): (u32, u32) = match request.url.path() {
+                    "/wide.png" => (1920, 1080),
+                    "/small.png" => (400, 300),
+                    _ => return Ok(Response::new(404)),
+                };
+                let mut data = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+                data.extend_from_slice(&width.to_be_bytes());
+                data.extend_from_slice(&height.to_be_bytes());
+                let mut response = Response::new(200);
+                response.body = Box::new(io::Cursor::new(data));
+                Ok(response)
+            }
+        }
+
+        let c = ComponentBuilder::default()
+            .id("org.example.Foo".into())
+            .name(TranslatableString::with_default("Foo"))
+            .screenshot(
+                ScreenshotBuilder::default()
+                    .image(
+                        ImageBuilder::new(Url::parse("https://example.org/wide.png")?)
+                            .width(1920)
+                            .height(1080)
+                            .build(),
+                    )
+                    .build(),
+            )
+            .screenshot(
+                ScreenshotBuilder::default()
+                    .image(
+                        ImageBuilder::new(Url::parse("https://example.org/small.png")?)
+                            .width(800)
+                            .height(600)
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build();
+        let rule = ImageDimensionsRule::new(FakeClient)
+            .min_width(620)
+            .aspect_ratio(16, 9);
+        let tags = Validator::empty()
+            .rule(rule)
+            .validate_component(&c)
+            .into_iter()
+            .map(|i| i.tag)
+            .collect::<Vec<String>>();
+        assert_eq!(
+            tags,
+            vec![
+                "screenshot-size-mismatch",
+                "screenshot-too-small",
+                "screenshot-wrong-aspect-ratio"
+            ]
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn reports() -> Result<(), Box<dyn Error>> {
+        let collection = Collection::from_path("./tests/collections/fedora-web-apps.xml".into())?;
+        let validator = Validator::empty().rule(NoConsoleApps).rule(SummaryRule);
+        let mut report = ValidationReport::default();
+        report.add(
+            "fedora-web-apps.xml",
+            validator.validate_collection(&collection),
+        );
+        assert!(report.passed());
+
+        let json = serde_json::to_value(&report)?;
+        assert_eq!(json["files"][0]["path"], "fedora-web-apps.xml");
+        let report2: ValidationReport = serde_json::from_value(json)?;
+        assert_eq!(report, report2);
+
+        report.add(
+            "app.xml",
+            vec![ValidationIssue::error("summary-missing", "No summary")
+                .for_component(&"org.example.App".into())],
+        );
+        assert!(!report.passed());
+        let sarif = serde_json::to_value(report.to_sarif())?;
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "appstream");
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert!(rules.iter().any(|r| r["id"] == "summary-missing"));
+        let result = run["results"].as_array().unwrap().last().unwrap();
+        assert_eq!(result["ruleId"], "summary-missing");
+        assert_eq!(result["level"], "error");
+        assert_eq!(result["message"]["text"], "No summary");
+        let location = &result["locations"][0];
+        assert_eq!(
+            location["physicalLocation"]["artifactLocation"]["uri"],
+            "app.xml"
+        );
+        assert_eq!(
+            location["logicalLocations"][0]["fullyQualifiedName"],
+            "org.example.App"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn flathub_quality() -> Result<(), Box<dyn Error>> {
+        let validator = Validator::empty().rule(FlathubQualityRule);
+        let tags = |component: &Component| {
+            validator
+                .validate_component(component)
+                .into_iter()
+                .map(|i| i.tag)
+                .collect::<Vec<String>>()
+        };
+
+        let xml = r#"<component type="desktop-application">
+            <id>org.example.Notes</id>
+            <name>Notes</name>
+            <summary>A simple app to take notes with Notes</summary>
+            <icon type="cached" width="128" height="128">notes.png</icon>
+            <screenshots>
+                <screenshot type="default">
+                    <image type="source" width="400" height="300">https://example.org/1.png</image>
+                </screenshot>
+            </screenshots>
+            <branding><color type="primary" scheme_preference="light">#ffc0cb</color></branding>
+        </component>"#;
+        let component = Component::try_from(&xmltree::Element::parse(xml.as_bytes())?)?;
+        assert_eq!(
+            tags(&component),
+            vec![
+                "flathub-icon-too-small",
+                "flathub-summary-too-long",
+                "flathub-summary-starts-with-article",
+                "flathub-summary-repeats-name",
+                "flathub-screenshot-caption-missing",
+                "flathub-screenshot-too-small",
+                "flathub-content-rating-missing",
+                "flathub-branding-color-scheme-missing",
+            ]
+        );
+
+        let xml = r#"<component type="desktop-application">
+            <id>org.example.Notes</id>
+            <name>Notes</name>
+            <summary>Take notes</summary>
+            <icon type="remote" width="256" height="256">https://example.org/notes.png</icon>
+            <screenshots>
+                <screenshot type="default">
+                    <caption>The main window</caption>
+                    <image type="source" width="1280" height="720">https://example.org/1.png</image>
+                </screenshot>
+            </screenshots>
+            <content_rating type="oars-1.1"/>
+            <branding>
+                <color type="primary" scheme_preference="light">#ffc0cb</color>
+                <color type="primary" scheme_preference="dark">#800080</color>
+            </branding>
+        </component>"#;
+        let component = Component::try_from(&xmltree::Element::parse(xml.as_bytes())?)?;
+        assert_eq!(component.branding[1].value, "#800080");
+        assert!(tags(&component).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,651 @@
+use super::enums::ComponentKind;
+use super::error::ParseError;
+use super::translatable_string::DEFAULT_LOCALE;
+use super::Component;
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::ops::Range;
+use std::str::FromStr;
+use xmltree::{Element, XMLNode};
+
+#[derive(Clone, Debug, PartialEq)]
+/// A node of an `ElementRef`.
+pub enum NodeRef<'a> {
+    /// A child element.
+    Element(ElementRef<'a>),
+    /// Some text, only owned when it contained entities.
+    Text(Cow<'a, str>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// An XML element borrowing its names and text from the parsed document.
+///
+/// Whitespace-only text between elements, comments and processing instructions
+/// are dropped, like `Element::parse` does.
+pub struct ElementRef<'a> {
+    /// The name of the element, without its namespace prefix.
+    pub name: &'a str,
+    /// The attributes of the element, keyed by their names without their
+    /// namespace prefix, `xml:lang` being `lang`.
+    pub attributes: Vec<(&'a str, Cow<'a, str>)>,
+    /// The children of the element.
+    pub children: Vec<NodeRef<'a>>,
+}
+
+impl<'a> ElementRef<'a> {
+    /// Parses the root element of a document.
+    ///
+    /// # Arguments
+    ///
+    /// * `xml` - The document.
+    pub fn parse(xml: &'a str) -> Result<Self, ParseError> {
+        let mut scanner = Scanner { xml, pos: 0 };
+        let (name, attributes, empty) = scanner.root()?;
+        let root = scanner.element(name, attributes, empty)?;
+        scanner.finish()?;
+        Ok(root)
+    }
+
+    /// The value of an attribute.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_ref())
+    }
+
+    /// The child elements.
+    pub fn elements(&self) -> impl Iterator<Item = &ElementRef<'a>> {
+        self.children.iter().filter_map(|node| match node {
+            NodeRef::Element(e) => Some(e),
+            NodeRef::Text(_) => None,
+        })
+    }
+
+    /// The first child element named `name`.
+    pub fn child(&self, name: &str) -> Option<&ElementRef<'a>> {
+        self.elements().find(|e| e.name == name)
+    }
+
+    /// The text of the element, `None` if it has none.
+    pub fn text(&self) -> Option<&str> {
+        self.children.iter().find_map(|node| match node {
+            NodeRef::Text(t) => Some(t.as_ref()),
+            NodeRef::Element(_) => None,
+        })
+    }
+
+    /// Converts the element into an owned `xmltree::Element`.
+    pub fn to_element(&self) -> Element {
+        let mut element = Element::new(self.name);
+        element.attributes = self
+            .attributes
+            .iter()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect();
+        element.children = self
+            .children
+            .iter()
+            .map(|node| match node {
+                NodeRef::Element(e) => XMLNode::Element(e.to_element()),
+                NodeRef::Text(t) => XMLNode::Text(t.to_string()),
+            })
+            .collect();
+        element
+    }
+
+    /// The text of the `name` child for `locale`, the untranslated one for `None`.
+    fn translated(&self, name: &str, locale: Option<&str>) -> Option<&str> {
+        self.elements()
+            .find(|e| {
+                e.name == name
+                    && match (e.attribute("lang"), locale) {
+                        (None, None) => true,
+                        (Some(lang), Some(locale)) => lang == locale,
+                        (Some(lang), None) => lang == DEFAULT_LOCALE,
+                        (None, Some(_)) => false,
+                    }
+            })
+            .and_then(|e| e.text())
+    }
+
+    /// The texts of the children of the `name` child.
+    fn list(&self, name: &str) -> impl Iterator<Item = &str> {
+        self.child(name)
+            .into_iter()
+            .flat_map(|e| e.elements())
+            .filter_map(|e| e.text())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A read-only view of a component, borrowing its strings from the parsed
+/// document instead of allocating them.
+///
+/// It is meant for pipelines that parse, query and throw away the metadata,
+/// `to_component` converts it to a `Component` when needed.
+///
+/// # Example
+///
+/// ```
+/// use appstream::view::CollectionRef;
+///
+/// let xml = std::fs::read_to_string("./tests/collections/gnome-apps.xml").unwrap();
+/// let collection = CollectionRef::parse(&xml).unwrap();
+/// let games = collection
+///     .components
+///     .iter()
+///     .filter(|c| c.categories().any(|c| c == "Game"))
+///     .filter_map(|c| c.id())
+///     .collect::<Vec<_>>();
+/// ```
+pub struct ComponentRef<'a>(pub ElementRef<'a>);
+
+impl<'a> ComponentRef<'a> {
+    /// Parses a metainfo file.
+    ///
+    /// # Arguments
+    ///
+    /// * `xml` - The content of the file.
+    pub fn parse(xml: &'a str) -> Result<Self, ParseError> {
+        Self::try_from(ElementRef::parse(xml)?)
+    }
+
+    /// The component type.
+    pub fn kind(&self) -> Result<ComponentKind, ParseError> {
+        match self.0.attribute("type") {
+            Some(kind) => ComponentKind::from_str(kind)
+                .map_err(|_| ParseError::invalid_value(kind, "type", "component")),
+            None => Ok(ComponentKind::default()),
+        }
+    }
+
+    /// The unique identifier of the component.
+    pub fn id(&self) -> Option<&str> {
+        self.text("id")
+    }
+
+    /// The name of the component.
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - The locale of the name, the untranslated one for `None`.
+    pub fn name(&self, locale: Option<&str>) -> Option<&str> {
+        self.0.translated("name", locale)
+    }
+
+    /// The summary of the component.
+    ///
+    /// # Arguments
+    ///
+    /// * `locale` - The locale of the summary, the untranslated one for `None`.
+    pub fn summary(&self, locale: Option<&str>) -> Option<&str> {
+        self.0.translated("summary", locale)
+    }
+
+    /// The license of the project.
+    pub fn project_license(&self) -> Option<&str> {
+        self.text("project_license")
+    }
+
+    /// The project group, like `GNOME`.
+    pub fn project_group(&self) -> Option<&str> {
+        self.text("project_group")
+    }
+
+    /// The name of the package shipping the component.
+    pub fn pkgname(&self) -> Option<&str> {
+        self.text("pkgname")
+    }
+
+    /// The categories of the component.
+    pub fn categories(&self) -> impl Iterator<Item = &str> {
+        self.0.list("categories")
+    }
+
+    /// The mimetypes handled by the component.
+    pub fn mimetypes(&self) -> impl Iterator<Item = &str> {
+        self.0.list("mimetypes")
+    }
+
+    /// The untranslated keywords of the component.
+    pub fn keywords(&self) -> impl Iterator<Item = &str> {
+        self.0
+            .child("keywords")
+            .into_iter()
+            .flat_map(|e| e.elements())
+            .filter(|e| matches!(e.attribute("lang"), None | Some(DEFAULT_LOCALE)))
+            .filter_map(|e| e.text())
+    }
+
+    /// Converts the view into a `Component`.
+    pub fn to_component(&self) -> Result<Component, ParseError> {
+        Component::try_from(&self.0.to_element())
+    }
+
+    fn text(&self, name: &str) -> Option<&str> {
+        self.0.child(name).and_then(|e| e.text())
+    }
+}
+
+impl<'a> TryFrom<ElementRef<'a>> for ComponentRef<'a> {
+    type Error = ParseError;
+
+    fn try_from(element: ElementRef<'a>) -> Result<Self, Self::Error> {
+        if element.name != "component" {
+            return Err(ParseError::invalid_tag(element.name));
+        }
+        Ok(Self(element))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A read-only view of a catalog, see `ComponentRef`.
+pub struct CollectionRef<'a> {
+    /// The specification version used on the components.
+    pub version: Cow<'a, str>,
+    /// The origin of the collection.
+    pub origin: Option<Cow<'a, str>>,
+    /// The targeted CPU architecture of the collection.
+    pub architecture: Option<Cow<'a, str>>,
+    /// The components of the collection.
+    pub components: Vec<ComponentRef<'a>>,
+}
+
+impl<'a> CollectionRef<'a> {
+    /// Parses an XML catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `xml` - The content of the catalog.
+    pub fn parse(xml: &'a str) -> Result<Self, ParseError> {
+        let root = ElementRef::parse(xml)?;
+        let attribute = |name: &str| {
+            root.attributes
+                .iter()
+                .position(|(n, _)| *n == name)
+                .map(|i| root.attributes[i].1.clone())
+        };
+        let version = attribute("version")
+            .ok_or_else(|| ParseError::missing_attribute("version", "collection"))?;
+        let origin = attribute("origin").filter(|o| !o.is_empty());
+        let architecture = attribute("architecture");
+        let components = root
+            .children
+            .into_iter()
+            .filter_map(|node| match node {
+                NodeRef::Element(e) if e.name == "component" => Some(ComponentRef(e)),
+                _ => None,
+            })
+            .collect();
+        Ok(Self {
+            version,
+            origin,
+            architecture,
+            components,
+        })
+    }
+}
+
+enum Token<'a> {
+    Start {
+        name: &'a str,
+        attributes: Vec<(&'a str, Cow<'a, str>)>,
+        empty: bool,
+    },
+    End(&'a str),
+    Text(Cow<'a, str>),
+}
+
+type StartTag<'a> = (&'a str, Vec<(&'a str, Cow<'a, str>)>, bool);
+pub(crate) type ComponentSpans<'a> = (ElementRef<'a>, Vec<(Range<usize>, String)>);
+
+/// A minimal XML tokenizer over a borrowed document.
+struct Scanner<'a> {
+    xml: &'a str,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    /// Reads up to the start tag of the root element.
+    fn root(&mut self) -> Result<StartTag<'a>, ParseError> {
+        loop {
+            match self.next_token()? {
+                Some(Token::Start {
+                    name,
+                    attributes,
+                    empty,
+                }) => return Ok((name, attributes, empty)),
+                Some(Token::Text(t)) if t.trim().is_empty() => (),
+                _ => return Err(malformed()),
+            }
+        }
+    }
+
+    /// Checks that nothing but whitespace follows the root element.
+    fn finish(&mut self) -> Result<(), ParseError> {
+        while let Some(token) = self.next_token()? {
+            if !matches!(token, Token::Text(ref t) if t.trim().is_empty()) {
+                return Err(malformed());
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the children of an element up to its end tag.
+    fn element(
+        &mut self,
+        name: &'a str,
+        attributes: Vec<(&'a str, Cow<'a, str>)>,
+        empty: bool,
+    ) -> Result<ElementRef<'a>, ParseError> {
+        let mut element = ElementRef {
+            name: local_name(name),
+            attributes,
+            children: vec![],
+        };
+        if empty {
+            return Ok(element);
+        }
+        loop {
+            match self.next_token()?.ok_or_else(malformed)? {
+                Token::Start {
+                    name,
+                    attributes,
+                    empty,
+                } => {
+                    let child = self.element(name, attributes, empty)?;
+                    element.children.push(NodeRef::Element(child));
+                }
+                Token::End(end) if end == name => return Ok(element),
+                Token::End(_) => return Err(malformed()),
+                Token::Text(text) => match element.children.last_mut() {
+                    Some(NodeRef::Text(previous)) => previous.to_mut().push_str(&text),
+                    _ if text.trim().is_empty() => (),
+                    _ => element.children.push(NodeRef::Text(text)),
+                },
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token<'a>>, ParseError> {
+        loop {
+            let rest = &self.xml[self.pos..];
+            if rest.is_empty() {
+                return Ok(None);
+            }
+            if !rest.starts_with('<') {
+                let end = rest.find('<').unwrap_or(rest.len());
+                self.pos += end;
+                return Ok(Some(Token::Text(unescape(&rest[..end])?)));
+            }
+            if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = cdata.find("]]>").ok_or_else(malformed)?;
+                self.pos += "<![CDATA[".len() + end + "]]>".len();
+                return Ok(Some(Token::Text(Cow::Borrowed(&cdata[..end]))));
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!") {
+                // A doctype, possibly with an internal subset.
+                let end = match (rest.find('['), rest.find('>')) {
+                    (Some(open), Some(close)) if open < close => {
+                        open + rest[open..].find("]>").ok_or_else(malformed)? + 1
+                    }
+                    (_, Some(close)) => close,
+                    _ => return Err(malformed()),
+                };
+                self.pos += end + 1;
+            } else if let Some(end) = rest.strip_prefix("</") {
+                let close = end.find('>').ok_or_else(malformed)?;
+                self.pos += 2 + close + 1;
+                return Ok(Some(Token::End(end[..close].trim_end())));
+            } else {
+                return self.start_tag().map(Some);
+            }
+        }
+    }
+
+    fn skip_past(&mut self, pattern: &str) -> Result<(), ParseError> {
+        let end = self.xml[self.pos..].find(pattern).ok_or_else(malformed)?;
+        self.pos += end + pattern.len();
+        Ok(())
+    }
+
+    fn start_tag(&mut self) -> Result<Token<'a>, ParseError> {
+        let xml = self.xml;
+        self.pos += 1;
+        let name = self.name()?;
+        let mut attributes = vec![];
+        loop {
+            self.skip_whitespace();
+            let rest = &xml[self.pos..];
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(Token::Start {
+                    name,
+                    attributes,
+                    empty: true,
+                });
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                return Ok(Token::Start {
+                    name,
+                    attributes,
+                    empty: false,
+                });
+            }
+            let attribute = self.name()?;
+            self.skip_whitespace();
+            if !xml[self.pos..].starts_with('=') {
+                return Err(malformed());
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let rest = &xml[self.pos..];
+            let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'');
+            let quote = quote.ok_or_else(malformed)?;
+            let end = rest[1..].find(quote).ok_or_else(malformed)?;
+            self.pos += end + 2;
+            // Namespace declarations aren't attributes.
+            if attribute != "xmlns" && !attribute.starts_with("xmlns:") {
+                attributes.push((local_name(attribute), unescape(&rest[1..=end])?));
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, ParseError> {
+        let rest = &self.xml[self.pos..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '/' | '>'))
+            .unwrap_or(rest.len());
+        if end == 0 {
+            return Err(malformed());
+        }
+        self.pos += end;
+        Ok(&rest[..end])
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.xml[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+}
+
+/// The root element of a catalog without its children, and the spans of its
+/// components in `xml` along with their ids.
+pub(crate) fn component_spans(xml: &str) -> Result<ComponentSpans<'_>, ParseError> {
+    let mut scanner = Scanner { xml, pos: 0 };
+    let (name, attributes, empty) = scanner.root()?;
+    let root = ElementRef {
+        name: local_name(name),
+        attributes,
+        children: vec![],
+    };
+    let mut spans = vec![];
+    if !empty {
+        loop {
+            let start = scanner.pos;
+            match scanner.next_token()?.ok_or_else(malformed)? {
+                Token::Start {
+                    name,
+                    attributes,
+                    empty,
+                } => {
+                    let element = scanner.element(name, attributes, empty)?;
+                    if element.name == "component" {
+                        let id = element
+                            .child("id")
+                            .and_then(|e| e.text())
+                            .ok_or_else(|| ParseError::missing_tag("id"))?;
+                        spans.push((start..scanner.pos, id.to_string()));
+                    }
+                }
+                Token::End(end) if end == name => break,
+                Token::End(_) => return Err(malformed()),
+                Token::Text(_) => (),
+            }
+        }
+    }
+    scanner.finish()?;
+    Ok((root, spans))
+}
+
+fn malformed() -> ParseError {
+    xmltree::ParseError::CannotParse.into()
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Replaces the entities of `text`, only allocating if there are some.
+fn unescape(text: &str) -> Result<Cow<'_, str>, ParseError> {
+    if !text.contains('&') {
+        return Ok(Cow::Borrowed(text));
+    }
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end = rest[start..].find(';').ok_or_else(malformed)? + start;
+        let entity = &rest[start + 1..end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(decimal) = entity.strip_prefix('#') {
+                    decimal.parse::<u32>().ok()
+                } else {
+                    None
+                };
+                code.and_then(std::char::from_u32).ok_or_else(malformed)?
+            }
+        };
+        unescaped.push(c);
+        rest = &rest[end + 1..];
+    }
+    unescaped.push_str(rest);
+    Ok(Cow::Owned(unescaped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Collection;
+    use std::error::Error;
+    use std::fs;
+
+    #[test]
+    fn same_as_owned() -> Result<(), Box<dyn Error>> {
+        for path in [
+            "./tests/collections/spec_example.xml",
+            "./tests/collections/gnome-apps.xml",
+            "./tests/collections/kde-apps.xml",
+            "./tests/collections/flathub-old.xml",
+        ]
+        .iter()
+        {
+            let xml = fs::read_to_string(path)?;
+            let view = CollectionRef::parse(&xml)?;
+            let collection = Collection::from_path(path.into())?;
+            assert_eq!(view.version, collection.version);
+            assert_eq!(view.origin.as_deref(), collection.origin.as_deref());
+            assert_eq!(view.components.len(), collection.components.len());
+            for (view, component) in view.components.iter().zip(collection.components.iter()) {
+                assert_eq!(view.id(), Some(component.id.as_ref()), "{}", path);
+                assert_eq!(
+                    view.name(None),
+                    component.name.get_default().map(|n| n.as_str())
+                );
+                assert_eq!(view.kind()?, component.kind);
+                assert_eq!(&view.to_component()?, component, "{}", path);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn borrowed_strings() -> Result<(), Box<dyn Error>> {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- Copyright 2021 -->
+<component type="desktop-application">
+  <id>org.gnome.Weather</id>
+  <name>Weather</name>
+  <name xml:lang="fr">Météo</name>
+  <summary>Show weather conditions &amp; forecast</summary>
+  <project_license>GPL-2.0+</project_license>
+  <categories>
+    <category>GNOME</category>
+    <category>Utility</category>
+  </categories>
+  <keywords>
+    <keyword>forecast</keyword>
+    <keyword xml:lang="fr">prévisions</keyword>
+  </keywords>
+  <description><![CDATA[<p>Small]]> &lt;app&gt;</description>
+</component>"#;
+        let component = ComponentRef::parse(xml)?;
+        assert_eq!(component.kind()?, ComponentKind::DesktopApplication);
+        assert_eq!(component.id(), Some("org.gnome.Weather"));
+        assert_eq!(component.name(None), Some("Weather"));
+        assert_eq!(component.name(Some("fr")), Some("Météo"));
+        assert_eq!(component.name(Some("de")), None);
+        assert_eq!(
+            component.summary(None),
+            Some("Show weather conditions & forecast")
+        );
+        assert_eq!(component.project_license(), Some("GPL-2.0+"));
+        assert_eq!(
+            component.categories().collect::<Vec<_>>(),
+            vec!["GNOME", "Utility"]
+        );
+        assert_eq!(component.keywords().collect::<Vec<_>>(), vec!["forecast"]);
+        assert_eq!(
+            component.0.child("description").and_then(|d| d.text()),
+            Some("<p>Small <app>")
+        );
+
+        match &component.0.child("name").ok_or("no name")?.children[0] {
+            NodeRef::Text(Cow::Borrowed(_)) => (),
+            node => panic!("{:?} isn't borrowed", node),
+        }
+        assert!(matches!(
+            component.0.child("summary").ok_or("no summary")?.children[0],
+            NodeRef::Text(Cow::Owned(_))
+        ));
+
+        assert!(ComponentRef::parse("<component><id>a</name></component>").is_err());
+        assert!(ComponentRef::parse("<component><id>a</id>").is_err());
+        assert!(ComponentRef::parse("<components/>").is_err());
+        assert!(ComponentRef::parse("<component>&foo;</component>").is_err());
+        Ok(())
+    }
+}
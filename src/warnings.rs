@@ -0,0 +1,93 @@
+use super::error::ParseWarning;
+use std::cell::RefCell;
+
+thread_local! {
+    static WARNINGS: RefCell<Option<Vec<ParseWarning>>> = const { RefCell::new(None) };
+}
+
+/// Restores the warnings of the enclosing `collect` when dropped, even if
+/// parsing panicked.
+struct Scope {
+    outer: Option<Vec<ParseWarning>>,
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let outer = self.outer.take();
+        WARNINGS.with(|warnings| *warnings.borrow_mut() = outer);
+    }
+}
+
+/// Runs `f` and returns the warnings reported meanwhile with `warn`.
+pub(crate) fn collect<T>(f: impl FnOnce() -> T) -> (T, Vec<ParseWarning>) {
+    let outer = WARNINGS.with(|warnings| warnings.borrow_mut().replace(Vec::new()));
+    let _scope = Scope { outer };
+    let value = f();
+    let warnings = WARNINGS.with(|warnings| warnings.borrow_mut().take());
+    (value, warnings.unwrap_or_default())
+}
+
+/// Reports a warning to the enclosing `collect`, it's dropped outside of one.
+pub(crate) fn warn(warning: ParseWarning) {
+    WARNINGS.with(|warnings| {
+        if let Some(warnings) = warnings.borrow_mut().as_mut() {
+            warnings.push(warning);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Collection, Component};
+    use std::convert::TryFrom;
+    use std::error::Error;
+    use xmltree::Element;
+
+    #[test]
+    fn collect_warnings() -> Result<(), Box<dyn Error>> {
+        let xml = r#"<components version="0.14" media_baseurl="https://example.org">
+            <info/>
+            <component type="desktop-application" importance="10">
+                <id>org.example.App</id>
+                <name>App</name>
+                <branding><colour>#ff0000</colour></branding>
+                <icon type="stock" width="big">app</icon>
+                <launchable type="snap">app</launchable>
+                <releases><release version="1.0"><notes/></release></releases>
+            </component>
+        </components>"#;
+        let element = Element::parse(xml.as_bytes())?;
+        let (collection, warnings) = collect(|| Collection::try_from(&element));
+        assert_eq!(collection?.components.len(), 1);
+        assert_eq!(
+            warnings,
+            vec![
+                ParseWarning::ignored_attribute("media_baseurl", "components"),
+                ParseWarning::unknown_tag("info", "components"),
+                ParseWarning::ignored_attribute("importance", "component"),
+                ParseWarning::unknown_tag("colour", "branding"),
+                ParseWarning::recovered_value("big", "width", "icon", "none"),
+                ParseWarning::recovered_value("snap", "type", "launchable", "unknown"),
+                ParseWarning::unknown_tag("notes", "release"),
+            ]
+        );
+
+        // Nested scopes keep their own warnings, and none are kept outside.
+        let (inner, outer) = collect(|| {
+            warn(ParseWarning::unknown_tag("a", "component"));
+            collect(|| warn(ParseWarning::unknown_tag("b", "component"))).1
+        });
+        assert_eq!(inner, vec![ParseWarning::unknown_tag("b", "component")]);
+        assert_eq!(outer, vec![ParseWarning::unknown_tag("a", "component")]);
+        warn(ParseWarning::unknown_tag("c", "component"));
+        assert!(WARNINGS.with(|warnings| warnings.borrow().is_none()));
+
+        let (_, warnings) = Component::from_path_with_warnings("./tests/desktop.xml".into())?;
+        assert!(warnings.is_empty());
+        let (_, warnings) =
+            Collection::from_path_with_warnings("./tests/collections/gnome-apps.xml".into())?;
+        assert!(warnings.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+use super::enums::{ComponentKind, Icon, Launchable, ProjectUrl};
+use super::{Component, DesktopEntry};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The browsers a web application can be installed in.
+pub enum Browser {
+    /// GNOME Web, which runs each web application in its own profile.
+    Epiphany,
+    /// Chromium, in application mode with its own user data directory.
+    Chromium,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A `web-application` component, with the URL it opens.
+///
+/// # Example
+///
+/// ```
+/// use appstream::webapp::WebApp;
+/// use appstream::Collection;
+///
+/// let collection = Collection::from_path("./tests/collections/fedora-web-apps.xml".into()).unwrap();
+/// let app = WebApp::new(&collection.components[0]).unwrap();
+/// assert_eq!(app.url().as_str(), "https://read.amazon.com/");
+/// assert!(app.icon().is_some());
+/// ```
+pub struct WebApp<'a> {
+    component: &'a Component,
+    url: &'a Url,
+}
+
+impl<'a> WebApp<'a> {
+    /// The minimum size of the icon of a web application, in pixels. The icons
+    /// are downloaded when the application is installed, an icon without a
+    /// declared size is accepted.
+    pub const MIN_ICON_SIZE: u32 = 128;
+
+    /// Wraps a web application, `None` if the component is of another kind or
+    /// has no URL to open.
+    ///
+    /// The URL is the one of its `url` launchable, else its homepage.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - The web application.
+    pub fn new(component: &'a Component) -> Option<Self> {
+        if component.kind != ComponentKind::WebApplication {
+            return None;
+        }
+        let launchable = component.launchables.iter().find_map(|l| match l {
+            Launchable::Url(url) => Some(url),
+            _ => None,
+        });
+        let url = launchable.or_else(|| {
+            component.urls.iter().find_map(|u| match u {
+                ProjectUrl::Homepage(url) => Some(url),
+                _ => None,
+            })
+        })?;
+        Some(Self { component, url })
+    }
+
+    /// The component of the web application.
+    pub fn component(&self) -> &'a Component {
+        self.component
+    }
+
+    /// The URL the web application opens.
+    pub fn url(&self) -> &'a Url {
+        self.url
+    }
+
+    /// The largest remote icon fulfilling the requirements of the web
+    /// applications, `None` if there is none.
+    ///
+    /// The icon of a web application isn't installed with a package, it has to
+    /// be a remote one of at least `MIN_ICON_SIZE` pixels.
+    pub fn icon(&self) -> Option<&'a Url> {
+        self.component
+            .icons
+            .iter()
+            .filter_map(|icon| match icon {
+                Icon::Remote { url, width, .. } => Some((url, *width)),
+                _ => None,
+            })
+            .filter(|(_, width)| width.is_none_or(|w| w >= Self::MIN_ICON_SIZE))
+            .max_by_key(|(_, width)| width.unwrap_or(0))
+            .map(|(url, _)| url)
+    }
+
+    /// The name of the web application, without the `.desktop` suffix nor the
+    /// browser prefix of its id, like `kindlecloud` for
+    /// `epiphany-kindlecloud.desktop`.
+    pub fn short_id(&self) -> &'a str {
+        let id = self.component.id.as_ref();
+        let id = id.strip_suffix(".desktop").unwrap_or(id);
+        ["epiphany-", "chrome-", "chromium-"]
+            .iter()
+            .find_map(|prefix| id.strip_prefix(prefix))
+            .unwrap_or(id)
+    }
+
+    /// A stub of the browser profile running the web application: its
+    /// directory and its desktop entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `browser` - The browser to run the web application in.
+    /// * `data_dir` - The directory the profile is created in, usually `$XDG_DATA_HOME`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use appstream::webapp::{Browser, WebApp};
+    /// use appstream::Collection;
+    ///
+    /// let collection = Collection::from_path("./tests/collections/fedora-web-apps.xml".into()).unwrap();
+    /// let app = WebApp::new(&collection.components[0]).unwrap();
+    /// let profile = app.profile(Browser::Epiphany, "/home/user/.local/share".as_ref());
+    /// assert_eq!(profile.desktop_file_id, "org.gnome.Epiphany.WebApp_kindlecloud.desktop");
+    /// assert!(profile.to_string().contains("Exec=epiphany --application-mode"));
+    /// ```
+    pub fn profile(&self, browser: Browser, data_dir: &Path) -> WebAppProfile {
+        let id = self.short_id();
+        let (name, exec) = match browser {
+            Browser::Epiphany => {
+                let name = format!("org.gnome.Epiphany.WebApp_{}", id);
+                let exec = format!(
+                    "epiphany --application-mode \"--profile={}\" {}",
+                    data_dir.join(&name).display(),
+                    self.url
+                );
+                (name, exec)
+            }
+            Browser::Chromium => {
+                let name = format!("chromium-webapp-{}", id);
+                let exec = format!(
+                    "chromium \"--user-data-dir={}\" --class={} --app={}",
+                    data_dir.join(&name).display(),
+                    name,
+                    self.url
+                );
+                (name, exec)
+            }
+        };
+
+        let mut entry = self.component.to_desktop_entry();
+        // The icon is downloaded into the profile.
+        entry.icon = self.icon().map(|_| {
+            data_dir
+                .join(&name)
+                .join("app-icon.png")
+                .display()
+                .to_string()
+        });
+        WebAppProfile {
+            browser,
+            directory: data_dir.join(&name),
+            desktop_file_id: format!("{}.desktop", name),
+            exec,
+            startup_wm_class: name,
+            entry,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// The stub of a browser profile running a web application, see
+/// `WebApp::profile`.
+///
+/// Formatting the profile gives the content of its desktop file. Epiphany also
+/// expects an empty `.app` file in the directory of the profile.
+pub struct WebAppProfile {
+    /// The browser running the web application.
+    pub browser: Browser,
+    /// The directory of the profile.
+    pub directory: PathBuf,
+    /// The id of the desktop file to install, like
+    /// `org.gnome.Epiphany.WebApp_kindlecloud.desktop`.
+    pub desktop_file_id: String,
+    /// The command line starting the web application.
+    pub exec: String,
+    /// The class of the windows of the web application.
+    pub startup_wm_class: String,
+    /// The desktop entry, with the name, icon and categories of the component.
+    pub entry: DesktopEntry,
+}
+
+impl fmt::Display for WebAppProfile {
+    /// Formats the profile as the content of its desktop file.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.entry)?;
+        writeln!(f, "Exec={}", self.exec)?;
+        writeln!(f, "StartupWMClass={}", self.startup_wm_class)?;
+        writeln!(f, "StartupNotify=true")?;
+        writeln!(f, "Terminal=false")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builders::ComponentBuilder;
+    use crate::{Collection, TranslatableString};
+    use std::error::Error;
+
+    #[test]
+    fn web_app() -> Result<(), Box<dyn Error>> {
+        let collection = Collection::from_path("./tests/collections/fedora-web-apps.xml".into())?;
+        let apps = collection
+            .components
+            .iter()
+            .filter_map(WebApp::new)
+            .collect::<Vec<_>>();
+        assert_eq!(apps.len(), collection.components.len());
+        assert_eq!(apps[0].short_id(), "kindlecloud");
+
+        let profile = apps[0].profile(Browser::Chromium, Path::new("/data"));
+        assert_eq!(
+            profile.directory,
+            Path::new("/data/chromium-webapp-kindlecloud")
+        );
+        assert_eq!(
+            profile.exec,
+            "chromium \"--user-data-dir=/data/chromium-webapp-kindlecloud\" \
+             --class=chromium-webapp-kindlecloud --app=https://read.amazon.com/"
+        );
+        let desktop = profile.to_string();
+        assert!(
+            desktop.starts_with("[Desktop Entry]\nType=Application\nName=Kindle Cloud Reader\n")
+        );
+        assert!(desktop.contains("Icon=/data/chromium-webapp-kindlecloud/app-icon.png\n"));
+        assert!(desktop.ends_with(
+            "StartupWMClass=chromium-webapp-kindlecloud\nStartupNotify=true\nTerminal=false\n"
+        ));
+
+        let builder = || -> Result<ComponentBuilder, url::ParseError> {
+            Ok(ComponentBuilder::default()
+                .id("org.example.Notes".into())
+                .name(TranslatableString::with_default("Notes"))
+                .kind(ComponentKind::WebApplication)
+                .url(ProjectUrl::Homepage(Url::parse("https://example.org")?))
+                .launchable(Launchable::Url(Url::parse("https://example.org/app")?))
+                .icon(Icon::Remote {
+                    url: Url::parse("https://example.org/64.png")?,
+                    width: Some(64),
+                    height: Some(64),
+                }))
+        };
+        let component = builder()?.build();
+        let app = WebApp::new(&component).unwrap();
+        assert_eq!(app.url().as_str(), "https://example.org/app");
+        assert_eq!(app.icon(), None);
+
+        let component = builder()?
+            .icon(Icon::Remote {
+                url: Url::parse("https://example.org/256.png")?,
+                width: Some(256),
+                height: Some(256),
+            })
+            .build();
+        let app = WebApp::new(&component).unwrap();
+        assert_eq!(
+            app.icon().map(Url::as_str),
+            Some("https://example.org/256.png")
+        );
+
+        let component = builder()?.kind(ComponentKind::DesktopApplication).build();
+        assert!(WebApp::new(&component).is_none());
+        Ok(())
+    }
+}
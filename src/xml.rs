@@ -1,4 +1,9 @@
-use super::error::ParseError;
+use super::error::{ParseError, ParseWarning};
+use super::firmware::FirmwareRequirement;
+use super::interner;
+#[cfg(feature = "raw")]
+use super::raw::RawNode;
+use super::warnings;
 use super::{Collection, Component};
 use std::convert::TryFrom;
 use std::str::FromStr;
@@ -10,24 +15,19 @@ use super::builders::{
     ScreenshotBuilder, VideoBuilder,
 };
 use super::enums::{
-    ArtifactKind, Bundle, Category, Checksum, ComponentKind, ContentAttribute,
-    ContentRatingVersion, ContentState, FirmwareKind, Icon, ImageKind, Kudo, Launchable,
-    ProjectUrl, Provide, ReleaseKind, ReleaseUrgency, Size, Translation,
+    ArtifactKind, Bundle, Category, Checksum, ColorScheme, Compare, ComponentKind,
+    ContentAttribute, ContentRatingVersion, ContentState, FirmwareKind, Icon, ImageKind, IssueKind,
+    Kudo, Launchable, MergeKind, ProjectUrl, Provide, ReleaseKind, ReleaseUrgency, Size,
+    Translation,
 };
+use super::Timestamp;
 use super::{
-    AppId, Artifact, ContentRating, Image, Language, License, MarkupTranslatableString, Release,
-    Screenshot, TranslatableList, TranslatableString, Video,
+    AppId, Artifact, BrandingColor, ContentRating, Image, Issue, Language, License,
+    MarkupTranslatableString, Release, Screenshot, TranslatableList, TranslatableString, Video,
 };
-use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
-
-fn deserialize_date(date: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
-    Utc.datetime_from_str(&date, "%s").or_else(
-        |_: chrono::ParseError| -> Result<DateTime<Utc>, chrono::ParseError> {
-            let date: NaiveDateTime =
-                NaiveDate::parse_from_str(&date, "%Y-%m-%d")?.and_hms(0, 0, 0);
-            Ok(DateTime::<Utc>::from_utc(date, Utc))
-        },
-    )
+
+fn deserialize_date(date: &str) -> Result<Timestamp, ParseError> {
+    date.parse()
 }
 
 impl TryFrom<&Element> for AppId {
@@ -41,6 +41,27 @@ impl TryFrom<&Element> for AppId {
     }
 }
 
+impl TryFrom<&Element> for FirmwareRequirement {
+    type Error = ParseError;
+
+    fn try_from(e: &Element) -> Result<Self, Self::Error> {
+        let compare = match e.attributes.get("compare") {
+            Some(compare) => Compare::from_str(compare)
+                .map_err(|_| ParseError::invalid_value(compare, "compare", "firmware"))?,
+            None => Compare::default(),
+        };
+        let target = e
+            .get_text()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+        Ok(FirmwareRequirement {
+            target,
+            compare,
+            version: e.attributes.get("version").cloned(),
+        })
+    }
+}
+
 impl TryFrom<&Element> for Artifact {
     type Error = ParseError;
 
@@ -74,7 +95,7 @@ impl TryFrom<&Element> for Artifact {
                     "checksum" => {
                         artifact = artifact.checksum(Checksum::try_from(e)?);
                     }
-                    _ => (),
+                    tag => warnings::warn(ParseWarning::unknown_tag(tag, "artifact")),
                 }
             }
         }
@@ -153,14 +174,37 @@ impl TryFrom<&Element> for Collection {
             }
         }
 
-        for node in &e.children {
-            if let xmltree::XMLNode::Element(ref e) = node {
-                if &*e.name == "component" {
-                    collection = collection.component(Component::try_from(e)?);
-                }
+        // A broken generation time doesn't make the components unusable.
+        if let Some(time) = e.attributes.get("time") {
+            match time.parse() {
+                Ok(time) => collection = collection.timestamp(time),
+                Err(_) => warnings::warn(ParseWarning::recovered_value(
+                    time,
+                    "time",
+                    "components",
+                    "none",
+                )),
+            }
+        }
+
+        for attr in e.attributes.keys() {
+            if !["version", "origin", "architecture", "time"].contains(&attr.as_str()) {
+                warnings::warn(ParseWarning::ignored_attribute(attr, "components"));
             }
         }
-        Ok(collection.build())
+
+        interner::scope(|| {
+            for node in &e.children {
+                if let xmltree::XMLNode::Element(ref e) = node {
+                    if &*e.name == "component" {
+                        collection = collection.component(Component::try_from(e)?);
+                    } else {
+                        warnings::warn(ParseWarning::unknown_tag(&e.name, "components"));
+                    }
+                }
+            }
+            Ok(collection.build())
+        })
     }
 }
 
@@ -175,6 +219,31 @@ impl TryFrom<&Element> for Component {
                     .map_err(|_| ParseError::invalid_value(kind, "type", "component"))?,
             );
         }
+        if let Some(merge) = e.attributes.get("merge") {
+            component = component.merge(
+                MergeKind::from_str(merge)
+                    .map_err(|_| ParseError::invalid_value(merge, "merge", "component"))?,
+            );
+        }
+        if let Some(priority) = e.attributes.get("priority") {
+            component = component.priority(
+                priority
+                    .parse()
+                    .map_err(|_| ParseError::invalid_value(priority, "priority", "component"))?,
+            );
+        }
+        if let Some(origin) = e.attributes.get("origin") {
+            if !origin.is_empty() {
+                component = component.origin(origin);
+            }
+        }
+        for attr in e
+            .attributes
+            .keys()
+            .filter(|a| !["type", "merge", "priority", "origin"].contains(&a.as_str()))
+        {
+            warnings::warn(ParseWarning::ignored_attribute(attr, "component"));
+        }
 
         let app_id = AppId::try_from(
             e.get_child("id")
@@ -189,6 +258,7 @@ impl TryFrom<&Element> for Component {
         for node in &e.children {
             if let xmltree::XMLNode::Element(ref e) = node {
                 match &*e.name {
+                    "id" => (),
                     "name" => name.add_for_element(e),
                     "summary" => summary.add_for_element(e),
                     "developer_name" => developer_name.add_for_element(e),
@@ -253,6 +323,18 @@ impl TryFrom<&Element> for Component {
                             );
                         }
                     }
+                    "branding" => {
+                        for child in e.children.iter() {
+                            if let xmltree::XMLNode::Element(ref e) = child {
+                                if &*e.name == "color" {
+                                    component =
+                                        component.branding_color(BrandingColor::try_from(e)?);
+                                } else {
+                                    warnings::warn(ParseWarning::unknown_tag(&e.name, "branding"));
+                                }
+                            }
+                        }
+                    }
                     "kudos" => {
                         for child in e.children.iter() {
                             let kudo = child
@@ -343,7 +425,20 @@ impl TryFrom<&Element> for Component {
                             )?);
                         }
                     }
-                    "metadata" => {
+                    "recommends" => {
+                        for child in e.children.iter().filter_map(|c| c.as_element()) {
+                            match child.name.as_str() {
+                                "id" => component = component.recommend(AppId::try_from(child)?),
+                                tag => warnings::warn(ParseWarning::unknown_tag(tag, "recommends")),
+                            }
+                        }
+                    }
+                    "replaces" => {
+                        for child in e.children.iter().filter_map(|c| c.as_element()) {
+                            component = component.replace(AppId::try_from(child)?);
+                        }
+                    }
+                    "metadata" | "custom" => {
                         for child in &e.children {
                             let child = child
                                 .as_element()
@@ -362,14 +457,23 @@ impl TryFrom<&Element> for Component {
                     }
                     "requires" => {
                         for child in e.children.iter() {
-                            component = component.require(AppId::try_from(
-                                child
-                                    .as_element()
-                                    .ok_or_else(|| ParseError::invalid_tag("id"))?,
-                            )?);
+                            let child = child
+                                .as_element()
+                                .ok_or_else(|| ParseError::invalid_tag("id"))?;
+                            component = match child.name.as_str() {
+                                "firmware" => component
+                                    .require_firmware(FirmwareRequirement::try_from(child)?),
+                                _ => component.require(AppId::try_from(child)?),
+                            };
+                        }
+                    }
+                    tag => {
+                        warnings::warn(ParseWarning::unknown_tag(tag, "component"));
+                        #[cfg(feature = "raw")]
+                        {
+                            component = component.unknown_node(RawNode::Xml(e.clone()));
                         }
                     }
-                    _ => (),
                 }
             };
         }
@@ -384,6 +488,24 @@ impl TryFrom<&Element> for Component {
     }
 }
 
+impl TryFrom<&Element> for BrandingColor {
+    type Error = ParseError;
+
+    fn try_from(e: &Element) -> Result<Self, Self::Error> {
+        let value = e
+            .get_text()
+            .ok_or_else(|| ParseError::missing_value("color"))?;
+        let mut color = BrandingColor::new(value.trim());
+        if let Some(scheme) = e.attributes.get("scheme_preference") {
+            color = color
+                .scheme_preference(ColorScheme::from_str(scheme).map_err(|_| {
+                    ParseError::invalid_value(scheme, "scheme_preference", "color")
+                })?);
+        }
+        Ok(color)
+    }
+}
+
 impl TryFrom<&Element> for ContentRating {
     type Error = ParseError;
 
@@ -392,7 +514,15 @@ impl TryFrom<&Element> for ContentRating {
             Some(t) => match t.as_str() {
                 "oars-1.0" => ContentRatingVersion::Oars1_0,
                 "oars-1.1" => ContentRatingVersion::Oars1_1,
-                _ => ContentRatingVersion::Unknown,
+                t => {
+                    warnings::warn(ParseWarning::recovered_value(
+                        t,
+                        "type",
+                        "content_rating",
+                        "unknown",
+                    ));
+                    ContentRatingVersion::Unknown
+                }
             },
             None => ContentRatingVersion::Unknown,
         };
@@ -424,40 +554,12 @@ impl TryFrom<&Element> for ContentAttribute {
         let val = ContentState::from_str(&val)
             .map_err(|_| ParseError::invalid_value(&val, "$value", "content-attribute"))?;
 
-        match e.attributes.get("id").as_deref() {
-            Some(t) => match t.as_str() {
-                "violence-cartoon" => Ok(ContentAttribute::ViolenceCartoon(val)),
-                "violence-fantasy" => Ok(ContentAttribute::ViolenceFantasy(val)),
-                "violence-bloodshed" => Ok(ContentAttribute::ViolenceBloodshed(val)),
-                "violence-sexual" => Ok(ContentAttribute::ViolenceSexual(val)),
-                "violence-desecration" => Ok(ContentAttribute::ViolenceDesecration(val)),
-                "violence-slavery" => Ok(ContentAttribute::ViolenceSlavery(val)),
-                "violence-realistic" => Ok(ContentAttribute::ViolenceRealistic(val)),
-                "violence-worship" => Ok(ContentAttribute::ViolenceWorship(val)),
-                "drugs-alcohol" => Ok(ContentAttribute::DrugsAlcohol(val)),
-                "drugs-narcotics" => Ok(ContentAttribute::DrugsNarcotics(val)),
-                "drugs-tobacco" => Ok(ContentAttribute::DrugsTobacco(val)),
-                "sex-nudity" => Ok(ContentAttribute::SexNudity(val)),
-                "sex-themes" => Ok(ContentAttribute::SexThemes(val)),
-                "sex-homosexuality" => Ok(ContentAttribute::SexHomosexuality(val)),
-                "sex-prostitution" => Ok(ContentAttribute::SexProstitution(val)),
-                "sex-adultery" => Ok(ContentAttribute::SexAdultery(val)),
-                "sex-appearance" => Ok(ContentAttribute::SexAppearance(val)),
-                "language-profanity" => Ok(ContentAttribute::LanguageProfanity(val)),
-                "language-humor" => Ok(ContentAttribute::LanguageHumor(val)),
-                "language-discrimination" => Ok(ContentAttribute::LanguageDiscrimination(val)),
-                "social-chat" => Ok(ContentAttribute::SocialChat(val)),
-                "social-info" => Ok(ContentAttribute::SocialInfo(val)),
-                "social-audio" => Ok(ContentAttribute::SocialAudio(val)),
-                "social-location" => Ok(ContentAttribute::SocialLocation(val)),
-                "social-contacts" => Ok(ContentAttribute::SocialContacts(val)),
-                "money-advertising" => Ok(ContentAttribute::MoneyAdvertising(val)),
-                "money-purchasing" => Ok(ContentAttribute::MoneyPurchasing(val)),
-                "money-gambling" => Ok(ContentAttribute::MoneyGambling(val)),
-                id => Err(ParseError::invalid_value(id, "id", "content-attribute")),
-            },
-            None => Err(ParseError::missing_attribute("id", "content-attribute")),
-        }
+        let id = e
+            .attributes
+            .get("id")
+            .ok_or_else(|| ParseError::missing_attribute("id", "content-attribute"))?;
+        ContentAttribute::from_id(id, val)
+            .ok_or_else(|| ParseError::invalid_value(id, "id", "content-attribute"))
     }
 }
 
@@ -475,15 +577,16 @@ impl TryFrom<&Element> for Icon {
             None => "local",
         };
 
-        let width: Option<u32> = match e.attributes.get("width") {
-            Some(w) => w.parse::<u32>().ok(),
-            _ => None,
-        };
-
-        let height: Option<u32> = match e.attributes.get("height") {
-            Some(h) => h.parse::<u32>().ok(),
-            _ => None,
+        let dimension = |attr: &str| {
+            let value = e.attributes.get(attr)?;
+            let parsed = value.parse::<u32>().ok();
+            if parsed.is_none() {
+                warnings::warn(ParseWarning::recovered_value(value, attr, "icon", "none"));
+            }
+            parsed
         };
+        let width = dimension("width");
+        let height = dimension("height");
 
         Ok(match kind {
             "stock" => Icon::Stock(val),
@@ -497,11 +600,16 @@ impl TryFrom<&Element> for Icon {
                 width,
                 height,
             },
-            _ => Icon::Local {
-                path: val.into(),
-                width,
-                height,
-            },
+            kind => {
+                if kind != "local" {
+                    warnings::warn(ParseWarning::recovered_value(kind, "type", "icon", "local"));
+                }
+                Icon::Local {
+                    path: val.into(),
+                    width,
+                    height,
+                }
+            }
         })
     }
 }
@@ -548,10 +656,10 @@ impl TryFrom<&Element> for Language {
     type Error = ParseError;
 
     fn try_from(e: &Element) -> Result<Self, Self::Error> {
-        let locale = e
-            .get_text()
-            .ok_or_else(|| ParseError::missing_value("language"))?
-            .into_owned();
+        let locale = interner::intern(
+            &e.get_text()
+                .ok_or_else(|| ParseError::missing_value("language"))?,
+        );
 
         match e.attributes.get("percentage") {
             Some(p) => {
@@ -588,7 +696,15 @@ impl TryFrom<&Element> for Launchable {
             "desktop-id" => Launchable::DesktopId(val),
             "service" => Launchable::Service(val),
             "url" => Launchable::Url(Url::parse(&val)?),
-            _ => Launchable::Unknown(val),
+            kind => {
+                warnings::warn(ParseWarning::recovered_value(
+                    kind,
+                    "type",
+                    "launchable",
+                    "unknown",
+                ));
+                Launchable::Unknown(val)
+            }
         })
     }
 }
@@ -622,7 +738,10 @@ impl TryFrom<&Element> for ProjectUrl {
                 "translate" => Ok(ProjectUrl::Translate(Url::parse(&val)?)),
                 "faq" => Ok(ProjectUrl::Faq(Url::parse(&val)?)),
                 "bugtracker" => Ok(ProjectUrl::BugTracker(Url::parse(&val)?)),
-                _ => Ok(ProjectUrl::Unknown(Url::parse(&val)?)),
+                t => {
+                    warnings::warn(ParseWarning::recovered_value(t, "type", "url", "unknown"));
+                    Ok(ProjectUrl::Unknown(Url::parse(&val)?))
+                }
             },
             None => Err(ParseError::missing_attribute("type", "url")),
         }
@@ -725,6 +844,11 @@ impl TryFrom<&Element> for Release {
                         release = release.size(Size::try_from(c)?);
                     }
                     "description" => description.add_for_element(c),
+                    "issues" => {
+                        for issue in c.children.iter().filter_map(|c| c.as_element()) {
+                            release = release.issue(Issue::try_from(issue)?);
+                        }
+                    }
                     "url" => {
                         release = release.url(Url::parse(
                             &c.get_text()
@@ -732,7 +856,7 @@ impl TryFrom<&Element> for Release {
                                 .as_ref(),
                         )?);
                     }
-                    _ => (),
+                    tag => warnings::warn(ParseWarning::unknown_tag(tag, "release")),
                 }
             }
         }
@@ -741,6 +865,31 @@ impl TryFrom<&Element> for Release {
     }
 }
 
+impl TryFrom<&Element> for Issue {
+    type Error = ParseError;
+
+    fn try_from(e: &Element) -> Result<Self, Self::Error> {
+        let kind = match e.attributes.get("type") {
+            Some(kind) => IssueKind::from_str(kind)
+                .map_err(|_| ParseError::invalid_value(kind, "type", "issue"))?,
+            None => IssueKind::default(),
+        };
+        let url = match e.attributes.get("url") {
+            Some(url) => Some(Url::parse(url)?),
+            None => None,
+        };
+        Ok(Issue {
+            kind,
+            id: e
+                .get_text()
+                .ok_or_else(|| ParseError::missing_value("issue"))?
+                .trim()
+                .to_string(),
+            url,
+        })
+    }
+}
+
 impl TryFrom<&Element> for Screenshot {
     type Error = ParseError;
 
@@ -751,6 +900,9 @@ impl TryFrom<&Element> for Screenshot {
                 .map(|t| t.as_str() == "default")
                 .unwrap_or_else(|| false),
         );
+        if let Some(environment) = e.attributes.get("environment") {
+            s = s.environment(environment);
+        }
         let mut caption = TranslatableString::default();
         for node in &e.children {
             if let xmltree::XMLNode::Element(ref e) = node {
@@ -764,7 +916,7 @@ impl TryFrom<&Element> for Screenshot {
                     "video" => {
                         s = s.video(Video::try_from(e)?);
                     }
-                    _ => (),
+                    tag => warnings::warn(ParseWarning::unknown_tag(tag, "screenshot")),
                 }
             }
         }
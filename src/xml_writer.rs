@@ -0,0 +1,804 @@
+use super::enums::{
+    Bundle, Category, Checksum, ComponentKind, ContentRatingVersion, Icon, Kudo, Launchable,
+    ProjectUrl, Provide, ReleaseKind, ReleaseUrgency, Size, Translation,
+};
+use super::firmware::FirmwareRequirement;
+#[cfg(feature = "raw")]
+use super::raw::RawNode;
+use super::translatable_string::{markup_tag_len, DEFAULT_LOCALE};
+use super::{
+    AppId, Artifact, BrandingColor, Collection, Component, ContentRating, Image, Issue, Language,
+    Release, Screenshot, TranslatableList, Video,
+};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+#[cfg(feature = "raw")]
+use xmltree::{Element, XMLNode};
+
+/// The indentation of each level of the written documents.
+const INDENT: &str = "  ";
+
+#[derive(Clone, Debug, PartialEq)]
+enum Content {
+    Empty,
+    Text(String),
+    /// Description markup, like `<p>Text</p>`, written as is.
+    Markup(String),
+    Children(Vec<XmlNode>),
+    #[cfg(feature = "raw")]
+    /// Texts interleaved with child elements, written on a single line since
+    /// their spaces are part of the content.
+    Mixed(Vec<Mixed>),
+}
+
+#[cfg(feature = "raw")]
+#[derive(Clone, Debug, PartialEq)]
+enum Mixed {
+    Text(String),
+    Element(XmlNode),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// An element to write, keeping its attributes in order so the written
+/// documents don't change from one run to the other.
+pub(crate) struct XmlNode {
+    name: String,
+    attributes: Vec<(String, String)>,
+    content: Content,
+}
+
+impl XmlNode {
+    pub(crate) fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            attributes: vec![],
+            content: Content::Empty,
+        }
+    }
+
+    /// An element with a text content.
+    fn text<T: ToString>(name: &str, text: T) -> Self {
+        Self::new(name).with_text(text)
+    }
+
+    /// An element listing its children, like `<categories>`.
+    fn list<I: IntoIterator<Item = XmlNode>>(name: &str, children: I) -> Self {
+        let mut node = Self::new(name);
+        for child in children {
+            node.push(child);
+        }
+        node
+    }
+
+    fn with_text<T: ToString>(mut self, text: T) -> Self {
+        self.content = Content::Text(text.to_string());
+        self
+    }
+
+    pub(crate) fn attr<T: ToString>(mut self, name: &str, value: T) -> Self {
+        self.attributes.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn attr_opt<T: ToString>(self, name: &str, value: Option<T>) -> Self {
+        match value {
+            Some(value) => self.attr(name, value),
+            None => self,
+        }
+    }
+
+    /// Adds a child element.
+    pub(crate) fn push(&mut self, child: XmlNode) {
+        match &mut self.content {
+            Content::Children(children) => children.push(child),
+            _ => self.content = Content::Children(vec![child]),
+        }
+    }
+
+    /// Adds a child element, unless it's a list without any item.
+    fn push_list(&mut self, list: XmlNode) {
+        if list.content != Content::Empty {
+            self.push(list);
+        }
+    }
+
+    fn extend<I: IntoIterator<Item = XmlNode>>(&mut self, children: I) {
+        for child in children {
+            self.push(child);
+        }
+    }
+
+    /// Writes the element and its children, indented by `depth` levels.
+    pub(crate) fn write<W: Write>(&self, w: &mut W, depth: usize) -> io::Result<()> {
+        let indent = INDENT.repeat(depth);
+        write!(w, "{}", indent)?;
+        match &self.content {
+            Content::Children(children) => {
+                self.write_start(w)?;
+                writeln!(w, ">")?;
+                for child in children {
+                    child.write(w, depth + 1)?;
+                }
+                writeln!(w, "{}</{}>", indent, self.name)
+            }
+            _ => {
+                self.write_inline(w)?;
+                writeln!(w)
+            }
+        }
+    }
+
+    /// Writes the element and its children on a single line.
+    fn write_inline<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        self.write_start(w)?;
+        match &self.content {
+            Content::Empty => return write!(w, "/>"),
+            Content::Text(text) => write!(w, ">{}", escape(text, false))?,
+            Content::Markup(markup) => write!(w, ">{}", escape_markup(markup))?,
+            Content::Children(children) => {
+                write!(w, ">")?;
+                for child in children {
+                    child.write_inline(w)?;
+                }
+            }
+            #[cfg(feature = "raw")]
+            Content::Mixed(nodes) => {
+                write!(w, ">")?;
+                for node in nodes {
+                    match node {
+                        Mixed::Text(text) => write!(w, "{}", escape(text, false))?,
+                        Mixed::Element(child) => child.write_inline(w)?,
+                    }
+                }
+            }
+        }
+        write!(w, "</{}>", self.name)
+    }
+
+    /// Writes the start tag, without its closing `>`.
+    fn write_start<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "<{}", self.name)?;
+        for (name, value) in self.attributes.iter() {
+            write!(w, " {}=\"{}\"", name, escape(value, true))?;
+        }
+        Ok(())
+    }
+}
+
+/// Escapes the special characters of a text, and of the quotes of an attribute.
+fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes the text of description markup, keeping its tags, see
+/// `markup_tag_len`.
+fn escape_markup(markup: &str) -> String {
+    let mut escaped = String::with_capacity(markup.len());
+    for (i, c) in markup.char_indices() {
+        match c {
+            '<' if markup_tag_len(&markup[i..]).is_some() => escaped.push('<'),
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The elements of a translated text, the untranslated one first then the
+/// translations with their `xml:lang` attribute.
+fn translations<T, F: Fn(XmlNode, &T) -> XmlNode>(
+    name: &str,
+    values: &BTreeMap<String, T>,
+    content: F,
+) -> Vec<XmlNode> {
+    let default = values.get(DEFAULT_LOCALE).map(|v| (None, v));
+    let translated = values
+        .iter()
+        .filter(|(locale, _)| locale.as_str() != DEFAULT_LOCALE)
+        .map(|(locale, v)| (Some(locale), v));
+    default
+        .into_iter()
+        .chain(translated)
+        .map(|(locale, value)| content(XmlNode::new(name).attr_opt("xml:lang", locale), value))
+        .collect()
+}
+
+fn text_translations(name: &str, values: &BTreeMap<String, String>) -> Vec<XmlNode> {
+    translations(name, values, |node, text| node.with_text(text))
+}
+
+fn markup_translations(name: &str, values: &BTreeMap<String, String>) -> Vec<XmlNode> {
+    translations(name, values, |mut node, markup| {
+        node.content = Content::Markup(markup.trim().to_string());
+        node
+    })
+}
+
+fn ids(name: &str, ids: &[AppId]) -> XmlNode {
+    XmlNode::list(name, ids.iter().map(|id| XmlNode::text("id", id.as_ref())))
+}
+
+/// The name of a component type in the specification.
+fn kind_name(kind: ComponentKind) -> String {
+    match kind {
+        ComponentKind::ConsoleApplication => "console-application".into(),
+        ComponentKind::DesktopApplication => "desktop-application".into(),
+        kind => kind.to_string(),
+    }
+}
+
+impl From<&Collection> for XmlNode {
+    fn from(collection: &Collection) -> Self {
+        let mut node = XmlNode::new("components")
+            .attr("version", &collection.version)
+            .attr_opt("origin", collection.origin.as_ref())
+            .attr_opt("architecture", collection.architecture.as_ref())
+            .attr_opt("media_baseurl", collection.media_base_url.as_ref())
+            .attr_opt("time", collection.timestamp.map(|t| t.as_unix()));
+        node.extend(collection.components.iter().map(XmlNode::from));
+        node
+    }
+}
+
+impl From<&Component> for XmlNode {
+    fn from(c: &Component) -> Self {
+        let mut node = XmlNode::new("component")
+            .attr("type", kind_name(c.kind))
+            .attr_opt("merge", c.merge.map(|m| m.to_string()))
+            .attr_opt("priority", c.priority)
+            .attr_opt("origin", c.origin.as_deref());
+
+        node.push(XmlNode::text("id", c.id.as_ref()));
+        node.extend(text_translations("name", &c.name.0));
+        if let Some(summary) = &c.summary {
+            node.extend(text_translations("summary", &summary.0));
+        }
+        if let Some(description) = &c.description {
+            node.extend(markup_translations("description", &description.0));
+        }
+        if let Some(developer_name) = &c.developer_name {
+            node.extend(text_translations("developer_name", &developer_name.0));
+        }
+        if let Some(license) = &c.project_license {
+            node.push(XmlNode::text("project_license", license.original()));
+        }
+        if let Some(license) = &c.metadata_license {
+            node.push(XmlNode::text("metadata_license", license.original()));
+        }
+        if let Some(group) = &c.project_group {
+            node.push(XmlNode::text("project_group", group));
+        }
+        if let Some(desktop) = &c.compulsory_for_desktop {
+            node.push(XmlNode::text("compulsory_for_desktop", desktop));
+        }
+        node.extend(
+            c.extends
+                .iter()
+                .map(|id| XmlNode::text("extends", id.as_ref())),
+        );
+        node.extend(c.icons.iter().map(XmlNode::from));
+        node.push_list(XmlNode::list(
+            "categories",
+            c.categories.iter().map(|category| {
+                let name = match category {
+                    Category::Unknown(name) => name.clone(),
+                    category => category.to_string(),
+                };
+                XmlNode::text("category", name)
+            }),
+        ));
+        if let Some(keywords) = &c.keywords {
+            node.push_list(XmlNode::from(&**keywords));
+        }
+        node.push_list(XmlNode::list(
+            "kudos",
+            c.kudos.iter().map(|kudo| {
+                let name = match kudo {
+                    Kudo::Unknown(name) => name.clone(),
+                    kudo => kudo.to_string(),
+                };
+                XmlNode::text("kudo", name)
+            }),
+        ));
+        node.push_list(XmlNode::list(
+            "mimetypes",
+            c.mimetypes.iter().map(|m| XmlNode::text("mimetype", m)),
+        ));
+        node.extend(c.launchables.iter().map(launchable));
+        node.extend(c.urls.iter().map(project_url));
+        node.extend(c.bundles.iter().map(XmlNode::from));
+        if let Some(pkgname) = &c.pkgname {
+            node.push(XmlNode::text("pkgname", pkgname));
+        }
+        if let Some(pkgname) = &c.source_pkgname {
+            node.push(XmlNode::text("source_pkgname", pkgname));
+        }
+        node.push_list(XmlNode::list(
+            "screenshots",
+            c.screenshots.iter().map(XmlNode::from),
+        ));
+        node.push_list(XmlNode::list(
+            "releases",
+            c.releases.iter().map(XmlNode::from),
+        ));
+        node.push_list(XmlNode::list(
+            "languages",
+            c.languages.iter().map(XmlNode::from),
+        ));
+        if let Some(rating) = &c.content_rating {
+            node.push(XmlNode::from(&**rating));
+        }
+        node.push_list(XmlNode::list(
+            "provides",
+            c.provides.iter().map(XmlNode::from),
+        ));
+        node.extend(c.translations.iter().filter_map(translation));
+        node.push_list(ids("suggests", &c.suggestions));
+        node.push_list(ids("recommends", &c.recommendations));
+        node.push_list(ids("replaces", &c.replaces));
+        let mut requires = ids("requires", &c.requirements);
+        requires.extend(c.firmware_requirements.iter().map(XmlNode::from));
+        node.push_list(requires);
+        node.push_list(XmlNode::list(
+            "branding",
+            c.branding.iter().map(XmlNode::from),
+        ));
+        if let Some(contact) = &c.update_contact {
+            node.push(XmlNode::text("update_contact", contact));
+        }
+        node.push_list(XmlNode::list(
+            "custom",
+            c.metadata.iter().map(|(key, value)| {
+                let node = XmlNode::new("value").attr("key", key);
+                match value {
+                    Some(value) => node.with_text(value),
+                    None => node,
+                }
+            }),
+        ));
+        #[cfg(feature = "raw")]
+        node.extend(c.unknown_nodes.iter().filter_map(|n| match n {
+            RawNode::Xml(e) => Some(XmlNode::from(e)),
+            // The DEP-11 keys have no XML equivalent.
+            RawNode::Yaml(..) => None,
+        }));
+        node
+    }
+}
+
+#[cfg(feature = "raw")]
+impl From<&Element> for XmlNode {
+    /// Converts an unknown element kept unparsed, with its attributes sorted by
+    /// name. The texts of an element with children are kept in between them,
+    /// unless they are only made of spaces. The comments and processing
+    /// instructions are left out.
+    fn from(e: &Element) -> Self {
+        raw_node(e, &[])
+    }
+}
+
+#[cfg(feature = "raw")]
+/// Converts an unknown element, declaring the namespaces of its prefixes unless
+/// they are part of `declared`, the ones its ancestors declared.
+fn raw_node(e: &Element, declared: &[&str]) -> XmlNode {
+    let name = match &e.prefix {
+        Some(prefix) => format!("{}:{}", prefix, e.name),
+        None => e.name.clone(),
+    };
+    let mut node = XmlNode::new(&name);
+
+    let mut attributes = e.attributes.iter().collect::<Vec<_>>();
+    attributes.sort();
+    let mut prefixes = e.prefix.iter().map(String::as_str).collect::<Vec<_>>();
+    for (name, _) in attributes.iter() {
+        if let Some((prefix, _)) = name.split_once(':') {
+            prefixes.push(prefix);
+        }
+    }
+    prefixes.sort_unstable();
+    prefixes.dedup();
+    let mut declared = declared.to_vec();
+    for prefix in prefixes {
+        if declared.contains(&prefix) {
+            continue;
+        }
+        if let Some(namespace) = e.namespaces.as_ref().and_then(|n| n.get(prefix)) {
+            if prefix != "xml" {
+                node = node.attr(&format!("xmlns:{}", prefix), namespace);
+            }
+            declared.push(prefix);
+        }
+    }
+    for (name, value) in attributes {
+        // The `xml:lang` attributes are keyed by their local name, like
+        // `Element::parse` does, see `reader::new_element`.
+        let name = if name == "lang" { "xml:lang" } else { name };
+        node = node.attr(name, value);
+    }
+
+    let is_mixed = e.children.iter().any(|c| match c {
+        XMLNode::Text(text) | XMLNode::CData(text) => !text.trim().is_empty(),
+        _ => false,
+    });
+    if e.children.iter().all(|c| c.as_element().is_none()) {
+        if let Some(text) = e.get_text() {
+            node = node.with_text(text);
+        }
+    } else if is_mixed {
+        node.content = Content::Mixed(
+            e.children
+                .iter()
+                .filter_map(|c| match c {
+                    XMLNode::Element(child) => Some(Mixed::Element(raw_node(child, &declared))),
+                    XMLNode::Text(text) | XMLNode::CData(text) => Some(Mixed::Text(text.clone())),
+                    _ => None,
+                })
+                .collect(),
+        );
+    } else {
+        node.extend(
+            e.children
+                .iter()
+                .filter_map(|c| c.as_element())
+                .map(|child| raw_node(child, &declared)),
+        );
+    }
+    node
+}
+
+/// The root of an upstream metainfo file: the `<component>` element without
+/// what only the catalogs have, like the package names, the bundles, the
+/// cached icons and the merge attributes.
+pub(crate) fn metainfo(component: &Component) -> XmlNode {
+    let mut component = component.clone();
+    component.merge = None;
+    component.priority = None;
+    component.origin = None;
+    component.pkgname = None;
+    component.source_pkgname = None;
+    component.bundles.clear();
+    component
+        .icons
+        .retain(|icon| !matches!(icon, Icon::Cached { .. }));
+    XmlNode::from(&component)
+}
+
+impl From<&TranslatableList> for XmlNode {
+    fn from(keywords: &TranslatableList) -> Self {
+        let mut node = XmlNode::new("keywords");
+        let default = keywords.0.get(DEFAULT_LOCALE).map(|k| (None, k));
+        let translated = keywords
+            .0
+            .iter()
+            .filter(|(locale, _)| locale.as_str() != DEFAULT_LOCALE)
+            .map(|(locale, k)| (Some(locale), k));
+        for (locale, words) in default.into_iter().chain(translated) {
+            node.extend(
+                words
+                    .iter()
+                    .map(|w| XmlNode::text("keyword", w).attr_opt("xml:lang", locale)),
+            );
+        }
+        node
+    }
+}
+
+impl From<&Icon> for XmlNode {
+    fn from(icon: &Icon) -> Self {
+        let (kind, value, width, height) = match icon {
+            Icon::Stock(name) => return XmlNode::text("icon", name).attr("type", "stock"),
+            Icon::Cached {
+                path,
+                width,
+                height,
+            } => ("cached", path.display().to_string(), width, height),
+            Icon::Local {
+                path,
+                width,
+                height,
+            } => ("local", path.display().to_string(), width, height),
+            Icon::Remote { url, width, height } => ("remote", url.to_string(), width, height),
+        };
+        XmlNode::text("icon", value)
+            .attr("type", kind)
+            .attr_opt("width", *width)
+            .attr_opt("height", *height)
+    }
+}
+
+/// The `<launchable>` element, without a type for the unknown ones.
+fn launchable(launchable: &Launchable) -> XmlNode {
+    let (kind, value) = match launchable {
+        Launchable::DesktopId(id) => (Some("desktop-id"), id.clone()),
+        Launchable::Service(name) => (Some("service"), name.clone()),
+        Launchable::Url(url) => (Some("url"), url.to_string()),
+        Launchable::CockpitManifest(name) => (Some("cockpit-manifest"), name.clone()),
+        Launchable::Unknown(value) => (None, value.clone()),
+    };
+    XmlNode::text("launchable", value).attr_opt("type", kind)
+}
+
+/// The `<url>` element.
+fn project_url(url: &ProjectUrl) -> XmlNode {
+    let (kind, url) = match url {
+        ProjectUrl::Donation(url) => ("donation", url),
+        ProjectUrl::Translate(url) => ("translate", url),
+        ProjectUrl::Homepage(url) => ("homepage", url),
+        ProjectUrl::BugTracker(url) => ("bugtracker", url),
+        ProjectUrl::Help(url) => ("help", url),
+        ProjectUrl::Faq(url) => ("faq", url),
+        ProjectUrl::Contact(url) => ("contact", url),
+        ProjectUrl::Unknown(url) => ("unknown", url),
+    };
+    XmlNode::text("url", url).attr("type", kind)
+}
+
+/// The `<translation>` element, `None` for the unknown types.
+fn translation(translation: &Translation) -> Option<XmlNode> {
+    let (kind, domain) = match translation {
+        Translation::Gettext(domain) => ("gettext", domain),
+        Translation::Qt(domain) => ("qt", domain),
+        Translation::Unknown => return None,
+    };
+    Some(XmlNode::text("translation", domain).attr("type", kind))
+}
+
+impl From<&Bundle> for XmlNode {
+    fn from(bundle: &Bundle) -> Self {
+        match bundle {
+            Bundle::Limba(id) => XmlNode::text("bundle", id).attr("type", "limba"),
+            Bundle::Flatpak {
+                runtime,
+                sdk,
+                reference,
+            } => XmlNode::text("bundle", reference)
+                .attr("type", "flatpak")
+                .attr_opt("runtime", runtime.as_ref())
+                .attr_opt("sdk", sdk.as_ref()),
+            Bundle::AppImage(id) => XmlNode::text("bundle", id).attr("type", "appimage"),
+            Bundle::Snap(id) => XmlNode::text("bundle", id).attr("type", "snap"),
+            Bundle::Tarball(id) => XmlNode::text("bundle", id).attr("type", "tarball"),
+        }
+    }
+}
+
+impl From<&Screenshot> for XmlNode {
+    fn from(screenshot: &Screenshot) -> Self {
+        let mut node = XmlNode::new("screenshot")
+            .attr_opt("type", Some("default").filter(|_| screenshot.is_default))
+            .attr_opt("environment", screenshot.environment.as_ref());
+        if let Some(caption) = &screenshot.caption {
+            node.extend(text_translations("caption", &caption.0));
+        }
+        node.extend(screenshot.images.iter().map(XmlNode::from));
+        node.extend(screenshot.videos.iter().map(XmlNode::from));
+        node
+    }
+}
+
+impl From<&Image> for XmlNode {
+    fn from(image: &Image) -> Self {
+        XmlNode::text("image", &image.url)
+            .attr("type", image.kind.to_string())
+            .attr_opt("width", image.width)
+            .attr_opt("height", image.height)
+    }
+}
+
+impl From<&Video> for XmlNode {
+    fn from(video: &Video) -> Self {
+        XmlNode::text("video", &video.url)
+            .attr_opt("container", video.container.as_ref())
+            .attr_opt("codec", video.codec.as_ref())
+            .attr_opt("width", video.width)
+            .attr_opt("height", video.height)
+    }
+}
+
+impl From<&Release> for XmlNode {
+    fn from(release: &Release) -> Self {
+        let mut node = XmlNode::new("release")
+            .attr("version", &release.version)
+            .attr_opt("date", release.date)
+            .attr_opt("date_eol", release.date_eol)
+            .attr_opt(
+                "type",
+                Some(release.kind).filter(|k| *k != ReleaseKind::default()),
+            )
+            .attr_opt(
+                "urgency",
+                Some(release.urgency).filter(|u| *u != ReleaseUrgency::default()),
+            );
+        if let Some(description) = &release.description {
+            node.extend(markup_translations("description", &description.0));
+        }
+        if let Some(url) = &release.url {
+            node.push(XmlNode::text("url", url));
+        }
+        node.push_list(XmlNode::list(
+            "issues",
+            release.issues.iter().map(XmlNode::from),
+        ));
+        node.push_list(XmlNode::list(
+            "artifacts",
+            release.artifacts.iter().map(XmlNode::from),
+        ));
+        node.extend(release.sizes.iter().map(XmlNode::from));
+        node
+    }
+}
+
+impl From<&Issue> for XmlNode {
+    fn from(issue: &Issue) -> Self {
+        XmlNode::text("issue", &issue.id)
+            .attr("type", issue.kind.to_string())
+            .attr_opt("url", issue.url.as_ref())
+    }
+}
+
+impl From<&Artifact> for XmlNode {
+    fn from(artifact: &Artifact) -> Self {
+        let mut node = XmlNode::new("artifact")
+            .attr("type", artifact.kind.to_string())
+            .attr_opt("platform", artifact.platform.as_ref());
+        node.push(XmlNode::text("location", &artifact.url));
+        node.extend(artifact.checksums.iter().map(XmlNode::from));
+        node.extend(artifact.sizes.iter().map(XmlNode::from));
+        node.extend(artifact.bundles.iter().map(XmlNode::from));
+        node
+    }
+}
+
+impl From<&Checksum> for XmlNode {
+    fn from(checksum: &Checksum) -> Self {
+        let (kind, value) = match checksum {
+            Checksum::Sha1(value) => ("sha1", value),
+            Checksum::Sha256(value) => ("sha256", value),
+            Checksum::Blake2b(value) => ("blake2b", value),
+            Checksum::Blake2s(value) => ("blake2s", value),
+        };
+        XmlNode::text("checksum", value).attr("type", kind)
+    }
+}
+
+impl From<&Size> for XmlNode {
+    fn from(size: &Size) -> Self {
+        match size {
+            Size::Download(bytes) => XmlNode::text("size", bytes).attr("type", "download"),
+            Size::Installed(bytes) => XmlNode::text("size", bytes).attr("type", "installed"),
+        }
+    }
+}
+
+impl From<&Language> for XmlNode {
+    fn from(language: &Language) -> Self {
+        XmlNode::text("lang", &language.locale).attr_opt("percentage", language.percentage)
+    }
+}
+
+impl From<&ContentRating> for XmlNode {
+    fn from(rating: &ContentRating) -> Self {
+        let version = match rating.version {
+            ContentRatingVersion::Oars1_0 => Some("oars-1.0"),
+            ContentRatingVersion::Oars1_1 => Some("oars-1.1"),
+            ContentRatingVersion::Unknown => None,
+        };
+        let mut node = XmlNode::new("content_rating").attr_opt("type", version);
+        node.extend(rating.attributes.iter().map(|attribute| {
+            XmlNode::text("content_attribute", attribute.state()).attr("id", attribute.id())
+        }));
+        node
+    }
+}
+
+impl From<&Provide> for XmlNode {
+    fn from(provide: &Provide) -> Self {
+        match provide {
+            Provide::Library(path) => XmlNode::text("library", path.display()),
+            Provide::Binary(name) => XmlNode::text("binary", name),
+            Provide::Font(name) => XmlNode::text("font", name),
+            Provide::Modalias(glob) => XmlNode::text("modalias", glob),
+            Provide::Firmware { kind, item } => {
+                XmlNode::text("firmware", item).attr("type", kind.to_string())
+            }
+            Provide::Python2(name) => XmlNode::text("python2", name),
+            Provide::Python3(name) => XmlNode::text("python3", name),
+            Provide::DBus(name) => XmlNode::text("dbus", name),
+            Provide::Id(id) => XmlNode::text("id", id.as_ref()),
+            Provide::Codec(codec) => XmlNode::text("codec", codec),
+        }
+    }
+}
+
+impl From<&FirmwareRequirement> for XmlNode {
+    fn from(requirement: &FirmwareRequirement) -> Self {
+        let node = XmlNode::new("firmware")
+            .attr("compare", requirement.compare.to_string())
+            .attr_opt("version", requirement.version.as_ref());
+        match &requirement.target {
+            Some(target) => node.with_text(target),
+            None => node,
+        }
+    }
+}
+
+impl From<&BrandingColor> for XmlNode {
+    fn from(color: &BrandingColor) -> Self {
+        XmlNode::text("color", &color.value)
+            .attr("type", "primary")
+            .attr_opt(
+                "scheme_preference",
+                color.scheme_preference.map(|s| s.to_string()),
+            )
+    }
+}
+
+/// Writes an XML document, with its declaration, whose root is `root`.
+pub(crate) fn write_document<W: Write>(root: &XmlNode, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    root.write(&mut writer, 0)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+    use std::error::Error;
+    use std::fs;
+    use xmltree::Element;
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn Error>> {
+        for entry in fs::read_dir("./tests/collections")? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != "xml") {
+                continue;
+            }
+            let collection = Collection::from_path(path.clone())?;
+            let parsed = Collection::try_from(&Element::parse(collection.to_xml().as_bytes())?)?;
+            assert_eq!(parsed, collection, "{}", path.display());
+        }
+
+        assert_eq!(
+            escape_markup("<p>Tom & Jerry <3</p><ul><li>a <html> tag</li></ul>"),
+            "<p>Tom &amp; Jerry &lt;3</p><ul><li>a &lt;html> tag</li></ul>"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "raw")]
+    #[test]
+    fn unknown_elements() -> Result<(), Box<dyn Error>> {
+        let xml = r#"<component xmlns:ex="https://example.org/ns">
+            <id>org.example.App</id>
+            <name>App</name>
+            <sponsor ex:id="1" xml:lang="fr">Fait par <em>Example</em> &amp; co</sponsor>
+            <ex:links>
+                <link ex:rel="home">https://example.org</link>
+            </ex:links>
+        </component>"#;
+        let component = crate::reader::read_component(xml.as_bytes(), &Default::default())?;
+        let written = component.to_xml();
+        assert!(written.contains(
+            "  <sponsor xmlns:ex=\"https://example.org/ns\" ex:id=\"1\" xml:lang=\"fr\">\
+             Fait par <em>Example</em> &amp; co</sponsor>\n"
+        ));
+        assert!(written.contains(
+            "  <ex:links xmlns:ex=\"https://example.org/ns\">\n    \
+             <link ex:rel=\"home\">https://example.org</link>\n  </ex:links>\n"
+        ));
+        Ok(())
+    }
+}
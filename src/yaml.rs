@@ -1,33 +1,59 @@
-use super::error::ParseError;
+use super::error::{ParseError, ParseWarning};
+use super::firmware::FirmwareRequirement;
+use super::interner;
+#[cfg(feature = "raw")]
+use super::raw::RawNode;
+use super::warnings;
 use super::{Collection, Component};
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryFrom;
 use std::str::FromStr;
 use url::Url;
-use yaml_rust::{Yaml, YamlLoader};
+use yaml_rust::yaml::Hash;
+use yaml_rust::Yaml;
 
 use super::builders::{
-    ArtifactBuilder, CollectionBuilder, ComponentBuilder, ImageBuilder, ReleaseBuilder,
-    ScreenshotBuilder, VideoBuilder,
+    CollectionBuilder, ComponentBuilder, ImageBuilder, ReleaseBuilder, ScreenshotBuilder,
 };
 use super::enums::{
-    ArtifactKind, Bundle, Category, Checksum, ComponentKind, ContentAttribute,
-    ContentRatingVersion, ContentState, FirmwareKind, Icon, ImageKind, Kudo, Launchable,
-    ProjectUrl, Provide, ReleaseKind, ReleaseUrgency, Size, Translation,
+    Category, Compare, ComponentKind, ContentAttribute, ContentRatingVersion, ContentState,
+    FirmwareKind, Icon, ImageKind, IssueKind, MergeKind, ProjectUrl, Provide, ReleaseKind,
+    ReleaseUrgency,
 };
+use super::Timestamp;
 use super::{
-    AppId, Artifact, ContentRating, Image, Language, License, MarkupTranslatableString, Release,
-    Screenshot, TranslatableList, TranslatableString, Video,
+    AppId, ContentRating, Issue, License, MarkupTranslatableString, TranslatableList,
+    TranslatableString,
 };
-use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
-
-fn deserialize_date(date: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
-    Utc.datetime_from_str(&date, "%s").or_else(
-        |_: chrono::ParseError| -> Result<DateTime<Utc>, chrono::ParseError> {
-            let date: NaiveDateTime =
-                NaiveDate::parse_from_str(&date, "%Y-%m-%d")?.and_hms(0, 0, 0);
-            Ok(DateTime::<Utc>::from_utc(date, Utc))
-        },
-    )
+
+fn deserialize_date(date: &str) -> Result<Timestamp, ParseError> {
+    date.parse()
+}
+
+/// The mapping of a node, an error naming `tag` if it's of another type.
+fn hash<'a>(e: &'a Yaml, tag: &str) -> Result<&'a Hash, ParseError> {
+    e.as_hash().ok_or_else(|| ParseError::invalid_tag(tag))
+}
+
+/// The items of a list node, an error naming `tag` if it's of another type.
+fn list<'a>(e: &'a Yaml, tag: &str) -> Result<&'a [Yaml], ParseError> {
+    e.as_vec()
+        .map(Vec::as_slice)
+        .ok_or_else(|| ParseError::invalid_tag(tag))
+}
+
+/// A key of a mapping, an error naming `tag` if it isn't a string.
+fn key<'a>(k: &'a Yaml, tag: &str) -> Result<&'a str, ParseError> {
+    k.as_str().ok_or_else(|| ParseError::invalid_tag(tag))
+}
+
+/// The text of a scalar value, like a version which may be read as a number.
+fn scalar(e: &Yaml) -> Option<String> {
+    match e {
+        Yaml::String(s) | Yaml::Real(s) => Some(s.clone()),
+        Yaml::Integer(i) => Some(i.to_string()),
+        Yaml::Boolean(b) => Some(b.to_string()),
+        _ => None,
+    }
 }
 
 impl TryFrom<&Yaml> for AppId {
@@ -44,7 +70,7 @@ impl TryFrom<&Vec<Yaml>> for Collection {
     type Error = ParseError;
 
     fn try_from(e: &Vec<Yaml>) -> Result<Self, Self::Error> {
-        let header = &e[0];
+        let header = e.first().ok_or_else(|| ParseError::missing_tag("File"))?;
         let version = header["Version"]
             .as_str()
             .ok_or_else(|| ParseError::missing_attribute("version", "collection"))?;
@@ -65,6 +91,16 @@ impl TryFrom<&Vec<Yaml>> for Collection {
             .as_str()
             .ok_or_else(|| ParseError::missing_value("Origin"))?;
         
+        // A broken generation time doesn't make the components unusable.
+        if let Some(time) = header["Time"].as_str() {
+            match deserialize_date(time) {
+                Ok(time) => collection = collection.timestamp(time),
+                Err(_) => warnings::warn(ParseWarning::recovered_value(
+                    time, "Time", "header", "none",
+                )),
+            }
+        }
+
         if let Some(media_base_url) = header["MediaBaseUrl"].as_str() {
             if !media_base_url.is_empty() {
                 collection = collection.media_base_url(media_base_url);
@@ -75,21 +111,27 @@ impl TryFrom<&Vec<Yaml>> for Collection {
             .as_str()
             .ok_or_else(|| ParseError::missing_value("MediaBaseUrl"))?;
 
-        for node in e.iter().skip(1) {
-            collection = collection.component(Component::try_from((origin, media_base_url, node))?);
-        }
-        Ok(collection.build())
+        interner::scope(|| {
+            for node in e.iter().skip(1) {
+                collection =
+                    collection.component(Component::try_from((origin, media_base_url, node))?);
+            }
+            Ok(collection.build())
+        })
     }
 }
 
 impl TryFrom<(&str, &str, &Yaml)> for Component {
     type Error = ParseError;
     fn try_from(tuple: (&str, &str, &Yaml)) -> Result<Self, Self::Error> {
-        let e: &Yaml = tuple.2.try_into().unwrap();
-        let baseurl: &str = tuple.1.try_into().unwrap();
-        let origin: &str = tuple.0.try_into().unwrap();
+        let (origin, baseurl, e) = tuple;
         let mut component = ComponentBuilder::default();
 
+        // The components of aggregated catalogs keep the origin they come from.
+        let origin = e["Origin"]
+            .as_str()
+            .filter(|o| !o.is_empty())
+            .unwrap_or(origin);
         component = component.origin(origin);
         if let Some(kind) = e["Type"].as_str() {
             component = component.kind(
@@ -98,9 +140,21 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
             );
         }
 
+        if let Some(merge) = e["Merge"].as_str() {
+            component = component.merge(
+                MergeKind::from_str(merge)
+                    .map_err(|_| ParseError::invalid_value(merge, "merge", "component"))?,
+            );
+        }
+
+        if let Some(priority) = e["Priority"].as_i64() {
+            component = component.priority(i32::try_from(priority).map_err(|_| {
+                ParseError::invalid_value(&priority.to_string(), "priority", "component")
+            })?);
+        }
+
         let app_id = AppId::try_from(
-            e.as_hash()
-                .unwrap()
+            hash(e, "component")?
                 .get(&Yaml::from_str("ID"))
                 .ok_or_else(|| ParseError::missing_tag("id"))?,
         )?;
@@ -110,18 +164,18 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
         let mut developer_name = TranslatableString::default();
         let mut keywords = TranslatableList::default();
         let mut description = MarkupTranslatableString::default();
-        for (k, v) in e.as_hash().unwrap() {
-            match k.as_str().unwrap() {
-                "Name" => name.add_for_yaml_element(v),
-                "Summary" => summary.add_for_yaml_element(v),
-                "DeveloperName" => developer_name.add_for_yaml_element(v),
-                "Description" => description.add_for_yaml_element(v),
+        for (k, v) in hash(e, "component")? {
+            match key(k, "component")? {
+                "Name" => name.add_for_yaml_element(v)?,
+                "Summary" => summary.add_for_yaml_element(v)?,
+                "DeveloperName" => developer_name.add_for_yaml_element(v)?,
+                "Description" => description.add_for_yaml_element(v)?,
                 "ProjectLicense" => {
                     component = component.project_license(License::try_from(v)?);
                 }
                 "Icon" => {
-                    for (x, y) in v.as_hash().unwrap() {
-                        let kind = x.as_str().unwrap();
+                    for (x, y) in hash(v, "icon")? {
+                        let kind = key(x, "icon")?;
                         match kind {
                             "stock" => {
                                 let name = y
@@ -130,7 +184,7 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
                                 component = component.icon(Icon::Stock(name.to_string()));
                             }
                             "cached" => {
-                                for icon in y.as_vec().unwrap() {
+                                for icon in list(y, "icon")? {
                                     let name = icon["name"]
                                         .as_str()
                                         .ok_or_else(|| ParseError::missing_value("icon_name"))?
@@ -153,7 +207,7 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
                                 }
                             }
                             "remote" => {
-                                for icon in y.as_vec().unwrap() {
+                                for icon in list(y, "icon")? {
                                     let path = icon["url"]
                                         .as_str()
                                         .ok_or_else(|| ParseError::missing_value("icon_name"))?;
@@ -176,7 +230,7 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
                                 }
                             }
                             _ => {
-                                for icon in y.as_vec().unwrap() {
+                                for icon in list(y, "icon")? {
                                     let name = icon["name"]
                                         .as_str()
                                         .ok_or_else(|| ParseError::missing_value("icon_name"))?
@@ -220,7 +274,7 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
                     component = component.pkgname(pkgname.as_ref());
                 }
                 "Categories" => {
-                    for x in v.as_vec().unwrap() {
+                    for x in list(v, "categories")? {
                         let category = x
                             .as_str()
                             .ok_or_else(|| ParseError::missing_value("category"))?
@@ -237,22 +291,27 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
                         .ok_or_else(|| ParseError::missing_value("source_pkgname"))?;
                     component = component.source_pkgname(source_pkgname.as_ref());
                 }
-                "Keywords" => keywords.add_for_yaml_element(v),
+                "Keywords" => keywords.add_for_yaml_element(v)?,
                 "Screenshots" => {
-                    for child in v.as_vec().unwrap() {
+                    for child in list(v, "screenshots")? {
                         let mut s = ScreenshotBuilder::default().set_default(false);
                         let mut caption = TranslatableString::default();
-                        for (x, y) in child.as_hash().unwrap() {
-                            let kind = x.as_str().unwrap();
+                        for (x, y) in hash(child, "screenshot")? {
+                            let kind = key(x, "screenshot")?;
                             match kind {
                                 "default" => {
                                     s = s.set_default(y.as_bool().unwrap_or_else(|| false));
                                 }
+                                "environment" => {
+                                    if let Some(environment) = y.as_str() {
+                                        s = s.environment(environment);
+                                    }
+                                }
                                 "caption" => {
-                                    caption.add_for_yaml_element(y);
+                                    caption.add_for_yaml_element(y)?;
                                 }
                                 "thumbnails" => {
-                                    for thumbnail in y.as_vec().unwrap() {
+                                    for thumbnail in list(y, "thumbnails")? {
                                         let path = thumbnail["url"].as_str().ok_or_else(|| {
                                             ParseError::missing_value("icon_name")
                                         })?;
@@ -271,8 +330,12 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
                                         let url = format!("{}{}", baseurl, path);
                                         let mut img = ImageBuilder::new(Url::parse(&url)?);
                                         img = img.kind(ImageKind::Thumbnail);
-                                        img = img.width(width.unwrap());
-                                        img = img.height(height.unwrap());
+                                        if let Some(width) = width {
+                                            img = img.width(width);
+                                        }
+                                        if let Some(height) = height {
+                                            img = img.height(height);
+                                        }
                                         s = s.image(img.build());
                                     }
                                 }
@@ -294,8 +357,12 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
                                     let url = format!("{}{}", baseurl, path);
                                     let mut img = ImageBuilder::new(Url::parse(&url)?);
                                     img = img.kind(ImageKind::Source);
-                                    img = img.width(width.unwrap());
-                                    img = img.height(height.unwrap());
+                                    if let Some(width) = width {
+                                        img = img.width(width);
+                                    }
+                                    if let Some(height) = height {
+                                        img = img.height(height);
+                                    }
                                     s = s.image(img.build());
                                 }
                                 _ => {}
@@ -307,28 +374,37 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
                 }
 
                 "Releases" => {
-                    for x in v.as_vec().unwrap() {
-                        let version = x["version"]
-                            .as_str()
-                            .ok_or_else(|| ParseError::missing_value("version"))?
-                            .to_owned();
+                    for x in list(v, "releases")? {
+                        let version = scalar(&x["version"])
+                            .ok_or_else(|| ParseError::missing_value("version"))?;
 
                         let mut release = ReleaseBuilder::new(&version);
 
-                        let date = x["date"].as_i64().map(|d| {
-                            deserialize_date(d.to_string().as_str()).map_err(|_| ParseError::invalid_value(d.to_string().as_str(), "date", "release"))
-                        });
+                        if let Some(d) = scalar(&x["date"]) {
+                            release =
+                                release.date(deserialize_date(&d).map_err(|_| {
+                                    ParseError::invalid_value(&d, "date", "release")
+                                })?);
+                        }
 
-                        if let Some(d) = date {
-                            release = release.date(d?);
+                        // The timestamp is preferred to the date, like in the XML catalogs.
+                        if let Some(d) = scalar(&x["unix-timestamp"]) {
+                            release = release.date(deserialize_date(&d).map_err(|_| {
+                                ParseError::invalid_value(&d, "unix-timestamp", "release")
+                            })?);
                         }
 
-                        let timestamp = x["unix-timestamp"].as_i64().map(|d| {
-                            deserialize_date(d.to_string().as_str()).map_err(|_| ParseError::invalid_value(d.to_string().as_str(), "date", "release"))
-                        });
+                        if let Some(d) = scalar(&x["date-eol"]) {
+                            release = release.date_eol(deserialize_date(&d).map_err(|_| {
+                                ParseError::invalid_value(&d, "date-eol", "release")
+                            })?);
+                        }
 
-                        if let Some(d) = timestamp {
-                            release = release.date(d?);
+                        if let Some(urgency) = x["urgency"].as_str() {
+                            let urgency = ReleaseUrgency::from_str(urgency).map_err(|_| {
+                                ParseError::invalid_value(urgency, "urgency", "release")
+                            })?;
+                            release = release.urgency(urgency);
                         }
 
                         if let Some(kind) = x["type"].as_str() {
@@ -337,23 +413,126 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
                             release = release.kind(kind);
                         }
 
+                        if let Some(issues) = x["issues"].as_vec() {
+                            for issue in issues {
+                                let id = issue["id"]
+                                    .as_str()
+                                    .ok_or_else(|| ParseError::missing_value("issue"))?;
+                                let kind = match issue["type"].as_str() {
+                                    Some(kind) => IssueKind::from_str(kind).map_err(|_| {
+                                        ParseError::invalid_value(kind, "type", "issue")
+                                    })?,
+                                    None => IssueKind::default(),
+                                };
+                                let url = match issue["url"].as_str() {
+                                    Some(url) => Some(Url::parse(url)?),
+                                    None => None,
+                                };
+                                release = release.issue(Issue {
+                                    kind,
+                                    id: id.to_string(),
+                                    url,
+                                });
+                            }
+                        }
+
+                        // Some generators copy the URLs without checking them.
+                        if let Some(url) = x["url"]["details"].as_str() {
+                            match Url::parse(url) {
+                                Ok(url) => release = release.url(url),
+                                Err(_) => warnings::warn(ParseWarning::recovered_value(
+                                    url, "details", "release", "none",
+                                )),
+                            }
+                        }
+
+                        if x["description"].as_hash().is_some() {
+                            let mut description = MarkupTranslatableString::default();
+                            description.add_for_yaml_element(&x["description"])?;
+                            release = release.description(description);
+                        }
+
                         component = component.release(release.build())
                     }
                 }
                 "Extends" => {
-                    for x in v.as_vec().unwrap() {
+                    for x in list(v, "extends")? {
                         component = component.extend(AppId::try_from(x)?);
                     }
                 }
+                "Provides" => {
+                    for (x, y) in hash(v, "provides")? {
+                        let kind = key(x, "provides")?;
+                        for item in list(y, kind)? {
+                            match kind {
+                                // The media types are listed in `<mimetypes>` in the XML
+                                // catalogs.
+                                "mediatypes" | "mimetypes" => {
+                                    let mimetype = item
+                                        .as_str()
+                                        .ok_or_else(|| ParseError::missing_value("mimetype"))?;
+                                    component = component.mimetype(mimetype);
+                                }
+                                kind => {
+                                    component = component.provide(Provide::try_from((kind, item))?)
+                                }
+                            }
+                        }
+                    }
+                }
+                "ContentRating" => {
+                    for (x, y) in hash(v, "content_rating")? {
+                        let version = x.as_str().ok_or_else(|| {
+                            ParseError::missing_attribute("type", "content_rating")
+                        })?;
+                        component =
+                            component.content_rating(ContentRating::try_from((version, y))?);
+                    }
+                }
+                "Url" => {
+                    for (x, y) in hash(v, "url")? {
+                        let kind = x
+                            .as_str()
+                            .ok_or_else(|| ParseError::missing_attribute("type", "url"))?;
+                        component = component.url(ProjectUrl::try_from((kind, y))?);
+                    }
+                }
+                "Suggests" => {
+                    for x in list(v, "suggests")? {
+                        for id in x["ids"].as_vec().into_iter().flatten() {
+                            component = component.suggest(AppId::try_from(id)?);
+                        }
+                    }
+                }
+                "Requires" => {
+                    for x in list(v, "requires")? {
+                        // The kind of a requirement is its only key besides the version
+                        // and the comparison, like `id` or `kernel`.
+                        let kind = x
+                            .as_hash()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|(k, _)| k.as_str())
+                            .find(|k| *k != "version" && *k != "compare");
+                        match kind {
+                            Some("id") => component = component.require(AppId::try_from(&x["id"])?),
+                            Some("firmware") => {
+                                component =
+                                    component.require_firmware(FirmwareRequirement::try_from(x)?)
+                            }
+                            Some(kind) => {
+                                warnings::warn(ParseWarning::unknown_tag(kind, "requires"))
+                            }
+                            None => return Err(ParseError::missing_value("requires")),
+                        }
+                    }
+                }
                 // "translation" => {
                 //     component = component.translation(Translation::try_from(e)?);
                 // }
                 // "launchable" => {
                 //     component = component.launchable(Launchable::try_from(e)?);
                 // }
-                // "content_rating" => {
-                //     component = component.content_rating(ContentRating::try_from(e)?);
-                // }
                 // "languages" => {
                 //     for child in e.children.iter() {
                 //         component = component.language(Language::try_from(
@@ -363,56 +542,21 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
                 //         )?);
                 //     }
                 // }
-                // "provides" => {
-                //     for child in e.children.iter() {
-                //         component = component.provide(Provide::try_from(
-                //             child
-                //                 .as_element()
-                //                 .ok_or_else(|| ParseError::invalid_tag("prorivdes"))?,
-                //         )?);
-                //     }
-                // }
-                // "url" => {
-                //     component = component.url(ProjectUrl::try_from(e)?);
-                // }
                 // "bundle" => {
                 //     component = component.bundle(Bundle::try_from(e)?);
                 // }
-                // "suggests" => {
-                //     for child in e.children.iter() {
-                //         component = component.suggest(AppId::try_from(
-                //             child
-                //                 .as_element()
-                //                 .ok_or_else(|| ParseError::invalid_tag("id"))?,
-                //         )?);
-                //     }
-                // }
-                // "metadata" => {
-                //     for child in &e.children {
-                //         let child = child
-                //             .as_element()
-                //             .ok_or_else(|| ParseError::invalid_tag("value"))?
-                //             .to_owned();
-
-                //         let key = child
-                //             .attributes
-                //             .get("key")
-                //             .ok_or_else(|| ParseError::missing_attribute("key", "value"))?
-                //             .to_owned();
-
-                //         let value = child.get_text().map(|c| c.to_string());
-                //         component = component.metadata(key, value);
-                //     }
-                // }
-                // "requires" => {
-                //     for child in e.children.iter() {
-                //         component = component.require(AppId::try_from(
-                //             child
-                //                 .as_element()
-                //                 .ok_or_else(|| ParseError::invalid_tag("id"))?,
-                //         )?);
-                //     }
-                // }
+                "Custom" => {
+                    for (x, y) in hash(v, "custom")? {
+                        let key = scalar(x)
+                            .ok_or_else(|| ParseError::missing_attribute("key", "value"))?;
+                        component = component.metadata(key, scalar(y));
+                    }
+                }
+                // The keys read before the loop.
+                "ID" | "Type" | "Merge" | "Priority" | "Origin" => (),
+                #[cfg(feature = "raw")]
+                key => component = component.unknown_node(RawNode::Yaml(key.into(), v.clone())),
+                #[cfg(not(feature = "raw"))]
                 _ => (),
             }
         }
@@ -427,6 +571,131 @@ impl TryFrom<(&str, &str, &Yaml)> for Component {
     }
 }
 
+impl TryFrom<(&str, &Yaml)> for Provide {
+    type Error = ParseError;
+
+    /// Converts an item of a list of the `Provides` mapping, whose key is `kind`.
+    fn try_from(tuple: (&str, &Yaml)) -> Result<Self, Self::Error> {
+        let (kind, e) = tuple;
+        let text = |e: &Yaml, tag: &str| {
+            e.as_str()
+                .map(|v| v.to_string())
+                .ok_or_else(|| ParseError::missing_value(tag))
+        };
+
+        match kind {
+            "libraries" => Ok(Provide::Library(text(e, "library")?.into())),
+            "binaries" => Ok(Provide::Binary(text(e, "binary")?)),
+            "fonts" => Ok(Provide::Font(text(&e["name"], "font")?)),
+            "modaliases" => Ok(Provide::Modalias(text(e, "modalias")?)),
+            "python2" => Ok(Provide::Python2(text(e, "python2")?)),
+            "python3" => Ok(Provide::Python3(text(e, "python3")?)),
+            "dbus" => Ok(Provide::DBus(text(&e["service"], "dbus")?)),
+            "ids" => Ok(Provide::Id(text(e, "id")?.into())),
+            "firmware" => match e["type"].as_str() {
+                Some("runtime") => Ok(Provide::Firmware {
+                    kind: FirmwareKind::Runtime,
+                    item: text(&e["file"], "firmware")?,
+                }),
+                Some("flashed") => Ok(Provide::Firmware {
+                    kind: FirmwareKind::Flashed,
+                    item: text(&e["guid"], "firmware")?,
+                }),
+                Some(kind) => Err(ParseError::invalid_value(kind, "type", "firmware")),
+                None => Err(ParseError::missing_attribute("type", "firmware")),
+            },
+            kind => Err(ParseError::invalid_value(kind, "type", "provide")),
+        }
+    }
+}
+
+impl TryFrom<(&str, &Yaml)> for ContentRating {
+    type Error = ParseError;
+
+    /// Converts a value of the `ContentRating` mapping, whose key is the OARS
+    /// version.
+    fn try_from(tuple: (&str, &Yaml)) -> Result<Self, Self::Error> {
+        let (version, e) = tuple;
+        let version = match version {
+            "oars-1.0" => ContentRatingVersion::Oars1_0,
+            "oars-1.1" => ContentRatingVersion::Oars1_1,
+            t => {
+                warnings::warn(ParseWarning::recovered_value(
+                    t,
+                    "type",
+                    "content_rating",
+                    "unknown",
+                ));
+                ContentRatingVersion::Unknown
+            }
+        };
+
+        let mut attributes = Vec::new();
+        for (id, state) in e.as_hash().into_iter().flatten() {
+            let id = id
+                .as_str()
+                .ok_or_else(|| ParseError::missing_attribute("id", "content-attribute"))?;
+            let state = state
+                .as_str()
+                .ok_or_else(|| ParseError::missing_value("content-attribute"))?;
+            let state = ContentState::from_str(state)
+                .map_err(|_| ParseError::invalid_value(state, "$value", "content-attribute"))?;
+            attributes.push(
+                ContentAttribute::from_id(id, state)
+                    .ok_or_else(|| ParseError::invalid_value(id, "id", "content-attribute"))?,
+            );
+        }
+        Ok(Self {
+            version,
+            attributes,
+        })
+    }
+}
+
+impl TryFrom<(&str, &Yaml)> for ProjectUrl {
+    type Error = ParseError;
+
+    /// Converts a value of the `Url` mapping, whose key is the type of the URL.
+    fn try_from(tuple: (&str, &Yaml)) -> Result<Self, Self::Error> {
+        let (kind, e) = tuple;
+        let url = Url::parse(e.as_str().ok_or_else(|| ParseError::missing_value("url"))?)?;
+        Ok(match kind {
+            "help" => ProjectUrl::Help(url),
+            "homepage" => ProjectUrl::Homepage(url),
+            "donation" => ProjectUrl::Donation(url),
+            "contact" => ProjectUrl::Contact(url),
+            "translate" => ProjectUrl::Translate(url),
+            "faq" => ProjectUrl::Faq(url),
+            "bugtracker" => ProjectUrl::BugTracker(url),
+            t => {
+                warnings::warn(ParseWarning::recovered_value(t, "type", "url", "unknown"));
+                ProjectUrl::Unknown(url)
+            }
+        })
+    }
+}
+
+impl TryFrom<&Yaml> for FirmwareRequirement {
+    type Error = ParseError;
+
+    fn try_from(e: &Yaml) -> Result<Self, Self::Error> {
+        let compare = match e["compare"].as_str() {
+            Some(compare) => Compare::from_str(compare)
+                .map_err(|_| ParseError::invalid_value(compare, "compare", "firmware"))?,
+            None => Compare::default(),
+        };
+        let target = e["firmware"]
+            .as_str()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+        Ok(FirmwareRequirement {
+            target,
+            compare,
+            version: scalar(&e["version"]),
+        })
+    }
+}
+
 impl TryFrom<&Yaml> for License {
     type Error = ParseError;
 
@@ -436,3 +705,67 @@ impl TryFrom<&Yaml> for License {
             .into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use yaml_rust::YamlLoader;
+
+    const HEADER: &str = "---\nFile: DEP-11\nVersion: '0.14'\nOrigin: example\nMediaBaseUrl: ''\n";
+
+    fn parse(components: &str) -> Result<Collection, Box<dyn Error>> {
+        let documents = YamlLoader::load_from_str(&format!("{}{}", HEADER, components))?;
+        Ok(Collection::try_from(&documents)?)
+    }
+
+    #[test]
+    fn damaged_yaml() -> Result<(), Box<dyn Error>> {
+        let component = "---\nID: org.example.App\nType: desktop-application\n";
+        assert!(parse(&format!("{}Name:\n  C: App\n", component)).is_ok());
+        for damaged in [
+            "Name: App\n",
+            "Name:\n  C: [App]\n",
+            "Keywords:\n  C: App\n",
+            "Icon:\n- stock\n",
+            "Icon:\n  cached: app.png\n",
+            "Screenshots:\n  default: true\n",
+            "Screenshots:\n- thumbnails: {}\n",
+            "Categories: Utility\n",
+            "Releases:\n  version: '1.0'\n",
+            "Provides:\n  binaries: app\n",
+            "Provides:\n- binaries\n",
+            "Url: https://example.org\n",
+            "ContentRating:\n  oars-1.1:\n    violence-cartoon: [mild]\n",
+            "Requires:\n- {}\n",
+            "Custom: true\n",
+        ]
+        .iter()
+        {
+            assert!(
+                parse(&format!("{}{}", component, damaged)).is_err(),
+                "{}",
+                damaged
+            );
+        }
+        assert!(parse("---\n- org.example.App\n").is_err());
+        assert!(Collection::try_from(&vec![]).is_err());
+
+        // Truncated catalogs are either valid or rejected.
+        let catalog = std::fs::read_to_string("./tests/collections/spec_example.yaml")?;
+        for end in (0..catalog.len()).filter(|i| catalog.is_char_boundary(*i)) {
+            if let Ok(documents) = YamlLoader::load_from_str(&catalog[..end]) {
+                let _ = Collection::try_from(&documents);
+            }
+        }
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("damaged.yml");
+        std::fs::write(&path, &catalog[..catalog.find("Icon:").unwrap_or(0) + 20])?;
+        let truncated = Collection::from_yaml_path(path.clone());
+        std::fs::write(&path, "---\nFile: DEP-11\nVersion: '0.14\n")?;
+        assert!(Collection::from_yaml_path(path.clone()).is_err());
+        assert!(truncated.is_err());
+        Ok(())
+    }
+}